@@ -0,0 +1,60 @@
+use regex_syntax::hir::{Class, Hir, HirKind};
+
+/// Set of bytes a match of some pattern can start with.
+pub type ByteSet = [bool; 256];
+
+/// Compute all bytes a (non-empty) match of the given regex can start with.
+pub fn first_bytes(pattern: &str) -> ByteSet {
+    let hir = regex_syntax::parse(pattern)
+        .unwrap_or_else(|e| panic!("invalid matcher for #[literal] '{pattern}': {e}"));
+
+    let mut bytes = [false; 256];
+    collect(&hir, &mut bytes);
+    bytes
+}
+
+/// Add the first bytes of all matches of `hir` to `bytes`. Returns whether `hir` can match the
+/// empty string (in which case the first bytes of whatever follows are possible as well).
+fn collect(hir: &Hir, bytes: &mut ByteSet) -> bool {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => true,
+        HirKind::Literal(literal) => match literal.0.first() {
+            Some(first) => {
+                bytes[*first as usize] = true;
+                false
+            }
+            None => true,
+        },
+        HirKind::Class(Class::Unicode(class)) => {
+            // the leading byte of the UTF-8 encoding grows monotonically with the code point
+            for range in class.ranges() {
+                let start = leading_byte(range.start());
+                let end = leading_byte(range.end());
+                bytes[start as usize..=end as usize].fill(true);
+            }
+            false
+        }
+        HirKind::Class(Class::Bytes(class)) => {
+            for range in class.ranges() {
+                bytes[range.start() as usize..=range.end() as usize].fill(true);
+            }
+            false
+        }
+        HirKind::Repetition(repetition) => collect(&repetition.sub, bytes) || repetition.min == 0,
+        HirKind::Capture(capture) => collect(&capture.sub, bytes),
+        HirKind::Concat(hirs) => hirs.iter().all(|hir| collect(hir, bytes)),
+        HirKind::Alternation(hirs) => {
+            // every alternative contributes its first bytes
+            let mut empty = false;
+            for hir in hirs {
+                empty |= collect(hir, bytes);
+            }
+            empty
+        }
+    }
+}
+
+fn leading_byte(c: char) -> u8 {
+    let mut buffer = [0; 4];
+    c.encode_utf8(&mut buffer).as_bytes()[0]
+}
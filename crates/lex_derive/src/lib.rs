@@ -1,3 +1,4 @@
+mod first_bytes;
 mod loose_eq;
 mod token;
 
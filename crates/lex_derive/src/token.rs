@@ -1,4 +1,6 @@
 use proc_macro::TokenStream;
+
+use crate::first_bytes::first_bytes;
 use quote::quote;
 use syn::{
     punctuated::Punctuated, token::Comma, DataEnum, DeriveInput, Expr, ExprLit, Lit, Variant,
@@ -131,29 +133,62 @@ pub fn impl_token_macro(ast: syn::DeriveInput) -> TokenStream {
         }
     });
 
-    let terminal_insertions = terminal_variants_tuples.iter().map(|(variant, literal)| {
-        let Variant {
-            ident: var_ident, ..
-        } = variant;
-
-        let literal = literal.value();
+    // every terminal and literal is an entry of the lexikon, terminals first (which take
+    // precedence over literals matching the same text, e.g., keywords over identifiers)
+    let terminal_count = terminal_variants_tuples.len();
 
-        quote! {
-            terminal!(entries, #var_ident, #literal);
-        }
-    });
-
-    let literal_insertions = literal_variants_tuples.iter().map(|(variant, literal)| {
-        let Variant {
-            ident: var_ident, ..
-        } = variant;
+    let terminal_values = terminal_variants_tuples
+        .iter()
+        .map(|(_, literal)| literal.value())
+        .collect::<Vec<_>>();
 
-        let literal = literal.value();
+    let literal_patterns = literal_variants_tuples
+        .iter()
+        .map(|(_, literal)| format!("^(?:{})", literal.value()));
 
-        quote! {
-            literal!(entries, #var_ident, #literal);
+    let entry_tokens = terminal_variants_tuples
+        .iter()
+        .enumerate()
+        .map(|(index, (variant, _))| {
+            let var_ident = &variant.ident;
+            quote! {
+                #index => Ok(#ident::#var_ident { position }),
+            }
+        })
+        .chain(
+            literal_variants_tuples
+                .iter()
+                .enumerate()
+                .map(|(index, (variant, _))| {
+                    let var_ident = &variant.ident;
+                    let index = terminal_count + index;
+                    quote! {
+                        #index => match matched.parse() {
+                            Ok(value) => Ok(#ident::#var_ident { value, position }),
+                            Err(e) => Err(format!("Invalid literal '{matched}' ({e})")),
+                        },
+                    }
+                }),
+        );
+
+    // entries which can match at all, grouped by the first byte of their matches
+    let mut dispatch = vec![vec![]; 256];
+    for (index, terminal) in terminal_values.iter().enumerate() {
+        let first = terminal
+            .as_bytes()
+            .first()
+            .unwrap_or_else(|| panic!("empty matcher for #[terminal]"));
+        dispatch[*first as usize].push(index);
+    }
+    for (index, (_, literal)) in literal_variants_tuples.iter().enumerate() {
+        let bytes = first_bytes(&literal.value());
+        for (byte, entries) in dispatch.iter_mut().enumerate() {
+            if bytes[byte] {
+                entries.push(terminal_count + index);
+            }
         }
-    });
+    }
+    let dispatch = dispatch.iter().map(|entries| quote! { &[#(#entries),*] });
 
     let terminal_variants_tuples = terminal_variants_tuples
         .iter()
@@ -206,78 +241,74 @@ pub fn impl_token_macro(ast: syn::DeriveInput) -> TokenStream {
             }
         }
 
-        macro_rules! terminal {
-            ($entries:ident, $name:ident, $value:expr) => {
-                Self::insert(
-                    &mut $entries,
-                    Regex::new(&$value.escape_unicode().to_string()).unwrap(),
-                    |matched, (line, col), source| Token::$name {
-                        position: Span { start: (line, col), end: (line, (col+matched.as_str().len())), source }
-                    },
-                );
-            };
-        }
+        /// File and byte offset of a match.
+        type EntryInputSpan = (FileId, usize);
 
-        macro_rules! literal {
-            ($entries:ident, $name:ident, $value:expr) => {
-                Self::insert(
-                    &mut $entries,
-                    Regex::new($value).unwrap(),
-                    |matched, (line, col), source| Token::$name {
-                        value: matched.as_str().parse().unwrap(),
-                        position: Span { start: (line, col), end: (line, (col+matched.as_str().len())), source }
-                    },
-                );
-            };
-        }
+        /// Text of all terminals.
+        const TERMINALS: [&str; #terminal_count] = [#(#terminal_values),*];
 
-        type EntryInputSpan = (usize, usize);
+        /// Entries (indices into the terminals, followed by the literals) which can match
+        /// something starting with a given byte, in order of precedence.
+        static DISPATCH: [&[usize]; 256] = [#(#dispatch),*];
 
-        type Entries = Vec<(Regex, Box<dyn Fn(Match, EntryInputSpan, String) -> Token>)>;
+        /// Anchored regular expressions of all literals.
+        static LITERALS: std::sync::LazyLock<Vec<Regex>> = std::sync::LazyLock::new(|| {
+            vec![#(Regex::new(#literal_patterns).unwrap()),*]
+        });
 
         pub struct Lexikon {
-            entries: Entries,
+            literals: &'static [Regex],
         }
 
-        impl<'a> Lexikon {
+        impl Lexikon {
             pub fn new() -> Lexikon {
-                let mut entries = vec![];
-
-                #(#terminal_insertions)*
-
-                #(#literal_insertions)*
-
-                Lexikon { entries }
-            }
-
-            fn insert<F: Fn(Match, EntryInputSpan, String) -> Token + 'static>(entries: &mut Entries, reg: Regex, f: F) {
-                entries.push((reg, Box::new(f)))
+                Lexikon { literals: &LITERALS }
             }
 
+            /// Find the longest token at the start of `pattern`. Only entries which can start
+            /// with the first byte of `pattern` are tried. Fails if the longest match is a
+            /// literal whose value can not be represented (e.g., an integer which is too large).
             pub fn find_longest_match(
                 &self,
-                pattern: &'a str,
-                position: EntryInputSpan,
-                source: String
-            ) -> (usize, Option<Token>) {
+                pattern: &str,
+                (file, start): EntryInputSpan,
+            ) -> (usize, Option<Result<Token, String>>) {
+                let Some(first) = pattern.as_bytes().first() else {
+                    return (0, None);
+                };
+
                 let mut longest = (0, None);
 
-                for (reg, mapper) in &self.entries {
-                    let Some(res) = reg.captures_at(pattern, 0).and_then(|res| res.get(0)) else {
-                        continue;
+                for &entry in DISPATCH[*first as usize] {
+                    let len = match TERMINALS.get(entry) {
+                        Some(terminal) if pattern.starts_with(terminal) => terminal.len(),
+                        Some(_) => continue,
+                        None => match self.literals[entry - TERMINALS.len()].find(pattern) {
+                            Some(matched) => matched.len(),
+                            None => continue,
+                        },
                     };
 
-                    let len = res.len();
-
-                    if len > longest.0 && res.start() == 0 {
-                        longest = (len, Some(mapper(res, position, source.clone())));
+                    if len > longest.0 {
+                        longest = (len, Some(entry));
                     }
                 }
 
-                longest
+                let (len, entry) = longest;
+                let token = entry.map(|entry| {
+                    Self::token(entry, &pattern[..len], Span::new(file, start, start + len))
+                });
+
+                (len, token)
             }
-        }
 
+            fn token(entry: usize, matched: &str, position: Span) -> Result<Token, String> {
+                match entry {
+                    #(#entry_tokens)*
+                    _ => unreachable!(),
+                }
+            }
+        }
 
     };
 
@@ -0,0 +1,59 @@
+//! Benchmarks of the lexer on generated sources of growing size. The throughput (bytes per
+//! second) should stay the same for all sizes, i.e., lexing has to scale linearly.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use why_lib::{lexer::Lexer, source_map::SourceMap};
+
+/// Generate a source with the given number of functions, which uses most kinds of tokens.
+fn functions(count: usize) -> String {
+    (0..count)
+        .map(|i| {
+            format!(
+                "// computes something important\n\
+                 fn function_{i}(x: i64, y: f64): i64 {{\n    \
+                     let mut value = x * {i} + 13.37;\n    \
+                     let text = \"größe {i}\";\n    \
+                     if (value >= 42) {{\n        \
+                         value = add(value, [1, 2, 3][0]);\n    \
+                     }} else {{\n        \
+                         value = \\(a) => a - 1;\n    \
+                     }}\n    \
+                     value\n\
+                 }}\n\n"
+            )
+        })
+        .collect()
+}
+
+/// Generate a source consisting of a single line with a very long comment and string.
+fn long_line(length: usize) -> String {
+    let text = "ab".repeat(length / 4);
+    format!("let x = \"{text}\"; // {text}")
+}
+
+fn bench_lexer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("lex_functions");
+    for count in [10, 100, 1_000, 10_000] {
+        let input = functions(count);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        let file = SourceMap::add(input);
+        group.bench_function(BenchmarkId::from_parameter(count), |b| {
+            b.iter(|| Lexer::for_file(file).lex().unwrap())
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("lex_long_line");
+    for length in [1_000, 10_000, 100_000, 1_000_000] {
+        let input = long_line(length);
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        let file = SourceMap::add(input);
+        group.bench_function(BenchmarkId::from_parameter(length), |b| {
+            b.iter(|| Lexer::for_file(file).lex().unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_lexer);
+criterion_main!(benches);
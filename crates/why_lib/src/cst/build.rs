@@ -0,0 +1,660 @@
+//! Construction of the green tree from the tokens and the AST of a file.
+//!
+//! The parser does not record where its nodes start and end (many AST positions only cover a
+//! keyword or an operator), so the extent of every node is reconstructed from the positions of
+//! its children: a node covers its own position and all of its children, extended by trailing
+//! semicolons, surrounding parentheses and the closing delimiters of everything it opens. Every
+//! byte of the source which is not part of any child node becomes a token of the enclosing node.
+
+use crate::{
+    lexer::{GetPosition, Span, Token},
+    parser::ast::{
+        Array, Assignment, Block, Constant, Declaration, Expression, Function, FunctionParameter,
+        If, Initialisation, Instance, LValue, Lambda, MethodDeclaration, Postfix, Prefix,
+        Statement, StructDeclaration, StructInitialisation, TopLevelStatement, TypeName, WhileLoop,
+    },
+};
+
+use super::{GreenElement, GreenNode, GreenToken, SyntaxKind};
+
+/// Extent and children of a node, before it is filled with tokens.
+struct Shape {
+    kind: SyntaxKind,
+    start: usize,
+    end: usize,
+    children: Vec<Shape>,
+}
+
+#[derive(Clone, Copy)]
+struct RawToken {
+    kind: SyntaxKind,
+    start: usize,
+    end: usize,
+}
+
+pub struct Builder<'a> {
+    source: &'a str,
+    /// All tokens of the source (including whitespace and comments) without any gaps.
+    raw: Vec<RawToken>,
+    /// Tokens which are relevant for the parser.
+    significant: Vec<RawToken>,
+    /// For every significant opening delimiter the index of its closing counterpart.
+    closing: Vec<Option<usize>>,
+}
+
+impl<'a> Builder<'a> {
+    pub fn new(source: &'a str, tokens: &[Token]) -> Self {
+        let mut raw = vec![];
+        let mut offset = 0;
+
+        for token in tokens {
+            let Span { start, end, .. } = token.position();
+            if offset < start {
+                raw.push(RawToken {
+                    kind: SyntaxKind::Whitespace,
+                    start: offset,
+                    end: start,
+                });
+            }
+            raw.push(RawToken {
+                kind: token.into(),
+                start,
+                end,
+            });
+            offset = end;
+        }
+
+        if offset < source.len() {
+            raw.push(RawToken {
+                kind: SyntaxKind::Whitespace,
+                start: offset,
+                end: source.len(),
+            });
+        }
+
+        let significant = raw
+            .iter()
+            .filter(|token| !token.kind.is_trivia())
+            .copied()
+            .collect::<Vec<_>>();
+
+        let mut closing = vec![None; significant.len()];
+        let mut open = vec![];
+        for (index, token) in significant.iter().enumerate() {
+            match token.kind {
+                SyntaxKind::LParen | SyntaxKind::LBrace | SyntaxKind::LBracket => open.push(index),
+                SyntaxKind::RParen | SyntaxKind::RBrace | SyntaxKind::RBracket => {
+                    if let Some(opening) = open.pop() {
+                        closing[opening] = Some(index);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Builder {
+            source,
+            raw,
+            significant,
+            closing,
+        }
+    }
+
+    pub fn build<T: Clone>(&self, statements: &[TopLevelStatement<T>]) -> GreenNode {
+        let children = statements
+            .iter()
+            .filter_map(|statement| self.top_level_statement(statement))
+            .collect();
+        let root = Shape {
+            kind: SyntaxKind::Root,
+            start: 0,
+            end: self.source.len(),
+            children: without_overlaps(children),
+        };
+
+        let mut index = 0;
+        self.green(&root, &mut index)
+    }
+
+    /// Fill a shape with all raw tokens (starting at `index`) until its end.
+    fn green(&self, shape: &Shape, index: &mut usize) -> GreenNode {
+        let mut elements = vec![];
+        let mut children = shape.children.iter().peekable();
+
+        while let Some(token) = self.raw.get(*index) {
+            if token.start >= shape.end {
+                break;
+            }
+
+            match children.peek() {
+                Some(child) if token.start >= child.start => {
+                    elements.push(GreenElement::from(self.green(child, index)));
+                    children.next();
+                }
+                _ => {
+                    elements.push(
+                        GreenToken::new(token.kind, &self.source[token.start..token.end]).into(),
+                    );
+                    *index += 1;
+                }
+            }
+        }
+
+        GreenNode::new(shape.kind, elements)
+    }
+
+    /// Create the shape of a node which covers `position` (if it is an actual position) and all
+    /// of the given children.
+    fn node(
+        &self,
+        kind: SyntaxKind,
+        position: &Span,
+        children: impl IntoIterator<Item = Option<Shape>>,
+    ) -> Option<Shape> {
+        let children = without_overlaps(children.into_iter().flatten().collect());
+        let own = (!position.is_dummy()).then_some(position);
+
+        let start = own
+            .map(|span| span.start)
+            .into_iter()
+            .chain(children.first().map(|child| child.start))
+            .min()?;
+        let end = own
+            .map(|span| span.end)
+            .into_iter()
+            .chain(children.last().map(|child| child.end))
+            .max()?;
+
+        Some(self.close_delimiters(Shape {
+            kind,
+            start,
+            end,
+            children,
+        }))
+    }
+
+    /// Index of the first significant token starting at or after `offset`.
+    fn next_token(&self, offset: usize) -> usize {
+        self.significant
+            .partition_point(|token| token.start < offset)
+    }
+
+    /// Extend a shape to the closing delimiters of all delimiters opened within it.
+    fn close_delimiters(&self, mut shape: Shape) -> Shape {
+        let mut index = self.next_token(shape.start);
+
+        while let Some(token) = self.significant.get(index) {
+            if token.start >= shape.end {
+                break;
+            }
+            if let Some(closing) = self.closing[index] {
+                shape.end = shape.end.max(self.significant[closing].end);
+            }
+            index += 1;
+        }
+
+        shape
+    }
+
+    /// Extend a shape to the next token if it is of the given kind.
+    fn extend_end(&self, shape: Option<Shape>, kind: SyntaxKind) -> Option<Shape> {
+        let mut shape = shape?;
+        if let Some(token) = self.significant.get(self.next_token(shape.end)) {
+            if token.kind == kind {
+                shape.end = token.end;
+            }
+        }
+        Some(shape)
+    }
+
+    /// Extend a shape to the previous token if it is of the given kind.
+    fn extend_start(&self, shape: Option<Shape>, kind: SyntaxKind) -> Option<Shape> {
+        let mut shape = shape?;
+        let index = self.next_token(shape.start);
+        if let Some(token) = index.checked_sub(1).and_then(|i| self.significant.get(i)) {
+            if token.kind == kind {
+                shape.start = token.start;
+            }
+        }
+        Some(shape)
+    }
+
+    fn top_level_statement<T: Clone>(&self, statement: &TopLevelStatement<T>) -> Option<Shape> {
+        match statement {
+            TopLevelStatement::Comment(..) => None,
+            TopLevelStatement::Function(function) => self.function(function),
+            TopLevelStatement::Constant(constant) => self.constant(constant),
+            TopLevelStatement::Declaration(declaration) => self.declaration(declaration),
+            TopLevelStatement::StructDeclaration(declaration) => {
+                self.struct_declaration(declaration)
+            }
+            TopLevelStatement::Instance(instance) => self.instance(instance),
+        }
+    }
+
+    fn statement<T: Clone>(&self, statement: &Statement<T>) -> Option<Shape> {
+        match statement {
+            Statement::Function(function) => self.function(function),
+            Statement::If(if_expression) => self.if_expression(if_expression),
+            Statement::WhileLoop(WhileLoop {
+                condition,
+                block,
+                position,
+                ..
+            }) => self.node(
+                SyntaxKind::WhileLoop,
+                position,
+                [self.expression(condition), self.block(block)],
+            ),
+            Statement::Initialization(Initialisation {
+                type_name,
+                value,
+                position,
+                ..
+            }) => self.extend_end(
+                self.node(
+                    SyntaxKind::Initialisation,
+                    position,
+                    [
+                        type_name
+                            .as_ref()
+                            .and_then(|type_name| self.type_name(type_name)),
+                        value.as_ref().and_then(|value| self.expression(value)),
+                    ],
+                ),
+                SyntaxKind::Semicolon,
+            ),
+            Statement::Constant(constant) => self.constant(constant),
+            Statement::Assignment(Assignment {
+                lvalue,
+                rvalue,
+                position,
+                ..
+            }) => {
+                let lvalue = match lvalue {
+                    LValue::Id(id) => self.node(SyntaxKind::Name, &id.position, []),
+                    LValue::Postfix(postfix) => self.postfix(postfix),
+                };
+                self.extend_end(
+                    self.node(
+                        SyntaxKind::Assignment,
+                        position,
+                        [lvalue, self.expression(rvalue)],
+                    ),
+                    SyntaxKind::Semicolon,
+                )
+            }
+            Statement::Expression(expression) => self.extend_end(
+                self.node(
+                    SyntaxKind::ExpressionStatement,
+                    &Span::default(),
+                    [self.expression(expression)],
+                ),
+                SyntaxKind::Semicolon,
+            ),
+            Statement::YieldingExpression(expression) => self.expression(expression),
+            Statement::Return(expression) => self.extend_end(
+                self.extend_start(
+                    self.node(
+                        SyntaxKind::Return,
+                        &Span::default(),
+                        [self.expression(expression)],
+                    ),
+                    SyntaxKind::ReturnKeyword,
+                ),
+                SyntaxKind::Semicolon,
+            ),
+            Statement::Comment(..) => None,
+            Statement::Declaration(declaration) => self.declaration(declaration),
+            Statement::StructDeclaration(declaration) => self.struct_declaration(declaration),
+        }
+    }
+
+    fn function<T: Clone>(&self, function: &Function<T>) -> Option<Shape> {
+        let Function {
+            parameters,
+            return_type,
+            statements,
+            position,
+            ..
+        } = function;
+
+        let parameters = parameters.iter().map(
+            |FunctionParameter {
+                 type_name,
+                 position,
+                 ..
+             }| {
+                self.node(SyntaxKind::Parameter, position, [self.type_name(type_name)])
+            },
+        );
+
+        self.node(
+            SyntaxKind::Function,
+            position,
+            parameters
+                .chain([self.type_name(return_type)])
+                .chain(statements.iter().map(|statement| self.statement(statement))),
+        )
+    }
+
+    fn constant<T: Clone>(&self, constant: &Constant<T>) -> Option<Shape> {
+        let Constant {
+            type_name,
+            value,
+            position,
+            ..
+        } = constant;
+
+        self.extend_end(
+            self.node(
+                SyntaxKind::Constant,
+                position,
+                [self.type_name(type_name), self.expression(value)],
+            ),
+            SyntaxKind::Semicolon,
+        )
+    }
+
+    fn declaration<T: Clone>(&self, declaration: &Declaration<T>) -> Option<Shape> {
+        let Declaration {
+            type_name,
+            position,
+            ..
+        } = declaration;
+
+        self.extend_end(
+            self.node(
+                SyntaxKind::Declaration,
+                position,
+                [self.type_name(type_name)],
+            ),
+            SyntaxKind::Semicolon,
+        )
+    }
+
+    fn struct_declaration<T: Clone>(&self, declaration: &StructDeclaration<T>) -> Option<Shape> {
+        let StructDeclaration {
+            fields, position, ..
+        } = declaration;
+
+        let fields = fields.iter().map(|field| {
+            self.node(
+                SyntaxKind::StructField,
+                &field.position,
+                [self.type_name(&field.type_name)],
+            )
+        });
+
+        self.node(SyntaxKind::StructDeclaration, position, fields)
+    }
+
+    fn instance<T: Clone>(&self, instance: &Instance<T>) -> Option<Shape> {
+        let Instance {
+            name,
+            functions,
+            declarations,
+            position,
+            ..
+        } = instance;
+
+        let declarations = declarations.iter().map(
+            |MethodDeclaration {
+                 parameter_types,
+                 return_type,
+                 position,
+                 ..
+             }| {
+                self.extend_end(
+                    self.node(
+                        SyntaxKind::MethodDeclaration,
+                        position,
+                        parameter_types
+                            .iter()
+                            .chain([return_type])
+                            .map(|type_name| self.type_name(type_name)),
+                    ),
+                    SyntaxKind::Semicolon,
+                )
+            },
+        );
+
+        self.node(
+            SyntaxKind::Instance,
+            position,
+            [self.type_name(name)]
+                .into_iter()
+                .chain(functions.iter().map(|function| self.function(function)))
+                .chain(declarations),
+        )
+    }
+
+    fn type_name(&self, type_name: &TypeName) -> Option<Shape> {
+        let children = match type_name {
+            TypeName::Literal(..) => vec![],
+            TypeName::Fn {
+                params,
+                return_type,
+                ..
+            } => params
+                .iter()
+                .chain([return_type.as_ref()])
+                .map(|type_name| self.type_name(type_name))
+                .collect(),
+            TypeName::Tuple(elements, _) => elements
+                .iter()
+                .map(|type_name| self.type_name(type_name))
+                .collect(),
+            TypeName::Array(inner, _)
+            | TypeName::Reference(inner, _)
+            | TypeName::FixedArray(inner, _, _)
+            | TypeName::Optional(inner, _) => {
+                vec![self.type_name(inner)]
+            }
+            TypeName::Result(value, error, _) => {
+                vec![self.type_name(value), self.type_name(error)]
+            }
+        };
+
+        self.node(SyntaxKind::Type, &type_name.position(), children)
+    }
+
+    fn block<T: Clone>(&self, block: &Block<T>) -> Option<Shape> {
+        self.node(
+            SyntaxKind::Block,
+            &block.position,
+            block
+                .statements
+                .iter()
+                .map(|statement| self.statement(statement)),
+        )
+    }
+
+    fn if_expression<T: Clone>(&self, if_expression: &If<T>) -> Option<Shape> {
+        let If {
+            condition,
+            statements,
+            else_statements,
+            position,
+            ..
+        } = if_expression;
+
+        self.node(
+            SyntaxKind::If,
+            position,
+            [self.expression(condition)]
+                .into_iter()
+                .chain(statements.iter().map(|statement| self.statement(statement)))
+                .chain(
+                    else_statements
+                        .iter()
+                        .map(|statement| self.statement(statement)),
+                ),
+        )
+    }
+
+    fn expression<T: Clone>(&self, expression: &Expression<T>) -> Option<Shape> {
+        match expression {
+            Expression::Id(id) => self.node(SyntaxKind::Name, &id.position, []),
+            Expression::Num(_)
+            | Expression::Character(_)
+            | Expression::AstString(_)
+            | Expression::Interpolation(_) => {
+                self.node(SyntaxKind::Literal, &expression.position(), [])
+            }
+            Expression::Function(function) => self.function(function),
+            Expression::Lambda(Lambda {
+                parameters,
+                expression,
+                position,
+                ..
+            }) => {
+                let parameters = parameters.iter().map(|parameter| {
+                    self.node(SyntaxKind::LambdaParameter, &parameter.position, [])
+                });
+                self.node(
+                    SyntaxKind::Lambda,
+                    position,
+                    parameters.chain([self.expression(expression)]),
+                )
+            }
+            Expression::If(if_expression) => self.if_expression(if_expression),
+            Expression::Block(block) => self.block(block),
+            Expression::Parens(inner) => self.extend_end(
+                self.extend_start(
+                    self.node(
+                        SyntaxKind::Parens,
+                        &Span::default(),
+                        [self.expression(inner)],
+                    ),
+                    SyntaxKind::LParen,
+                ),
+                SyntaxKind::RParen,
+            ),
+            Expression::Postfix(postfix) => self.postfix(postfix),
+            Expression::Prefix(prefix) => {
+                let (Prefix::Negation { expr, position } | Prefix::Minus { expr, position }) =
+                    prefix;
+                self.node(SyntaxKind::Prefix, position, [self.expression(expr)])
+            }
+            Expression::Binary(binary) => self.node(
+                SyntaxKind::Binary,
+                &binary.position,
+                [
+                    self.expression(&binary.left),
+                    self.expression(&binary.right),
+                ],
+            ),
+            Expression::Array(Array::Literal {
+                values, position, ..
+            }) => self.node(
+                SyntaxKind::ArrayLiteral,
+                position,
+                values.iter().map(|value| self.expression(value)),
+            ),
+            Expression::Array(Array::Default {
+                initial_value,
+                length,
+                position,
+                ..
+            }) => self.node(
+                SyntaxKind::ArrayDefault,
+                position,
+                [self.expression(initial_value), self.expression(length)],
+            ),
+            Expression::StructInitialisation(StructInitialisation {
+                fields, position, ..
+            }) => {
+                let fields = fields.iter().map(|field| {
+                    self.node(
+                        SyntaxKind::StructFieldInitialisation,
+                        &field.position,
+                        [self.expression(&field.value)],
+                    )
+                });
+                self.node(SyntaxKind::StructInitialisation, position, fields)
+            }
+        }
+    }
+
+    fn postfix<T: Clone>(&self, postfix: &Postfix<T>) -> Option<Shape> {
+        match postfix {
+            Postfix::Call {
+                expr,
+                args,
+                position,
+                ..
+            } => self.node(
+                SyntaxKind::Call,
+                position,
+                [self.expression(expr)]
+                    .into_iter()
+                    .chain(args.iter().map(|arg| self.expression(arg))),
+            ),
+            Postfix::Index {
+                expr,
+                index,
+                position,
+                ..
+            } => self.node(
+                SyntaxKind::Index,
+                position,
+                [self.expression(expr), self.expression(index)],
+            ),
+            Postfix::PropertyAccess {
+                expr,
+                property,
+                position,
+                ..
+            } => self.node(
+                SyntaxKind::PropertyAccess,
+                position,
+                [
+                    self.expression(expr),
+                    self.node(SyntaxKind::Name, &property.position, []),
+                ],
+            ),
+            Postfix::OptionalPropertyAccess {
+                expr,
+                property,
+                position,
+                ..
+            } => self.node(
+                SyntaxKind::OptionalPropertyAccess,
+                position,
+                [
+                    self.expression(expr),
+                    self.node(SyntaxKind::Name, &property.position, []),
+                ],
+            ),
+            Postfix::Propagate { expr, position, .. } => {
+                self.node(SyntaxKind::Propagate, position, [self.expression(expr)])
+            }
+            Postfix::Cast {
+                expr,
+                target,
+                position,
+                ..
+            } => self.node(
+                SyntaxKind::Cast,
+                position,
+                [self.expression(expr), self.type_name(target)],
+            ),
+        }
+    }
+}
+
+/// Sort shapes by their position and drop every shape which overlaps a previous one, so tokens
+/// can be distributed unambiguously.
+fn without_overlaps(mut shapes: Vec<Shape>) -> Vec<Shape> {
+    shapes.sort_by_key(|shape| shape.start);
+
+    let mut result: Vec<Shape> = vec![];
+    for shape in shapes {
+        if result.last().is_none_or(|last| last.end <= shape.start) {
+            result.push(shape);
+        }
+    }
+
+    result
+}
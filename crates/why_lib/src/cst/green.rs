@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use super::SyntaxKind;
+
+/// Immutable token of the green tree. Green elements only know their kind and text, but not
+/// their position, so they can be shared between (versions of) trees.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenToken {
+    kind: SyntaxKind,
+    text: String,
+}
+
+impl GreenToken {
+    pub fn new(kind: SyntaxKind, text: impl Into<String>) -> Self {
+        GreenToken {
+            kind,
+            text: text.into(),
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn width(&self) -> usize {
+        self.text.len()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GreenElement {
+    Node(Arc<GreenNode>),
+    Token(Arc<GreenToken>),
+}
+
+impl GreenElement {
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            GreenElement::Node(node) => node.kind(),
+            GreenElement::Token(token) => token.kind(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.width(),
+            GreenElement::Token(token) => token.width(),
+        }
+    }
+}
+
+impl From<GreenNode> for GreenElement {
+    fn from(value: GreenNode) -> Self {
+        GreenElement::Node(Arc::new(value))
+    }
+}
+
+impl From<GreenToken> for GreenElement {
+    fn from(value: GreenToken) -> Self {
+        GreenElement::Token(Arc::new(value))
+    }
+}
+
+/// Immutable node of the green tree, which knows its width (in bytes) and its children.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GreenNode {
+    kind: SyntaxKind,
+    width: usize,
+    children: Vec<GreenElement>,
+}
+
+impl GreenNode {
+    pub fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> Self {
+        GreenNode {
+            kind,
+            width: children.iter().map(GreenElement::width).sum(),
+            children,
+        }
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.kind
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn children(&self) -> &[GreenElement] {
+        &self.children
+    }
+
+    /// The source text covered by this node.
+    pub fn text(&self) -> String {
+        let mut text = String::with_capacity(self.width);
+        self.write_text(&mut text);
+        text
+    }
+
+    fn write_text(&self, text: &mut String) {
+        for child in &self.children {
+            match child {
+                GreenElement::Node(node) => node.write_text(text),
+                GreenElement::Token(token) => text.push_str(token.text()),
+            }
+        }
+    }
+
+    /// Create a copy of this node where the child at `index` is replaced by `element`. All other
+    /// children are shared with this node.
+    pub fn replace_child(&self, index: usize, element: GreenElement) -> GreenNode {
+        let mut children = self.children.clone();
+        children[index] = element;
+        GreenNode::new(self.kind, children)
+    }
+}
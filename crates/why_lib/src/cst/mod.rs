@@ -0,0 +1,387 @@
+//! Lossless concrete syntax tree (CST) of a source file.
+//!
+//! The AST drops whitespace and only keeps comments as statements, so it cannot be used to
+//! rewrite source code without losing its layout. The CST contains every byte of the source:
+//! every token (including whitespace and comments, the so called trivia) is a leaf of the tree,
+//! and the nodes above the tokens mirror the structure of the AST.
+//!
+//! The tree is split into two layers (like Roslyn or rust-analyzer): the immutable green tree
+//! only knows kinds, texts and widths and can be shared between versions of a tree, while the red
+//! tree ([SyntaxNode], [SyntaxToken]) adds parents and absolute positions on top of it. Edits
+//! create a new tree which shares all untouched green nodes with the old one.
+//!
+//! Whitespace and comments between the children of a node belong to that node, i.e., leading
+//! comments of a statement are siblings of the statement within its parent.
+
+mod build;
+mod green;
+mod red;
+
+pub use self::green::*;
+pub use self::red::*;
+
+use std::{error::Error, fmt::Display};
+
+use crate::{
+    diagnostic::Diagnostic,
+    lexer::{LexError, Lexer, Token},
+    parser::{ast::TopLevelStatement, parse, ParseError},
+    source_map::{FileId, SourceMap},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SyntaxKind {
+    // tokens
+    Whitespace,
+    Comment,
+    Assign,
+    Let,
+    Const,
+    Mut,
+    Id,
+    Integer,
+    FloatingPoint,
+    Character,
+    String,
+    Semicolon,
+    Plus,
+    Minus,
+    Times,
+    Slash,
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    LBracket,
+    RBracket,
+    FnKeyword,
+    IfKeyword,
+    ElseKeyword,
+    WhileKeyword,
+    ReturnKeyword,
+    Colon,
+    Comma,
+    Dot,
+    SmallRightArrow,
+    BigRightArrow,
+    Backslash,
+    Equal,
+    NotEqual,
+    GreaterThan,
+    LessThan,
+    GreaterOrEqual,
+    LessOrEqual,
+    Ampersand,
+    DeclareKeyword,
+    StructKeyword,
+    ClassKeyword,
+    InstanceKeyword,
+    ExclamationMark,
+    Hash,
+    AsKeyword,
+    QuestionMark,
+    QuestionDot,
+    DoubleQuestionMark,
+
+    // nodes
+    Root,
+    Function,
+    Parameter,
+    Constant,
+    Declaration,
+    StructDeclaration,
+    StructField,
+    Instance,
+    MethodDeclaration,
+    Initialisation,
+    Assignment,
+    ExpressionStatement,
+    Return,
+    WhileLoop,
+    Type,
+    Name,
+    Literal,
+    Lambda,
+    LambdaParameter,
+    If,
+    Block,
+    Parens,
+    Call,
+    Index,
+    PropertyAccess,
+    OptionalPropertyAccess,
+    Propagate,
+    Cast,
+    Prefix,
+    Binary,
+    ArrayLiteral,
+    ArrayDefault,
+    StructInitialisation,
+    StructFieldInitialisation,
+}
+
+impl SyntaxKind {
+    /// Whether tokens of this kind carry no meaning for the parser.
+    pub fn is_trivia(&self) -> bool {
+        matches!(self, SyntaxKind::Whitespace | SyntaxKind::Comment)
+    }
+
+    pub fn is_token(&self) -> bool {
+        *self < SyntaxKind::Root
+    }
+}
+
+impl From<&Token> for SyntaxKind {
+    fn from(value: &Token) -> Self {
+        match value {
+            Token::Assign { .. } => SyntaxKind::Assign,
+            Token::Let { .. } => SyntaxKind::Let,
+            Token::Const { .. } => SyntaxKind::Const,
+            Token::Mut { .. } => SyntaxKind::Mut,
+            Token::Id { .. } => SyntaxKind::Id,
+            Token::Integer { .. } => SyntaxKind::Integer,
+            Token::FloatingPoint { .. } => SyntaxKind::FloatingPoint,
+            Token::Character { .. } => SyntaxKind::Character,
+            Token::String { .. } => SyntaxKind::String,
+            Token::Semicolon { .. } => SyntaxKind::Semicolon,
+            Token::Comment { .. } => SyntaxKind::Comment,
+            Token::Plus { .. } => SyntaxKind::Plus,
+            Token::Minus { .. } => SyntaxKind::Minus,
+            Token::Times { .. } => SyntaxKind::Times,
+            Token::Slash { .. } => SyntaxKind::Slash,
+            Token::LParen { .. } => SyntaxKind::LParen,
+            Token::RParen { .. } => SyntaxKind::RParen,
+            Token::LBrace { .. } => SyntaxKind::LBrace,
+            Token::RBrace { .. } => SyntaxKind::RBrace,
+            Token::LBracket { .. } => SyntaxKind::LBracket,
+            Token::RBracket { .. } => SyntaxKind::RBracket,
+            Token::FnKeyword { .. } => SyntaxKind::FnKeyword,
+            Token::IfKeyword { .. } => SyntaxKind::IfKeyword,
+            Token::ElseKeyword { .. } => SyntaxKind::ElseKeyword,
+            Token::WhileKeyword { .. } => SyntaxKind::WhileKeyword,
+            Token::ReturnKeyword { .. } => SyntaxKind::ReturnKeyword,
+            Token::Colon { .. } => SyntaxKind::Colon,
+            Token::Comma { .. } => SyntaxKind::Comma,
+            Token::Dot { .. } => SyntaxKind::Dot,
+            Token::SmallRightArrow { .. } => SyntaxKind::SmallRightArrow,
+            Token::BigRightArrow { .. } => SyntaxKind::BigRightArrow,
+            Token::Backslash { .. } => SyntaxKind::Backslash,
+            Token::Equal { .. } => SyntaxKind::Equal,
+            Token::NotEqual { .. } => SyntaxKind::NotEqual,
+            Token::GreaterThan { .. } => SyntaxKind::GreaterThan,
+            Token::LessThan { .. } => SyntaxKind::LessThan,
+            Token::GreaterOrEqual { .. } => SyntaxKind::GreaterOrEqual,
+            Token::LessOrEqual { .. } => SyntaxKind::LessOrEqual,
+            Token::Ampersand { .. } => SyntaxKind::Ampersand,
+            Token::DeclareKeyword { .. } => SyntaxKind::DeclareKeyword,
+            Token::StructKeyword { .. } => SyntaxKind::StructKeyword,
+            Token::ClassKeyword { .. } => SyntaxKind::ClassKeyword,
+            Token::InstanceKeyword { .. } => SyntaxKind::InstanceKeyword,
+            Token::ExclamationMark { .. } => SyntaxKind::ExclamationMark,
+            Token::Hash { .. } => SyntaxKind::Hash,
+            Token::AsKeyword { .. } => SyntaxKind::AsKeyword,
+            Token::QuestionMark { .. } => SyntaxKind::QuestionMark,
+            Token::QuestionDot { .. } => SyntaxKind::QuestionDot,
+            Token::DoubleQuestionMark { .. } => SyntaxKind::DoubleQuestionMark,
+        }
+    }
+}
+
+/// Result of parsing a file: the AST and the lossless syntax tree of the same source.
+#[derive(Debug, Clone)]
+pub struct Parse {
+    pub statements: Vec<TopLevelStatement<()>>,
+    pub syntax: SyntaxNode,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxError {
+    Lex(LexError),
+    Parse(ParseError),
+}
+
+impl SyntaxError {
+    pub fn diagnostic(&self) -> Diagnostic {
+        match self {
+            SyntaxError::Lex(e) => e.diagnostic(),
+            SyntaxError::Parse(e) => e.diagnostic(),
+        }
+    }
+}
+
+impl Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyntaxError::Lex(e) => e.fmt(f),
+            SyntaxError::Parse(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for SyntaxError {}
+
+/// Lex and parse a file of the source map and build its syntax tree alongside the AST.
+pub fn parse_file(file: FileId) -> Result<Parse, SyntaxError> {
+    let tokens = Lexer::for_file(file).lex().map_err(SyntaxError::Lex)?;
+    let statements = parse(&mut tokens.clone().into()).map_err(SyntaxError::Parse)?;
+    let syntax = build(file, &tokens, &statements);
+
+    Ok(Parse { statements, syntax })
+}
+
+/// Build the syntax tree of a file from its tokens and its AST.
+pub fn build<T: Clone>(
+    file: FileId,
+    tokens: &[Token],
+    statements: &[TopLevelStatement<T>],
+) -> SyntaxNode {
+    let source = SourceMap::get(file).expect("file should be part of the source map");
+    let green = build::Builder::new(source.source(), tokens).build(statements);
+
+    SyntaxNode::new_root(green, file)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::lexer::Span;
+
+    fn parse_source(source: &str) -> Parse {
+        parse_file(SourceMap::add(source)).expect("source should parse")
+    }
+
+    #[test]
+    fn test_lossless_examples() {
+        for entry in fs::read_dir("../../examples").unwrap() {
+            let path = entry.unwrap().path();
+            let source = fs::read_to_string(&path).unwrap();
+
+            // not every example is a valid program
+            let Ok(parse) = parse_file(SourceMap::add(source.as_str())) else {
+                continue;
+            };
+            assert_eq!(parse.syntax.text(), source, "{}", path.display());
+        }
+    }
+
+    #[test]
+    fn test_lossless_attributes() {
+        let source = "// adds\n#[test]\nfn adds(): void {\n    assert(true);\n}\n";
+        let parse = parse_source(source);
+
+        assert_eq!(parse.syntax.text(), source);
+        assert_eq!(parse.syntax.children()[0].kind(), SyntaxKind::Function);
+        assert_eq!(
+            parse.syntax.children()[0].text(),
+            "#[test]\nfn adds(): void {\n    assert(true);\n}"
+        );
+    }
+
+    #[test]
+    fn test_lossless_trivia() {
+        let source =
+            "  // leading\r\nfn main(): void {\r\n\t// inner\r\n  let x  =  ( 1 +2 ) ;  }\n\n";
+        let parse = parse_source(source);
+
+        assert_eq!(parse.syntax.text(), source);
+        assert_eq!(
+            parse.syntax.span(),
+            Span::new(parse.syntax.span().file, 0, source.len())
+        );
+    }
+
+    #[test]
+    fn test_structure() {
+        let parse = parse_source("// adds\nfn add(x: i64): i64 {\n    // result\n    (x + 1)\n}");
+        let root = &parse.syntax;
+
+        // the leading comment belongs to the root, the inner one to the function
+        let comments = root
+            .tokens()
+            .into_iter()
+            .filter(|token| token.kind() == SyntaxKind::Comment)
+            .map(|token| (token.parent().kind(), token.text().to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            comments,
+            vec![
+                (SyntaxKind::Root, "// adds".to_string()),
+                (SyntaxKind::Function, "// result".to_string())
+            ]
+        );
+
+        let kinds = root
+            .descendants()
+            .iter()
+            .map(SyntaxNode::kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                SyntaxKind::Root,
+                SyntaxKind::Function,
+                SyntaxKind::Parameter,
+                SyntaxKind::Type,
+                SyntaxKind::Type,
+                SyntaxKind::Parens,
+                SyntaxKind::Binary,
+                SyntaxKind::Name,
+                SyntaxKind::Literal,
+            ]
+        );
+
+        let function = &root.children()[0];
+        assert_eq!(
+            function.text(),
+            "fn add(x: i64): i64 {\n    // result\n    (x + 1)\n}"
+        );
+        assert_eq!(
+            function.children()[2].text(),
+            "(x + 1)",
+            "parens should include the parentheses"
+        );
+    }
+
+    #[test]
+    fn test_token_at_offset() {
+        let parse = parse_source("fn main(): void {\n    let foo = 42;\n}");
+
+        let token = parse.syntax.token_at_offset(27).unwrap();
+        assert_eq!(token.kind(), SyntaxKind::Id);
+        assert_eq!(token.text(), "foo");
+        assert_eq!(token.parent().kind(), SyntaxKind::Initialisation);
+
+        let token = parse.syntax.token_at_offset(20).unwrap();
+        assert!(token.is_trivia());
+        assert!(parse.syntax.token_at_offset(100).is_none());
+    }
+
+    #[test]
+    fn test_replace_token() {
+        let source = "// first\nfn foo(): i64 {\n    42\n}\n\n// second\nfn bar(): i64 {\n    1\n}";
+        let parse = parse_source(source);
+
+        let name = parse
+            .syntax
+            .tokens()
+            .into_iter()
+            .find(|token| token.text() == "bar")
+            .unwrap();
+        let edited = name.replace_text("baz");
+
+        assert_eq!(edited.text(), source.replace("bar", "baz"));
+        assert_eq!(
+            parse.syntax.text(),
+            source,
+            "the old tree should not change"
+        );
+
+        // untouched nodes are shared between both trees
+        let old = parse.syntax.children();
+        let new = edited.children();
+        assert!(std::ptr::eq(old[0].green(), new[0].green()));
+        assert!(!std::ptr::eq(old[1].green(), new[1].green()));
+    }
+}
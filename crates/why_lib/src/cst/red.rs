@@ -0,0 +1,258 @@
+use std::{fmt::Debug, sync::Arc};
+
+use crate::{lexer::Span, source_map::FileId};
+
+use super::{
+    green::{GreenElement, GreenNode, GreenToken},
+    SyntaxKind,
+};
+
+/// Node of the red tree, i.e., a green node together with its absolute position and its parent.
+/// Red nodes are created on demand while navigating the tree and are cheap to clone.
+#[derive(Clone)]
+pub struct SyntaxNode(Arc<NodeData>);
+
+struct NodeData {
+    green: Arc<GreenNode>,
+    parent: Option<SyntaxNode>,
+    /// Index of this node within the children of its parent.
+    index: usize,
+    /// Byte offset of this node within its file.
+    offset: usize,
+    file: FileId,
+}
+
+/// Token of the red tree, i.e., a green token together with its absolute position and parent.
+#[derive(Clone)]
+pub struct SyntaxToken {
+    green: Arc<GreenToken>,
+    parent: SyntaxNode,
+    index: usize,
+    offset: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(SyntaxToken),
+}
+
+impl SyntaxNode {
+    /// Create the root of a red tree for the given file.
+    pub fn new_root(green: GreenNode, file: FileId) -> Self {
+        SyntaxNode(Arc::new(NodeData {
+            green: Arc::new(green),
+            parent: None,
+            index: 0,
+            offset: 0,
+            file,
+        }))
+    }
+
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.green.kind()
+    }
+
+    pub fn green(&self) -> &GreenNode {
+        &self.0.green
+    }
+
+    pub fn parent(&self) -> Option<SyntaxNode> {
+        self.0.parent.clone()
+    }
+
+    pub fn span(&self) -> Span {
+        Span::new(
+            self.0.file,
+            self.0.offset,
+            self.0.offset + self.0.green.width(),
+        )
+    }
+
+    pub fn text(&self) -> String {
+        self.0.green.text()
+    }
+
+    /// All direct children, including tokens.
+    pub fn children_with_tokens(&self) -> Vec<SyntaxElement> {
+        let mut offset = self.0.offset;
+
+        self.0
+            .green
+            .children()
+            .iter()
+            .enumerate()
+            .map(|(index, child)| {
+                let element = match child {
+                    GreenElement::Node(green) => {
+                        SyntaxElement::Node(SyntaxNode(Arc::new(NodeData {
+                            green: green.clone(),
+                            parent: Some(self.clone()),
+                            index,
+                            offset,
+                            file: self.0.file,
+                        })))
+                    }
+                    GreenElement::Token(green) => SyntaxElement::Token(SyntaxToken {
+                        green: green.clone(),
+                        parent: self.clone(),
+                        index,
+                        offset,
+                    }),
+                };
+                offset += child.width();
+                element
+            })
+            .collect()
+    }
+
+    /// All direct child nodes.
+    pub fn children(&self) -> Vec<SyntaxNode> {
+        self.children_with_tokens()
+            .into_iter()
+            .filter_map(|element| match element {
+                SyntaxElement::Node(node) => Some(node),
+                SyntaxElement::Token(_) => None,
+            })
+            .collect()
+    }
+
+    /// This node and all nodes below it, in preorder.
+    pub fn descendants(&self) -> Vec<SyntaxNode> {
+        let mut nodes = vec![self.clone()];
+        for child in self.children() {
+            nodes.extend(child.descendants());
+        }
+        nodes
+    }
+
+    /// All tokens below this node (including trivia), in source order.
+    pub fn tokens(&self) -> Vec<SyntaxToken> {
+        self.children_with_tokens()
+            .into_iter()
+            .flat_map(|element| match element {
+                SyntaxElement::Node(node) => node.tokens(),
+                SyntaxElement::Token(token) => vec![token],
+            })
+            .collect()
+    }
+
+    /// The token containing the given byte offset (if there is any).
+    pub fn token_at_offset(&self, offset: usize) -> Option<SyntaxToken> {
+        for element in self.children_with_tokens() {
+            match element {
+                SyntaxElement::Node(node)
+                    if node.span().start <= offset && offset < node.span().end =>
+                {
+                    return node.token_at_offset(offset);
+                }
+                SyntaxElement::Token(token)
+                    if token.span().start <= offset && offset < token.span().end =>
+                {
+                    return Some(token);
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    /// The root of the tree this node belongs to.
+    pub fn root(&self) -> SyntaxNode {
+        match self.parent() {
+            Some(parent) => parent.root(),
+            None => self.clone(),
+        }
+    }
+
+    /// Create a new tree where this node is replaced by `green`. Returns the root of the new
+    /// tree, this tree stays unchanged.
+    pub fn replace_with(&self, green: GreenNode) -> SyntaxNode {
+        self.replace_green(GreenElement::Node(Arc::new(green)))
+    }
+
+    fn replace_green(&self, green: GreenElement) -> SyntaxNode {
+        match (&self.0.parent, green) {
+            (Some(parent), green) => {
+                parent.replace_with(parent.green().replace_child(self.0.index, green))
+            }
+            (None, GreenElement::Node(green)) => SyntaxNode(Arc::new(NodeData {
+                green,
+                parent: None,
+                index: 0,
+                offset: 0,
+                file: self.0.file,
+            })),
+            (None, GreenElement::Token(_)) => unreachable!("the root is always a node"),
+        }
+    }
+}
+
+impl PartialEq for SyntaxNode {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0.green, &other.0.green) && self.0.offset == other.0.offset
+    }
+}
+
+impl Eq for SyntaxNode {}
+
+impl Debug for SyntaxNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Span { start, end, .. } = self.span();
+        write!(f, "{:?}@{start}..{end}", self.kind())
+    }
+}
+
+impl SyntaxToken {
+    pub fn kind(&self) -> SyntaxKind {
+        self.green.kind()
+    }
+
+    pub fn text(&self) -> &str {
+        self.green.text()
+    }
+
+    pub fn parent(&self) -> SyntaxNode {
+        self.parent.clone()
+    }
+
+    pub fn span(&self) -> Span {
+        Span::new(
+            self.parent.0.file,
+            self.offset,
+            self.offset + self.green.width(),
+        )
+    }
+
+    /// Whether this token carries no meaning for the parser (whitespace and comments).
+    pub fn is_trivia(&self) -> bool {
+        self.kind().is_trivia()
+    }
+
+    /// Create a new tree where the text of this token is replaced. Returns the root of the new
+    /// tree, this tree stays unchanged.
+    pub fn replace_text(&self, text: impl Into<String>) -> SyntaxNode {
+        let green = GreenToken::new(self.kind(), text);
+        self.parent.replace_with(
+            self.parent
+                .green()
+                .replace_child(self.index, GreenElement::Token(Arc::new(green))),
+        )
+    }
+}
+
+impl PartialEq for SyntaxToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.green, &other.green) && self.offset == other.offset
+    }
+}
+
+impl Eq for SyntaxToken {}
+
+impl Debug for SyntaxToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Span { start, end, .. } = self.span();
+        write!(f, "{:?}@{start}..{end} {:?}", self.kind(), self.text())
+    }
+}
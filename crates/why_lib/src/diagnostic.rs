@@ -0,0 +1,421 @@
+//! Diagnostics reported by the different stages of the compiler and their rendering for humans.
+
+use std::{collections::BTreeSet, fmt::Display, path::Path};
+
+use colored::{ColoredString, Colorize};
+use unicode_width::UnicodeWidthChar;
+
+use crate::lexer::Span;
+
+/// Number of columns a tab is expanded to.
+const TAB_WIDTH: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => f.write_str("error"),
+            Severity::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+/// A span of source code pointed at by a diagnostic. Primary labels mark the location of the
+/// problem itself, secondary labels add related locations (e.g., a declaration).
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Label {
+    pub span: Span,
+    pub message: Option<String>,
+    pub primary: bool,
+}
+
+/// A change to the source code, replacing everything within `span` with `replacement`. Empty
+/// spans denote insertions.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Fix {
+    /// Short description of the change.
+    pub message: String,
+    pub span: Span,
+    pub replacement: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Stable code identifying the kind of this diagnostic.
+    pub code: &'static str,
+    pub message: String,
+    pub labels: Vec<Label>,
+    pub notes: Vec<String>,
+    pub suggestions: Vec<Fix>,
+}
+
+impl Diagnostic {
+    pub fn error(code: &'static str, message: impl ToString) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code,
+            message: message.to_string(),
+            labels: vec![],
+            notes: vec![],
+            suggestions: vec![],
+        }
+    }
+
+    pub fn warning(code: &'static str, message: impl ToString) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Self::error(code, message)
+        }
+    }
+
+    pub fn with_primary(mut self, span: Span, message: Option<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message,
+            primary: true,
+        });
+        self
+    }
+
+    pub fn with_secondary(mut self, span: Span, message: impl ToString) -> Self {
+        self.labels.push(Label {
+            span,
+            message: Some(message.to_string()),
+            primary: false,
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl ToString) -> Self {
+        self.notes.push(note.to_string());
+        self
+    }
+
+    pub fn with_suggestion(mut self, fix: Option<Fix>) -> Self {
+        self.suggestions.extend(fix);
+        self
+    }
+
+    /// The span of the first primary label (if there is any).
+    pub fn primary_span(&self) -> Option<&Span> {
+        self.labels
+            .iter()
+            .find(|label| label.primary)
+            .map(|label| &label.span)
+    }
+
+    /// Render this diagnostic for humans. The location is prefixed with `file`, if given.
+    pub fn render(&self, file: Option<&Path>) -> String {
+        let severity = match self.severity {
+            Severity::Error => self.severity.to_string().red().bold(),
+            Severity::Warning => self.severity.to_string().yellow().bold(),
+        };
+
+        let mut output = format!("{severity}[{}]: {}", self.code, self.message.bold());
+
+        let labels = self.visible_labels();
+        let gutter = Self::gutter_width(&labels);
+        let padding = " ".repeat(gutter);
+
+        if let Some(Label { span, .. }) =
+            labels.iter().find(|label| label.primary).or(labels.first())
+        {
+            let source = span
+                .source_file()
+                .expect("visible labels should have a source");
+            let (line, column) = span.start_chars();
+            let column = column + 1;
+            let location = match file.map(|file| file.display().to_string()) {
+                Some(file) => format!("{file}:{}:{column}", line + 1),
+                None => match source.name() {
+                    Some(name) => format!("{name}:{}:{column}", line + 1),
+                    None => format!("{}:{column}", line + 1),
+                },
+            };
+            output.push_str(&format!("\n{padding}{} {location}", "-->".blue().bold()));
+        }
+
+        output.push_str(&self.snippet(&labels, gutter));
+
+        for note in &self.notes {
+            output.push_str(&format!("\n{padding} = {}: {note}", "note".bold()));
+        }
+
+        for Fix {
+            message,
+            replacement,
+            ..
+        } in &self.suggestions
+        {
+            output.push_str(&format!("\n{padding} = {}: {message}", "help".bold()));
+            if !replacement.trim().is_empty() && !replacement.contains('\n') {
+                output.push_str(&format!(": `{replacement}`"));
+            }
+        }
+
+        output
+    }
+
+    /// Labels which point into actual source code. Only labels within the file of the first
+    /// such label are shown.
+    fn visible_labels(&self) -> Vec<&Label> {
+        let mut labels = self
+            .labels
+            .iter()
+            .filter(|label| label.span.source_file().is_some());
+
+        let Some(first) = labels.next() else {
+            return vec![];
+        };
+
+        std::iter::once(first)
+            .chain(labels.filter(|label| label.span.file == first.span.file))
+            .collect()
+    }
+
+    fn gutter_width(labels: &[&Label]) -> usize {
+        labels
+            .iter()
+            .map(|label| (label.span.end_line_col().0 + 1).to_string().len())
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Render the source lines covered by the labels, underlining the labelled parts of them.
+    fn snippet(&self, labels: &[&Label], gutter: usize) -> String {
+        let Some(first) = labels.first() else {
+            return String::new();
+        };
+
+        let source = first
+            .span
+            .source_file()
+            .expect("visible labels should have a source");
+        let padding = " ".repeat(gutter);
+        let bar = "|".blue().bold();
+
+        let lines = labels
+            .iter()
+            // only the first and the last line of labels spanning multiple lines are shown
+            .flat_map(|label| {
+                let (start, end) = (label.span.start_line_col().0, label.span.end_line_col().0);
+                [start, end.max(start)]
+            })
+            .collect::<BTreeSet<_>>();
+
+        let mut output = format!("\n{padding} {bar}");
+        let mut previous = None;
+
+        for line in lines {
+            if previous.is_some_and(|previous| line > previous + 1) {
+                output.push_str(&format!("\n{}", "...".blue().bold()));
+            }
+            previous = Some(line);
+
+            let text = source.line(line);
+            let number = format!("{:>gutter$}", line + 1).blue().bold();
+            output.push_str(&format!("\n{number} {bar}"));
+            if !text.is_empty() {
+                output.push_str(&format!(" {}", expand_tabs(text)));
+            }
+
+            let mut on_line = labels
+                .iter()
+                .filter(|label| {
+                    label.span.start_line_col().0 == line || label.span.end_line_col().0 == line
+                })
+                .collect::<Vec<_>>();
+            on_line.sort_by_key(|label| {
+                let (start_line, start_col) = label.span.start_line_col();
+                (start_line == line, start_col)
+            });
+
+            for label in on_line {
+                output.push_str(&format!(
+                    "\n{padding} {bar} {}",
+                    self.underline(label, line, text)
+                ));
+            }
+        }
+
+        output.push_str(&format!("\n{padding} {bar}"));
+        output
+    }
+
+    /// Underline the part of a line covered by a label. The message of the label is placed
+    /// behind the last line of the label.
+    fn underline(&self, label: &Label, line: usize, text: &str) -> String {
+        let (start, end) = (label.span.start_line_col(), label.span.end_line_col());
+
+        let from = if start.0 == line {
+            start.1
+        } else {
+            text.len() - text.trim_start().len()
+        };
+        let to = if end.0 == line { end.1 } else { text.len() };
+
+        let from = display_width(text, from);
+        let to = display_width(text, to).max(from + 1);
+
+        let marker = if label.primary { "^" } else { "-" };
+        let mut underline = marker.repeat(to - from);
+        if let (true, Some(message)) = (end.0 == line, &label.message) {
+            underline.push(' ');
+            underline.push_str(message);
+        }
+
+        format!(
+            "{}{}",
+            " ".repeat(from),
+            self.colorize(&underline, label.primary)
+        )
+    }
+
+    fn colorize(&self, text: &str, primary: bool) -> ColoredString {
+        match (primary, self.severity) {
+            (true, Severity::Error) => text.red().bold(),
+            (true, Severity::Warning) => text.yellow().bold(),
+            (false, _) => text.blue().bold(),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(None))
+    }
+}
+
+/// Enable or disable colors in all diagnostics rendered from now on.
+pub fn set_colored(colored: bool) {
+    colored::control::set_override(colored);
+}
+
+/// Render the source lines of a single span, labelled with the given message.
+pub(crate) fn render_span(span: &Span, message: impl ToString) -> String {
+    let diagnostic =
+        Diagnostic::error("", "").with_primary(span.clone(), Some(message.to_string()));
+    let labels = diagnostic.visible_labels();
+
+    diagnostic.snippet(&labels, Diagnostic::gutter_width(&labels))
+}
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+/// Number of columns the first `byte` bytes of a line occupy on a terminal.
+fn display_width(text: &str, byte: usize) -> usize {
+    let mut byte = byte.min(text.len());
+    while !text.is_char_boundary(byte) {
+        byte -= 1;
+    }
+
+    text[..byte]
+        .chars()
+        .map(|c| match c {
+            '\t' => TAB_WIDTH,
+            c => c.width().unwrap_or(0),
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::source_map::{FileId, SourceMap};
+
+    use super::*;
+
+    fn span(
+        file: FileId,
+        (start_line, start_col): (usize, usize),
+        (end_line, end_col): (usize, usize),
+    ) -> Span {
+        let source = SourceMap::get(file).unwrap();
+        Span::new(
+            file,
+            source.offset(start_line, start_col),
+            source.offset(end_line, end_col),
+        )
+    }
+
+    #[test]
+    fn test_render_labels() {
+        colored::control::set_override(false);
+
+        let source = SourceMap::add("fn main(): void {\n    let x = 42;\n    x = 43;\n}");
+        let diagnostic = Diagnostic::error("E0011", "Can not reassign immutable variable 'x'")
+            .with_primary(span(source, (2, 4), (2, 10)), Some("assigned here".into()))
+            .with_secondary(span(source, (1, 4), (1, 15)), "declared here")
+            .with_note("variables are immutable by default")
+            .with_suggestion(Some(Fix {
+                message: "Make 'x' mutable".into(),
+                span: span(source, (1, 4), (1, 7)),
+                replacement: "let mut".into(),
+            }));
+
+        assert_eq!(
+            diagnostic.render(Some(Path::new("main.why"))),
+            "error[E0011]: Can not reassign immutable variable 'x'
+ --> main.why:3:5
+  |
+2 |     let x = 42;
+  |     ----------- declared here
+3 |     x = 43;
+  |     ^^^^^^ assigned here
+  |
+  = note: variables are immutable by default
+  = help: Make 'x' mutable: `let mut`"
+        );
+    }
+
+    #[test]
+    fn test_render_tabs_and_unicode() {
+        colored::control::set_override(false);
+
+        let source = SourceMap::add("\tlet ü = 'ä' + 1;");
+        let diagnostic = Diagnostic::warning("W0001", "Something is odd")
+            .with_primary(span(source, (0, 10), (0, 18)), None);
+
+        assert_eq!(
+            diagnostic.render(None),
+            "warning[W0001]: Something is odd
+ --> 1:10
+  |
+1 |     let ü = 'ä' + 1;
+  |             ^^^^^^^
+  |"
+        );
+    }
+
+    #[test]
+    fn test_render_without_source() {
+        colored::control::set_override(false);
+
+        let diagnostic = Diagnostic::error("E0012", "Missing main function!")
+            .with_primary(Span::default(), None);
+
+        assert_eq!(
+            diagnostic.render(None),
+            "error[E0012]: Missing main function!"
+        );
+    }
+
+    #[test]
+    fn test_render_span_line_numbers() {
+        colored::control::set_override(false);
+
+        let source = SourceMap::add("fn main(): void {\n    foo\n}");
+
+        assert_eq!(
+            render_span(&span(source, (1, 4), (1, 7)), "undefined"),
+            "\n  |\n2 |     foo\n  |     ^^^ undefined\n  |"
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use crate::parser::ast::*;
+
+use super::Formatter;
+
+impl Formatter {
+    /// Render an expression starting at `column` within a statement of the given indentation.
+    pub(super) fn expression(
+        &mut self,
+        expression: &Expression<()>,
+        indent: usize,
+        column: usize,
+    ) -> String {
+        match expression {
+            Expression::Id(id) => id.name.clone(),
+            Expression::Num(num) => Self::num(num),
+            Expression::Character(Character { character, .. }) => format!("'{character}'"),
+            Expression::AstString(AstString { value, .. }) => {
+                format!("\"{}\"", Self::escape_braces(value))
+            }
+            Expression::Interpolation(Interpolation { segments, .. }) => {
+                let mut result = String::from("\"");
+                for segment in segments {
+                    match segment {
+                        InterpolationSegment::Text(text) => {
+                            result.push_str(&Self::escape_braces(text))
+                        }
+                        InterpolationSegment::Expression(expression) => {
+                            // strings can not span multiple lines
+                            let flat = std::mem::replace(&mut self.flat, true);
+                            let column = self.after(column, &result) + 1;
+                            let expression = self.expression(expression, indent, column);
+                            self.flat = flat;
+                            result.push_str(&format!("{{{expression}}}"));
+                        }
+                    }
+                }
+                result.push('"');
+                result
+            }
+            Expression::Function(function) => self.function(function, indent, column),
+            Expression::Lambda(Lambda {
+                parameters,
+                expression,
+                ..
+            }) => {
+                let parameters = parameters
+                    .iter()
+                    .map(|parameter| parameter.name.name.clone())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let head = format!("\\({parameters}) => ");
+                let column = self.after(column, &head);
+                format!("{head}{}", self.expression(expression, indent, column))
+            }
+            Expression::If(if_expression) => self.if_expression(if_expression, indent, column),
+            Expression::Block(Block { statements, .. }) => self.block(statements, indent),
+            Expression::Parens(inner) => {
+                format!("({})", self.expression(inner, indent, column + 1))
+            }
+            Expression::Postfix(postfix) => self.postfix(postfix, indent, column),
+            Expression::Prefix(Prefix::Negation { expr, .. }) => {
+                format!("!{}", self.expression(expr, indent, column + 1))
+            }
+            Expression::Prefix(Prefix::Minus { expr, .. }) => {
+                format!("-{}", self.expression(expr, indent, column + 1))
+            }
+            Expression::Binary(binary) => {
+                let BinaryExpression {
+                    left,
+                    right,
+                    operator,
+                    ..
+                } = binary.as_ref();
+
+                let left = format!("{} {operator} ", self.expression(left, indent, column));
+                let column = self.after(column, &left);
+                format!("{left}{}", self.expression(right, indent, column))
+            }
+            Expression::Array(Array::Literal { values, .. }) => {
+                self.list(values, ("[", "]"), indent, column, Self::expression)
+            }
+            Expression::Array(Array::Default {
+                initial_value,
+                length,
+                ..
+            }) => {
+                let initial_value = self.expression(initial_value, indent, column + 1);
+                let column = self.after(column, &format!("[{initial_value}; "));
+                format!(
+                    "[{initial_value}; {}]",
+                    self.expression(length, indent, column)
+                )
+            }
+            Expression::StructInitialisation(StructInitialisation { id, fields, .. }) => {
+                let head = format!("{} ", id.name);
+                if fields.is_empty() {
+                    return format!("{head}{{}}");
+                }
+
+                let fields = self.list(
+                    fields,
+                    ("{ ", " }"),
+                    indent,
+                    self.after(column, &head),
+                    |formatter, StructFieldInitialisation { name, value, .. }, indent, column| {
+                        let name = format!("{}: ", name.name);
+                        let column = formatter.after(column, &name);
+                        format!("{name}{}", formatter.expression(value, indent, column))
+                    },
+                );
+
+                format!("{head}{}", Self::trim_braces(fields))
+            }
+        }
+    }
+
+    pub(super) fn postfix(
+        &mut self,
+        postfix: &Postfix<()>,
+        indent: usize,
+        column: usize,
+    ) -> String {
+        match postfix {
+            Postfix::Call { expr, args, .. } => {
+                let callee = self.expression(expr, indent, column);
+                let column = self.after(column, &callee);
+                let args = self.list(args, ("(", ")"), indent, column, Self::expression);
+                format!("{callee}{args}")
+            }
+            Postfix::Index { expr, index, .. } => {
+                let expr = self.expression(expr, indent, column);
+                let column = self.after(column, &expr) + 1;
+                format!("{expr}[{}]", self.expression(index, indent, column))
+            }
+            Postfix::PropertyAccess { expr, property, .. } => {
+                format!(
+                    "{}.{}",
+                    self.expression(expr, indent, column),
+                    property.name
+                )
+            }
+            Postfix::OptionalPropertyAccess { expr, property, .. } => {
+                format!(
+                    "{}?.{}",
+                    self.expression(expr, indent, column),
+                    property.name
+                )
+            }
+            Postfix::Propagate { expr, .. } => {
+                format!("{}?", self.expression(expr, indent, column))
+            }
+            Postfix::Cast { expr, target, .. } => {
+                format!("{} as {target}", self.expression(expr, indent, column))
+            }
+        }
+    }
+
+    pub(super) fn if_expression(
+        &mut self,
+        If {
+            condition,
+            statements,
+            else_statements,
+            ..
+        }: &If<()>,
+        indent: usize,
+        column: usize,
+    ) -> String {
+        let condition = self.expression(condition, indent, column + "if (".len());
+        let mut rendered = format!("if ({condition}) {}", self.block(statements, indent));
+
+        if !else_statements.is_empty() {
+            rendered.push_str(" else ");
+            rendered.push_str(&self.block(else_statements, indent));
+        }
+
+        rendered
+    }
+
+    /// Braces within strings have to be written twice, since they would start an interpolation.
+    fn escape_braces(text: &str) -> String {
+        text.replace('{', "{{").replace('}', "}}")
+    }
+
+    fn num(num: &Num<()>) -> String {
+        match num {
+            Num::Integer(value, ..) => value.to_string(),
+            Num::FloatingPoint(value, ..) => {
+                let value = value.to_string();
+                // floating point literals always need a fractional part
+                if value.contains('.') {
+                    value
+                } else {
+                    format!("{value}.0")
+                }
+            }
+        }
+    }
+
+    /// Broken struct initialisations put their fields on separate lines, so the padding inside
+    /// of the braces is not needed.
+    fn trim_braces(fields: String) -> String {
+        match fields.strip_prefix("{ \n") {
+            Some(rest) => {
+                let rest = rest.strip_suffix(" }").unwrap_or(rest);
+                format!("{{\n{rest}}}")
+            }
+            None => fields,
+        }
+    }
+}
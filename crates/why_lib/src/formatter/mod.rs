@@ -0,0 +1,481 @@
+mod expression;
+mod statement;
+
+use std::{collections::HashSet, error::Error, fmt::Display};
+
+use crate::{
+    lexer::{GetPosition, LexError, Lexer, Token},
+    parser::{ast::TopLevelStatement, parse, ParseError},
+};
+
+/// Options for rendering source code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatConfig {
+    /// Number of spaces per level of indentation.
+    pub indent_width: usize,
+    /// Indent with tabs instead of spaces.
+    pub use_tabs: bool,
+    /// Maximum width of a line before lists (e.g., arguments) get broken up.
+    pub max_width: usize,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig {
+            indent_width: 4,
+            use_tabs: false,
+            max_width: 100,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    Lex(LexError),
+    Parse(ParseError),
+}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::Lex(e) => e.fmt(f),
+            FormatError::Parse(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for FormatError {}
+
+/// Format the given source code. Blank lines between statements and the placement of comments
+/// are preserved.
+pub fn format(input: &str, config: &FormatConfig) -> Result<String, FormatError> {
+    let tokens = Lexer::new(input).lex().map_err(FormatError::Lex)?;
+    let trivia = Trivia::from_tokens(&tokens);
+    let statements = parse(&mut tokens.into()).map_err(FormatError::Parse)?;
+
+    Ok(Formatter::with_trivia(config.clone(), trivia).format(&statements))
+}
+
+/// Layout of the original source which is not part of the AST.
+#[derive(Debug, Clone, Default)]
+struct Trivia {
+    /// Lines which are preceded by at least one blank line.
+    blank_lines_before: HashSet<usize>,
+    /// Information about every comment, in order of appearance.
+    comments: Vec<CommentTrivia>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct CommentTrivia {
+    blank_line_before: bool,
+    /// The comment follows some code on the same line.
+    trailing: bool,
+}
+
+impl Trivia {
+    fn from_tokens(tokens: &[Token]) -> Self {
+        let mut trivia = Trivia::default();
+        let mut previous_line = None;
+
+        for token in tokens {
+            let position = token.position();
+            let line = position.start_line_col().0;
+
+            let blank_line_before = previous_line.is_some_and(|previous| line > previous + 1);
+            if blank_line_before {
+                trivia.blank_lines_before.insert(line);
+            }
+
+            if let Token::Comment { .. } = token {
+                trivia.comments.push(CommentTrivia {
+                    blank_line_before,
+                    trailing: previous_line == Some(line),
+                });
+            }
+
+            previous_line = Some(position.end_line_col().0);
+        }
+
+        trivia
+    }
+}
+
+/// Pretty printer rendering an AST back to canonical source code.
+#[derive(Debug, Clone, Default)]
+pub struct Formatter {
+    config: FormatConfig,
+    trivia: Trivia,
+    /// Index of the next comment to render.
+    next_comment: usize,
+    /// Render all lists on a single line, because an enclosing list tries to do so.
+    flat: bool,
+}
+
+/// A rendered item of a sequence of statements.
+enum Line {
+    Code {
+        text: String,
+        blank_line_before: bool,
+    },
+    Comment {
+        text: String,
+        trivia: CommentTrivia,
+    },
+}
+
+impl Formatter {
+    pub fn new(config: FormatConfig) -> Self {
+        Formatter {
+            config,
+            ..Default::default()
+        }
+    }
+
+    fn with_trivia(config: FormatConfig, trivia: Trivia) -> Self {
+        Formatter {
+            config,
+            trivia,
+            ..Default::default()
+        }
+    }
+
+    /// Render all toplevel statements. Toplevel items (except comments) are always separated by
+    /// a blank line.
+    pub fn format(&mut self, statements: &[TopLevelStatement<()>]) -> String {
+        let mut lines = vec![];
+        let mut previous_line = None;
+        let mut previous_was_item = false;
+
+        for statement in statements {
+            if let TopLevelStatement::Comment(comment, _) = statement {
+                lines.push(self.comment(comment));
+                previous_was_item = false;
+                continue;
+            }
+
+            let start_line = Self::top_level_start_line(statement);
+            let text = self.top_level_statement(statement);
+            let Line::Code {
+                text,
+                blank_line_before,
+            } = self.code(text, start_line, &mut previous_line)
+            else {
+                unreachable!()
+            };
+
+            lines.push(Line::Code {
+                text,
+                blank_line_before: blank_line_before || previous_was_item,
+            });
+            previous_was_item = true;
+        }
+
+        let mut output = self.join(lines, 0);
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output
+    }
+
+    fn indentation(&self, indent: usize) -> String {
+        if self.config.use_tabs {
+            "\t".repeat(indent)
+        } else {
+            " ".repeat(indent * self.config.indent_width)
+        }
+    }
+
+    /// Column at which code of the given indentation level starts.
+    fn column(&self, indent: usize) -> usize {
+        indent * self.config.indent_width
+    }
+
+    /// Display width of a single line, counting tabs as one level of indentation.
+    fn width(&self, line: &str) -> usize {
+        line.chars()
+            .map(|c| {
+                if c == '\t' {
+                    self.config.indent_width
+                } else {
+                    1
+                }
+            })
+            .sum()
+    }
+
+    /// Column after the given text, if it was rendered starting at `column`.
+    fn after(&self, column: usize, text: &str) -> usize {
+        match text.rsplit_once('\n') {
+            Some((_, last)) => self.width(last),
+            None => column + self.width(text),
+        }
+    }
+
+    /// Check whether text rendered starting at `column` stays within the maximum width.
+    /// Subsequent lines of the text are expected to be indented already.
+    fn fits(&self, column: usize, text: &str) -> bool {
+        let mut lines = text.lines();
+        let first = lines.next().unwrap_or_default();
+
+        column + self.width(first) <= self.config.max_width
+            && lines.all(|line| self.width(line) <= self.config.max_width)
+    }
+
+    /// Render a comma separated list enclosed by `open` and `close`, which starts at `column`.
+    /// If the list does not fit into a single line, every item is put on its own line. Enclosing
+    /// lists are broken before nested ones.
+    fn list<I>(
+        &mut self,
+        items: &[I],
+        (open, close): (&str, &str),
+        indent: usize,
+        column: usize,
+        render: impl Fn(&mut Self, &I, usize, usize) -> String,
+    ) -> String {
+        let next_comment = self.next_comment;
+        let nested = self.flat;
+        self.flat = true;
+
+        let mut flat = open.to_string();
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                flat.push_str(", ");
+            }
+            let column = self.after(column, &flat);
+            flat.push_str(&render(self, item, indent, column));
+        }
+        flat.push_str(close);
+        self.flat = nested;
+
+        if items.is_empty() || nested || self.fits(column, &flat) {
+            return flat;
+        }
+
+        // render again, so comments within the items are not consumed twice
+        self.next_comment = next_comment;
+
+        let indentation = self.indentation(indent + 1);
+        let column = self.column(indent + 1);
+        let items = items
+            .iter()
+            .map(|item| format!("{indentation}{}", render(self, item, indent + 1, column)))
+            .collect::<Vec<_>>();
+
+        format!(
+            "{open}\n{}\n{}{close}",
+            items.join(",\n"),
+            self.indentation(indent)
+        )
+    }
+
+    fn comment(&mut self, comment: &str) -> Line {
+        let trivia = self
+            .trivia
+            .comments
+            .get(self.next_comment)
+            .copied()
+            .unwrap_or_default();
+        self.next_comment += 1;
+
+        Line::Comment {
+            text: comment.to_string(),
+            trivia,
+        }
+    }
+
+    /// Wrap rendered code of a statement starting at the given line. Only the first statement
+    /// starting on a line can be preceded by a blank line.
+    fn code(&self, text: String, start_line: usize, previous_line: &mut Option<usize>) -> Line {
+        let blank_line_before = *previous_line != Some(start_line)
+            && self.trivia.blank_lines_before.contains(&start_line);
+        *previous_line = Some(start_line);
+
+        Line::Code {
+            text,
+            blank_line_before,
+        }
+    }
+
+    /// Join rendered lines of a sequence, indenting them and placing trailing comments behind
+    /// the code they belong to.
+    fn join(&self, lines: Vec<Line>, indent: usize) -> String {
+        let indentation = self.indentation(indent);
+        let mut output = String::new();
+
+        for (i, line) in lines.into_iter().enumerate() {
+            let (text, blank_line_before) = match line {
+                Line::Comment { text, trivia } if trivia.trailing && i > 0 => {
+                    output.push(' ');
+                    output.push_str(&text);
+                    continue;
+                }
+                Line::Comment { text, trivia } => (text, trivia.blank_line_before),
+                Line::Code {
+                    text,
+                    blank_line_before,
+                } => (text, blank_line_before),
+            };
+
+            if i > 0 {
+                output.push('\n');
+                if blank_line_before {
+                    output.push('\n');
+                }
+            }
+            output.push_str(&indentation);
+            output.push_str(&text);
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{error::Error, fs};
+
+    use super::*;
+
+    fn token_values(input: &str) -> Vec<String> {
+        let tokens = Lexer::new(input).lex().expect("should lex");
+        tokens.iter().map(|token| format!("{token:?}")).collect()
+    }
+
+    #[test]
+    fn test_examples_round_trip() -> Result<(), Box<dyn Error>> {
+        for entry in fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/../../examples"))? {
+            let path = entry?.path();
+            let input = fs::read_to_string(&path)?;
+
+            // not every example is valid
+            let Ok(formatted) = format(&input, &FormatConfig::default()) else {
+                continue;
+            };
+
+            // formatting must only change the layout, i.e., the tokens (without their positions)
+            // stay the same
+            assert_eq!(
+                token_values(&input),
+                token_values(&formatted),
+                "{path:?} does not round trip"
+            );
+            assert_eq!(
+                format(&formatted, &FormatConfig::default())?,
+                formatted,
+                "{path:?} is not idempotent"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_canonical_layout() -> Result<(), Box<dyn Error>> {
+        let input = "const  X:i64=42;\nfn   main( ) : void{let x=X+1;\n\n\nx=2;}";
+
+        assert_eq!(
+            format(input, &FormatConfig::default())?,
+            "const X: i64 = 42;\n\nfn main(): void {\n    let x = X + 1;\n\n    x = 2;\n}\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_comments() -> Result<(), Box<dyn Error>> {
+        let input = "// leading\nfn main(): void {\n    // inside\n    let x = 42; // trailing\n\n    // separated\n    x\n}\n// end";
+
+        assert_eq!(
+            format(input, &FormatConfig::default())?,
+            "// leading\nfn main(): void {\n    // inside\n    let x = 42; // trailing\n\n    // separated\n    x\n}\n// end\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_indentation() -> Result<(), Box<dyn Error>> {
+        let input = "fn main(): void {\n    let x = 42;\n}";
+
+        let config = FormatConfig {
+            indent_width: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            format(input, &config)?,
+            "fn main(): void {\n  let x = 42;\n}\n"
+        );
+
+        let config = FormatConfig {
+            use_tabs: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            format(input, &config)?,
+            "fn main(): void {\n\tlet x = 42;\n}\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_width() -> Result<(), Box<dyn Error>> {
+        let input = "fn main(): void {\n    foo(argument, [1, 2, 3], Point { x: 1, y: 2 });\n}";
+
+        let config = FormatConfig {
+            max_width: 40,
+            ..Default::default()
+        };
+        assert_eq!(
+            format(input, &config)?,
+            "fn main(): void {\n    foo(\n        argument,\n        [1, 2, 3],\n        Point { x: 1, y: 2 }\n    );\n}\n"
+        );
+
+        let config = FormatConfig {
+            max_width: 20,
+            ..Default::default()
+        };
+        assert_eq!(
+            format(input, &config)?,
+            "fn main(): void {\n    foo(\n        argument,\n        [1, 2, 3],\n        Point {\n            x: 1,\n            y: 2\n        }\n    );\n}\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_declarations() -> Result<(), Box<dyn Error>> {
+        let input = "struct Foo { a: i64; b: &Foo; }\ninstance Foo { declare get(i64): i64; fn a(): i64 { 42 } }\nstruct Empty {}";
+
+        assert_eq!(
+            format(input, &FormatConfig::default())?,
+            "struct Foo {\n    a: i64;\n    b: &Foo;\n}\n\ninstance Foo {\n    declare get(i64): i64;\n    fn a(): i64 {\n        42\n    }\n}\n\nstruct Empty {}\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_attributes() -> Result<(), Box<dyn Error>> {
+        let input = "#[test]   fn adds( ) : void { assert_eq(1+1,2); }";
+
+        assert_eq!(
+            format(input, &FormatConfig::default())?,
+            "#[test]\nfn adds(): void {\n    assert_eq(1 + 1, 2);\n}\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_strings() -> Result<(), Box<dyn Error>> {
+        let input = "fn main(): void {\n    let x = \"{{ {a+1} }}\";\n    let y = \"{{}}\";\n}";
+
+        assert_eq!(
+            format(input, &FormatConfig::default())?,
+            "fn main(): void {\n    let x = \"{{ {a + 1} }}\";\n    let y = \"{{}}\";\n}\n"
+        );
+
+        Ok(())
+    }
+}
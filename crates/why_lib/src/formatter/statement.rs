@@ -0,0 +1,316 @@
+use crate::parser::ast::*;
+
+use super::Formatter;
+
+/// A member of an instance, which are stored separately in the AST.
+enum Member<'a> {
+    Function(&'a Function<()>),
+    Declaration(&'a MethodDeclaration<()>),
+}
+
+impl Formatter {
+    pub(super) fn top_level_statement(&mut self, statement: &TopLevelStatement<()>) -> String {
+        match statement {
+            TopLevelStatement::Comment(comment, _) => comment.clone(),
+            TopLevelStatement::Function(function) => self.function(function, 0, 0),
+            TopLevelStatement::Constant(constant) => format!("{};", self.constant(constant, 0)),
+            TopLevelStatement::Declaration(declaration) => {
+                format!("{};", Self::declaration(declaration))
+            }
+            TopLevelStatement::StructDeclaration(declaration) => {
+                self.struct_declaration(declaration, 0)
+            }
+            TopLevelStatement::Instance(instance) => self.instance(instance, 0),
+        }
+    }
+
+    pub(super) fn top_level_start_line(statement: &TopLevelStatement<()>) -> usize {
+        match statement {
+            TopLevelStatement::Comment(..) => 0,
+            TopLevelStatement::Function(Function { position, .. })
+            | TopLevelStatement::Constant(Constant { position, .. })
+            | TopLevelStatement::Declaration(Declaration { position, .. })
+            | TopLevelStatement::StructDeclaration(StructDeclaration { position, .. })
+            | TopLevelStatement::Instance(Instance { position, .. }) => position.start_line_col().0,
+        }
+    }
+
+    /// Render a block of statements, including its braces. Empty blocks are rendered as `{}`.
+    pub(super) fn block(&mut self, statements: &[Statement<()>], indent: usize) -> String {
+        if statements.is_empty() {
+            return "{}".into();
+        }
+
+        let mut lines = vec![];
+        let mut previous_line = None;
+
+        for statement in statements {
+            let line = match statement {
+                Statement::Comment(comment, _) => self.comment(comment),
+                statement => {
+                    let text = self.statement(statement, indent + 1);
+                    self.code(text, Self::start_line(statement), &mut previous_line)
+                }
+            };
+            lines.push(line);
+        }
+
+        format!(
+            "{{\n{}\n{}}}",
+            self.join(lines, indent + 1),
+            self.indentation(indent)
+        )
+    }
+
+    fn statement(&mut self, statement: &Statement<()>, indent: usize) -> String {
+        let column = self.column(indent);
+
+        match statement {
+            Statement::Function(function) => self.function(function, indent, column),
+            Statement::If(if_statement) => self.if_expression(if_statement, indent, column),
+            Statement::WhileLoop(WhileLoop {
+                condition, block, ..
+            }) => {
+                let condition = self.expression(condition, indent, column + "while (".len());
+                format!(
+                    "while ({condition}) {}",
+                    self.block(&block.statements, indent)
+                )
+            }
+            Statement::Initialization(initialisation) => {
+                format!("{};", self.initialisation(initialisation, indent))
+            }
+            Statement::Constant(constant) => format!("{};", self.constant(constant, indent)),
+            Statement::Assignment(Assignment { lvalue, rvalue, .. }) => {
+                let lvalue = match lvalue {
+                    LValue::Id(id) => id.name.clone(),
+                    LValue::Postfix(postfix) => self.postfix(postfix, indent, column),
+                };
+                let column = self.after(column, &lvalue) + " = ".len();
+                format!("{lvalue} = {};", self.expression(rvalue, indent, column))
+            }
+            Statement::Expression(expression) => {
+                format!("{};", self.expression(expression, indent, column))
+            }
+            Statement::YieldingExpression(expression) => {
+                self.expression(expression, indent, column)
+            }
+            Statement::Return(expression) => {
+                let column = column + "return ".len();
+                format!("return {};", self.expression(expression, indent, column))
+            }
+            Statement::Comment(comment, _) => comment.clone(),
+            Statement::Declaration(declaration) => format!("{};", Self::declaration(declaration)),
+            Statement::StructDeclaration(declaration) => {
+                self.struct_declaration(declaration, indent)
+            }
+        }
+    }
+
+    /// The line a statement starts at. For expressions, this is the line of their leftmost token.
+    fn start_line(statement: &Statement<()>) -> usize {
+        match statement {
+            Statement::Expression(expression)
+            | Statement::YieldingExpression(expression)
+            | Statement::Return(expression) => Self::expression_start_line(expression),
+            Statement::Assignment(Assignment {
+                lvalue: LValue::Postfix(postfix),
+                ..
+            }) => Self::expression_start_line(&Expression::Postfix(postfix.clone())),
+            statement => statement.position().start_line_col().0,
+        }
+    }
+
+    fn expression_start_line(expression: &Expression<()>) -> usize {
+        match expression {
+            Expression::Parens(inner) => Self::expression_start_line(inner),
+            Expression::Binary(binary) => Self::expression_start_line(&binary.left),
+            Expression::Postfix(
+                Postfix::Call { expr, .. }
+                | Postfix::Index { expr, .. }
+                | Postfix::PropertyAccess { expr, .. },
+            ) => Self::expression_start_line(expr),
+            expression => expression.position().start_line_col().0,
+        }
+    }
+
+    pub(super) fn function(
+        &mut self,
+        Function {
+            attributes,
+            id,
+            parameters,
+            return_type,
+            statements,
+            ..
+        }: &Function<()>,
+        indent: usize,
+        column: usize,
+    ) -> String {
+        // every attribute goes on its own line in front of the function
+        let attributes = attributes
+            .iter()
+            .map(|attribute| format!("#[{}]\n{}", attribute.name, self.indentation(indent)))
+            .collect::<String>();
+
+        let head = format!("fn {}", id.name);
+        let parameters = self.list(
+            parameters,
+            ("(", ")"),
+            indent,
+            self.after(column, &head),
+            |_,
+             FunctionParameter {
+                 name, type_name, ..
+             },
+             _,
+             _| format!("{}: {type_name}", name.name),
+        );
+
+        format!(
+            "{attributes}{head}{parameters}: {return_type} {}",
+            self.block(statements, indent)
+        )
+    }
+
+    fn initialisation(
+        &mut self,
+        Initialisation {
+            id,
+            mutable,
+            type_name,
+            value,
+            ..
+        }: &Initialisation<()>,
+        indent: usize,
+    ) -> String {
+        let mut head = String::from("let ");
+        if *mutable {
+            head.push_str("mut ");
+        }
+        head.push_str(&id.name);
+        if let Some(type_name) = type_name {
+            head.push_str(&format!(": {type_name}"));
+        }
+
+        let Some(value) = value else {
+            return head;
+        };
+        head.push_str(" = ");
+
+        let column = self.after(self.column(indent), &head);
+        format!("{head}{}", self.expression(value, indent, column))
+    }
+
+    fn constant(
+        &mut self,
+        Constant {
+            id,
+            type_name,
+            value,
+            ..
+        }: &Constant<()>,
+        indent: usize,
+    ) -> String {
+        let head = format!("const {}: {type_name} = ", id.name);
+        let column = self.after(self.column(indent), &head);
+        format!("{head}{}", self.expression(value, indent, column))
+    }
+
+    fn declaration(
+        Declaration {
+            name, type_name, ..
+        }: &Declaration<()>,
+    ) -> String {
+        format!("declare {}: {type_name}", name.name)
+    }
+
+    fn struct_declaration(
+        &self,
+        StructDeclaration { id, fields, .. }: &StructDeclaration<()>,
+        indent: usize,
+    ) -> String {
+        if fields.is_empty() {
+            return format!("struct {} {{}}", id.name);
+        }
+
+        let indentation = self.indentation(indent + 1);
+        let fields = fields
+            .iter()
+            .map(
+                |StructFieldDeclaration {
+                     name, type_name, ..
+                 }| format!("{indentation}{}: {type_name};\n", name.name),
+            )
+            .collect::<String>();
+
+        format!(
+            "struct {} {{\n{fields}{}}}",
+            id.name,
+            self.indentation(indent)
+        )
+    }
+
+    fn instance(
+        &mut self,
+        Instance {
+            name,
+            functions,
+            declarations,
+            ..
+        }: &Instance<()>,
+        indent: usize,
+    ) -> String {
+        let mut members = functions
+            .iter()
+            .map(Member::Function)
+            .chain(declarations.iter().map(Member::Declaration))
+            .collect::<Vec<_>>();
+
+        if members.is_empty() {
+            return format!("instance {name} {{}}");
+        }
+
+        members.sort_by_key(|member| match member {
+            Member::Function(function) => function.position.start,
+            Member::Declaration(declaration) => declaration.position.start,
+        });
+
+        let mut lines = vec![];
+        let mut previous_line = None;
+
+        for member in members {
+            let (text, start_line) = match member {
+                Member::Function(function) => (
+                    self.function(function, indent + 1, self.column(indent + 1)),
+                    function.position.start_line_col().0,
+                ),
+                Member::Declaration(MethodDeclaration {
+                    id,
+                    parameter_types,
+                    return_type,
+                    position,
+                    ..
+                }) => (
+                    format!(
+                        "declare {}({}): {return_type};",
+                        id.name,
+                        parameter_types
+                            .iter()
+                            .map(TypeName::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    position.start_line_col().0,
+                ),
+            };
+            lines.push(self.code(text, start_line, &mut previous_line));
+        }
+
+        format!(
+            "instance {name} {{\n{}\n{}}}",
+            self.join(lines, indent + 1),
+            self.indentation(indent)
+        )
+    }
+}
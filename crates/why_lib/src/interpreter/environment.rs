@@ -0,0 +1,69 @@
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use super::value::Value;
+
+/// Variables of a single scope. Variables which were declared without a value are `None`.
+type Frame<'a> = Rc<RefCell<HashMap<String, Option<Value<'a>>>>>;
+
+/// Stack of frames holding the values of variables. Cloning an environment is shallow, so
+/// closures see later changes of the variables they captured.
+#[derive(Debug, Clone, Default)]
+pub struct Environment<'a> {
+    frames: Vec<Frame<'a>>,
+}
+
+impl<'a> Environment<'a> {
+    pub fn enter_scope(&mut self) {
+        self.frames.push(Frame::default());
+    }
+
+    pub fn exit_scope(&mut self) {
+        self.frames.pop();
+    }
+
+    /// Define a new variable in the innermost frame, shadowing other variables of the same name.
+    pub fn define(&mut self, name: impl ToString, value: Value<'a>) {
+        self.insert(name, Some(value));
+    }
+
+    /// Define a new variable without a value, which has to be assigned before it is used.
+    pub fn declare(&mut self, name: impl ToString) {
+        self.insert(name, None);
+    }
+
+    fn insert(&mut self, name: impl ToString, value: Option<Value<'a>>) {
+        if self.frames.is_empty() {
+            self.enter_scope();
+        }
+
+        if let Some(frame) = self.frames.last() {
+            frame.borrow_mut().insert(name.to_string(), value);
+        }
+    }
+
+    /// Get the value of a variable. Returns None, if there is no such variable or it has not been
+    /// assigned yet.
+    pub fn get(&self, name: &str) -> Option<Value<'a>> {
+        self.frames
+            .iter()
+            .rev()
+            .find(|frame| frame.borrow().contains_key(name))
+            .and_then(|frame| frame.borrow().get(name).cloned().flatten())
+    }
+
+    /// Update an existing variable. Returns false, if there is no variable with the given name.
+    pub fn assign(&mut self, name: &str, value: Value<'a>) -> bool {
+        match self
+            .frames
+            .iter()
+            .rev()
+            .find(|frame| frame.borrow().contains_key(name))
+        {
+            Some(frame) => {
+                frame.borrow_mut().insert(name.to_string(), Some(value));
+                true
+            }
+            None => false,
+        }
+    }
+}
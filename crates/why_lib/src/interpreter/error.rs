@@ -0,0 +1,141 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{diagnostic::Diagnostic, lexer::Span};
+
+/// Error which aborts the execution of a program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RuntimeError {
+    /// An `assert` did not hold or the values of an `assert_eq` differ.
+    AssertionFailed {
+        /// Both compared values of an `assert_eq`.
+        values: Option<(String, String)>,
+        position: Span,
+    },
+    DivisionByZero {
+        position: Span,
+    },
+    Overflow {
+        position: Span,
+    },
+    IndexOutOfBounds {
+        index: i128,
+        length: usize,
+        position: Span,
+    },
+    /// Call of something which was only declared.
+    MissingImplementation {
+        name: String,
+        position: Span,
+    },
+    /// Access to something which does not have a value (yet), e.g., a constant which is used
+    /// before its definition.
+    UndefinedValue {
+        name: String,
+        position: Span,
+    },
+    UnsupportedOperation {
+        operands: Vec<String>,
+        position: Span,
+    },
+    StackOverflow {
+        position: Span,
+    },
+    /// The program called `panic` with the given message.
+    Panic {
+        message: String,
+        position: Span,
+    },
+}
+
+impl RuntimeError {
+    /// Stable code identifying the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RuntimeError::AssertionFailed { .. } => "E0018",
+            RuntimeError::DivisionByZero { .. } => "E0019",
+            RuntimeError::Overflow { .. } => "E0020",
+            RuntimeError::IndexOutOfBounds { .. } => "E0021",
+            RuntimeError::MissingImplementation { .. } => "E0022",
+            RuntimeError::UndefinedValue { .. } => "E0023",
+            RuntimeError::UnsupportedOperation { .. } => "E0024",
+            RuntimeError::StackOverflow { .. } => "E0025",
+            RuntimeError::Panic { .. } => "E0036",
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            RuntimeError::AssertionFailed { position, .. }
+            | RuntimeError::DivisionByZero { position }
+            | RuntimeError::Overflow { position }
+            | RuntimeError::IndexOutOfBounds { position, .. }
+            | RuntimeError::MissingImplementation { position, .. }
+            | RuntimeError::UndefinedValue { position, .. }
+            | RuntimeError::UnsupportedOperation { position, .. }
+            | RuntimeError::StackOverflow { position }
+            | RuntimeError::Panic { position, .. } => position.clone(),
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.code(), self.message());
+        let span = self.span();
+
+        match self {
+            RuntimeError::AssertionFailed {
+                values: Some((left, right)),
+                ..
+            } => diagnostic
+                .with_primary(span, Some("values are not equal".into()))
+                .with_note(format!("left: {left}"))
+                .with_note(format!("right: {right}")),
+            RuntimeError::AssertionFailed { values: None, .. } => {
+                diagnostic.with_primary(span, Some("condition is false".into()))
+            }
+            RuntimeError::IndexOutOfBounds { length, .. } => {
+                diagnostic.with_primary(span, Some(format!("the length is {length}")))
+            }
+            RuntimeError::MissingImplementation { .. } => {
+                diagnostic.with_primary(span, Some("declared, but not defined".into()))
+            }
+            RuntimeError::StackOverflow { .. } => {
+                diagnostic.with_note("this is most likely caused by infinite recursion")
+            }
+            RuntimeError::Panic { .. } => {
+                diagnostic.with_primary(span, Some("panicked here".into()))
+            }
+            _ => diagnostic.with_primary(span, None),
+        }
+    }
+
+    fn message(&self) -> String {
+        match self {
+            RuntimeError::AssertionFailed {
+                values: Some(_), ..
+            } => "Assertion failed: left != right".into(),
+            RuntimeError::AssertionFailed { values: None, .. } => "Assertion failed".into(),
+            RuntimeError::DivisionByZero { .. } => "Attempted to divide by zero".into(),
+            RuntimeError::Overflow { .. } => "Integer overflow".into(),
+            RuntimeError::IndexOutOfBounds { index, length, .. } => {
+                format!("Index {index} is out of bounds for array of length {length}")
+            }
+            RuntimeError::MissingImplementation { name, .. } => {
+                format!("'{name}' has no implementation")
+            }
+            RuntimeError::UndefinedValue { name, .. } => format!("'{name}' has no value"),
+            RuntimeError::UnsupportedOperation { operands, .. } => {
+                format!("Operation is not supported for {}", operands.join(" and "))
+            }
+            RuntimeError::StackOverflow { .. } => "Maximum call depth exceeded".into(),
+            RuntimeError::Panic { message, .. } => format!("Program panicked: {message}"),
+        }
+    }
+}
+
+impl Display for RuntimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.diagnostic().to_string())
+    }
+}
+
+impl Error for RuntimeError {}
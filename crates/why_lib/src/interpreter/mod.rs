@@ -0,0 +1,858 @@
+//! Tree-walking interpreter for validated programs. It is the execution backend of the test
+//! runner, so it favors simplicity and precise errors over speed.
+
+mod environment;
+mod error;
+mod value;
+
+pub use self::environment::*;
+pub use self::error::*;
+pub use self::value::*;
+
+use std::{collections::HashMap, rc::Rc};
+
+use crate::{
+    lexer::Span,
+    parser::ast::{
+        Array, Assignment, BinaryExpression, BinaryOperator, Block, Character, Constant,
+        Declaration, Expression, Function, Id, If, Initialisation, Instance, Interpolation,
+        InterpolationSegment, LValue, Lambda, LambdaParameter, Num, Postfix, Prefix, Statement,
+        StructFieldInitialisation, StructInitialisation, TopLevelStatement, WhileLoop,
+    },
+    typechecker::{
+        bounds_check, constant_order, BoundsCheck, Intrinsic, Type, ValidatedTypeInformation, NONE,
+    },
+};
+
+type Info = ValidatedTypeInformation;
+
+/// Maximum depth of nested calls before the execution is aborted.
+const MAX_CALL_DEPTH: usize = 256;
+
+/// Reason for leaving the evaluation of a node early.
+enum Unwind<'a> {
+    Return(Value<'a>),
+    Error(RuntimeError),
+}
+
+impl From<RuntimeError> for Unwind<'_> {
+    fn from(value: RuntimeError) -> Self {
+        Unwind::Error(value)
+    }
+}
+
+type Eval<'a, T> = Result<T, Unwind<'a>>;
+
+pub struct Interpreter<'a> {
+    globals: Environment<'a>,
+    /// Methods of every type with an instance. Declared methods do not have a function.
+    methods: HashMap<Type, HashMap<&'a str, Option<&'a Function<Info>>>>,
+    depth: usize,
+}
+
+impl<'a> Interpreter<'a> {
+    /// Set up the global scope of a program, i.e., define its functions and methods and evaluate
+    /// its constants (after the constants they use).
+    pub fn new(program: &'a [TopLevelStatement<Info>]) -> Result<Interpreter<'a>, RuntimeError> {
+        let mut interpreter = Interpreter::empty();
+
+        for statement in program {
+            if !matches!(statement, TopLevelStatement::Constant(_)) {
+                interpreter.add(statement)?;
+            }
+        }
+
+        for index in constant_order(program) {
+            interpreter.add(&program[index])?;
+        }
+
+        Ok(interpreter)
+    }
+
+    /// Set up an empty global scope, e.g., for an interactive session which adds its items one
+    /// after another.
+    pub fn empty() -> Interpreter<'a> {
+        let mut interpreter = Interpreter {
+            globals: Environment::default(),
+            methods: HashMap::new(),
+            depth: 0,
+        };
+        interpreter.globals.enter_scope();
+
+        interpreter
+    }
+
+    /// Add a toplevel item to the global scope. Constants are evaluated right away, so the
+    /// constants they use have to be added before.
+    pub fn add(&mut self, statement: &'a TopLevelStatement<Info>) -> Result<(), RuntimeError> {
+        match statement {
+            TopLevelStatement::Function(function) => {
+                let (name, value) = self.function(function, &self.globals);
+                self.globals.define(name, value);
+            }
+            TopLevelStatement::Declaration(declaration) => {
+                self.declaration(declaration, &mut self.globals.clone());
+            }
+            TopLevelStatement::Instance(instance) => self.instance(instance),
+            TopLevelStatement::Constant(constant) => {
+                let mut globals = self.globals.clone();
+                match self.constant(constant, &mut globals) {
+                    Ok(()) => {}
+                    Err(Unwind::Error(e)) => return Err(e),
+                    Err(Unwind::Return(_)) => unreachable!("constants can not return"),
+                }
+            }
+            TopLevelStatement::Comment(..) | TopLevelStatement::StructDeclaration(_) => {}
+        }
+
+        Ok(())
+    }
+
+    /// Evaluate a statement within the global scope, e.g., of an interactive session. Unlike
+    /// within a block, the value of an expression is returned even if it is not yielded.
+    pub fn evaluate(&mut self, statement: &'a Statement<Info>) -> Result<Value<'a>, RuntimeError> {
+        let mut globals = self.globals.clone();
+
+        let result = match statement {
+            Statement::Expression(expression) => self.expression(expression, &mut globals),
+            statement => self.statement(statement, &mut globals),
+        };
+
+        match result {
+            Ok(value) | Err(Unwind::Return(value)) => Ok(value),
+            Err(Unwind::Error(e)) => Err(e),
+        }
+    }
+
+    /// Get the value of a global variable or constant (if it has one).
+    pub fn global(&self, name: &str) -> Option<Value<'a>> {
+        self.globals.get(name)
+    }
+
+    /// Call a global function with the given arguments.
+    pub fn call(&mut self, name: &str, args: Vec<Value<'a>>) -> Result<Value<'a>, RuntimeError> {
+        let Some(Value::Function(callable)) = self.globals.get(name) else {
+            return Err(RuntimeError::UndefinedValue {
+                name: name.to_string(),
+                position: Span::default(),
+            });
+        };
+
+        let position = match callable.as_ref() {
+            Callable::Function { function, .. } => function.position.clone(),
+            _ => Span::default(),
+        };
+
+        match self.call_value(&callable, args, &position) {
+            Ok(value) | Err(Unwind::Return(value)) => Ok(value),
+            Err(Unwind::Error(e)) => Err(e),
+        }
+    }
+
+    fn function(
+        &self,
+        function: &'a Function<Info>,
+        environment: &Environment<'a>,
+    ) -> (String, Value<'a>) {
+        let callable = Callable::Function {
+            function,
+            environment: environment.clone(),
+            this: None,
+        };
+
+        (function.id.name.clone(), Value::Function(Rc::new(callable)))
+    }
+
+    fn declaration(&self, declaration: &'a Declaration<Info>, environment: &mut Environment<'a>) {
+        let Declaration { name, info, .. } = declaration;
+
+        let value = match info.type_id {
+            Type::Function { .. } => {
+                Value::Function(Rc::new(Callable::Declared(name.name.clone())))
+            }
+            _ => Value::Declared(name.name.clone()),
+        };
+
+        environment.define(&name.name, value);
+    }
+
+    fn instance(&mut self, instance: &'a Instance<Info>) {
+        let Instance {
+            name,
+            functions,
+            declarations,
+            info,
+            ..
+        } = instance;
+
+        let Ok(type_id) = Type::try_from((name, &info.context)) else {
+            unreachable!("instances of undefined types do not typecheck")
+        };
+
+        let methods = self.methods.entry(type_id).or_default();
+
+        for function in functions {
+            methods.insert(function.id.name.as_str(), Some(function));
+        }
+
+        for declaration in declarations {
+            methods.insert(declaration.id.name.as_str(), None);
+        }
+    }
+
+    fn constant(
+        &mut self,
+        constant: &'a Constant<Info>,
+        environment: &mut Environment<'a>,
+    ) -> Eval<'a, ()> {
+        let Constant { id, value, .. } = constant;
+
+        let value = self.expression(value, environment)?;
+        environment.define(&id.name, value);
+
+        Ok(())
+    }
+
+    /// Evaluate statements in a new scope. The value of a block is the value of its last
+    /// statement (if it yields one).
+    fn block(
+        &mut self,
+        statements: &'a [Statement<Info>],
+        environment: &mut Environment<'a>,
+    ) -> Eval<'a, Value<'a>> {
+        environment.enter_scope();
+
+        let mut result = Ok(Value::Void);
+        for statement in statements {
+            result = self.statement(statement, environment);
+            if result.is_err() {
+                break;
+            }
+        }
+
+        environment.exit_scope();
+        result
+    }
+
+    fn statement(
+        &mut self,
+        statement: &'a Statement<Info>,
+        environment: &mut Environment<'a>,
+    ) -> Eval<'a, Value<'a>> {
+        match statement {
+            Statement::Function(function) => {
+                let (name, value) = self.function(function, environment);
+                environment.define(name, value);
+            }
+            Statement::If(if_statement) => return self.if_expression(if_statement, environment),
+            Statement::WhileLoop(WhileLoop {
+                condition,
+                block: Block { statements, .. },
+                ..
+            }) => {
+                while self.condition(condition, environment)? {
+                    self.block(statements, environment)?;
+                }
+            }
+            Statement::Initialization(Initialisation {
+                id, value: None, ..
+            }) => environment.declare(&id.name),
+            Statement::Initialization(Initialisation {
+                id,
+                value: Some(value),
+                ..
+            }) => {
+                let value = self.expression(value, environment)?;
+                environment.define(&id.name, value);
+            }
+            Statement::Constant(constant) => self.constant(constant, environment)?,
+            Statement::Assignment(assignment) => self.assignment(assignment, environment)?,
+            Statement::Expression(expression) => {
+                self.expression(expression, environment)?;
+            }
+            Statement::YieldingExpression(expression) => {
+                return self.expression(expression, environment);
+            }
+            Statement::Return(expression) => {
+                let value = self.expression(expression, environment)?;
+                return Err(Unwind::Return(value));
+            }
+            Statement::Declaration(declaration) => self.declaration(declaration, environment),
+            Statement::Comment(..) | Statement::StructDeclaration(_) => {}
+        }
+
+        Ok(Value::Void)
+    }
+
+    fn assignment(
+        &mut self,
+        Assignment { lvalue, rvalue, .. }: &'a Assignment<Info>,
+        environment: &mut Environment<'a>,
+    ) -> Eval<'a, ()> {
+        let value = self.expression(rvalue, environment)?;
+
+        match lvalue {
+            LValue::Id(Id { name, position, .. }) => {
+                if !environment.assign(name, value) {
+                    return Err(RuntimeError::UndefinedValue {
+                        name: name.clone(),
+                        position: position.clone(),
+                    }
+                    .into());
+                }
+            }
+            LValue::Postfix(Postfix::Index { expr, index, .. }) => {
+                let values = self.array(expr, environment)?;
+                let index = self.index(expr, index, values.borrow().len(), environment)?;
+                values.borrow_mut()[index] = value;
+            }
+            LValue::Postfix(Postfix::PropertyAccess { expr, property, .. }) => {
+                let target = self.expression(expr, environment)?;
+                let Value::Struct(_, fields) = &target else {
+                    return Err(RuntimeError::UndefinedValue {
+                        name: target.to_string(),
+                        position: expr.position(),
+                    }
+                    .into());
+                };
+
+                let mut fields = fields.borrow_mut();
+                match fields.iter_mut().find(|(name, _)| *name == property.name) {
+                    Some((_, field)) => *field = value,
+                    None => fields.push((property.name.clone(), value)),
+                }
+            }
+            LValue::Postfix(
+                Postfix::Call { .. }
+                | Postfix::Cast { .. }
+                | Postfix::OptionalPropertyAccess { .. }
+                | Postfix::Propagate { .. },
+            ) => {
+                unreachable!("only variables, indices and properties are assignable")
+            }
+        }
+
+        Ok(())
+    }
+
+    fn expression(
+        &mut self,
+        expression: &'a Expression<Info>,
+        environment: &mut Environment<'a>,
+    ) -> Eval<'a, Value<'a>> {
+        let value = match expression {
+            Expression::Id(Id { name, position, .. }) => match environment.get(name) {
+                Some(value) => value,
+                None => match Intrinsic::from_name(name) {
+                    Some(intrinsic) => Value::Function(Rc::new(Callable::Intrinsic(intrinsic))),
+                    None if name == NONE => Value::None,
+                    None => {
+                        return Err(RuntimeError::UndefinedValue {
+                            name: name.clone(),
+                            position: position.clone(),
+                        }
+                        .into())
+                    }
+                },
+            },
+            // the typechecker makes sure that literals fit into their type
+            Expression::Num(Num::Integer(value, ..)) => Value::Integer((*value).into()),
+            Expression::Num(Num::FloatingPoint(value, info, _)) => match &info.type_id {
+                Type::FloatingPoint(float_type) => Value::FloatingPoint(float_type.round(*value)),
+                _ => Value::FloatingPoint(*value),
+            },
+            Expression::Character(Character { character, .. }) => Value::Character(*character),
+            Expression::AstString(string) => Value::String(string.value.clone()),
+            Expression::Interpolation(Interpolation { segments, .. }) => {
+                let mut result = String::new();
+                for segment in segments {
+                    match segment {
+                        InterpolationSegment::Text(text) => result.push_str(text),
+                        InterpolationSegment::Expression(expression) => {
+                            match self.expression(expression, environment)? {
+                                Value::String(value) => result.push_str(&value),
+                                Value::Character(value) => result.push(value),
+                                value => result.push_str(&value.to_string()),
+                            }
+                        }
+                    }
+                }
+                Value::String(result)
+            }
+            Expression::Function(function) => self.function(function, environment).1,
+            Expression::Lambda(lambda) => Value::Function(Rc::new(Callable::Lambda {
+                lambda,
+                environment: environment.clone(),
+            })),
+            Expression::If(if_expression) => self.if_expression(if_expression, environment)?,
+            Expression::Block(Block { statements, .. }) => self.block(statements, environment)?,
+            Expression::Parens(expression) => self.expression(expression, environment)?,
+            Expression::Postfix(postfix) => self.postfix(postfix, environment)?,
+            Expression::Prefix(prefix) => self.prefix(prefix, environment)?,
+            Expression::Binary(binary) => self.binary(binary, environment)?,
+            Expression::Array(Array::Literal { values, .. }) => {
+                let mut evaluated = vec![];
+                for value in values {
+                    evaluated.push(self.expression(value, environment)?);
+                }
+                Value::Array(Rc::new(evaluated.into()))
+            }
+            Expression::Array(Array::Default {
+                initial_value,
+                length,
+                ..
+            }) => {
+                let Value::Integer(length) = self.expression(length, environment)? else {
+                    unreachable!("array lengths are integers")
+                };
+
+                // the initial value is evaluated for every element, so elements are not shared
+                let mut values = vec![];
+                for _ in 0..length {
+                    values.push(self.expression(initial_value, environment)?);
+                }
+                Value::Array(Rc::new(values.into()))
+            }
+            Expression::StructInitialisation(StructInitialisation { id, fields, .. }) => {
+                let mut values = vec![];
+                for StructFieldInitialisation { name, value, .. } in fields {
+                    values.push((name.name.clone(), self.expression(value, environment)?));
+                }
+                Value::Struct(id.name.clone(), Rc::new(values.into()))
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn if_expression(
+        &mut self,
+        If {
+            condition,
+            statements,
+            else_statements,
+            ..
+        }: &'a If<Info>,
+        environment: &mut Environment<'a>,
+    ) -> Eval<'a, Value<'a>> {
+        if self.condition(condition, environment)? {
+            self.block(statements, environment)
+        } else {
+            self.block(else_statements, environment)
+        }
+    }
+
+    fn condition(
+        &mut self,
+        condition: &'a Expression<Info>,
+        environment: &mut Environment<'a>,
+    ) -> Eval<'a, bool> {
+        match self.expression(condition, environment)? {
+            Value::Boolean(value) => Ok(value),
+            value => unreachable!("conditions are typechecked as booleans, got {value}"),
+        }
+    }
+
+    fn postfix(
+        &mut self,
+        postfix: &'a Postfix<Info>,
+        environment: &mut Environment<'a>,
+    ) -> Eval<'a, Value<'a>> {
+        match postfix {
+            Postfix::Call {
+                expr,
+                args,
+                position,
+                ..
+            } => {
+                let callee = self.expression(expr, environment)?;
+
+                let mut values = vec![];
+                for arg in args {
+                    values.push(self.expression(arg, environment)?);
+                }
+
+                // errors of calls point to the whole call, including the callee
+                let position = expr.position().merge(position);
+
+                let Value::Function(callable) = callee else {
+                    return Err(RuntimeError::MissingImplementation {
+                        name: callee.to_string(),
+                        position,
+                    }
+                    .into());
+                };
+
+                self.call_value(&callable, values, &position)
+            }
+            Postfix::Index { expr, index, .. } => {
+                let values = self.array(expr, environment)?;
+                let index = self.index(expr, index, values.borrow().len(), environment)?;
+                let value = values.borrow()[index].clone();
+                Ok(value)
+            }
+            Postfix::PropertyAccess { expr, property, .. } => {
+                let target = self.expression(expr, environment)?;
+                self.property(target, &expr.get_info().type_id, property)
+            }
+            Postfix::OptionalPropertyAccess { expr, property, .. } => {
+                let target = self.expression(expr, environment)?;
+                match (target, &expr.get_info().type_id) {
+                    (Value::None, _) => Ok(Value::None),
+                    (target, Type::Optional(type_id)) => self.property(target, type_id, property),
+                    (target, type_id) => self.property(target, type_id, property),
+                }
+            }
+            // the error is returned from the enclosing function as it is
+            Postfix::Propagate { expr, .. } => match self.expression(expr, environment)? {
+                Value::Ok(value) => Ok(*value),
+                error => Err(Unwind::Return(error)),
+            },
+            Postfix::Cast { expr, info, .. } => {
+                Ok(self.expression(expr, environment)?.convert(&info.type_id))
+            }
+        }
+    }
+
+    /// Access a field of a struct or a method of the value `target` of type `type_id`.
+    fn property(
+        &mut self,
+        target: Value<'a>,
+        type_id: &Type,
+        property: &'a Id<Info>,
+    ) -> Eval<'a, Value<'a>> {
+        if let Value::Struct(_, fields) = &target {
+            let field = fields
+                .borrow()
+                .iter()
+                .find(|(name, _)| *name == property.name)
+                .map(|(_, value)| value.clone());
+
+            if let Some(field) = field {
+                return Ok(field);
+            }
+        }
+
+        let method = |type_id: &Type| {
+            self.methods
+                .get(type_id)
+                .and_then(|methods| methods.get(property.name.as_str()))
+        };
+
+        // fixed-size arrays share the methods of slices
+        let method = match type_id {
+            Type::FixedArray(inner, _) => {
+                method(type_id).or_else(|| method(&Type::Array(inner.clone())))
+            }
+            _ => method(type_id),
+        };
+
+        let callable = match method {
+            Some(Some(function)) => Callable::Function {
+                function,
+                environment: self.globals.clone(),
+                this: Some(target),
+            },
+            Some(None) => Callable::Declared(property.name.clone()),
+            None => {
+                return Err(RuntimeError::UndefinedValue {
+                    name: format!("{target}.{}", property.name),
+                    position: property.position.clone(),
+                }
+                .into())
+            }
+        };
+
+        Ok(Value::Function(Rc::new(callable)))
+    }
+
+    fn array(
+        &mut self,
+        expression: &'a Expression<Info>,
+        environment: &mut Environment<'a>,
+    ) -> Eval<'a, Rc<std::cell::RefCell<Vec<Value<'a>>>>> {
+        match self.expression(expression, environment)? {
+            Value::Array(values) => Ok(values),
+            value => Err(RuntimeError::UndefinedValue {
+                name: value.to_string(),
+                position: expression.position(),
+            }
+            .into()),
+        }
+    }
+
+    fn index(
+        &mut self,
+        array: &'a Expression<Info>,
+        index: &'a Expression<Info>,
+        length: usize,
+        environment: &mut Environment<'a>,
+    ) -> Eval<'a, usize> {
+        let Value::Integer(value) = self.expression(index, environment)? else {
+            unreachable!("indices are typechecked as integers")
+        };
+
+        // the typechecker already proved constant indices into fixed-size arrays to be in bounds
+        if bounds_check(&array.get_info().type_id, index) == BoundsCheck::InBounds {
+            return Ok(value as usize);
+        }
+
+        match usize::try_from(value) {
+            Ok(value) if value < length => Ok(value),
+            _ => Err(RuntimeError::IndexOutOfBounds {
+                index: value,
+                length,
+                position: index.position(),
+            }
+            .into()),
+        }
+    }
+
+    fn prefix(
+        &mut self,
+        prefix: &'a Prefix<Info>,
+        environment: &mut Environment<'a>,
+    ) -> Eval<'a, Value<'a>> {
+        let (Prefix::Negation { expr, position } | Prefix::Minus { expr, position }) = prefix;
+        let value = self.expression(expr, environment)?;
+
+        let value = match (prefix, value) {
+            (Prefix::Negation { .. }, Value::Boolean(value)) => Value::Boolean(!value),
+            (Prefix::Minus { .. }, Value::Integer(value)) => Value::Integer(Self::in_range(
+                value.checked_neg(),
+                &expr.get_info().type_id,
+                position,
+            )?),
+            (Prefix::Minus { .. }, Value::FloatingPoint(value)) => Value::FloatingPoint(-value),
+            (_, value) => {
+                return Err(RuntimeError::UnsupportedOperation {
+                    operands: vec![value.to_string()],
+                    position: position.clone(),
+                }
+                .into())
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn binary(
+        &mut self,
+        binary: &'a BinaryExpression<Info>,
+        environment: &mut Environment<'a>,
+    ) -> Eval<'a, Value<'a>> {
+        let BinaryExpression {
+            left,
+            right,
+            operator,
+            info,
+            position,
+        } = binary;
+
+        let left = self.expression(left, environment)?;
+
+        // the default is only evaluated if needed
+        if *operator == BinaryOperator::Coalesce {
+            return match left {
+                Value::None | Value::Err(_) => self.expression(right, environment),
+                Value::Ok(value) => Ok(*value),
+                value => Ok(value),
+            };
+        }
+
+        let right = self.expression(right, environment)?;
+
+        let in_range = |value| Self::in_range(value, &info.type_id, position);
+        let round = |value| match &info.type_id {
+            Type::FloatingPoint(float_type) => float_type.round(value),
+            _ => value,
+        };
+
+        let value = match (operator, &left, &right) {
+            (BinaryOperator::Equals, left, right) => Value::Boolean(left.equals(right)),
+            (BinaryOperator::NotEquals, left, right) => Value::Boolean(!left.equals(right)),
+            (BinaryOperator::Add, Value::Integer(l), Value::Integer(r)) => {
+                Value::Integer(in_range(l.checked_add(*r))?)
+            }
+            (BinaryOperator::Substract, Value::Integer(l), Value::Integer(r)) => {
+                Value::Integer(in_range(l.checked_sub(*r))?)
+            }
+            (BinaryOperator::Multiply, Value::Integer(l), Value::Integer(r)) => {
+                Value::Integer(in_range(l.checked_mul(*r))?)
+            }
+            (BinaryOperator::Divide, Value::Integer(_), Value::Integer(0)) => {
+                return Err(RuntimeError::DivisionByZero {
+                    position: position.clone(),
+                }
+                .into())
+            }
+            (BinaryOperator::Divide, Value::Integer(l), Value::Integer(r)) => {
+                Value::Integer(in_range(l.checked_div(*r))?)
+            }
+            (BinaryOperator::Add, Value::FloatingPoint(l), Value::FloatingPoint(r)) => {
+                Value::FloatingPoint(round(l + r))
+            }
+            (BinaryOperator::Add, Value::String(l), Value::String(r)) => {
+                Value::String(format!("{l}{r}"))
+            }
+            (BinaryOperator::Substract, Value::FloatingPoint(l), Value::FloatingPoint(r)) => {
+                Value::FloatingPoint(round(l - r))
+            }
+            (BinaryOperator::Multiply, Value::FloatingPoint(l), Value::FloatingPoint(r)) => {
+                Value::FloatingPoint(round(l * r))
+            }
+            (BinaryOperator::Divide, Value::FloatingPoint(l), Value::FloatingPoint(r)) => {
+                Value::FloatingPoint(round(l / r))
+            }
+            (
+                BinaryOperator::GreaterThan
+                | BinaryOperator::LessThan
+                | BinaryOperator::GreaterOrEqual
+                | BinaryOperator::LessOrEqual,
+                left,
+                right,
+            ) => {
+                let ordering = match (left, right) {
+                    (Value::Integer(l), Value::Integer(r)) => l.partial_cmp(r),
+                    (Value::FloatingPoint(l), Value::FloatingPoint(r)) => l.partial_cmp(r),
+                    (Value::Boolean(l), Value::Boolean(r)) => l.partial_cmp(r),
+                    (Value::Character(l), Value::Character(r)) => l.partial_cmp(r),
+                    (Value::String(l), Value::String(r)) => l.partial_cmp(r),
+                    _ => None,
+                };
+
+                // comparisons with NaN are always false
+                let result = ordering.is_some_and(|ordering| match operator {
+                    BinaryOperator::GreaterThan => ordering.is_gt(),
+                    BinaryOperator::LessThan => ordering.is_lt(),
+                    BinaryOperator::GreaterOrEqual => ordering.is_ge(),
+                    _ => ordering.is_le(),
+                });
+                Value::Boolean(result)
+            }
+            (_, left, right) => {
+                return Err(RuntimeError::UnsupportedOperation {
+                    operands: vec![left.to_string(), right.to_string()],
+                    position: position.clone(),
+                }
+                .into())
+            }
+        };
+
+        Ok(value)
+    }
+
+    /// Make sure that the result of an integer operation fits into its type (`None` if it does
+    /// not even fit into an `i128`).
+    fn in_range(value: Option<i128>, type_id: &Type, position: &Span) -> Eval<'a, i128> {
+        match (value, type_id) {
+            (Some(value), Type::Integer(integer_type)) if integer_type.contains(value) => Ok(value),
+            _ => Err(RuntimeError::Overflow {
+                position: position.clone(),
+            }
+            .into()),
+        }
+    }
+
+    fn call_value(
+        &mut self,
+        callable: &Callable<'a>,
+        args: Vec<Value<'a>>,
+        position: &Span,
+    ) -> Eval<'a, Value<'a>> {
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(RuntimeError::StackOverflow {
+                position: position.clone(),
+            }
+            .into());
+        }
+
+        self.depth += 1;
+        let result = match callable {
+            Callable::Function {
+                function,
+                environment,
+                this,
+            } => {
+                let mut environment = environment.clone();
+                environment.enter_scope();
+
+                if let Some(this) = this {
+                    environment.define("this", this.clone());
+                }
+                for (parameter, arg) in function.parameters.iter().zip(args) {
+                    environment.define(&parameter.name.name, arg);
+                }
+
+                self.block(&function.statements, &mut environment)
+            }
+            Callable::Lambda {
+                lambda:
+                    Lambda {
+                        parameters,
+                        expression,
+                        ..
+                    },
+                environment,
+            } => {
+                let mut environment = environment.clone();
+                environment.enter_scope();
+
+                for (LambdaParameter { name, .. }, arg) in parameters.iter().zip(args) {
+                    environment.define(&name.name, arg);
+                }
+
+                self.expression(expression, &mut environment)
+            }
+            Callable::Declared(name) => Err(RuntimeError::MissingImplementation {
+                name: name.clone(),
+                position: position.clone(),
+            }
+            .into()),
+            Callable::Intrinsic(intrinsic) => self.intrinsic(*intrinsic, args, position),
+        };
+        self.depth -= 1;
+
+        // a return only leaves the function it is part of
+        match result {
+            Err(Unwind::Return(value)) => Ok(value),
+            result => result,
+        }
+    }
+
+    fn intrinsic(
+        &mut self,
+        intrinsic: Intrinsic,
+        args: Vec<Value<'a>>,
+        position: &Span,
+    ) -> Eval<'a, Value<'a>> {
+        match (intrinsic, args.as_slice()) {
+            (Intrinsic::Assert, [Value::Boolean(true)]) => Ok(Value::Void),
+            (Intrinsic::Assert, _) => Err(RuntimeError::AssertionFailed {
+                values: None,
+                position: position.clone(),
+            }
+            .into()),
+            (Intrinsic::AssertEq, [left, right]) if left.equals(right) => Ok(Value::Void),
+            (Intrinsic::AssertEq, [left, right]) => Err(RuntimeError::AssertionFailed {
+                values: Some((left.to_string(), right.to_string())),
+                position: position.clone(),
+            }
+            .into()),
+            (Intrinsic::AssertEq, _) => unreachable!("assert_eq takes exactly two arguments"),
+            // present optional values are represented by the value itself
+            (Intrinsic::Some, [value]) => Ok(value.clone()),
+            (Intrinsic::Some, _) => unreachable!("some takes exactly one argument"),
+            (Intrinsic::Ok, [value]) => Ok(Value::Ok(Box::new(value.clone()))),
+            (Intrinsic::Err, [error]) => Ok(Value::Err(Box::new(error.clone()))),
+            (Intrinsic::Panic, [Value::String(message)]) => Err(RuntimeError::Panic {
+                message: message.clone(),
+                position: position.clone(),
+            }
+            .into()),
+            (Intrinsic::Ok | Intrinsic::Err | Intrinsic::Panic, _) => {
+                unreachable!("{} takes exactly one argument", intrinsic.name())
+            }
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+use crate::{
+    parser::ast::{Function, Lambda},
+    typechecker::{Intrinsic, Type, ValidatedTypeInformation},
+};
+
+use super::environment::Environment;
+
+/// Value of an evaluated expression. Arrays and structs are shared between all copies of a
+/// value, i.e., mutating a struct which was passed to a function is visible to the caller.
+#[derive(Debug, Clone)]
+pub enum Value<'a> {
+    /// Value of any integer type, the typechecker makes sure it is within the range of its type.
+    Integer(i128),
+    FloatingPoint(f64),
+    Boolean(bool),
+    Character(char),
+    String(String),
+    Void,
+    Array(Rc<RefCell<Vec<Value<'a>>>>),
+    Struct(String, Rc<RefCell<Vec<(String, Value<'a>)>>>),
+    Function(Rc<Callable<'a>>),
+    /// Value of something which was only declared, e.g., `declare Sys: System;`.
+    Declared(String),
+    /// A missing optional value. Present optional values are represented by the value itself.
+    None,
+    /// Successful value of a result.
+    Ok(Box<Value<'a>>),
+    /// Error of a result.
+    Err(Box<Value<'a>>),
+}
+
+/// Everything which can be called.
+#[derive(Debug)]
+pub enum Callable<'a> {
+    Function {
+        function: &'a Function<ValidatedTypeInformation>,
+        environment: Environment<'a>,
+        /// Instance the function was accessed on (if it is a method).
+        this: Option<Value<'a>>,
+    },
+    Lambda {
+        lambda: &'a Lambda<ValidatedTypeInformation>,
+        environment: Environment<'a>,
+    },
+    /// A function or method which was declared, but never defined.
+    Declared(String),
+    Intrinsic(Intrinsic),
+}
+
+impl Value<'_> {
+    /// Structural equality of two values. Functions are only equal to themselves.
+    pub fn equals(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Value::Integer(left), Value::Integer(right)) => left == right,
+            (Value::FloatingPoint(left), Value::FloatingPoint(right)) => left == right,
+            (Value::Boolean(left), Value::Boolean(right)) => left == right,
+            (Value::Character(left), Value::Character(right)) => left == right,
+            (Value::String(left), Value::String(right)) => left == right,
+            (Value::Void, Value::Void) => true,
+            (Value::Array(left), Value::Array(right)) => {
+                let (left, right) = (left.borrow(), right.borrow());
+                left.len() == right.len()
+                    && left
+                        .iter()
+                        .zip(right.iter())
+                        .all(|(left, right)| left.equals(right))
+            }
+            (Value::Struct(left_name, left), Value::Struct(right_name, right)) => {
+                let (left, right) = (left.borrow(), right.borrow());
+                left_name == right_name
+                    && left.len() == right.len()
+                    && left.iter().all(|(name, left)| {
+                        right
+                            .iter()
+                            .any(|(field, right)| field == name && left.equals(right))
+                    })
+            }
+            (Value::Function(left), Value::Function(right)) => Rc::ptr_eq(left, right),
+            (Value::Declared(left), Value::Declared(right)) => left == right,
+            (Value::None, Value::None) => true,
+            (Value::Ok(left), Value::Ok(right)) | (Value::Err(left), Value::Err(right)) => {
+                left.equals(right)
+            }
+            _ => false,
+        }
+    }
+
+    /// Convert this value to the given type (as checked by [`Type::conversion`]). Integers wrap
+    /// around, conversions from floating point numbers saturate and integers which are no valid
+    /// code point become the replacement character.
+    pub fn convert(self, target: &Type) -> Self {
+        match (self, target) {
+            (Value::Integer(value), Type::Integer(integer_type)) => {
+                Value::Integer(integer_type.wrap(value))
+            }
+            (Value::Integer(value), Type::FloatingPoint(float_type)) => {
+                Value::FloatingPoint(float_type.round(value as f64))
+            }
+            (Value::FloatingPoint(value), Type::Integer(integer_type)) => {
+                Value::Integer((value as i128).clamp(integer_type.min(), integer_type.max()))
+            }
+            (Value::FloatingPoint(value), Type::FloatingPoint(float_type)) => {
+                Value::FloatingPoint(float_type.round(value))
+            }
+            (Value::Character(value), Type::Integer(integer_type)) => {
+                Value::Integer(integer_type.wrap(u32::from(value).into()))
+            }
+            (Value::Boolean(value), Type::Integer(_)) => Value::Integer(value.into()),
+            (Value::Integer(value), Type::Character) => Value::Character(
+                u32::try_from(value)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .unwrap_or(char::REPLACEMENT_CHARACTER),
+            ),
+            (value, _) => value,
+        }
+    }
+}
+
+impl Display for Value<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Integer(value) => write!(f, "{value}"),
+            Value::FloatingPoint(value) => write!(f, "{value:?}"),
+            Value::Boolean(value) => write!(f, "{value}"),
+            Value::Character(value) => write!(f, "{value:?}"),
+            Value::String(value) => write!(f, "{value:?}"),
+            Value::Void => write!(f, "void"),
+            Value::Array(values) => {
+                let values = values
+                    .borrow()
+                    .iter()
+                    .map(Value::to_string)
+                    .collect::<Vec<_>>();
+                write!(f, "[{}]", values.join(", "))
+            }
+            Value::Struct(name, fields) => {
+                let fields = fields
+                    .borrow()
+                    .iter()
+                    .map(|(name, value)| format!("{name}: {value}"))
+                    .collect::<Vec<_>>();
+                write!(f, "{name} {{ {} }}", fields.join(", "))
+            }
+            Value::Function(callable) => match callable.as_ref() {
+                Callable::Function { function, .. } => write!(f, "fn {}", function.id.name),
+                Callable::Lambda { .. } => write!(f, "lambda"),
+                Callable::Declared(name) => write!(f, "fn {name}"),
+                Callable::Intrinsic(intrinsic) => write!(f, "fn {}", intrinsic.name()),
+            },
+            Value::Declared(name) => write!(f, "{name}"),
+            Value::None => write!(f, "none"),
+            Value::Ok(value) => write!(f, "ok({value})"),
+            Value::Err(error) => write!(f, "err({error})"),
+        }
+    }
+}
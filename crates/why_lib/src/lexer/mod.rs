@@ -2,86 +2,127 @@ mod token;
 
 pub use token::*;
 
-use std::{error::Error, fmt::Display};
+use std::{error::Error, fmt::Display, sync::Arc};
+
+use crate::{
+    diagnostic::Diagnostic,
+    source_map::{FileId, SourceFile, SourceMap},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
-pub struct LexError(String);
+pub struct LexError {
+    pub message: String,
+    pub position: Span,
+}
 
 pub type LexResult<T> = Result<T, LexError>;
 
+impl LexError {
+    /// Stable code identifying this kind of error.
+    pub fn code(&self) -> &'static str {
+        "E0001"
+    }
+
+    pub fn span(&self) -> Span {
+        self.position.clone()
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::error(self.code(), &self.message).with_primary(self.span(), None)
+    }
+}
+
 impl Display for LexError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.0.as_str())
+        f.write_str(&self.diagnostic().to_string())
     }
 }
 
 impl Error for LexError {}
 
-pub struct Lexer<'a> {
+pub struct Lexer {
     tokens: Vec<Token>,
     lexikon: Lexikon,
     position: usize,
-    col: usize,
-    line: usize,
-    input: &'a str,
+    /// Byte offset at which lexing stops.
+    end: usize,
+    file: FileId,
+    source: Arc<SourceFile>,
 }
 
-impl<'a> Lexer<'a> {
-    pub fn new(input: &'a str) -> Self {
+impl Lexer {
+    /// Lex a source without a name, which is added to the source map as a new file.
+    pub fn new(input: &str) -> Self {
+        Self::for_file(SourceMap::add(input))
+    }
+
+    /// Lex a file of the source map.
+    pub fn for_file(file: FileId) -> Self {
+        let source = SourceMap::get(file).expect("file should be part of the source map");
+
         Self {
             tokens: vec![],
             lexikon: Lexikon::new(),
             position: 0,
-            col: 0,
-            line: 0,
-            input,
+            end: source.source().len(),
+            file,
+            source,
         }
     }
 
-    fn eat_whitespace(&mut self) {
-        while let Some(c) = self.input.as_bytes().get(self.position) {
-            if !c.is_ascii_whitespace() {
-                return;
-            }
-
-            if *c == b'\n' {
-                self.line += 1;
-                self.col = 0;
-            } else {
-                self.col += 1;
-            }
-            self.position += 1;
+    /// Lex only the part of a file covered by the given span, e.g., an expression which is
+    /// interpolated into a string.
+    pub fn for_span(span: &Span) -> Self {
+        Self {
+            position: span.start,
+            end: span.end,
+            ..Self::for_file(span.file)
         }
     }
 
+    fn eat_whitespace(&mut self) {
+        self.position += self.source.source()[self.position..self.end]
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .map(char::len_utf8)
+            .sum::<usize>();
+    }
+
     pub fn lex(mut self) -> LexResult<Vec<Token>> {
-        while self.position != self.input.len() {
+        let source = self.source.clone();
+        let input = &source.source()[..self.end];
+
+        while self.position != input.len() {
             self.eat_whitespace();
             let (len, res) = self
                 .lexikon
-                .find_longest_match(
-                    &self.input[self.position..],
-                    (self.line, self.col),
-                    self.input.to_string(),
-                )
-                .clone();
+                .find_longest_match(&input[self.position..], (self.file, self.position));
 
             match res {
-                Some(t) => self.tokens.push(t),
+                Some(Ok(t)) => self.tokens.push(t),
+                Some(Err(message)) => {
+                    return Err(LexError {
+                        message,
+                        position: Span::new(self.file, self.position, self.position + len),
+                    });
+                }
                 None => {
-                    if self.position == self.input.len() {
+                    if self.position == input.len() {
                         return Ok(self.tokens);
                     }
-                    return Err(LexError(format!(
-                        "Failed to lex '{}' at position {}; remaining '{}'",
-                        self.input,
-                        self.position,
-                        &self.input[self.position..]
-                    )));
+                    let remaining = &input[self.position..];
+                    let character = remaining.chars().next().unwrap_or_default();
+                    return Err(LexError {
+                        message: format!("Unexpected character '{character}'"),
+                        position: Span::new(
+                            self.file,
+                            self.position,
+                            self.position + character.len_utf8(),
+                        ),
+                    });
                 }
             };
             self.position += len;
-            self.col += len;
         }
 
         Ok(self.tokens)
@@ -118,6 +159,19 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_lex_too_large_integer() {
+        let error = Lexer::new("let x = 99999999999999999999;")
+            .lex()
+            .expect_err("should not fit into an integer");
+
+        assert_eq!(
+            error.message,
+            "Invalid literal '99999999999999999999' (number too large to fit in target type)"
+        );
+        assert_eq!((error.position.start, error.position.end), (8, 28));
+    }
+
     #[test]
     fn test_lex_function() {
         let lexer = Lexer::new("fn () {}");
@@ -171,4 +225,127 @@ mod tests {
             lexer.lex()
         );
     }
+
+    #[test]
+    fn test_lex_positions() {
+        let file = SourceMap::add("let foo\n    = 42;");
+        let tokens = Lexer::for_file(file).lex().unwrap();
+
+        assert_eq!(
+            tokens.iter().map(GetPosition::position).collect::<Vec<_>>(),
+            vec![
+                Span::new(file, 0, 3),
+                Span::new(file, 4, 7),
+                Span::new(file, 12, 13),
+                Span::new(file, 14, 16),
+                Span::new(file, 16, 17),
+            ]
+        );
+        assert_ne!(tokens[0].position(), tokens[1].position());
+        assert_ne!(tokens[0].position(), Span::default());
+        assert_eq!(tokens[2].position().start_line_col(), (1, 4));
+        assert_eq!(tokens[3].position().end_line_col(), (1, 8));
+    }
+
+    #[test]
+    fn test_lex_error_position() {
+        let file = SourceMap::add("let ä = 1 § 2;");
+
+        assert_eq!(
+            Lexer::for_file(file).lex().map_err(|e| e.span()),
+            Err(Span::new(file, 11, 13))
+        );
+    }
+
+    #[test]
+    fn test_lex_strings() {
+        let tokens = Lexer::new("\"foo\" + \"bar\"").lex().unwrap();
+
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| format!("{token:?}"))
+                .collect::<Vec<_>>(),
+            vec![
+                "String { value: \"\\\"foo\\\"\" }",
+                "Plus",
+                "String { value: \"\\\"bar\\\"\" }"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lex_span() {
+        let file = SourceMap::add("\"Hello { name }!\"");
+        let tokens = Lexer::for_span(&Span::new(file, 8, 14)).lex().unwrap();
+
+        assert_eq!(
+            tokens.iter().map(Token::position).collect::<Vec<_>>(),
+            vec![Span::new(file, 9, 13)]
+        );
+    }
+
+    #[test]
+    fn test_lex_unicode() {
+        let file = SourceMap::add("// größe\nlet größe = \"😀\";\u{3000}_x1 µs");
+        let tokens = Lexer::for_file(file).lex().unwrap();
+
+        assert_eq!(
+            tokens
+                .iter()
+                .filter_map(|token| match token {
+                    Token::Id { value, .. } => Some(value.as_str()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+            vec!["größe", "_x1", "µs"]
+        );
+
+        // the ideographic space is whitespace as well
+        let underscore = tokens[6].position();
+        assert_eq!(underscore.start_line_col(), (1, 24));
+        assert_eq!(underscore.start_chars(), (1, 17));
+        assert_eq!(underscore.start_utf16(), (1, 18));
+
+        let string = tokens[4].position();
+        assert_eq!(string.end_chars(), (1, 15));
+        assert_eq!(string.end_utf16(), (1, 16));
+    }
+
+    #[test]
+    fn test_lex_longest_match() {
+        let lexer = Lexer::new("a>=b<=c==d=>e->f//g\nletx let/h!=i?.j??k?");
+
+        assert_eq!(
+            lexer.lex().map(|tokens| tokens
+                .iter()
+                .map(|token| format!("{token:?}"))
+                .collect::<Vec<_>>()),
+            Ok(vec![
+                "Id { value: \"a\" }".into(),
+                "GreaterOrEqual".into(),
+                "Id { value: \"b\" }".into(),
+                "LessOrEqual".into(),
+                "Id { value: \"c\" }".into(),
+                "Equal".into(),
+                "Id { value: \"d\" }".into(),
+                "BigRightArrow".into(),
+                "Id { value: \"e\" }".into(),
+                "SmallRightArrow".into(),
+                "Id { value: \"f\" }".into(),
+                "Comment { value: \"//g\" }".into(),
+                "Id { value: \"letx\" }".into(),
+                "Let".into(),
+                "Slash".into(),
+                "Id { value: \"h\" }".into(),
+                "NotEqual".into(),
+                "Id { value: \"i\" }".into(),
+                "QuestionDot".into(),
+                "Id { value: \"j\" }".into(),
+                "DoubleQuestionMark".into(),
+                "Id { value: \"k\" }".into(),
+                "QuestionMark".into(),
+            ])
+        );
+    }
 }
@@ -1,91 +1,88 @@
-use colored::Colorize;
-
 use lex_derive::{LooseEq, Token as ParseToken};
-use regex::{Match, Regex};
+use regex::Regex;
+
+use std::sync::Arc;
 
-#[derive(Default, Debug, Clone, Eq, serde::Serialize, serde::Deserialize)]
+use crate::{
+    diagnostic::render_span,
+    source_map::{FileId, SourceFile, SourceMap},
+};
+
+/// A range of bytes within a file of the source map. Lines and columns are computed from the
+/// source map when needed.
+#[derive(Default, Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Span {
-    pub start: (usize, usize),
-    pub end: (usize, usize),
-    pub source: String,
+    pub file: FileId,
+    /// Byte offset of the first character.
+    pub start: usize,
+    /// Byte offset after the last character.
+    pub end: usize,
 }
 
 impl Span {
-    pub fn to_string(&self, msg: impl ToString) -> String {
-        let Span { start, end, source } = self;
-        let line = start.0;
-        let lines = source.lines().collect::<Vec<_>>();
-        let prev_line = if line > 0 { lines[line - 1] } else { "" };
-        let line_str = if lines.len() > line { lines[line] } else { "" };
-
-        // margin _before_ left border
-        let left_margin = format!("{}", end.0).len();
-        let left_margin_fill = vec![' '; left_margin].iter().collect::<String>();
-
-        // split right at the start of the error in the first line
-        let (left, right) = line_str.split_at(start.1);
-
-        // some case magic
-        let (left, right) = if start.0 != end.0 {
-            // if the error ranges over more than a single line, we can just mark rest of the line
-            // as an error
-            (left.to_string(), right.to_string().red().to_string())
-        } else {
-            // however, if the lines does not range beyond this line, we need to split at the end
-            // again
-            let (err_str, after_err) = right.split_at(end.1 - start.1);
-
-            // now, just color the error part red
-            (
-                left.to_string(),
-                format!("{err_str}{after_err}", err_str = err_str.to_string().red()),
-            )
-        };
-
-        // and concatentate both together
-        let line_str = format!("{left}{right}");
-
-        // padding between border and squiggles
-        let left_padding_fill = vec![' '; if end.1 > 0 { end.1 - 1 } else { 0 }]
-            .iter()
-            .collect::<String>();
-
-        // the error with the first line
-        let mut error_string = format!(
-            "{left_margin_fill} |\n{left_margin_fill} |{prev_line} \n{line} |{line_str}",
-            line = line + 1
-        );
-
-        // iterate over all lines of the error and make them shine red
-        ((start.0 + 1)..(end.0 + 1)).for_each(|line_number| {
-            error_string = format!(
-                "{error_string}\n{left_margin_fill} |{}",
-                lines[line_number].to_string().red()
-            );
-        });
-
-        // actually add error message at bottom
-        error_string = format!(
-            "{error_string}\n{} |{left_padding_fill}^--- {}\n{left_margin_fill} |",
-            end.0 + 2,
-            msg.to_string()
-        );
-
-        error_string
+    pub fn new(file: FileId, start: usize, end: usize) -> Self {
+        Span { file, start, end }
     }
 
-    pub fn merge(&self, other: &Span) -> Span {
-        let Span { start, source, .. } = self.clone();
-        let Span { end, .. } = other.clone();
+    /// Whether this span does not point into any file (e.g., `Span::default()`).
+    pub fn is_dummy(&self) -> bool {
+        self.file.is_none()
+    }
 
-        Span { start, end, source }
+    pub fn source_file(&self) -> Option<Arc<SourceFile>> {
+        SourceMap::get(self.file)
+    }
+
+    /// Line and byte column of the start of this span (both zero based).
+    pub fn start_line_col(&self) -> (usize, usize) {
+        self.line_col(self.start, SourceFile::line_col)
+    }
+
+    /// Line and byte column of the end of this span (both zero based).
+    pub fn end_line_col(&self) -> (usize, usize) {
+        self.line_col(self.end, SourceFile::line_col)
+    }
+
+    /// Line and character column of the start of this span (both zero based).
+    pub fn start_chars(&self) -> (usize, usize) {
+        self.line_col(self.start, SourceFile::line_col_chars)
     }
-}
 
-impl PartialEq<Span> for Span {
-    fn eq(&self, _other: &Span) -> bool {
-        // TODO: maybe this should not be the case...
-        true
+    /// Line and character column of the end of this span (both zero based).
+    pub fn end_chars(&self) -> (usize, usize) {
+        self.line_col(self.end, SourceFile::line_col_chars)
+    }
+
+    /// Line and UTF-16 column of the start of this span (both zero based).
+    pub fn start_utf16(&self) -> (usize, usize) {
+        self.line_col(self.start, SourceFile::line_col_utf16)
+    }
+
+    /// Line and UTF-16 column of the end of this span (both zero based).
+    pub fn end_utf16(&self) -> (usize, usize) {
+        self.line_col(self.end, SourceFile::line_col_utf16)
+    }
+
+    fn line_col(
+        &self,
+        offset: usize,
+        convert: impl Fn(&SourceFile, usize) -> (usize, usize),
+    ) -> (usize, usize) {
+        self.source_file()
+            .map(|file| convert(&file, offset))
+            .unwrap_or_default()
+    }
+
+    /// Render the source lines of this span, labelled with the given message.
+    pub fn to_string(&self, msg: impl ToString) -> String {
+        render_span(self, msg)
+    }
+
+    pub fn merge(&self, other: &Span) -> Span {
+        Span {
+            end: other.end,
+            ..self.clone()
+        }
     }
 }
 
@@ -99,7 +96,7 @@ pub enum Token {
     Const { position: Span },
     #[terminal("mut")]
     Mut { position: Span },
-    #[literal("[a-zA-Z_][a-zA-Z0-9_]*")]
+    #[literal("[_\\p{XID_Start}]\\p{XID_Continue}*")]
     Id { value: String, position: Span },
     #[literal("[0-9]+")]
     Integer { value: u64, position: Span },
@@ -107,7 +104,7 @@ pub enum Token {
     FloatingPoint { value: f64, position: Span },
     #[literal("'.'")]
     Character { value: String, position: Span },
-    #[literal("\".*\"")]
+    #[literal("\"[^\"\n]*\"")]
     String { value: String, position: Span },
     #[terminal(";")]
     Semicolon { position: Span },
@@ -119,6 +116,8 @@ pub enum Token {
     Minus { position: Span },
     #[terminal("*")]
     Times { position: Span },
+    #[terminal("/")]
+    Slash { position: Span },
     #[terminal("(")]
     LParen { position: Span },
     #[terminal(")")]
@@ -155,13 +154,15 @@ pub enum Token {
     Backslash { position: Span },
     #[terminal("==")]
     Equal { position: Span },
+    #[terminal("!=")]
+    NotEqual { position: Span },
     #[terminal(">")]
     GreaterThan { position: Span },
     #[terminal("<")]
     LessThan { position: Span },
     #[terminal(">=")]
     GreaterOrEqual { position: Span },
-    #[terminal("<0")]
+    #[terminal("<=")]
     LessOrEqual { position: Span },
     #[terminal("&")]
     Ampersand { position: Span },
@@ -177,6 +178,14 @@ pub enum Token {
     ExclamationMark { position: Span },
     #[terminal("#")]
     Hash { position: Span },
+    #[terminal("as")]
+    AsKeyword { position: Span },
+    #[terminal("?")]
+    QuestionMark { position: Span },
+    #[terminal("?.")]
+    QuestionDot { position: Span },
+    #[terminal("??")]
+    DoubleQuestionMark { position: Span },
 }
 
 impl std::fmt::Debug for Token {
@@ -201,6 +210,7 @@ impl std::fmt::Debug for Token {
             Self::Plus { .. } => f.debug_struct("Plus").finish(),
             Self::Minus { .. } => f.debug_struct("Minus").finish(),
             Self::Times { .. } => f.debug_struct("Times").finish(),
+            Self::Slash { .. } => f.debug_struct("Slash").finish(),
             Self::LParen { .. } => f.debug_struct("LParen").finish(),
             Self::RParen { .. } => f.debug_struct("RParen").finish(),
             Self::LBrace { .. } => f.debug_struct("LBrace").finish(),
@@ -219,6 +229,7 @@ impl std::fmt::Debug for Token {
             Self::BigRightArrow { .. } => f.debug_struct("BigRightArrow").finish(),
             Self::Backslash { .. } => f.debug_struct("Backslash").finish(),
             Self::Equal { .. } => f.debug_struct("Equal").finish(),
+            Self::NotEqual { .. } => f.debug_struct("NotEqual").finish(),
             Self::GreaterThan { .. } => f.debug_struct("GreaterThan").finish(),
             Self::LessThan { .. } => f.debug_struct("LessThan").finish(),
             Self::GreaterOrEqual { .. } => f.debug_struct("GreaterOrEqual").finish(),
@@ -230,6 +241,10 @@ impl std::fmt::Debug for Token {
             Self::InstanceKeyword { .. } => f.debug_struct("InstanceKeyword").finish(),
             Self::ExclamationMark { .. } => f.debug_struct("ExclamationMark").finish(),
             Self::Hash { .. } => f.debug_struct("Hash").finish(),
+            Self::AsKeyword { .. } => f.debug_struct("AsKeyword").finish(),
+            Self::QuestionMark { .. } => f.debug_struct("QuestionMark").finish(),
+            Self::QuestionDot { .. } => f.debug_struct("QuestionDot").finish(),
+            Self::DoubleQuestionMark { .. } => f.debug_struct("DoubleQuestionMark").finish(),
         }
     }
 }
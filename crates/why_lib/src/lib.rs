@@ -1,3 +1,13 @@
+#[cfg(test)]
+#[macro_use]
+mod testing;
+
+pub mod cst;
+pub mod diagnostic;
+pub mod formatter;
+pub mod interpreter;
 pub mod lexer;
 pub mod parser;
+pub mod source_map;
+pub mod test_runner;
 pub mod typechecker;
@@ -3,7 +3,7 @@ use crate::{
     parser::{ast::AstNode, combinators::Comb, FromTokens, ParseError, ParseState},
 };
 
-use super::{Expression, Num};
+use super::Expression;
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum Array<T> {
@@ -14,7 +14,7 @@ pub enum Array<T> {
     },
     Default {
         initial_value: Box<Expression<T>>,
-        length: Num<T>,
+        length: Box<Expression<T>>,
         info: T,
         position: Span,
     },
@@ -63,19 +63,19 @@ impl FromTokens<Token> for Array<()> {
         }
         tokens.set_index(start);
 
-        let matcher = Comb::LBRACKET >> Comb::EXPR >> Comb::SEMI >> Comb::NUM >> Comb::RBRACKET;
+        let matcher = Comb::LBRACKET >> Comb::EXPR >> Comb::SEMI >> Comb::EXPR >> Comb::RBRACKET;
         if let Ok(result) = matcher.parse(tokens) {
             let Some(AstNode::Expression(initial_value)) = result.first().cloned() else {
                 unreachable!()
             };
 
-            let Some(AstNode::Num(length)) = result.get(1).cloned() else {
+            let Some(AstNode::Expression(length)) = result.get(1).cloned() else {
                 unreachable!()
             };
 
             return Ok(Array::Default {
                 initial_value: Box::new(initial_value),
-                length,
+                length: Box::new(length),
                 info: (),
                 position,
             }
@@ -97,7 +97,10 @@ impl From<Array<()>> for AstNode {
 
 #[cfg(test)]
 mod tests {
-    use crate::lexer::{Lexer, Span};
+    use crate::{
+        lexer::{Lexer, Span},
+        parser::ast::{BinaryExpression, BinaryOperator, Id, Num},
+    };
 
     use super::*;
 
@@ -106,7 +109,7 @@ mod tests {
         let mut tokens = Lexer::new("[]").lex().expect("something is wrong").into();
 
         let result = Array::parse(&mut tokens);
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Array::Literal {
                 values: vec![],
                 info: (),
@@ -125,7 +128,7 @@ mod tests {
             .into();
 
         let result = Array::parse(&mut tokens);
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Array::Literal {
                 values: vec![
                     Expression::Num(Num::Integer(42, (), Span::default())),
@@ -147,10 +150,40 @@ mod tests {
             .into();
 
         let result = Array::parse(&mut tokens);
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Array::Default {
                 initial_value: Box::new(Expression::Num(Num::Integer(42, (), Span::default()))),
-                length: Num::Integer(5, (), Span::default()),
+                length: Box::new(Expression::Num(Num::Integer(5, (), Span::default()))),
+                info: (),
+                position: Span::default()
+            }
+            .into()),
+            result
+        );
+    }
+
+    #[test]
+    fn test_default_with_constant_length() {
+        let mut tokens = Lexer::new("[0; N * 2]")
+            .lex()
+            .expect("something is wrong")
+            .into();
+
+        let result = Array::parse(&mut tokens);
+        assert_eq_ignoring_span!(
+            Ok(Array::Default {
+                initial_value: Box::new(Expression::Num(Num::Integer(0, (), Span::default()))),
+                length: Box::new(Expression::Binary(Box::new(BinaryExpression {
+                    left: Expression::Id(Id {
+                        name: "N".into(),
+                        info: (),
+                        position: Span::default()
+                    }),
+                    right: Expression::Num(Num::Integer(2, (), Span::default())),
+                    operator: BinaryOperator::Multiply,
+                    info: (),
+                    position: Span::default()
+                }))),
                 info: (),
                 position: Span::default()
             }
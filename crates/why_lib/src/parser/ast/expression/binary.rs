@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use crate::lexer::Span;
 
 use super::Expression;
@@ -9,10 +11,31 @@ pub enum BinaryOperator {
     Multiply,
     Divide,
     Equals,
+    NotEquals,
     GreaterThan,
     LessThan,
     GreaterOrEqual,
     LessOrEqual,
+    /// `a ?? b` evaluates to `b`, if `a` is `none`.
+    Coalesce,
+}
+
+impl Display for BinaryOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BinaryOperator::Add => "+",
+            BinaryOperator::Substract => "-",
+            BinaryOperator::Multiply => "*",
+            BinaryOperator::Divide => "/",
+            BinaryOperator::Equals => "==",
+            BinaryOperator::NotEquals => "!=",
+            BinaryOperator::GreaterThan => ">",
+            BinaryOperator::LessThan => "<",
+            BinaryOperator::GreaterOrEqual => ">=",
+            BinaryOperator::LessOrEqual => "<=",
+            BinaryOperator::Coalesce => "??",
+        })
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -82,9 +105,11 @@ impl BinaryExpression<()> {
 
     pub fn precedence(&self) -> usize {
         match self.operator {
-            BinaryOperator::Add | BinaryOperator::Substract => 1,
-            BinaryOperator::Multiply | BinaryOperator::Divide => 2,
+            BinaryOperator::Coalesce => 1,
+            BinaryOperator::Add | BinaryOperator::Substract => 2,
+            BinaryOperator::Multiply | BinaryOperator::Divide => 3,
             BinaryOperator::Equals
+            | BinaryOperator::NotEquals
             | BinaryOperator::GreaterThan
             | BinaryOperator::LessThan
             | BinaryOperator::GreaterOrEqual
@@ -69,7 +69,7 @@ mod tests {
 
         let result = Block::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Block {
                 statements: vec![],
                 info: (),
@@ -89,7 +89,7 @@ mod tests {
 
         let result = Block::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Block {
                 statements: vec![Statement::YieldingExpression(Expression::Id(Id {
                     name: "x".into(),
@@ -118,7 +118,7 @@ mod tests {
 
         let result = Block::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Block {
                 statements: vec![
                     Statement::Initialization(Initialisation {
@@ -128,7 +128,7 @@ mod tests {
                             position: Span::default()
                         },
                         mutable: false,
-                        value: Expression::Num(Num::Integer(42, (), Span::default())),
+                        value: Some(Expression::Num(Num::Integer(42, (), Span::default()))),
                         type_name: None,
                         info: (),
                         position: Span::default()
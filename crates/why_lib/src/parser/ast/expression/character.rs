@@ -55,7 +55,7 @@ mod tests {
         let mut tokens = Lexer::new("'a'").lex().expect("should work").into();
         let result = Character::parse(&mut tokens).expect("should work");
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             Character {
                 character: 'a',
@@ -71,7 +71,7 @@ mod tests {
         let mut tokens = Lexer::new("'\t'").lex().expect("should work").into();
         let result = Character::parse(&mut tokens).expect("should work");
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             Character {
                 character: '\t',
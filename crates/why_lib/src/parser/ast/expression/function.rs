@@ -11,6 +11,7 @@ use super::Id;
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Function<T> {
+    pub attributes: Vec<Attribute>,
     pub id: Id<T>,
     pub parameters: Vec<FunctionParameter<T>>,
     pub return_type: TypeName,
@@ -23,7 +24,8 @@ impl FromTokens<Token> for Function<()> {
     fn parse(tokens: &mut ParseState<Token>) -> Result<AstNode, ParseError> {
         let position = tokens.span()?;
 
-        let matcher = Comb::FN_KEYWORD
+        let matcher = (Comb::ATTRIBUTE ^ ())
+            >> Comb::FN_KEYWORD
             >> Comb::ID
             >> Comb::LPAREN
             // parameter list (optional)
@@ -37,6 +39,14 @@ impl FromTokens<Token> for Function<()> {
 
         let mut result = matcher.parse(tokens)?.into_iter().peekable();
 
+        let mut attributes = vec![];
+
+        while let Some(AstNode::Attribute(attribute)) =
+            result.next_if(|item| matches!(item, AstNode::Attribute(_)))
+        {
+            attributes.push(attribute);
+        }
+
         let Some(AstNode::Id(id)) = result.next() else {
             unreachable!()
         };
@@ -57,13 +67,16 @@ impl FromTokens<Token> for Function<()> {
             unreachable!();
         };
 
+        let end = tokens.prev_span()?;
+
         Ok(Function {
+            attributes,
             id,
             parameters,
             return_type,
             statements: block.statements,
             info: (),
-            position,
+            position: position.merge(&end),
         }
         .into())
     }
@@ -75,6 +88,49 @@ impl From<Function<()>> for AstNode {
     }
 }
 
+impl<T> Function<T> {
+    /// Whether this function is annotated with the attribute of the given name.
+    pub fn has_attribute(&self, name: &str) -> bool {
+        self.attributes
+            .iter()
+            .any(|attribute| attribute.name == name)
+    }
+}
+
+/// Attribute of a toplevel function, e.g., `#[test]`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Attribute {
+    pub name: String,
+    pub position: Span,
+}
+
+impl FromTokens<Token> for Attribute {
+    fn parse(tokens: &mut ParseState<Token>) -> Result<AstNode, ParseError> {
+        let position = tokens.span()?;
+
+        let matcher = Comb::HASH >> Comb::LBRACKET >> Comb::ID >> Comb::RBRACKET;
+        let result = matcher.parse(tokens)?;
+
+        let Some(AstNode::Id(Id { name, .. })) = result.first().cloned() else {
+            unreachable!()
+        };
+
+        let end = tokens.prev_span()?;
+
+        Ok(Attribute {
+            name,
+            position: position.merge(&end),
+        }
+        .into())
+    }
+}
+
+impl From<Attribute> for AstNode {
+    fn from(value: Attribute) -> Self {
+        AstNode::Attribute(value)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct FunctionParameter<T> {
     pub name: Id<T>,
@@ -131,8 +187,9 @@ mod tests {
 
         let result = Function::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Function {
+                attributes: vec![],
                 id: Id {
                     name: "foo".into(),
                     info: (),
@@ -158,8 +215,9 @@ mod tests {
 
         let result = Function::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Function {
+                attributes: vec![],
                 id: Id {
                     name: "foo".into(),
                     info: (),
@@ -194,8 +252,9 @@ mod tests {
 
         let result = Function::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Function {
+                attributes: vec![],
                 id: Id {
                     name: "foo".into(),
                     info: (),
@@ -242,8 +301,9 @@ mod tests {
 
         let result = Function::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Function {
+                attributes: vec![],
                 id: Id {
                     name: "foo".into(),
                     info: (),
@@ -306,8 +366,9 @@ mod tests {
 
         let result = Function::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Function {
+                attributes: vec![],
                 id: Id {
                     name: "main".into(),
                     info: (),
@@ -360,4 +421,41 @@ mod tests {
             result
         )
     }
+
+    #[test]
+    fn test_function_with_attributes() {
+        let mut tokens = Lexer::new("#[test]\n#[ignore]\nfn foo(): void {}")
+            .lex()
+            .expect("something is wrong")
+            .into();
+
+        let result = Function::parse(&mut tokens);
+
+        assert_eq_ignoring_span!(
+            Ok(Function {
+                attributes: vec![
+                    Attribute {
+                        name: "test".into(),
+                        position: Span::default()
+                    },
+                    Attribute {
+                        name: "ignore".into(),
+                        position: Span::default()
+                    }
+                ],
+                id: Id {
+                    name: "foo".into(),
+                    info: (),
+                    position: Span::default()
+                },
+                parameters: vec![],
+                return_type: TypeName::Literal("void".into(), Span::default()),
+                statements: vec![],
+                info: (),
+                position: Span::default()
+            }
+            .into()),
+            result
+        )
+    }
 }
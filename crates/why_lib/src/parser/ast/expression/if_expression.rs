@@ -53,11 +53,7 @@ impl FromTokens<Token> for If<()> {
             statements: if_block.statements,
             else_statements,
             info: (),
-            position: Span {
-                start: position.start,
-                end,
-                source: position.source,
-            },
+            position: Span { end, ..position },
         }
         .into())
     }
@@ -82,7 +78,7 @@ mod tests {
     fn test_simple_if() {
         let mut tokens = Lexer::new("if (x) {}").lex().expect("should work").into();
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(If {
                 condition: Box::new(Expression::Id(Id {
                     name: "x".into(),
@@ -106,7 +102,7 @@ mod tests {
             .expect("should work")
             .into();
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(If {
                 condition: Box::new(Expression::Id(Id {
                     name: "x".into(),
@@ -130,7 +126,7 @@ mod tests {
             .expect("should work")
             .into();
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(If {
                 condition: Box::new(Expression::Id(Id {
                     name: "x".into(),
@@ -162,7 +158,7 @@ mod tests {
             .expect("should work")
             .into();
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(If {
                 condition: Box::new(Expression::Id(Id {
                     name: "x".into(),
@@ -0,0 +1,277 @@
+use crate::lexer::{GetPosition, Lexer, Span};
+use crate::parser::ast::AstNode;
+use crate::parser::{FromTokens, ParseError, ParseState};
+
+use super::Expression;
+
+/// String with expressions interpolated into it, e.g., `"Hello {name}!"`. Braces which are part
+/// of the text are written twice (`{{` and `}}`).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Interpolation<T> {
+    pub segments: Vec<InterpolationSegment<T>>,
+    pub info: T,
+    pub position: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum InterpolationSegment<T> {
+    Text(String),
+    Expression(Expression<T>),
+}
+
+impl<T> Interpolation<T> {
+    /// All expressions interpolated into this string.
+    pub fn expressions(&self) -> impl Iterator<Item = &Expression<T>> {
+        self.segments.iter().filter_map(|segment| match segment {
+            InterpolationSegment::Text(_) => None,
+            InterpolationSegment::Expression(expression) => Some(expression),
+        })
+    }
+}
+
+impl Interpolation<()> {
+    /// Split the content of a string literal (without its quotes) into text and interpolated
+    /// expressions. The content starts at byte offset `start` within the file of `position`.
+    pub fn parse_segments(
+        content: &str,
+        start: usize,
+        position: Span,
+    ) -> Result<Interpolation<()>, ParseError> {
+        let mut segments = vec![];
+        let mut text = String::new();
+
+        let mut chars = content.char_indices().peekable();
+
+        while let Some((index, character)) = chars.next() {
+            match character {
+                '{' | '}' if chars.peek().map(|(_, next)| *next) == Some(character) => {
+                    chars.next();
+                    text.push(character);
+                }
+                '{' => {
+                    // the interpolated expression might contain braces itself, e.g., a block
+                    let mut depth = 1;
+                    let end = chars.find_map(|(index, character)| {
+                        match character {
+                            '{' => depth += 1,
+                            '}' => depth -= 1,
+                            _ => {}
+                        }
+                        (depth == 0).then_some(index)
+                    });
+
+                    let brace = Span::new(position.file, start + index, start + index + 1);
+
+                    let Some(end) = end else {
+                        return Err(ParseError {
+                            message: "Unterminated interpolation in string".into(),
+                            position: Some(brace),
+                        });
+                    };
+
+                    if !text.is_empty() {
+                        segments.push(InterpolationSegment::Text(std::mem::take(&mut text)));
+                    }
+
+                    let span = Span::new(position.file, start + index + 1, start + end);
+                    segments.push(InterpolationSegment::Expression(Self::parse_expression(
+                        &span, brace,
+                    )?));
+                }
+                '}' => {
+                    return Err(ParseError {
+                        message: "Unmatched '}' in string (use '}}' for a literal brace)".into(),
+                        position: Some(Span::new(position.file, start + index, start + index + 1)),
+                    });
+                }
+                character => text.push(character),
+            }
+        }
+
+        if !text.is_empty() {
+            segments.push(InterpolationSegment::Text(text));
+        }
+
+        Ok(Interpolation {
+            segments,
+            info: (),
+            position,
+        })
+    }
+
+    fn parse_expression(span: &Span, brace: Span) -> Result<Expression<()>, ParseError> {
+        let tokens = Lexer::for_span(span).lex().map_err(|e| ParseError {
+            message: e.message,
+            position: Some(e.position),
+        })?;
+
+        if tokens.is_empty() {
+            return Err(ParseError {
+                message: "Expected an expression in interpolation".into(),
+                position: Some(brace),
+            });
+        }
+
+        let mut tokens = ParseState::from(tokens);
+
+        let Ok(AstNode::Expression(expression)) = Expression::parse(&mut tokens) else {
+            // errors within nested strings are more specific
+            return Err(tokens.errors.first().cloned().unwrap_or(ParseError {
+                message: "Expected an expression in interpolation".into(),
+                position: Some(span.clone()),
+            }));
+        };
+
+        if let Some(token) = tokens.peek() {
+            return Err(ParseError {
+                message: "Unexpected token in interpolation".into(),
+                position: Some(token.position()),
+            });
+        }
+
+        Ok(expression)
+    }
+}
+
+impl From<Interpolation<()>> for AstNode {
+    fn from(interpolation: Interpolation<()>) -> AstNode {
+        AstNode::Interpolation(interpolation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lexer::Lexer,
+        parser::ast::{AstString, BinaryExpression, BinaryOperator, Id, Num},
+    };
+
+    #[test]
+    fn test_interpolation_parse() {
+        let mut tokens = Lexer::new("\"Hello {name}, {1 + 2}!\"")
+            .lex()
+            .expect("should work")
+            .into();
+        let result = AstString::parse(&mut tokens).expect("should work");
+
+        assert_eq_ignoring_span!(
+            result,
+            Interpolation {
+                segments: vec![
+                    InterpolationSegment::Text("Hello ".into()),
+                    InterpolationSegment::Expression(Expression::Id(Id {
+                        name: "name".into(),
+                        info: (),
+                        position: Span::default()
+                    })),
+                    InterpolationSegment::Text(", ".into()),
+                    InterpolationSegment::Expression(Expression::Binary(Box::new(
+                        BinaryExpression {
+                            left: Expression::Num(Num::Integer(1, (), Span::default())),
+                            right: Expression::Num(Num::Integer(2, (), Span::default())),
+                            operator: BinaryOperator::Add,
+                            info: (),
+                            position: Span::default()
+                        }
+                    ))),
+                    InterpolationSegment::Text("!".into()),
+                ],
+                info: (),
+                position: Span::default()
+            }
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_interpolation_positions() {
+        let mut tokens = Lexer::new("\"{{x}} is {x}\"")
+            .lex()
+            .expect("should work")
+            .into();
+        let result = AstString::parse(&mut tokens).expect("should work");
+
+        let AstNode::Interpolation(Interpolation { segments, .. }) = result else {
+            panic!("expected interpolation");
+        };
+
+        assert_eq!(segments[0], InterpolationSegment::Text("{x} is ".into()));
+        let InterpolationSegment::Expression(Expression::Id(Id { position, .. })) = &segments[1]
+        else {
+            panic!("expected interpolated id");
+        };
+        assert_eq!((position.start, position.end), (11, 12));
+    }
+
+    #[test]
+    fn test_escaped_braces() {
+        let mut tokens = Lexer::new("\"{{}}\"").lex().expect("should work").into();
+        let result = AstString::parse(&mut tokens).expect("should work");
+
+        assert_eq_ignoring_span!(
+            result,
+            AstString {
+                value: "{}".into(),
+                info: (),
+                position: Span::default()
+            }
+            .into()
+        );
+    }
+
+    #[test]
+    fn test_error_on_invalid_interpolation() {
+        for input in ["\"{x\"", "\"x}\"", "\"{}\"", "\"{x y}\""] {
+            let mut tokens = Lexer::new(input).lex().expect("should work").into();
+            assert!(AstString::parse(&mut tokens).is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_interpolation_errors_in_programs() {
+        let error = |input: &str| {
+            let tokens = Lexer::new(input).lex().expect("should lex");
+            let error = crate::parser::parse(&mut tokens.into()).expect_err("should fail");
+            let position = error.position.expect("should have a position");
+            (
+                error.message,
+                position.start_line_col(),
+                position.end_line_col(),
+            )
+        };
+
+        assert_eq!(
+            error("fn main(): void {\n    let c = \"a{\";\n}"),
+            (
+                "Unterminated interpolation in string".into(),
+                (1, 14),
+                (1, 15)
+            )
+        );
+        assert_eq!(
+            error("fn main(): void {\n    let c = \"{}\";\n}"),
+            (
+                "Expected an expression in interpolation".into(),
+                (1, 13),
+                (1, 14)
+            )
+        );
+        assert_eq!(
+            error("fn main(): void {\n    let c = \"{1 + }\";\n}"),
+            (
+                "Expected an expression in interpolation".into(),
+                (1, 14),
+                (1, 18)
+            )
+        );
+        assert_eq!(
+            error("const C: str = \"a{\";"),
+            (
+                "Unterminated interpolation in string".into(),
+                (0, 17),
+                (0, 18)
+            )
+        );
+    }
+}
@@ -106,7 +106,7 @@ mod tests {
 
         let result = Lambda::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Lambda {
                 parameters: vec![],
                 expression: Box::new(Expression::Num(Num::Integer(42, (), Span::default()))),
@@ -127,7 +127,7 @@ mod tests {
 
         let result = Lambda::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Lambda {
                 parameters: vec![
                     LambdaParameter {
@@ -181,7 +181,7 @@ mod tests {
 
         let result = Lambda::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Lambda {
                 parameters: vec![LambdaParameter {
                     name: Id {
@@ -214,7 +214,7 @@ mod tests {
 
         let result = Lambda::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Lambda {
                 parameters: vec![LambdaParameter {
                     name: Id {
@@ -5,6 +5,7 @@ mod character;
 mod function;
 mod id;
 mod if_expression;
+mod interpolation;
 mod lambda;
 mod num;
 mod postfix;
@@ -19,6 +20,7 @@ pub use self::character::*;
 pub use self::function::*;
 pub use self::id::*;
 pub use self::if_expression::*;
+pub use self::interpolation::*;
 pub use self::lambda::*;
 pub use self::num::*;
 pub use self::postfix::*;
@@ -41,6 +43,7 @@ pub enum Expression<T> {
     Num(Num<T>),
     Character(Character<T>),
     AstString(AstString<T>),
+    Interpolation(Interpolation<T>),
     Function(Function<T>),
     Lambda(Lambda<T>),
     If(If<T>),
@@ -63,6 +66,7 @@ where
             Expression::Num(num) => num.get_info(),
             Expression::Character(Character { info, .. }) => info.clone(),
             Expression::AstString(AstString { info, .. }) => info.clone(),
+            Expression::Interpolation(Interpolation { info, .. }) => info.clone(),
             Expression::Function(Function { info, .. }) => info.clone(),
             Expression::Lambda(Lambda { info, .. }) => info.clone(),
             Expression::If(If { info, .. }) => info.clone(),
@@ -82,6 +86,7 @@ where
             Expression::Num(num) => num.position(),
             Expression::Character(Character { position, .. }) => position.clone(),
             Expression::AstString(AstString { position, .. }) => position.clone(),
+            Expression::Interpolation(Interpolation { position, .. }) => position.clone(),
             Expression::Function(Function { position, .. }) => position.clone(),
             Expression::Lambda(Lambda { position, .. }) => position.clone(),
             Expression::If(If { position, .. }) => position.clone(),
@@ -164,6 +169,9 @@ impl FromTokens<Token> for Expression<()> {
                     Some(AstNode::Num(num)) => Expression::Num(num.clone()),
                     Some(AstNode::Character(character)) => Expression::Character(character.clone()),
                     Some(AstNode::AstString(string)) => Expression::AstString(string.clone()),
+                    Some(AstNode::Interpolation(interpolation)) => {
+                        Expression::Interpolation(interpolation.clone())
+                    }
                     Some(AstNode::Function(func)) => {
                         return Ok(Expression::Function(func.clone()).into())
                     }
@@ -199,14 +207,35 @@ impl FromTokens<Token> for Expression<()> {
                     expr = Expression::Postfix(Self::parse_property_access(expr, tokens)?);
                     continue;
                 }
+                Token::QuestionDot { .. } => {
+                    expr = Expression::Postfix(Self::parse_optional_property_access(expr, tokens)?);
+                    continue;
+                }
+                Token::AsKeyword { .. } => {
+                    expr = Expression::Postfix(Self::parse_cast(expr, tokens)?);
+                    continue;
+                }
+                Token::QuestionMark { position } => {
+                    let position = position.clone();
+                    tokens.next();
+                    expr = Expression::Postfix(Postfix::Propagate {
+                        expr: Box::new(expr),
+                        info: (),
+                        position,
+                    });
+                    continue;
+                }
                 Token::Plus { .. }
                 | Token::Minus { .. }
                 | Token::Times { .. }
+                | Token::Slash { .. }
                 | Token::Equal { .. }
+                | Token::NotEqual { .. }
                 | Token::GreaterThan { .. }
                 | Token::LessThan { .. }
                 | Token::GreaterOrEqual { .. }
-                | Token::LessOrEqual { .. } => {
+                | Token::LessOrEqual { .. }
+                | Token::DoubleQuestionMark { .. } => {
                     return Ok(Self::parse_binary(expr, tokens)?.into());
                 }
                 _ => return Ok(expr.into()),
@@ -225,6 +254,7 @@ impl Expression<()> {
         let matcher = Comb::LPAREN >> (Comb::EXPR % Comb::COMMA) >> Comb::RPAREN;
 
         let result = matcher.parse(tokens)?.into_iter();
+        let end = tokens.prev_span()?;
 
         let mut args = vec![];
 
@@ -240,7 +270,8 @@ impl Expression<()> {
             expr: Box::new(expr),
             args,
             info: (),
-            position,
+            // the call spans all of its arguments, including the parentheses
+            position: position.merge(&end),
         })
     }
 
@@ -288,6 +319,51 @@ impl Expression<()> {
         })
     }
 
+    fn parse_optional_property_access(
+        expr: Expression<()>,
+        tokens: &mut ParseState<Token>,
+    ) -> Result<Postfix<()>, ParseError> {
+        let position = tokens.span()?;
+
+        let matcher = Comb::QUESTION_DOT >> Comb::ID;
+
+        let result = matcher.parse(tokens)?;
+
+        let Some(AstNode::Id(property)) = result.first().cloned() else {
+            unreachable!()
+        };
+
+        Ok(Postfix::OptionalPropertyAccess {
+            expr: Box::new(expr),
+            property,
+            info: (),
+            position,
+        })
+    }
+
+    fn parse_cast(
+        expr: Expression<()>,
+        tokens: &mut ParseState<Token>,
+    ) -> Result<Postfix<()>, ParseError> {
+        let position = tokens.span()?;
+
+        let matcher = Comb::AS_KEYWORD >> Comb::TYPE_NAME;
+
+        let result = matcher.parse(tokens)?;
+        let end = tokens.prev_span()?;
+
+        let Some(AstNode::TypeName(target)) = result.first().cloned() else {
+            unreachable!()
+        };
+
+        Ok(Postfix::Cast {
+            expr: Box::new(expr),
+            target,
+            info: (),
+            position: position.merge(&end),
+        })
+    }
+
     fn parse_binary(
         lhs: Expression<()>,
         tokens: &mut ParseState<Token>,
@@ -327,6 +403,13 @@ impl Expression<()> {
                 info: (),
                 position,
             },
+            Token::Slash { .. } => BinaryExpression {
+                left: lhs,
+                right: rhs,
+                operator: BinaryOperator::Divide,
+                info: (),
+                position,
+            },
             Token::Equal { .. } => BinaryExpression {
                 left: lhs,
                 right: rhs,
@@ -334,6 +417,13 @@ impl Expression<()> {
                 info: (),
                 position,
             },
+            Token::NotEqual { .. } => BinaryExpression {
+                left: lhs,
+                right: rhs,
+                operator: BinaryOperator::NotEquals,
+                info: (),
+                position,
+            },
             Token::GreaterThan { .. } => BinaryExpression {
                 left: lhs,
                 right: rhs,
@@ -362,6 +452,13 @@ impl Expression<()> {
                 info: (),
                 position,
             },
+            Token::DoubleQuestionMark { .. } => BinaryExpression {
+                left: lhs,
+                right: rhs,
+                operator: BinaryOperator::Coalesce,
+                info: (),
+                position,
+            },
             _ => unreachable!(),
         };
 
@@ -427,8 +524,9 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Function(Function {
+                attributes: vec![],
                 id: Id {
                     name: "foo".into(),
                     info: (),
@@ -458,8 +556,9 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Function(Function {
+                attributes: vec![],
                 id: Id {
                     name: "foo".into(),
                     info: (),
@@ -522,7 +621,7 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Lambda(Lambda {
                 parameters: vec![],
                 expression: Box::new(Expression::Num(Num::Integer(42, (), Span::default()))),
@@ -543,7 +642,7 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Lambda(Lambda {
                 parameters: vec![
                     LambdaParameter {
@@ -601,7 +700,7 @@ mod tests {
             .expect("should work")
             .into();
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::If(If {
                 condition: Box::new(Expression::Id(Id {
                     name: "x".into(),
@@ -640,7 +739,7 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Postfix(Postfix::Call {
                 expr: Box::new(Expression::Id(Id {
                     name: "foo".into(),
@@ -665,7 +764,7 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Postfix(Postfix::Call {
                 expr: Box::new(Expression::Parens(Box::new(Expression::Lambda(Lambda {
                     parameters: vec![
@@ -723,7 +822,7 @@ mod tests {
         let mut tokens = Lexer::new("[]").lex().expect("something is wrong").into();
 
         let result = Expression::parse(&mut tokens);
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Array(Array::Literal {
                 values: vec![],
                 info: (),
@@ -742,7 +841,7 @@ mod tests {
             .into();
 
         let result = Expression::parse(&mut tokens);
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Array(Array::Literal {
                 values: vec![
                     Expression::Num(Num::Integer(42, (), Span::default())),
@@ -765,7 +864,7 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Postfix(Postfix::Index {
                 expr: Box::new(Expression::Id(Id {
                     name: "foo".into(),
@@ -790,7 +889,7 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::StructInitialisation(StructInitialisation {
                 id: Id {
                     name: "Foo".into(),
@@ -863,7 +962,7 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Postfix(Postfix::PropertyAccess {
                 expr: Box::new(Expression::Id(Id {
                     name: "foo".into(),
@@ -883,6 +982,75 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_cast() {
+        let mut tokens = Lexer::new("x + y as f64")
+            .lex()
+            .expect("something is wrong")
+            .into();
+
+        let result = Expression::parse(&mut tokens);
+
+        assert_eq_ignoring_span!(
+            Ok(Expression::Binary(Box::new(BinaryExpression {
+                left: Expression::Id(Id {
+                    name: "x".into(),
+                    info: (),
+                    position: Span::default()
+                }),
+                right: Expression::Postfix(Postfix::Cast {
+                    expr: Box::new(Expression::Id(Id {
+                        name: "y".into(),
+                        info: (),
+                        position: Span::default()
+                    })),
+                    target: TypeName::Literal("f64".into(), Span::default()),
+                    info: (),
+                    position: Span::default()
+                }),
+                operator: BinaryOperator::Add,
+                info: (),
+                position: Span::default()
+            }))
+            .into()),
+            result
+        );
+    }
+
+    #[test]
+    fn parse_propagate() {
+        let mut tokens = Lexer::new("x? ?? y")
+            .lex()
+            .expect("something is wrong")
+            .into();
+
+        let result = Expression::parse(&mut tokens);
+
+        assert_eq_ignoring_span!(
+            Ok(Expression::Binary(Box::new(BinaryExpression {
+                left: Expression::Postfix(Postfix::Propagate {
+                    expr: Box::new(Expression::Id(Id {
+                        name: "x".into(),
+                        info: (),
+                        position: Span::default()
+                    })),
+                    info: (),
+                    position: Span::default()
+                }),
+                right: Expression::Id(Id {
+                    name: "y".into(),
+                    info: (),
+                    position: Span::default()
+                }),
+                operator: BinaryOperator::Coalesce,
+                info: (),
+                position: Span::default()
+            }))
+            .into()),
+            result
+        );
+    }
+
     #[test]
     fn parse_property_access_complex() {
         let mut tokens = Lexer::new("foo().bar")
@@ -892,7 +1060,7 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Postfix(Postfix::PropertyAccess {
                 expr: Box::new(Expression::Postfix(Postfix::Call {
                     expr: Box::new(Expression::Id(Id {
@@ -923,7 +1091,7 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Prefix(Prefix::Minus {
                 expr: Box::new(Expression::Num(Num::Integer(42, (), Span::default()))),
                 position: Span::default()
@@ -942,7 +1110,7 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Prefix(Prefix::Minus {
                 expr: Box::new(Expression::Postfix(Postfix::Call {
                     expr: Box::new(Expression::Id(Id {
@@ -967,7 +1135,7 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Prefix(Prefix::Negation {
                 expr: Box::new(Expression::Num(Num::Integer(42, (), Span::default()))),
                 position: Span::default()
@@ -986,7 +1154,7 @@ mod tests {
 
         let result = Expression::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Expression::Prefix(Prefix::Negation {
                 expr: Box::new(Expression::Postfix(Postfix::Call {
                     expr: Box::new(Expression::Id(Id {
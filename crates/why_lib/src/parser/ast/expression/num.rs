@@ -95,7 +95,7 @@ mod tests {
 
         let result = Num::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Num::FloatingPoint(1337.42, (), Span::default()).into()),
             result
         );
@@ -1,5 +1,7 @@
 use crate::lexer::Span;
 
+use crate::parser::ast::TypeName;
+
 use super::{Expression, Id};
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -22,6 +24,27 @@ pub enum Postfix<T> {
         info: T,
         position: Span,
     },
+    /// Access of a property of an optional value, e.g., `x?.y`, which is `none` if `x` is.
+    OptionalPropertyAccess {
+        expr: Box<Expression<T>>,
+        property: Id<T>,
+        info: T,
+        position: Span,
+    },
+    /// Unwrapping of a result, e.g., `parse(x)?`, which returns the error from the enclosing
+    /// function.
+    Propagate {
+        expr: Box<Expression<T>>,
+        info: T,
+        position: Span,
+    },
+    /// Conversion of a value to another type, e.g., `x as f64`.
+    Cast {
+        expr: Box<Expression<T>>,
+        target: TypeName,
+        info: T,
+        position: Span,
+    },
 }
 
 impl<T> Postfix<T>
@@ -33,6 +56,9 @@ where
             Postfix::Call { info, .. } => info.clone(),
             Postfix::Index { info, .. } => info.clone(),
             Postfix::PropertyAccess { info, .. } => info.clone(),
+            Postfix::OptionalPropertyAccess { info, .. } => info.clone(),
+            Postfix::Propagate { info, .. } => info.clone(),
+            Postfix::Cast { info, .. } => info.clone(),
         }
     }
 
@@ -41,6 +67,9 @@ where
             Postfix::Call { position, .. } => position.clone(),
             Postfix::Index { position, .. } => position.clone(),
             Postfix::PropertyAccess { position, .. } => position.clone(),
+            Postfix::OptionalPropertyAccess { position, .. } => position.clone(),
+            Postfix::Propagate { position, .. } => position.clone(),
+            Postfix::Cast { position, .. } => position.clone(),
         }
     }
 }
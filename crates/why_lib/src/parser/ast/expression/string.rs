@@ -2,6 +2,8 @@ use crate::lexer::{Span, Token};
 use crate::parser::ast::AstNode;
 use crate::parser::{FromTokens, ParseError, ParseState};
 
+use super::{Interpolation, InterpolationSegment};
+
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct AstString<T> {
     pub value: String,
@@ -20,6 +22,36 @@ impl FromTokens<Token> for AstString<()> {
                 .strip_suffix('\"')
                 .unwrap()
                 .to_string();
+
+            if value.contains(['{', '}']) {
+                // the string token is consumed for good, so an invalid interpolation must not be
+                // hidden by backtracking into other alternatives
+                let interpolation =
+                    Interpolation::parse_segments(&value, position.start + 1, position.clone())
+                        .inspect_err(|e| {
+                            if !tokens.errors.contains(e) {
+                                tokens.add_error(e.clone());
+                            }
+                        })?;
+
+                // a string which only contains escaped braces is still a plain string
+                return match interpolation.segments.as_slice() {
+                    [] => Ok(AstString {
+                        value: String::new(),
+                        position,
+                        info: (),
+                    }
+                    .into()),
+                    [InterpolationSegment::Text(value)] => Ok(AstString {
+                        value: value.clone(),
+                        position,
+                        info: (),
+                    }
+                    .into()),
+                    _ => Ok(interpolation.into()),
+                };
+            }
+
             Ok(AstString {
                 value,
                 position,
@@ -50,7 +82,7 @@ mod tests {
         let mut tokens = Lexer::new("\"\"").lex().expect("should work").into();
         let result = AstString::parse(&mut tokens).expect("should work");
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             AstString {
                 value: "".into(),
@@ -66,7 +98,7 @@ mod tests {
         let mut tokens = Lexer::new("\"foo\"").lex().expect("should work").into();
         let result = AstString::parse(&mut tokens).expect("should work");
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             AstString {
                 value: "foo".into(),
@@ -82,7 +114,7 @@ mod tests {
         let mut tokens = Lexer::new("\"\t\"").lex().expect("should work").into();
         let result = AstString::parse(&mut tokens).expect("should work");
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             AstString {
                 value: "\t".into(),
@@ -95,16 +127,17 @@ mod tests {
 
     #[test]
     fn test_escaped_string_parse_complex() {
-        let mut tokens = Lexer::new("\"this is a test\"\"")
+        // a string ends at the first closing quote
+        let mut tokens = Lexer::new("\"this is a test\"\"\"")
             .lex()
             .expect("should work")
             .into();
         let result = AstString::parse(&mut tokens).expect("should work");
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             AstString {
-                value: "this is a test\"".into(),
+                value: "this is a test".into(),
                 info: (),
                 position: Span::default()
             }
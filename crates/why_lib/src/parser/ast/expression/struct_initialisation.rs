@@ -111,7 +111,7 @@ mod tests {
 
         let result = StructFieldInitialisation::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(StructFieldInitialisation {
                 name: Id {
                     name: "bar".into(),
@@ -136,7 +136,7 @@ mod tests {
 
         let result = StructInitialisation::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(StructInitialisation {
                 id: Id {
                     name: "Foo".into(),
@@ -161,7 +161,7 @@ mod tests {
 
         let result = StructInitialisation::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(StructInitialisation {
                 id: Id {
                     name: "Foo".into(),
@@ -195,7 +195,7 @@ mod tests {
 
         let result = StructInitialisation::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(StructInitialisation {
                 id: Id {
                     name: "Foo".into(),
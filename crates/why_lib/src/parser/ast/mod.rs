@@ -1,8 +1,10 @@
 mod expression;
+mod spans;
 mod statement;
 mod type_name;
 
 pub use self::expression::*;
+pub use self::spans::*;
 pub use self::statement::*;
 pub use self::type_name::*;
 
@@ -13,11 +15,13 @@ pub enum AstNode {
     Num(Num<()>),
     Character(Character<()>),
     AstString(AstString<()>),
+    Interpolation(Interpolation<()>),
     Statement(Statement<()>),
     Initialization(Initialisation<()>),
     Constant(Constant<()>),
     Assignment(Assignment<()>),
     Function(Function<()>),
+    Attribute(Attribute),
     Lambda(Lambda<()>),
     LambdaParameter(LambdaParameter<()>),
     If(If<()>),
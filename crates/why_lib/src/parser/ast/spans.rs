@@ -0,0 +1,565 @@
+use crate::{lexer::Span, source_map::FileId};
+
+use super::{
+    Array, ArrayLength, Assignment, AstString, Attribute, BinaryExpression, Block, Character,
+    Constant, Declaration, Expression, Function, FunctionParameter, Id, If, Initialisation,
+    Instance, Interpolation, InterpolationSegment, LValue, Lambda, LambdaParameter,
+    MethodDeclaration, Num, Postfix, Prefix, Statement, StructDeclaration, StructFieldDeclaration,
+    StructFieldInitialisation, StructInitialisation, TopLevelStatement, TypeName, WhileLoop,
+};
+
+/// Mutable access to every span within a node.
+pub trait SpansMut {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span));
+
+    /// Move every (actual) span within this node by `delta` bytes into the given file, e.g., after
+    /// the source in front of the node changed.
+    fn relocate(&mut self, file: FileId, delta: isize) {
+        self.spans_mut(&mut |span| {
+            if !span.is_dummy() {
+                span.file = file;
+                span.start = span.start.saturating_add_signed(delta);
+                span.end = span.end.saturating_add_signed(delta);
+            }
+        });
+    }
+}
+
+impl SpansMut for Span {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        f(self)
+    }
+}
+
+impl<S: SpansMut> SpansMut for Option<S> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        if let Some(inner) = self {
+            inner.spans_mut(f);
+        }
+    }
+}
+
+impl<S: SpansMut> SpansMut for Box<S> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        self.as_mut().spans_mut(f);
+    }
+}
+
+impl<S: SpansMut> SpansMut for Vec<S> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        for inner in self {
+            inner.spans_mut(f);
+        }
+    }
+}
+
+impl<T> SpansMut for TopLevelStatement<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        match self {
+            TopLevelStatement::Comment(_, position) => f(position),
+            TopLevelStatement::Function(function) => function.spans_mut(f),
+            TopLevelStatement::Constant(constant) => constant.spans_mut(f),
+            TopLevelStatement::Declaration(declaration) => declaration.spans_mut(f),
+            TopLevelStatement::StructDeclaration(declaration) => declaration.spans_mut(f),
+            TopLevelStatement::Instance(instance) => instance.spans_mut(f),
+        }
+    }
+}
+
+impl<T> SpansMut for Statement<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        match self {
+            Statement::Function(function) => function.spans_mut(f),
+            Statement::If(if_statement) => if_statement.spans_mut(f),
+            Statement::WhileLoop(while_loop) => while_loop.spans_mut(f),
+            Statement::Initialization(initialisation) => initialisation.spans_mut(f),
+            Statement::Constant(constant) => constant.spans_mut(f),
+            Statement::Assignment(assignment) => assignment.spans_mut(f),
+            Statement::Expression(expression)
+            | Statement::YieldingExpression(expression)
+            | Statement::Return(expression) => expression.spans_mut(f),
+            Statement::Comment(_, position) => f(position),
+            Statement::Declaration(declaration) => declaration.spans_mut(f),
+            Statement::StructDeclaration(declaration) => declaration.spans_mut(f),
+        }
+    }
+}
+
+impl<T> SpansMut for Function<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let Function {
+            attributes,
+            id,
+            parameters,
+            return_type,
+            statements,
+            info: _,
+            position,
+        } = self;
+
+        attributes.spans_mut(f);
+        id.spans_mut(f);
+        parameters.spans_mut(f);
+        return_type.spans_mut(f);
+        statements.spans_mut(f);
+        f(position);
+    }
+}
+
+impl SpansMut for Attribute {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        f(&mut self.position);
+    }
+}
+
+impl<T> SpansMut for FunctionParameter<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let FunctionParameter {
+            name,
+            type_name,
+            info: _,
+            position,
+        } = self;
+
+        name.spans_mut(f);
+        type_name.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for Id<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        f(&mut self.position);
+    }
+}
+
+impl SpansMut for TypeName {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        match self {
+            TypeName::Literal(_, position) => f(position),
+            TypeName::Fn {
+                params,
+                return_type,
+                position,
+            } => {
+                params.spans_mut(f);
+                return_type.spans_mut(f);
+                f(position);
+            }
+            TypeName::Tuple(elements, position) => {
+                elements.spans_mut(f);
+                f(position);
+            }
+            TypeName::Array(inner, position)
+            | TypeName::Reference(inner, position)
+            | TypeName::Optional(inner, position) => {
+                inner.spans_mut(f);
+                f(position);
+            }
+            TypeName::Result(value, error, position) => {
+                value.spans_mut(f);
+                error.spans_mut(f);
+                f(position);
+            }
+            TypeName::FixedArray(inner, length, position) => {
+                inner.spans_mut(f);
+                match length {
+                    ArrayLength::Literal(_, length_position)
+                    | ArrayLength::Constant(_, length_position) => f(length_position),
+                }
+                f(position);
+            }
+        }
+    }
+}
+
+impl<T> SpansMut for Constant<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let Constant {
+            id,
+            type_name,
+            value,
+            info: _,
+            position,
+        } = self;
+
+        id.spans_mut(f);
+        type_name.spans_mut(f);
+        value.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for Declaration<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let Declaration {
+            name,
+            type_name,
+            info: _,
+            position,
+        } = self;
+
+        name.spans_mut(f);
+        type_name.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for StructDeclaration<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let StructDeclaration {
+            id,
+            fields,
+            info: _,
+            position,
+        } = self;
+
+        id.spans_mut(f);
+        fields.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for StructFieldDeclaration<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let StructFieldDeclaration {
+            name,
+            type_name,
+            info: _,
+            position,
+        } = self;
+
+        name.spans_mut(f);
+        type_name.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for Instance<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let Instance {
+            name,
+            functions,
+            declarations,
+            info: _,
+            position,
+        } = self;
+
+        name.spans_mut(f);
+        functions.spans_mut(f);
+        declarations.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for MethodDeclaration<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let MethodDeclaration {
+            id,
+            parameter_types,
+            return_type,
+            info: _,
+            position,
+        } = self;
+
+        id.spans_mut(f);
+        parameter_types.spans_mut(f);
+        return_type.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for If<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let If {
+            condition,
+            statements,
+            else_statements,
+            info: _,
+            position,
+        } = self;
+
+        condition.spans_mut(f);
+        statements.spans_mut(f);
+        else_statements.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for WhileLoop<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let WhileLoop {
+            condition,
+            block,
+            info: _,
+            position,
+        } = self;
+
+        condition.spans_mut(f);
+        block.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for Block<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        self.statements.spans_mut(f);
+        f(&mut self.position);
+    }
+}
+
+impl<T> SpansMut for Initialisation<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let Initialisation {
+            id,
+            mutable: _,
+            type_name,
+            value,
+            info: _,
+            position,
+        } = self;
+
+        id.spans_mut(f);
+        type_name.spans_mut(f);
+        value.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for Assignment<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let Assignment {
+            lvalue,
+            rvalue,
+            info: _,
+            position,
+        } = self;
+
+        match lvalue {
+            LValue::Id(id) => id.spans_mut(f),
+            LValue::Postfix(postfix) => postfix.spans_mut(f),
+        }
+        rvalue.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for Expression<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        match self {
+            Expression::Id(id) => id.spans_mut(f),
+            Expression::Num(num) => num.spans_mut(f),
+            Expression::Character(Character { position, .. })
+            | Expression::AstString(AstString { position, .. }) => f(position),
+            Expression::Function(function) => function.spans_mut(f),
+            Expression::Lambda(lambda) => lambda.spans_mut(f),
+            Expression::If(if_expression) => if_expression.spans_mut(f),
+            Expression::Block(block) => block.spans_mut(f),
+            Expression::Parens(inner) => inner.spans_mut(f),
+            Expression::Postfix(postfix) => postfix.spans_mut(f),
+            Expression::Prefix(Prefix::Negation { expr, position })
+            | Expression::Prefix(Prefix::Minus { expr, position }) => {
+                expr.spans_mut(f);
+                f(position);
+            }
+            Expression::Binary(binary) => binary.spans_mut(f),
+            Expression::Array(array) => array.spans_mut(f),
+            Expression::StructInitialisation(initialisation) => initialisation.spans_mut(f),
+            Expression::Interpolation(Interpolation {
+                segments, position, ..
+            }) => {
+                for segment in segments {
+                    if let InterpolationSegment::Expression(expression) = segment {
+                        expression.spans_mut(f);
+                    }
+                }
+                f(position);
+            }
+        }
+    }
+}
+
+impl<T> SpansMut for Num<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        match self {
+            Num::Integer(_, _, position) | Num::FloatingPoint(_, _, position) => f(position),
+        }
+    }
+}
+
+impl<T> SpansMut for Lambda<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let Lambda {
+            parameters,
+            expression,
+            info: _,
+            position,
+        } = self;
+
+        parameters.spans_mut(f);
+        expression.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for LambdaParameter<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        self.name.spans_mut(f);
+        f(&mut self.position);
+    }
+}
+
+impl<T> SpansMut for Postfix<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        match self {
+            Postfix::Call {
+                expr,
+                args,
+                position,
+                ..
+            } => {
+                expr.spans_mut(f);
+                args.spans_mut(f);
+                f(position);
+            }
+            Postfix::Index {
+                expr,
+                index,
+                position,
+                ..
+            } => {
+                expr.spans_mut(f);
+                index.spans_mut(f);
+                f(position);
+            }
+            Postfix::PropertyAccess {
+                expr,
+                property,
+                position,
+                ..
+            }
+            | Postfix::OptionalPropertyAccess {
+                expr,
+                property,
+                position,
+                ..
+            } => {
+                expr.spans_mut(f);
+                property.spans_mut(f);
+                f(position);
+            }
+            Postfix::Propagate { expr, position, .. } => {
+                expr.spans_mut(f);
+                f(position);
+            }
+            Postfix::Cast {
+                expr,
+                target,
+                position,
+                ..
+            } => {
+                expr.spans_mut(f);
+                target.spans_mut(f);
+                f(position);
+            }
+        }
+    }
+}
+
+impl<T> SpansMut for BinaryExpression<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let BinaryExpression {
+            left,
+            right,
+            operator: _,
+            info: _,
+            position,
+        } = self;
+
+        left.spans_mut(f);
+        right.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for Array<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        match self {
+            Array::Literal {
+                values, position, ..
+            } => {
+                values.spans_mut(f);
+                f(position);
+            }
+            Array::Default {
+                initial_value,
+                length,
+                position,
+                ..
+            } => {
+                initial_value.spans_mut(f);
+                length.spans_mut(f);
+                f(position);
+            }
+        }
+    }
+}
+
+impl<T> SpansMut for StructInitialisation<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let StructInitialisation {
+            id,
+            fields,
+            info: _,
+            position,
+        } = self;
+
+        id.spans_mut(f);
+        fields.spans_mut(f);
+        f(position);
+    }
+}
+
+impl<T> SpansMut for StructFieldInitialisation<T> {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        let StructFieldInitialisation {
+            name,
+            value,
+            info: _,
+            position,
+        } = self;
+
+        name.spans_mut(f);
+        value.spans_mut(f);
+        f(position);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{lexer::Lexer, parser::parse};
+
+    use super::*;
+
+    #[test]
+    fn test_relocate() {
+        let statements = |input: &str| {
+            let tokens = Lexer::new(input).lex().expect("should lex");
+            parse(&mut tokens.into()).expect("should parse")
+        };
+        let spans = |statements: &mut Vec<TopLevelStatement<()>>| {
+            let mut spans = vec![];
+            statements.spans_mut(&mut |span| spans.push(span.clone()));
+            spans
+        };
+
+        let mut moved = statements("fn main(): i64 {\n    let x = [1, 2];\n    x[0] + 1\n}");
+        let mut expected =
+            statements("\n\n  fn main(): i64 {\n    let x = [1, 2];\n    x[0] + 1\n}");
+        let expected = spans(&mut expected);
+
+        moved.relocate(expected[0].file, 4);
+
+        assert_eq!(format!("{:?}", spans(&mut moved)), format!("{expected:?}"));
+    }
+}
@@ -95,6 +95,24 @@ impl LValue<()> {
                     message: "Unexpected postfix call in lvalue".into(),
                 })
             }
+            Postfix::Cast { .. } => {
+                return Err(ParseError {
+                    position: Some(position),
+                    message: "Unexpected cast in lvalue".into(),
+                })
+            }
+            Postfix::OptionalPropertyAccess { .. } => {
+                return Err(ParseError {
+                    position: Some(position),
+                    message: "Unexpected optional property access in lvalue".into(),
+                })
+            }
+            Postfix::Propagate { .. } => {
+                return Err(ParseError {
+                    position: Some(position),
+                    message: "Unexpected '?' in lvalue".into(),
+                })
+            }
             Postfix::Index { expr, .. } => expr,
             Postfix::PropertyAccess { expr, .. } => expr,
         }
@@ -170,7 +188,7 @@ mod tests {
 
         let result = Assignment::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             Ok(Assignment {
                 lvalue: LValue::Id(Id {
@@ -192,7 +210,7 @@ mod tests {
 
         let result = Assignment::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             Ok(Assignment {
                 lvalue: LValue::Postfix(Postfix::Index {
@@ -219,7 +237,7 @@ mod tests {
 
         let result = Assignment::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             Ok(Assignment {
                 lvalue: LValue::Postfix(Postfix::PropertyAccess {
@@ -253,7 +271,7 @@ mod tests {
 
         let result = Assignment::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             Ok(Assignment {
                 lvalue: LValue::Postfix(Postfix::PropertyAccess {
@@ -76,7 +76,7 @@ mod tests {
 
         let result = Constant::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Constant {
                 id: Id {
                     name: "foo".into(),
@@ -68,7 +68,7 @@ mod tests {
 
         let result = Declaration::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Declaration {
                 name: Id {
                     name: "foo".into(),
@@ -92,7 +92,7 @@ mod tests {
             .into();
 
         let result = Declaration::parse(&mut tokens);
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Declaration {
                 name: Id {
                     name: "foo".into(),
@@ -119,7 +119,7 @@ mod tests {
             .into();
 
         let result = Declaration::parse(&mut tokens);
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Declaration {
                 name: Id {
                     name: "foo".into(),
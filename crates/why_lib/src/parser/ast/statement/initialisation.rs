@@ -12,7 +12,8 @@ pub struct Initialisation<T> {
     pub id: Id<T>,
     pub mutable: bool,
     pub type_name: Option<TypeName>,
-    pub value: Expression<T>,
+    /// The initial value. Variables without one have to be assigned before they are used.
+    pub value: Option<Expression<T>>,
     pub info: T,
     pub position: Span,
 }
@@ -31,8 +32,7 @@ impl FromTokens<Token> for Initialisation<()> {
         let matcher = !Comb::MUT
             >> Comb::ID
             >> !(Comb::COLON >> Comb::TYPE_NAME)
-            >> Comb::ASSIGN
-            >> Comb::EXPR;
+            >> !(Comb::ASSIGN >> Comb::EXPR);
 
         let result = matcher.parse(tokens)?;
 
@@ -41,28 +41,20 @@ impl FromTokens<Token> for Initialisation<()> {
         };
 
         let mut type_name = None;
+        let mut value = None;
 
-        let value: Expression<()>;
-
-        match result.get(1) {
-            Some(AstNode::TypeName(type_)) => {
-                type_name = Some(type_.clone());
-
-                let Some(AstNode::Expression(expr)) = result.get(2) else {
-                    unreachable!()
-                };
-                value = expr.clone();
+        for node in &result[1..] {
+            match node {
+                AstNode::TypeName(type_) => type_name = Some(type_.clone()),
+                AstNode::Expression(expr) => value = Some(expr.clone()),
+                _ => unreachable!(),
             }
-            Some(AstNode::Expression(expr)) => {
-                value = expr.clone();
-            }
-            _ => unreachable!(),
         }
 
         Ok(Initialisation {
             id: id.clone(),
             mutable,
-            value: value.clone(),
+            value,
             type_name,
             info: (),
             position,
@@ -95,7 +87,7 @@ mod tests {
 
         let result = Initialisation::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Initialisation {
                 id: Id {
                     name: "foo".into(),
@@ -104,7 +96,7 @@ mod tests {
                 },
                 mutable: false,
                 type_name: None,
-                value: Expression::Num(Num::Integer(42, (), Span::default())),
+                value: Some(Expression::Num(Num::Integer(42, (), Span::default()))),
                 info: (),
                 position: Span::default()
             }
@@ -122,7 +114,7 @@ mod tests {
 
         let result = Initialisation::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Initialisation {
                 id: Id {
                     name: "foo".into(),
@@ -131,7 +123,7 @@ mod tests {
                 },
                 mutable: false,
                 type_name: Some(TypeName::Literal("i32".into(), Span::default())),
-                value: Expression::Num(Num::Integer(42, (), Span::default())),
+                value: Some(Expression::Num(Num::Integer(42, (), Span::default()))),
                 info: (),
                 position: Span::default()
             }
@@ -149,7 +141,7 @@ mod tests {
 
         let result = Initialisation::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Initialisation {
                 id: Id {
                     name: "foo".into(),
@@ -158,7 +150,34 @@ mod tests {
                 },
                 mutable: true,
                 type_name: None,
-                value: Expression::Num(Num::Integer(42, (), Span::default())),
+                value: Some(Expression::Num(Num::Integer(42, (), Span::default()))),
+                info: (),
+                position: Span::default()
+            }
+            .into()),
+            result
+        )
+    }
+
+    #[test]
+    fn test_initialisation_without_value() {
+        let mut tokens = Lexer::new("let foo: i64;")
+            .lex()
+            .expect("should work")
+            .into();
+
+        let result = Initialisation::parse(&mut tokens);
+
+        assert_eq_ignoring_span!(
+            Ok(Initialisation {
+                id: Id {
+                    name: "foo".into(),
+                    info: (),
+                    position: Span::default()
+                },
+                mutable: false,
+                type_name: Some(TypeName::Literal("i64".into(), Span::default())),
+                value: None,
                 info: (),
                 position: Span::default()
             }
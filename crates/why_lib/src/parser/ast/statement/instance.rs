@@ -52,11 +52,7 @@ impl FromTokens<Token> for Instance<()> {
             functions,
             declarations,
             info: (),
-            position: Span {
-                start: position.start,
-                end,
-                source: position.source,
-            },
+            position: Span { end, ..position },
         }
         .into())
     }
@@ -88,7 +84,7 @@ mod tests {
 
         let result = Instance::parse(&mut tokens)?;
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             Instance {
                 name: TypeName::Literal("Foo".into(), Span::default()),
@@ -117,11 +113,12 @@ mod tests {
 
         let result = Instance::parse(&mut tokens)?;
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             Instance {
                 name: TypeName::Literal("Foo".into(), Span::default()),
                 functions: vec![Function {
+                    attributes: vec![],
                     id: Id {
                         name: "bar".into(),
                         info: (),
@@ -161,11 +158,12 @@ mod tests {
 
         let result = Instance::parse(&mut tokens)?;
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             Instance {
                 name: TypeName::Literal("Foo".into(), Span::default()),
                 functions: vec![Function {
+                    attributes: vec![],
                     id: Id {
                         name: "bar".into(),
                         info: (),
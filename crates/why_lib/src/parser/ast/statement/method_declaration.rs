@@ -86,7 +86,7 @@ mod tests {
 
         let result = MethodDeclaration::parse(&mut tokens)?;
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             AstNode::MethodDeclaration(MethodDeclaration {
                 id: Id {
@@ -111,7 +111,7 @@ mod tests {
 
         let result = MethodDeclaration::parse(&mut tokens)?;
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             result,
             AstNode::MethodDeclaration(MethodDeclaration {
                 id: Id {
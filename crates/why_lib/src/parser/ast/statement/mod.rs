@@ -36,7 +36,7 @@ pub enum Statement<T> {
     Expression(Expression<T>),
     YieldingExpression(Expression<T>),
     Return(Expression<T>),
-    Comment(String),
+    Comment(String, Span),
     Declaration(Declaration<T>),
     StructDeclaration(StructDeclaration<T>),
 }
@@ -44,7 +44,7 @@ pub enum Statement<T> {
 /// Everything that is allowed at toplevel
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum TopLevelStatement<T> {
-    Comment(String),
+    Comment(String, Span),
     Function(Function<T>),
     Constant(Constant<T>),
     Declaration(Declaration<T>),
@@ -62,7 +62,8 @@ impl TopLevelStatement<()> {
         };
 
         match next {
-            Token::FnKeyword { .. } => {
+            // only toplevel functions can have attributes
+            Token::FnKeyword { .. } | Token::Hash { .. } => {
                 let matcher = Comb::FUNCTION;
                 let result = matcher.parse(tokens)?;
 
@@ -78,7 +79,12 @@ impl TopLevelStatement<()> {
                 let [AstNode::Constant(constant)] = result.as_slice() else {
                     unreachable!()
                 };
-                Ok(TopLevelStatement::Constant(constant.clone()))
+                // the constant runs through its semicolon
+                let position = constant.position.merge(&tokens.prev_span()?);
+                Ok(TopLevelStatement::Constant(Constant {
+                    position,
+                    ..constant.clone()
+                }))
             }
             Token::DeclareKeyword { .. } => {
                 let matcher = Comb::DECLARATION >> Comb::SEMI;
@@ -87,11 +93,16 @@ impl TopLevelStatement<()> {
                 let Some(AstNode::Declaration(declaration)) = result.first().cloned() else {
                     unreachable!()
                 };
-                Ok(TopLevelStatement::Declaration(declaration))
+                // the declaration runs through its semicolon
+                let position = declaration.position.merge(&tokens.prev_span()?);
+                Ok(TopLevelStatement::Declaration(Declaration {
+                    position,
+                    ..declaration
+                }))
             }
-            Token::Comment { value, .. } => {
+            Token::Comment { value, position } => {
                 tokens.next();
-                Ok(TopLevelStatement::Comment(value))
+                Ok(TopLevelStatement::Comment(value, position))
             }
             Token::StructKeyword { .. } => {
                 let matcher = Comb::STRUCT_DECLARATION;
@@ -181,7 +192,13 @@ impl FromTokens<Token> for Statement<()> {
                 let [AstNode::Constant(constant)] = result.as_slice() else {
                     unreachable!()
                 };
-                Ok(Statement::Constant(constant.clone()).into())
+                // the constant runs through its semicolon
+                let position = constant.position.merge(&tokens.prev_span()?);
+                Ok(Statement::Constant(Constant {
+                    position,
+                    ..constant.clone()
+                })
+                .into())
             }
             Token::ReturnKeyword { .. } => {
                 let matcher = Comb::RETURN_KEYWORD >> Comb::EXPR >> Comb::SEMI;
@@ -199,11 +216,17 @@ impl FromTokens<Token> for Statement<()> {
                 let Some(AstNode::Declaration(declaration)) = result.first().cloned() else {
                     unreachable!()
                 };
-                Ok(Statement::Declaration(declaration).into())
+                // the declaration runs through its semicolon
+                let position = declaration.position.merge(&tokens.prev_span()?);
+                Ok(Statement::Declaration(Declaration {
+                    position,
+                    ..declaration
+                })
+                .into())
             }
-            Token::Comment { value, .. } => {
+            Token::Comment { value, position } => {
                 tokens.next();
-                Ok(Statement::Comment(value).into())
+                Ok(Statement::Comment(value, position).into())
             }
             Token::StructKeyword { .. } => {
                 let matcher = Comb::STRUCT_DECLARATION;
@@ -289,23 +312,25 @@ impl<T> Statement<T>
 where
     T: Clone,
 {
-    pub fn get_info(&self) -> T {
+    /// The information attached to this statement. Comments do not have any.
+    pub fn get_info(&self) -> Option<T> {
         match self {
-            Statement::Function(Function { info, .. }) => info.clone(),
-            Statement::If(If { info, .. }) => info.clone(),
-            Statement::WhileLoop(WhileLoop { info, .. }) => info.clone(),
-            Statement::Initialization(Initialisation { info, .. }) => info.clone(),
-            Statement::Constant(Constant { info, .. }) => info.clone(),
-            Statement::Assignment(Assignment { info, .. }) => info.clone(),
-            Statement::Expression(exp) => exp.get_info(),
-            Statement::YieldingExpression(exp) => exp.get_info(),
-            Statement::Return(exp) => exp.get_info(),
-            Statement::Comment(_) => unimplemented!("Comments to not have type information"),
-            Statement::Declaration(Declaration { info, .. }) => info.clone(),
-            Statement::StructDeclaration(StructDeclaration { info, .. }) => info.clone(),
+            Statement::Function(Function { info, .. }) => Some(info.clone()),
+            Statement::If(If { info, .. }) => Some(info.clone()),
+            Statement::WhileLoop(WhileLoop { info, .. }) => Some(info.clone()),
+            Statement::Initialization(Initialisation { info, .. }) => Some(info.clone()),
+            Statement::Constant(Constant { info, .. }) => Some(info.clone()),
+            Statement::Assignment(Assignment { info, .. }) => Some(info.clone()),
+            Statement::Expression(exp) => Some(exp.get_info()),
+            Statement::YieldingExpression(exp) => Some(exp.get_info()),
+            Statement::Return(exp) => Some(exp.get_info()),
+            Statement::Comment(..) => None,
+            Statement::Declaration(Declaration { info, .. }) => Some(info.clone()),
+            Statement::StructDeclaration(StructDeclaration { info, .. }) => Some(info.clone()),
         }
     }
 
+    /// The position of this statement.
     pub fn position(&self) -> Span {
         match self {
             Statement::Function(Function { position, .. }) => position.clone(),
@@ -317,7 +342,7 @@ where
             Statement::Expression(exp) => exp.position(),
             Statement::YieldingExpression(exp) => exp.position(),
             Statement::Return(exp) => exp.position(),
-            Statement::Comment(_) => todo!(),
+            Statement::Comment(_, position) => position.clone(),
             Statement::Declaration(Declaration { position, .. }) => position.clone(),
             Statement::StructDeclaration(StructDeclaration { position, .. }) => position.clone(),
         }
@@ -342,7 +367,7 @@ mod tests {
 
         let result = Statement::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Statement::Constant(Constant {
                 id: Id {
                     name: "foo".into(),
@@ -365,7 +390,7 @@ mod tests {
 
         let result = Statement::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Statement::Return(Expression::Num(Num::Integer(42, (), Span::default()))).into()),
             result
         );
@@ -380,7 +405,7 @@ mod tests {
 
         let result = Statement::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Statement::If(If {
                 condition: Box::new(Expression::Id(Id {
                     name: "x".into(),
@@ -422,7 +447,7 @@ mod tests {
 
         let result = Statement::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Statement::If(If {
                 condition: Box::new(Expression::Id(Id {
                     name: "x".into(),
@@ -464,7 +489,7 @@ mod tests {
 
         let result = Statement::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Statement::If(If {
                 condition: Box::new(Expression::Id(Id {
                     name: "x".into(),
@@ -503,7 +528,7 @@ mod tests {
 
         let result = Statement::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(Statement::Assignment(Assignment {
                 lvalue: LValue::Id(Id {
                     name: "x".into(),
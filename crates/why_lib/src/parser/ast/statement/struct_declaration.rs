@@ -36,11 +36,13 @@ impl FromTokens<Token> for StructDeclaration<()> {
             fields.push(field);
         }
 
+        let end = tokens.prev_span()?;
+
         Ok(StructDeclaration {
             id,
             fields,
             info: (),
-            position,
+            position: position.merge(&end),
         }
         .into())
     }
@@ -112,7 +114,7 @@ mod tests {
 
         let result = StructDeclaration::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(StructDeclaration {
                 id: Id {
                     name: "Foo".into(),
@@ -141,7 +143,7 @@ mod tests {
 
         let result = StructDeclaration::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(StructDeclaration {
                 id: Id {
                     name: "Foo".into(),
@@ -180,7 +182,7 @@ mod tests {
 
         let result = StructDeclaration::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(StructDeclaration {
                 id: Id {
                     name: "Foo".into(),
@@ -8,7 +8,7 @@ use crate::parser::FromTokens;
 use crate::parser::ParseError;
 use crate::parser::ParseState;
 
-use super::AstNode;
+use super::{AstNode, Id, Num};
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum TypeName {
@@ -20,7 +20,38 @@ pub enum TypeName {
     },
     Tuple(Vec<TypeName>, Span),
     Array(Box<TypeName>, Span),
+    FixedArray(Box<TypeName>, ArrayLength, Span),
     Reference(Box<TypeName>, Span),
+    /// A value which might be missing, e.g., `i64?`.
+    Optional(Box<TypeName>, Span),
+    /// Either a value or an error, e.g., `i64 ! str`.
+    Result(Box<TypeName>, Box<TypeName>, Span),
+}
+
+/// Length of a fixed-size array type, i.e., `4` in `[i64; 4]` or `N` in `[i64; N]`.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ArrayLength {
+    Literal(u64, Span),
+    Constant(String, Span),
+}
+
+impl ArrayLength {
+    pub fn position(&self) -> Span {
+        match self {
+            ArrayLength::Literal(_, position) | ArrayLength::Constant(_, position) => {
+                position.clone()
+            }
+        }
+    }
+}
+
+impl Display for ArrayLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArrayLength::Literal(length, _) => write!(f, "{length}"),
+            ArrayLength::Constant(name, _) => f.write_str(name),
+        }
+    }
 }
 
 impl TypeName {
@@ -30,7 +61,10 @@ impl TypeName {
             TypeName::Fn { position, .. } => position.clone(),
             TypeName::Tuple(_, position) => position.clone(),
             TypeName::Array(_, position) => position.clone(),
+            TypeName::FixedArray(_, _, position) => position.clone(),
             TypeName::Reference(_, position) => position.clone(),
+            TypeName::Optional(_, position) => position.clone(),
+            TypeName::Result(_, _, position) => position.clone(),
         }
     }
 }
@@ -59,7 +93,10 @@ impl Display for TypeName {
                     .join(", ")
             )),
             TypeName::Array(el, _) => f.write_fmt(format_args!("[{el}]")),
+            TypeName::FixedArray(el, length, _) => f.write_fmt(format_args!("[{el}; {length}]")),
             TypeName::Reference(el, _) => f.write_fmt(format_args!("&{el}")),
+            TypeName::Optional(el, _) => f.write_fmt(format_args!("{el}?")),
+            TypeName::Result(value, error, _) => f.write_fmt(format_args!("{value} ! {error}")),
         }
     }
 }
@@ -72,6 +109,45 @@ impl From<&TypeName> for TypeName {
 
 impl FromTokens<Token> for TypeName {
     fn parse(tokens: &mut ParseState<Token>) -> Result<AstNode, ParseError> {
+        let position = tokens.span()?;
+
+        let value = Self::parse_optional(tokens)?;
+
+        let Some(Token::ExclamationMark { .. }) = tokens.peek() else {
+            return Ok(value.into());
+        };
+        tokens.next();
+
+        let error = Self::parse_optional(tokens)?;
+
+        let Span { end, .. } = tokens.prev_span()?;
+        Ok(TypeName::Result(Box::new(value), Box::new(error), Span { end, ..position }).into())
+    }
+}
+
+impl TypeName {
+    /// Parse a type name without a trailing `! E`.
+    fn parse_optional(tokens: &mut ParseState<Token>) -> Result<TypeName, ParseError> {
+        let position = tokens.span()?;
+
+        let AstNode::TypeName(type_name) = Self::parse_plain(tokens)? else {
+            unreachable!()
+        };
+
+        let Some(Token::QuestionMark { .. }) = tokens.peek() else {
+            return Ok(type_name);
+        };
+        tokens.next();
+
+        let Span { end, .. } = tokens.prev_span()?;
+        Ok(TypeName::Optional(
+            Box::new(type_name),
+            Span { end, ..position },
+        ))
+    }
+
+    /// Parse a type name without a trailing `?`.
+    fn parse_plain(tokens: &mut ParseState<Token>) -> Result<AstNode, ParseError> {
         let position = tokens.peek().map(|token| token.position());
         if let Ok(type_name) = Self::parse_literal(tokens) {
             return Ok(type_name);
@@ -89,6 +165,10 @@ impl FromTokens<Token> for TypeName {
             return Ok(array);
         }
 
+        if let Ok(array) = Self::parse_fixed_array(tokens) {
+            return Ok(array);
+        }
+
         if let Ok(reference) = Self::parse_reference(tokens) {
             return Ok(reference);
         }
@@ -98,9 +178,7 @@ impl FromTokens<Token> for TypeName {
             position,
         })
     }
-}
 
-impl TypeName {
     fn parse_literal(tokens: &mut ParseState<Token>) -> Result<AstNode, ParseError> {
         let position = tokens.span()?;
 
@@ -144,15 +222,7 @@ impl TypeName {
 
         let Span { end, .. } = tokens.prev_span()?;
 
-        Ok(TypeName::Tuple(
-            elems,
-            Span {
-                start: position.start,
-                end,
-                source: position.source,
-            },
-        )
-        .into())
+        Ok(TypeName::Tuple(elems, Span { end, ..position }).into())
     }
 
     fn parse_fn(tokens: &mut ParseState<Token>) -> Result<AstNode, ParseError> {
@@ -178,11 +248,7 @@ impl TypeName {
         Ok(TypeName::Fn {
             params,
             return_type: Box::new(type_name.clone()),
-            position: Span {
-                start: position.start,
-                end,
-                source: position.source,
-            },
+            position: Span { end, ..position },
         }
         .into())
     }
@@ -203,13 +269,49 @@ impl TypeName {
         };
 
         let Span { end, .. } = tokens.prev_span()?;
-        Ok(TypeName::Array(
+        Ok(TypeName::Array(Box::new(type_name.clone()), Span { end, ..position }).into())
+    }
+
+    fn parse_fixed_array(tokens: &mut ParseState<Token>) -> Result<AstNode, ParseError> {
+        let position = tokens.span()?;
+
+        let index = tokens.get_index();
+
+        let matcher = Comb::LBRACKET
+            >> Comb::TYPE_NAME
+            >> Comb::SEMI
+            >> (Comb::NUM | Comb::ID)
+            >> Comb::RBRACKET;
+
+        let result = matcher.parse(tokens).inspect_err(|_| {
+            tokens.set_index(index);
+        })?;
+
+        let Some(AstNode::TypeName(type_name)) = result.first() else {
+            unreachable!()
+        };
+
+        let length = match result.get(1) {
+            Some(AstNode::Num(Num::Integer(length, _, position))) => {
+                ArrayLength::Literal(*length, position.clone())
+            }
+            Some(AstNode::Id(Id { name, position, .. })) => {
+                ArrayLength::Constant(name.clone(), position.clone())
+            }
+            _ => {
+                tokens.set_index(index);
+                return Err(ParseError {
+                    message: "Expected an integer or a constant as length of an array".into(),
+                    position: Some(position),
+                });
+            }
+        };
+
+        let Span { end, .. } = tokens.prev_span()?;
+        Ok(TypeName::FixedArray(
             Box::new(type_name.clone()),
-            Span {
-                start: position.start,
-                end,
-                source: position.source,
-            },
+            length,
+            Span { end, ..position },
         )
         .into())
     }
@@ -230,15 +332,7 @@ impl TypeName {
         };
 
         let Span { end, .. } = tokens.prev_span()?;
-        Ok(TypeName::Reference(
-            Box::new(type_name.clone()),
-            Span {
-                start: position.start,
-                end,
-                source: position.source,
-            },
-        )
-        .into())
+        Ok(TypeName::Reference(Box::new(type_name.clone()), Span { end, ..position }).into())
     }
 }
 
@@ -255,7 +349,7 @@ mod tests {
         parser::FromTokens,
     };
 
-    use super::TypeName;
+    use super::{ArrayLength, TypeName};
 
     #[test]
     fn test_parse_simple_literal() {
@@ -265,7 +359,7 @@ mod tests {
             .into();
 
         let result = TypeName::parse(&mut tokens);
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(TypeName::Literal("i32".into(), Span::default()).into()),
             result
         );
@@ -279,7 +373,7 @@ mod tests {
             .into();
 
         let result = TypeName::parse(&mut tokens);
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(TypeName::Tuple(
                 vec![TypeName::Literal("i32".into(), Span::default()); 2],
                 Span::default()
@@ -297,7 +391,7 @@ mod tests {
             .into();
 
         let result = TypeName::parse(&mut tokens);
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(TypeName::Fn {
                 params: vec![],
                 return_type: Box::new(TypeName::Literal("i32".into(), Span::default())),
@@ -316,7 +410,7 @@ mod tests {
             .into();
 
         let result = TypeName::parse(&mut tokens);
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(TypeName::Reference(
                 Box::new(TypeName::Literal("i32".into(), Span::default())),
                 Span::default()
@@ -326,6 +420,86 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_fixed_array() {
+        let mut tokens = Lexer::new("[i32; 4]")
+            .lex()
+            .expect("something went wrong")
+            .into();
+
+        assert_eq_ignoring_span!(
+            Ok(TypeName::FixedArray(
+                Box::new(TypeName::Literal("i32".into(), Span::default())),
+                ArrayLength::Literal(4, Span::default()),
+                Span::default()
+            )
+            .into()),
+            TypeName::parse(&mut tokens)
+        );
+
+        let mut tokens = Lexer::new("[[i32]; N]")
+            .lex()
+            .expect("something went wrong")
+            .into();
+
+        assert_eq_ignoring_span!(
+            Ok(TypeName::FixedArray(
+                Box::new(TypeName::Array(
+                    Box::new(TypeName::Literal("i32".into(), Span::default())),
+                    Span::default()
+                )),
+                ArrayLength::Constant("N".into(), Span::default()),
+                Span::default()
+            )
+            .into()),
+            TypeName::parse(&mut tokens)
+        );
+    }
+
+    #[test]
+    fn test_parse_optional() {
+        let mut tokens = Lexer::new("[i64?]?")
+            .lex()
+            .expect("something went wrong")
+            .into();
+
+        assert_eq_ignoring_span!(
+            Ok(TypeName::Optional(
+                Box::new(TypeName::Array(
+                    Box::new(TypeName::Optional(
+                        Box::new(TypeName::Literal("i64".into(), Span::default())),
+                        Span::default()
+                    )),
+                    Span::default()
+                )),
+                Span::default()
+            )
+            .into()),
+            TypeName::parse(&mut tokens)
+        );
+    }
+
+    #[test]
+    fn test_parse_result() {
+        let mut tokens = Lexer::new("i64? ! str")
+            .lex()
+            .expect("something went wrong")
+            .into();
+
+        assert_eq_ignoring_span!(
+            Ok(TypeName::Result(
+                Box::new(TypeName::Optional(
+                    Box::new(TypeName::Literal("i64".into(), Span::default())),
+                    Span::default()
+                )),
+                Box::new(TypeName::Literal("str".into(), Span::default())),
+                Span::default()
+            )
+            .into()),
+            TypeName::parse(&mut tokens)
+        );
+    }
+
     #[test]
     fn test_parse_reference_of_tuple() {
         let mut tokens = Lexer::new("&(i32, i32)")
@@ -335,7 +509,7 @@ mod tests {
 
         let result = TypeName::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(TypeName::Reference(
                 Box::new(TypeName::Tuple(
                     vec![TypeName::Literal("i32".into(), Span::default()); 2],
@@ -357,7 +531,7 @@ mod tests {
 
         let result = TypeName::parse(&mut tokens);
 
-        assert_eq!(
+        assert_eq_ignoring_span!(
             Ok(TypeName::Tuple(
                 vec![
                     TypeName::Reference(
@@ -2,7 +2,7 @@ use std::ops::{BitOr, BitXor, Not, Rem, Shr};
 
 use super::{
     ast::{
-        Array, Assignment, AstNode, Block, Constant, Declaration, Expression, Function,
+        Array, Assignment, AstNode, Attribute, Block, Constant, Declaration, Expression, Function,
         FunctionParameter, Id, If, Initialisation, Instance, Lambda, LambdaParameter,
         MethodDeclaration, Num, Statement, StructDeclaration, StructFieldDeclaration,
         StructFieldInitialisation, StructInitialisation, TypeName, WhileLoop,
@@ -213,6 +213,14 @@ impl Comb<'_, Token, Terminal, AstNode> {
 
     terminal_comb!(INSTANCE_KEYWORD, InstanceKeyword);
 
+    terminal_comb!(HASH, Hash);
+
+    terminal_comb!(AS_KEYWORD, AsKeyword);
+
+    terminal_comb!(QUESTION_MARK, QuestionMark);
+
+    terminal_comb!(QUESTION_DOT, QuestionDot);
+
     node_comb!(ID, Id);
 
     node_comb!(NUM, Num);
@@ -231,6 +239,8 @@ impl Comb<'_, Token, Terminal, AstNode> {
 
     node_comb!(FUNCTION, Function);
 
+    node_comb!(ATTRIBUTE, Attribute);
+
     node_comb!(LAMBDA, Lambda);
 
     node_comb!(LAMBDA_PARAMETER, LambdaParameter);
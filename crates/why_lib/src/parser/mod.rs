@@ -6,9 +6,12 @@ mod parse_state;
 
 pub use self::parse_state::*;
 
-use crate::lexer::{GetPosition, Span, Token};
+use crate::{
+    diagnostic::Diagnostic,
+    lexer::{GetPosition, Span, Token},
+};
 
-use self::ast::{AstNode, TopLevelStatement};
+use self::ast::{AstNode, Statement, TopLevelStatement};
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ParseError {
@@ -17,6 +20,20 @@ pub struct ParseError {
 }
 
 impl ParseError {
+    /// Stable code identifying this kind of error.
+    pub fn code(&self) -> &'static str {
+        "E0002"
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.code(), &self.message);
+
+        match &self.position {
+            Some(position) => diagnostic.with_primary(position.clone(), None),
+            None => diagnostic,
+        }
+    }
+
     pub fn eof(item: &str) -> ParseError {
         ParseError {
             message: format!("hit EOF while parsing {item}"),
@@ -49,11 +66,7 @@ impl ParseState<Token> {
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if let Some(pos) = &self.position {
-            f.write_str(pos.to_string(&self.message).as_str())
-        } else {
-            f.write_str(&self.message)
-        }
+        f.write_str(&self.diagnostic().to_string())
     }
 }
 
@@ -86,3 +99,23 @@ pub fn parse(tokens: &mut ParseState<Token>) -> Result<Vec<TopLevelStatement<()>
 
     Ok(statements)
 }
+
+/// Parse a sequence of statements as they would appear within a block, e.g., for interactive
+/// sessions.
+pub fn parse_statements(tokens: &mut ParseState<Token>) -> Result<Vec<Statement<()>>, ParseError> {
+    let mut statements = vec![];
+
+    while tokens.peek().is_some() {
+        match Statement::parse(tokens) {
+            Ok(AstNode::Statement(statement)) => statements.push(statement),
+            Ok(_) => unreachable!(),
+            Err(e) => return Err(tokens.errors.first().cloned().unwrap_or(e)),
+        }
+    }
+
+    if let Some(e) = tokens.errors.first() {
+        return Err(e.clone());
+    }
+
+    Ok(statements)
+}
@@ -0,0 +1,233 @@
+//! Registry of all source files known to the compiler. Spans only store the id of their file and
+//! byte offsets into it, everything else (the source code itself, lines and columns) is looked up
+//! here when it is actually needed.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock, OnceLock, RwLock},
+};
+
+/// Identifier of a file within the source map. The default id does not belong to any file and is
+/// used for spans without an actual location.
+#[derive(
+    Default, Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize,
+)]
+pub struct FileId(u32);
+
+impl FileId {
+    /// Whether this id does not belong to any file.
+    pub fn is_none(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+#[derive(Debug)]
+pub struct SourceFile {
+    name: Option<String>,
+    source: String,
+    /// Byte offsets of the start of every line, computed on first use.
+    line_starts: OnceLock<Vec<usize>>,
+}
+
+impl SourceFile {
+    fn new(name: Option<String>, source: String) -> Self {
+        SourceFile {
+            name,
+            source,
+            line_starts: OnceLock::new(),
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    fn line_starts(&self) -> &[usize] {
+        self.line_starts.get_or_init(|| {
+            std::iter::once(0)
+                .chain(
+                    self.source
+                        .match_indices('\n')
+                        .map(|(offset, _)| offset + 1),
+                )
+                .collect()
+        })
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts().len()
+    }
+
+    /// The text of a line (without the line break).
+    pub fn line(&self, line: usize) -> &str {
+        let starts = self.line_starts();
+        let Some(&start) = starts.get(line) else {
+            return "";
+        };
+        let end = starts
+            .get(line + 1)
+            .map_or(self.source.len(), |end| end - 1);
+
+        self.source[start..end].trim_end_matches('\r')
+    }
+
+    /// Convert a byte offset to a line and the byte offset within that line.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.source.len());
+        let line = self.line_starts().partition_point(|&start| start <= offset) - 1;
+
+        (line, offset - self.line_starts()[line])
+    }
+
+    /// Convert a byte offset to a line and the number of characters (i.e., code points) before
+    /// the offset within that line.
+    pub fn line_col_chars(&self, offset: usize) -> (usize, usize) {
+        self.line_col_units(offset, |_| 1)
+    }
+
+    /// Convert a byte offset to a line and the number of UTF-16 code units before the offset
+    /// within that line (which is how LSP counts columns).
+    pub fn line_col_utf16(&self, offset: usize) -> (usize, usize) {
+        self.line_col_units(offset, char::len_utf16)
+    }
+
+    fn line_col_units(&self, offset: usize, units: impl Fn(char) -> usize) -> (usize, usize) {
+        let (line, col) = self.line_col(offset);
+        let col = self
+            .line(line)
+            .char_indices()
+            .take_while(|(index, _)| *index < col)
+            .map(|(_, c)| units(c))
+            .sum();
+
+        (line, col)
+    }
+
+    /// Convert a line and a byte offset within that line to a byte offset. Positions past the
+    /// end of a line or of the file are clamped.
+    pub fn offset(&self, line: usize, col: usize) -> usize {
+        match self.line_starts().get(line) {
+            Some(start) => start + col.min(self.line(line).len()),
+            None => self.source.len(),
+        }
+    }
+
+    /// Convert a line and a UTF-16 column within that line to a byte offset.
+    pub fn offset_utf16(&self, line: usize, col: usize) -> usize {
+        let mut units = 0;
+        let byte = self
+            .line(line)
+            .char_indices()
+            .find(|(_, c)| {
+                units += c.len_utf16();
+                units > col
+            })
+            .map_or(self.line(line).len(), |(index, _)| index);
+
+        self.offset(line, byte)
+    }
+}
+
+#[derive(Default)]
+struct Files {
+    files: Vec<Arc<SourceFile>>,
+    by_name: HashMap<String, FileId>,
+}
+
+static FILES: LazyLock<RwLock<Files>> = LazyLock::new(RwLock::default);
+
+/// The global source map.
+pub struct SourceMap;
+
+impl SourceMap {
+    /// Add a source without a name (e.g., from stdin or a test). Every call creates a new file.
+    pub fn add(source: impl Into<String>) -> FileId {
+        Self::insert(None, source.into())
+    }
+
+    /// Add the content of a named file. Adding a file with the same name again replaces its
+    /// previous content (e.g., for documents changing in an editor) and keeps its id.
+    pub fn add_named(name: impl Into<String>, source: impl Into<String>) -> FileId {
+        Self::insert(Some(name.into()), source.into())
+    }
+
+    fn insert(name: Option<String>, source: String) -> FileId {
+        let mut files = FILES.write().unwrap();
+
+        if let Some(id) = name
+            .as_ref()
+            .and_then(|name| files.by_name.get(name))
+            .copied()
+        {
+            files.files[id.0 as usize - 1] = Arc::new(SourceFile::new(name, source));
+            return id;
+        }
+
+        files
+            .files
+            .push(Arc::new(SourceFile::new(name.clone(), source)));
+        let id = FileId(files.files.len() as u32);
+        if let Some(name) = name {
+            files.by_name.insert(name, id);
+        }
+
+        id
+    }
+
+    pub fn get(id: FileId) -> Option<Arc<SourceFile>> {
+        if id.is_none() {
+            return None;
+        }
+
+        FILES.read().unwrap().files.get(id.0 as usize - 1).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_col() {
+        let file = SourceMap::get(SourceMap::add("fn main(): void {\n    foo\n}")).unwrap();
+
+        assert_eq!(file.line_count(), 3);
+        assert_eq!(file.line(1), "    foo");
+        assert_eq!(file.line_col(0), (0, 0));
+        assert_eq!(file.line_col(17), (0, 17));
+        assert_eq!(file.line_col(18), (1, 0));
+        assert_eq!(file.line_col(22), (1, 4));
+        assert_eq!(file.offset(1, 4), 22);
+        assert_eq!(file.offset(5, 0), file.source().len());
+    }
+
+    #[test]
+    fn test_utf16() {
+        let file = SourceMap::get(SourceMap::add("let ä = '😀';\nx")).unwrap();
+
+        // 'ä' takes two bytes but only one UTF-16 code unit, '😀' takes four bytes and two units
+        assert_eq!(file.line_col(9), (0, 9));
+        assert_eq!(file.line_col_chars(9), (0, 8));
+        assert_eq!(file.line_col_utf16(9), (0, 8));
+        assert_eq!(file.line_col_chars(14), (0, 10));
+        assert_eq!(file.line_col_utf16(14), (0, 11));
+        assert_eq!(file.offset_utf16(0, 8), 9);
+        assert_eq!(file.offset_utf16(0, 11), 14);
+        assert_eq!(file.line_col_utf16(17), (1, 0));
+    }
+
+    #[test]
+    fn test_named_files() {
+        let first = SourceMap::add_named("test_named_files.why", "fn main(): void {}");
+        let second = SourceMap::add_named("test_named_files.why", "\nfn main(): void {}");
+
+        assert_eq!(first, second);
+        assert_ne!(SourceMap::add("a"), SourceMap::add("a"));
+        assert_eq!(SourceMap::get(first).unwrap().line_count(), 2);
+        assert!(SourceMap::get(FileId::default()).is_none());
+    }
+}
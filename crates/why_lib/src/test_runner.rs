@@ -0,0 +1,397 @@
+//! Discovery and execution of test functions, i.e., toplevel functions annotated with `#[test]`.
+
+use crate::{
+    interpreter::{Interpreter, RuntimeError},
+    lexer::Span,
+    parser::ast::TopLevelStatement,
+    typechecker::ValidatedTypeInformation,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestCase {
+    pub name: String,
+    pub position: Span,
+}
+
+/// All test functions of a program, in the order of their definition.
+pub fn discover(program: &[TopLevelStatement<ValidatedTypeInformation>]) -> Vec<TestCase> {
+    program
+        .iter()
+        .filter_map(|statement| match statement {
+            TopLevelStatement::Function(function) if function.has_attribute("test") => {
+                Some(TestCase {
+                    name: function.id.name.clone(),
+                    position: function.position.clone(),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Run a single test. Every test gets its own interpreter, so tests can not influence each other.
+pub fn run(
+    program: &[TopLevelStatement<ValidatedTypeInformation>],
+    test: &TestCase,
+) -> Result<(), RuntimeError> {
+    Interpreter::new(program)?.call(&test.name, vec![])?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{lexer::Lexer, parser::parse, typechecker::TypeChecker};
+
+    fn program(input: &str) -> Vec<TopLevelStatement<ValidatedTypeInformation>> {
+        let tokens = Lexer::new(input).lex().unwrap();
+        let statements = parse(&mut tokens.into()).unwrap();
+        let checked = TypeChecker::new(statements)
+            .check_all_without_main()
+            .unwrap();
+
+        TypeChecker::validate(checked).unwrap()
+    }
+
+    fn results(input: &str) -> Vec<(String, Result<(), RuntimeError>)> {
+        let program = program(input);
+
+        discover(&program)
+            .iter()
+            .map(|test| (test.name.clone(), run(&program, test)))
+            .collect()
+    }
+
+    #[test]
+    fn test_discover() {
+        let program = program(
+            "fn helper(): i64 {\n    42\n}\n\n#[test]\nfn first(): void {}\n\n#[test]\nfn second(): void {}",
+        );
+
+        let names = discover(&program)
+            .into_iter()
+            .map(|test| test.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["first".to_string(), "second".to_string()]);
+    }
+
+    #[test]
+    fn test_passing_tests() {
+        let results = results(
+            r#"struct Point {
+    x: i64;
+    y: i64;
+}
+
+instance Point {
+    fn sum(): i64 {
+        this.x + this.y
+    }
+}
+
+fn fib(n: i64): i64 {
+    if (n < 2) {
+        return n;
+    }
+
+    fib(n - 1) + fib(n - 2)
+}
+
+#[test]
+fn adds(): void {
+    let mut total = 0;
+    let mut i = 0;
+    while (i < 4) {
+        i = i + 1;
+        total = total + i;
+    }
+    assert_eq(total, 10);
+    assert(fib(10) == 55);
+}
+
+#[test]
+fn methods(): void {
+    let mut point = Point { x: 1, y: 2 };
+    point.x = 40;
+    assert_eq(point.sum(), 42);
+}
+
+#[test]
+fn arrays(): void {
+    let mut numbers = [1, 2, 3];
+    numbers[1] = 5;
+    assert_eq(numbers[1] * 2, 10);
+    assert_eq(numbers, [1, 5, 3]);
+}"#,
+        );
+
+        for (name, result) in results {
+            assert_eq!(result, Ok(()), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_deferred_initialisation() {
+        let results = results(
+            "fn sign(x: i64): i64 {\n    let result: i64;\n    if (x < 0) {\n        result = 0 - 1;\n    } else {\n        result = 1;\n    }\n    result\n}\n\n#[test]\nfn signs(): void {\n    assert_eq(sign(0 - 5), 0 - 1);\n    assert_eq(sign(5), 1);\n}",
+        );
+
+        assert_eq!(results, vec![("signs".to_string(), Ok(()))]);
+    }
+
+    #[test]
+    fn test_strings() {
+        let results = results(
+            r#"#[test]
+fn strings(): void {
+    let name = "World";
+    let greeting = "Hello " + name;
+    assert_eq(greeting, "Hello World");
+    assert("abc" < "abd");
+    assert('a' < 'b');
+    assert_eq("{greeting}! {{{40 + 2}}} {1.5} {'x'}", "Hello World! {{42}} 1.5 x");
+}"#,
+        );
+
+        assert_eq!(results, vec![("strings".to_string(), Ok(()))]);
+    }
+
+    #[test]
+    fn test_casts() {
+        let results = results(
+            r#"#[test]
+fn casts(): void {
+    assert_eq(7 as f64 * 0.5, 3.5);
+    assert_eq(3.99 as i64, 3);
+    assert_eq((0.0 - 1.5) as i64, 0 - 1);
+    assert_eq('a' as i64, 97);
+    assert_eq(98 as char, 'b');
+    assert_eq((1 < 2) as i64, 1);
+    assert_eq((0 - 1) as char as i64, 65533);
+}"#,
+        );
+
+        assert_eq!(results, vec![("casts".to_string(), Ok(()))]);
+    }
+
+    #[test]
+    fn test_constants() {
+        let results = results(
+            r#"const N: i64 = HALF * 2;
+const HALF: i64 = 16 / 8;
+const GREETING: str = "Hello" + ", World";
+
+#[test]
+fn constants(): void {
+    const M: usize = N as usize * 2;
+    let mut xs = [0; M + 1];
+    xs[8] = 42;
+    assert_eq(xs[8], 42);
+    assert_eq(GREETING, "Hello, World");
+    assert(N / 3 == 1);
+}
+
+#[test]
+fn out_of_bounds(): void {
+    let xs = [1.5; N];
+    let index = N as usize;
+    xs[index];
+}"#,
+        );
+
+        let codes = results
+            .iter()
+            .map(|(name, result)| (name.as_str(), result.as_ref().err().map(|e| e.code())))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            vec![("constants", None), ("out_of_bounds", Some("E0021"))]
+        );
+    }
+
+    #[test]
+    fn test_sized_integers() {
+        let results = results(
+            r#"fn double(x: u8): u8 {
+    x * 2
+}
+
+#[test]
+fn sized(): void {
+    let values = [1, 2, 3];
+    let mut i = 0;
+    let mut total: u16 = 0;
+    while (i < 3) {
+        total = total + values[i];
+        i = i + 1;
+    }
+    assert_eq(total, 6);
+    assert_eq(double(100), 200);
+    assert_eq(200 as u8 as i8, 0 - 56);
+    assert_eq(0.1 as f32 as f64 == 0.1, 1 > 2);
+}
+
+#[test]
+fn overflows(): void {
+    double(200);
+}"#,
+        );
+
+        let codes = results
+            .iter()
+            .map(|(name, result)| (name.as_str(), result.as_ref().err().map(|e| e.code())))
+            .collect::<Vec<_>>();
+        assert_eq!(codes, vec![("sized", None), ("overflows", Some("E0020"))]);
+    }
+
+    #[test]
+    fn test_optionals() {
+        let results = results(
+            r#"struct Point {
+    x: i64;
+}
+
+fn find(x: i64): Point? {
+    if (x > 0) {
+        return some(Point { x: x });
+    }
+    none
+}
+
+fn x_or_zero(x: i64): i64 {
+    let point = find(x);
+    if (point != none) {
+        return point.x;
+    }
+    0
+}
+
+#[test]
+fn optionals(): void {
+    assert_eq(find(2)?.x ?? 0, 2);
+    assert_eq(find(0)?.x ?? 0, 0);
+    assert(find(0) == none);
+    assert(find(1) != none);
+    assert_eq(x_or_zero(3), 3);
+    assert_eq(x_or_zero(0), 0);
+}"#,
+        );
+
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+    }
+
+    #[test]
+    fn test_results() {
+        let results = results(
+            r#"fn digit(c: char): i64 ! str {
+    if (c >= '0') {
+        if (c <= '9') {
+            return ok(c as i64 - '0' as i64);
+        }
+    }
+    err("not a digit")
+}
+
+fn pair(a: char, b: char): i64 ! str {
+    let tens = digit(a)?;
+    ok(tens * 10 + digit(b)?)
+}
+
+#[test]
+fn results(): void {
+    assert_eq(pair('4', '2') ?? 0, 42);
+    assert_eq(pair('x', '2') ?? 0, 0);
+    assert(pair('4', 'y') == err("not a digit"));
+}
+
+#[test]
+fn panics(): void {
+    panic("something went wrong");
+}"#,
+        );
+
+        assert!(results[0].1.is_ok());
+
+        let Err(error) = &results[1].1 else {
+            panic!("test should fail");
+        };
+        assert_eq!(error.code(), "E0036");
+        assert_eq!(
+            error.to_string().lines().next(),
+            Some("error[E0036]: Program panicked: something went wrong")
+        );
+    }
+
+    #[test]
+    fn test_failing_assertion() {
+        let input = "#[test]\nfn fails(): void {\n    assert_eq(1 + 1, 3);\n}";
+        let results = results(input);
+
+        let Err(error) = &results[0].1 else {
+            panic!("test should fail");
+        };
+        assert_eq!(
+            error,
+            &RuntimeError::AssertionFailed {
+                values: Some(("2".into(), "3".into())),
+                position: error.span(),
+            }
+        );
+
+        let span = error.span();
+        assert_eq!(&input[span.start..span.end], "assert_eq(1 + 1, 3)");
+    }
+
+    #[test]
+    fn test_isolation() {
+        // structs are shared between all copies, so incrementing the counter changes the value of
+        // the constant itself
+        let input = r#"struct Counter {
+    count: i64;
+}
+
+const COUNTER: Counter = Counter { count: 0 };
+
+fn increment(): i64 {
+    let mut counter = COUNTER;
+    counter.count = counter.count + 1;
+    counter.count
+}
+
+#[test]
+fn first(): void {
+    assert_eq(increment(), 1);
+}
+
+#[test]
+fn second(): void {
+    assert_eq(increment(), 1);
+}"#;
+
+        let results = results(input);
+        assert!(results.iter().all(|(_, result)| result.is_ok()));
+
+        // within the same interpreter, the second test sees the changes of the first one
+        let program = program(input);
+        let mut interpreter = Interpreter::new(&program).unwrap();
+        assert!(interpreter.call("first", vec![]).is_ok());
+        assert_eq!(
+            interpreter.call("second", vec![]).err().map(|e| e.code()),
+            Some("E0018")
+        );
+    }
+
+    #[test]
+    fn test_runtime_errors() {
+        let results = results(
+            "declare print: (i64) -> void;\n\n#[test]\nfn overflows(): void {\n    let max = 9223372036854775807;\n    let x = max + 1;\n}\n\n#[test]\nfn indexes(): void {\n    let index = 2;\n    let x = [1, 2][index];\n}\n\n#[test]\nfn prints(): void {\n    print(42);\n}",
+        );
+
+        let codes = results
+            .iter()
+            .map(|(_, result)| result.as_ref().unwrap_err().code())
+            .collect::<Vec<_>>();
+        assert_eq!(codes, vec!["E0020", "E0021", "E0022"]);
+    }
+}
@@ -0,0 +1,26 @@
+//! Helpers for tests which compare syntax trees without caring about their positions.
+
+use std::fmt::Debug;
+
+use regex::Regex;
+
+/// Whether both values are equal when all of their spans are ignored. Both values are compared by
+/// their debug representation, in which every span is replaced by a placeholder.
+pub fn eq_ignoring_span<T: Debug>(left: &T, right: &T) -> bool {
+    let span = Regex::new(r"Span \{ file: FileId\(\d+\), start: \d+, end: \d+ \}").unwrap();
+    let strip = |value: &T| span.replace_all(&format!("{value:?}"), "Span").into_owned();
+
+    strip(left) == strip(right)
+}
+
+/// Like `assert_eq!`, but ignoring the spans of both values (see [`eq_ignoring_span`]).
+macro_rules! assert_eq_ignoring_span {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left, right) => assert!(
+                $crate::testing::eq_ignoring_span(left, right),
+                "assertion `left == right` (ignoring spans) failed\n  left: {left:?}\n right: {right:?}"
+            ),
+        }
+    };
+}
@@ -0,0 +1,100 @@
+//! Bounds checks of indexing into arrays. Indexing a fixed-size array with a constant index is
+//! checked at compile time, every other index has to be checked at runtime by the backend.
+
+use crate::parser::ast::Expression;
+
+use super::{
+    folding::{self, ConstValue, ConstantInformation},
+    types::Type,
+};
+
+/// How indexing into an array has to be checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoundsCheck {
+    /// The index is a constant within the length of a fixed-size array, so it does not have to
+    /// be checked at runtime.
+    InBounds,
+    /// The index is a constant beyond the length of a fixed-size array.
+    OutOfBounds { index: i128, length: usize },
+    /// The index is only known at runtime (or the length of the array is), so it has to be
+    /// checked against the length of the array at runtime.
+    Runtime,
+}
+
+/// Decide how indexing into a value of type `array_type` with the given index has to be checked.
+pub fn bounds_check<T: ConstantInformation>(
+    array_type: &Type,
+    index: &Expression<T>,
+) -> BoundsCheck {
+    let Type::FixedArray(_, length) = array_type else {
+        return BoundsCheck::Runtime;
+    };
+
+    match folding::evaluate(index) {
+        Ok(Some(ConstValue::Integer(index))) => match usize::try_from(index) {
+            Ok(index) if index < *length => BoundsCheck::InBounds,
+            _ => BoundsCheck::OutOfBounds {
+                index,
+                length: *length,
+            },
+        },
+        _ => BoundsCheck::Runtime,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{
+        lexer::Span,
+        parser::ast::{Expression, Id, Num},
+        typechecker::{context::Context, types::Type, TypeInformation},
+    };
+
+    use super::{bounds_check, BoundsCheck};
+
+    fn index(value: u64) -> Expression<TypeInformation> {
+        Expression::Num(Num::Integer(
+            value,
+            TypeInformation {
+                type_id: Rc::new(RefCell::new(Some(Type::USIZE))),
+                context: Context::default(),
+            },
+            Span::default(),
+        ))
+    }
+
+    #[test]
+    fn test_constant_indices() {
+        let array = Type::FixedArray(Box::new(Type::I64), 2);
+
+        assert_eq!(bounds_check(&array, &index(1)), BoundsCheck::InBounds);
+        assert_eq!(
+            bounds_check(&array, &index(2)),
+            BoundsCheck::OutOfBounds {
+                index: 2,
+                length: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_runtime_indices() {
+        let slice = Type::Array(Box::new(Type::I64));
+        assert_eq!(bounds_check(&slice, &index(5)), BoundsCheck::Runtime);
+
+        let variable = Expression::Id(Id {
+            name: "i".into(),
+            info: TypeInformation {
+                type_id: Rc::new(RefCell::new(Some(Type::USIZE))),
+                context: Context::default(),
+            },
+            position: Span::default(),
+        });
+        assert_eq!(
+            bounds_check(&Type::FixedArray(Box::new(Type::I64), 2), &variable),
+            BoundsCheck::Runtime
+        );
+    }
+}
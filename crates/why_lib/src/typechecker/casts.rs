@@ -0,0 +1,243 @@
+//! Lint for conversions with `as` which might change the converted value.
+
+use crate::parser::ast::{
+    Array, Assignment, Block, Constant, Expression, Function, If, Initialisation, Instance, LValue,
+    Lambda, Postfix, Prefix, Statement, StructInitialisation, TopLevelStatement, WhileLoop,
+};
+
+use super::{folding, types::Conversion, TypeCheckWarning, TypeInformation};
+
+/// Find all casts which are lossy, e.g., from `f64` to `i64`.
+pub fn lossy_casts(statements: &[TopLevelStatement<TypeInformation>]) -> Vec<TypeCheckWarning> {
+    let mut warnings = vec![];
+
+    for statement in statements {
+        match statement {
+            TopLevelStatement::Function(function) => self::function(function, &mut warnings),
+            TopLevelStatement::Constant(Constant { value, .. }) => expression(value, &mut warnings),
+            TopLevelStatement::Instance(Instance { functions, .. }) => {
+                for function in functions {
+                    self::function(function, &mut warnings);
+                }
+            }
+            TopLevelStatement::Comment(..)
+            | TopLevelStatement::Declaration(_)
+            | TopLevelStatement::StructDeclaration(_) => {}
+        }
+    }
+
+    warnings
+}
+
+fn function(function: &Function<TypeInformation>, warnings: &mut Vec<TypeCheckWarning>) {
+    statements(&function.statements, warnings);
+}
+
+fn statements(statements: &[Statement<TypeInformation>], warnings: &mut Vec<TypeCheckWarning>) {
+    for statement in statements {
+        match statement {
+            Statement::Function(function) => self::function(function, warnings),
+            Statement::If(if_expression) => self::if_expression(if_expression, warnings),
+            Statement::WhileLoop(WhileLoop {
+                condition, block, ..
+            }) => {
+                expression(condition, warnings);
+                self::statements(&block.statements, warnings);
+            }
+            Statement::Initialization(Initialisation { value, .. }) => {
+                if let Some(value) = value {
+                    expression(value, warnings);
+                }
+            }
+            Statement::Constant(Constant { value, .. })
+            | Statement::Expression(value)
+            | Statement::YieldingExpression(value)
+            | Statement::Return(value) => expression(value, warnings),
+            Statement::Assignment(Assignment { lvalue, rvalue, .. }) => {
+                if let LValue::Postfix(postfix) = lvalue {
+                    self::postfix(postfix, warnings);
+                }
+                expression(rvalue, warnings);
+            }
+            Statement::Declaration(_) | Statement::StructDeclaration(_) => {}
+            Statement::Comment(..) => {}
+        }
+    }
+}
+
+fn if_expression(if_expression: &If<TypeInformation>, warnings: &mut Vec<TypeCheckWarning>) {
+    let If {
+        condition,
+        statements,
+        else_statements,
+        ..
+    } = if_expression;
+
+    expression(condition, warnings);
+    self::statements(statements, warnings);
+    self::statements(else_statements, warnings);
+}
+
+fn expression(expression: &Expression<TypeInformation>, warnings: &mut Vec<TypeCheckWarning>) {
+    match expression {
+        Expression::Id(_)
+        | Expression::Num(_)
+        | Expression::Character(_)
+        | Expression::AstString(_) => {}
+        Expression::Function(function) => self::function(function, warnings),
+        Expression::Lambda(Lambda { expression, .. })
+        | Expression::Parens(expression)
+        | Expression::Prefix(Prefix::Negation {
+            expr: expression, ..
+        })
+        | Expression::Prefix(Prefix::Minus {
+            expr: expression, ..
+        }) => self::expression(expression, warnings),
+        Expression::If(if_expression) => self::if_expression(if_expression, warnings),
+        Expression::Block(Block { statements, .. }) => self::statements(statements, warnings),
+        Expression::Postfix(postfix) => self::postfix(postfix, warnings),
+        Expression::Binary(binary) => {
+            self::expression(&binary.left, warnings);
+            self::expression(&binary.right, warnings);
+        }
+        Expression::Array(Array::Literal { values, .. }) => values
+            .iter()
+            .for_each(|value| self::expression(value, warnings)),
+        Expression::Array(Array::Default {
+            initial_value,
+            length,
+            ..
+        }) => {
+            self::expression(initial_value, warnings);
+            self::expression(length, warnings);
+        }
+        Expression::StructInitialisation(StructInitialisation { fields, .. }) => fields
+            .iter()
+            .for_each(|field| self::expression(&field.value, warnings)),
+        Expression::Interpolation(interpolation) => interpolation
+            .expressions()
+            .for_each(|expression| self::expression(expression, warnings)),
+    }
+}
+
+fn postfix(postfix: &Postfix<TypeInformation>, warnings: &mut Vec<TypeCheckWarning>) {
+    match postfix {
+        Postfix::Call { expr, args, .. } => {
+            expression(expr, warnings);
+            args.iter().for_each(|arg| expression(arg, warnings));
+        }
+        Postfix::Index { expr, index, .. } => {
+            expression(expr, warnings);
+            expression(index, warnings);
+        }
+        Postfix::PropertyAccess { expr, .. }
+        | Postfix::OptionalPropertyAccess { expr, .. }
+        | Postfix::Propagate { expr, .. } => expression(expr, warnings),
+        Postfix::Cast {
+            expr,
+            info,
+            position,
+            ..
+        } => {
+            expression(expr, warnings);
+
+            let (Some(from), Some(to)) = (expr.get_info().resolved_type(), info.resolved_type())
+            else {
+                return;
+            };
+
+            // the value of a constant is only changed, if it can not be represented exactly
+            let exact = matches!(
+                folding::evaluate(expr.as_ref()),
+                Ok(Some(value)) if value.converts_exactly(&to)
+            );
+
+            if from.conversion(&to) == Some(Conversion::Lossy) && !exact {
+                warnings.push(TypeCheckWarning::LossyCast(
+                    from,
+                    to,
+                    expr.position().merge(position),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lexer::Lexer,
+        parser::parse,
+        typechecker::{
+            types::{FloatType, IntegerType},
+            Type, TypeCheckWarning, TypeChecker,
+        },
+    };
+
+    use super::*;
+
+    fn lossy_casts_of(input: &str) -> Vec<(Type, Type)> {
+        let tokens = Lexer::new(input).lex().expect("should lex");
+        let statements = parse(&mut tokens.into()).expect("should parse");
+        let checked = TypeChecker::new(statements)
+            .check_all()
+            .expect("should typecheck");
+
+        lossy_casts(&checked)
+            .into_iter()
+            .map(|warning| match warning {
+                TypeCheckWarning::LossyCast(from, to, _) => (from, to),
+                warning => panic!("unexpected warning {warning:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_lossy_casts() {
+        assert_eq!(
+            lossy_casts_of(
+                "fn main(): void {\n    let x = 1.5 as i64;\n    let y = 'a' as i64 + ((1 < 2) as i64);\n    let z = [x as f64, (y as char) as i64 as f64];\n}"
+            ),
+            vec![
+                (Type::F64, Type::I64),
+                (Type::I64, Type::F64),
+                (Type::I64, Type::Character),
+                (Type::I64, Type::F64),
+            ]
+        );
+
+        // constants are only reported, if their value changes
+        assert_eq!(
+            lossy_casts_of(
+                "const C: i64 = 300;\nconst D: f64 = 0.1;\n\nfn main(): void {\n    let a = 3 as f64;\n    let b = 2.0 as i64;\n    let c = (C - 200) as u8;\n    let d = C as u8;\n    let e = D as f32;\n    let f = (C - 301) as u64;\n}"
+            ),
+            vec![
+                (Type::I64, Type::Integer(IntegerType::U8)),
+                (Type::F64, Type::FloatingPoint(FloatType::F32)),
+                (Type::I64, Type::Integer(IntegerType::U64)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_casts_keep_operand_types() {
+        // the cast does not turn `x` into an `i32`
+        assert_eq!(
+            lossy_casts_of(
+                "fn main(): void {\n    let x = 5;\n    let _y = x as i32;\n    let _z: i64 = x;\n}"
+            ),
+            vec![(Type::I64, Type::Integer(IntegerType::I32))]
+        );
+
+        // values out of range of the target type wrap around
+        assert_eq!(
+            lossy_casts_of(
+                "fn main(): void {\n    let a = 256;\n    let b = a as u8;\n    let c = 1000 as u8;\n    let d = 255 as u8;\n}"
+            ),
+            vec![
+                (Type::I64, Type::Integer(IntegerType::U8)),
+                (Type::I64, Type::Integer(IntegerType::U8)),
+            ]
+        );
+    }
+}
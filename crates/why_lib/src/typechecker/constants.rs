@@ -0,0 +1,303 @@
+//! Dependencies between toplevel constants. The value of a constant can use other constants
+//! (e.g., `const B: i64 = A * 2;`), so constants are checked after the constants they use, which
+//! makes their values known wherever they are needed. Constants whose values depend on
+//! themselves can not be evaluated at all and are reported.
+
+use std::collections::{BTreeSet, HashMap};
+
+use crate::parser::ast::{
+    Array, Assignment, Block, Constant, Expression, If, Initialisation, LValue, Postfix, Prefix,
+    Statement, StructInitialisation, TopLevelStatement, WhileLoop,
+};
+
+use super::error::{CyclicConstant, TypeCheckError};
+
+#[derive(Debug, Default)]
+pub struct ConstantGraph {
+    /// Indices of all constants, where every constant comes after the constants it uses.
+    pub order: Vec<usize>,
+    /// Errors for cycles of constants, together with the index of the constant they are
+    /// reported at (the first constant of the cycle in the source).
+    pub cycles: Vec<(usize, TypeCheckError)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    New,
+    Active,
+    Done,
+}
+
+struct Visitor<'a, T> {
+    constants: Vec<(usize, &'a Constant<T>)>,
+    /// Constants (by their position in `constants`) used by the value of every constant.
+    dependencies: Vec<Vec<usize>>,
+    states: Vec<State>,
+    stack: Vec<usize>,
+    graph: ConstantGraph,
+}
+
+/// Order the toplevel constants among the given statements by their dependencies and find the
+/// cycles between them.
+pub fn analyse<T>(statements: &[TopLevelStatement<T>]) -> ConstantGraph {
+    let constants = statements
+        .iter()
+        .enumerate()
+        .filter_map(|(index, statement)| match statement {
+            TopLevelStatement::Constant(constant) => Some((index, constant)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let by_name = constants
+        .iter()
+        .enumerate()
+        .map(|(node, (_, constant))| (constant.id.name.as_str(), node))
+        .collect::<HashMap<_, _>>();
+
+    let dependencies = constants
+        .iter()
+        .map(|(_, constant)| {
+            let mut names = BTreeSet::new();
+            expression(&constant.value, &mut names);
+
+            names
+                .iter()
+                .filter_map(|name| by_name.get(name.as_str()).copied())
+                .collect()
+        })
+        .collect();
+
+    let mut visitor = Visitor {
+        states: vec![State::New; constants.len()],
+        constants,
+        dependencies,
+        stack: vec![],
+        graph: ConstantGraph::default(),
+    };
+
+    for node in 0..visitor.constants.len() {
+        visitor.visit(node);
+    }
+
+    visitor.graph.cycles.sort_by_key(|(index, _)| *index);
+    visitor.graph
+}
+
+/// Indices of all toplevel constants among the given statements, where every constant comes after
+/// the constants it uses, i.e., the order in which their values can be evaluated.
+pub fn constant_order<T>(statements: &[TopLevelStatement<T>]) -> Vec<usize> {
+    analyse(statements).order
+}
+
+impl<T> Visitor<'_, T> {
+    fn visit(&mut self, node: usize) {
+        match self.states[node] {
+            State::Done => return,
+            State::Active => {
+                let start = self
+                    .stack
+                    .iter()
+                    .position(|other| *other == node)
+                    .expect("active constants should be on the stack");
+                self.cycle(self.stack[start..].to_vec());
+                return;
+            }
+            State::New => {}
+        }
+
+        self.states[node] = State::Active;
+        self.stack.push(node);
+
+        for dependency in self.dependencies[node].clone() {
+            self.visit(dependency);
+        }
+
+        self.stack.pop();
+        self.states[node] = State::Done;
+        self.graph.order.push(self.constants[node].0);
+    }
+
+    fn cycle(&mut self, mut cycle: Vec<usize>) {
+        // the cycle is reported at the constant which comes first in the source
+        let first = (0..cycle.len())
+            .min_by_key(|i| self.constants[cycle[*i]].0)
+            .unwrap_or_default();
+        cycle.rotate_left(first);
+
+        let (index, constant) = self.constants[cycle[0]];
+        if self.graph.cycles.iter().any(|(other, _)| *other == index) {
+            return;
+        }
+
+        let names = cycle
+            .iter()
+            .chain(cycle.first())
+            .map(|node| self.constants[*node].1.id.name.clone())
+            .collect();
+
+        self.graph.cycles.push((
+            index,
+            TypeCheckError::CyclicConstant(
+                CyclicConstant { cycle: names },
+                constant.id.position.clone(),
+            ),
+        ));
+    }
+}
+
+fn statements<T>(statements: &[Statement<T>], names: &mut BTreeSet<String>) {
+    for statement in statements {
+        match statement {
+            Statement::If(if_expression) => self::if_expression(if_expression, names),
+            Statement::WhileLoop(WhileLoop {
+                condition, block, ..
+            }) => {
+                expression(condition, names);
+                self::statements(&block.statements, names);
+            }
+            Statement::Initialization(Initialisation { value, .. }) => {
+                if let Some(value) = value {
+                    expression(value, names);
+                }
+            }
+            Statement::Constant(Constant { value, .. })
+            | Statement::Expression(value)
+            | Statement::YieldingExpression(value)
+            | Statement::Return(value) => expression(value, names),
+            Statement::Assignment(Assignment { lvalue, rvalue, .. }) => {
+                if let LValue::Postfix(postfix) = lvalue {
+                    self::postfix(postfix, names);
+                }
+                expression(rvalue, names);
+            }
+            // functions are not evaluated when the constant is
+            Statement::Function(_)
+            | Statement::Declaration(_)
+            | Statement::StructDeclaration(_)
+            | Statement::Comment(..) => {}
+        }
+    }
+}
+
+fn if_expression<T>(if_expression: &If<T>, names: &mut BTreeSet<String>) {
+    let If {
+        condition,
+        statements,
+        else_statements,
+        ..
+    } = if_expression;
+
+    expression(condition, names);
+    self::statements(statements, names);
+    self::statements(else_statements, names);
+}
+
+fn expression<T>(expression: &Expression<T>, names: &mut BTreeSet<String>) {
+    match expression {
+        Expression::Id(id) => {
+            names.insert(id.name.clone());
+        }
+        // functions and lambdas are not evaluated when the constant is
+        Expression::Num(_)
+        | Expression::Character(_)
+        | Expression::AstString(_)
+        | Expression::Function(_)
+        | Expression::Lambda(_) => {}
+        Expression::Parens(expression)
+        | Expression::Prefix(Prefix::Negation {
+            expr: expression, ..
+        })
+        | Expression::Prefix(Prefix::Minus {
+            expr: expression, ..
+        }) => self::expression(expression, names),
+        Expression::If(if_expression) => self::if_expression(if_expression, names),
+        Expression::Block(Block { statements, .. }) => self::statements(statements, names),
+        Expression::Postfix(postfix) => self::postfix(postfix, names),
+        Expression::Binary(binary) => {
+            self::expression(&binary.left, names);
+            self::expression(&binary.right, names);
+        }
+        Expression::Array(Array::Literal { values, .. }) => values
+            .iter()
+            .for_each(|value| self::expression(value, names)),
+        Expression::Array(Array::Default {
+            initial_value,
+            length,
+            ..
+        }) => {
+            self::expression(initial_value, names);
+            self::expression(length, names);
+        }
+        Expression::StructInitialisation(StructInitialisation { fields, .. }) => fields
+            .iter()
+            .for_each(|field| self::expression(&field.value, names)),
+        Expression::Interpolation(interpolation) => interpolation
+            .expressions()
+            .for_each(|expression| self::expression(expression, names)),
+    }
+}
+
+fn postfix<T>(postfix: &Postfix<T>, names: &mut BTreeSet<String>) {
+    match postfix {
+        Postfix::Call { expr, args, .. } => {
+            expression(expr, names);
+            args.iter().for_each(|arg| expression(arg, names));
+        }
+        Postfix::Index { expr, index, .. } => {
+            expression(expr, names);
+            expression(index, names);
+        }
+        Postfix::PropertyAccess { expr, .. }
+        | Postfix::OptionalPropertyAccess { expr, .. }
+        | Postfix::Propagate { expr, .. }
+        | Postfix::Cast { expr, .. } => expression(expr, names),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{lexer::Lexer, parser::parse};
+
+    use super::*;
+
+    fn analyse_source(input: &str) -> (Vec<usize>, Vec<(usize, String)>) {
+        let tokens = Lexer::new(input).lex().expect("should lex");
+        let statements = parse(&mut tokens.into()).expect("should parse");
+        let graph = analyse(&statements);
+
+        (
+            graph.order,
+            graph
+                .cycles
+                .into_iter()
+                .map(|(index, error)| (index, error.err().to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_constant_order() {
+        assert_eq!(
+            analyse_source(
+                "const A: i64 = B * C;\nconst B: i64 = C + 1;\nfn main(): void {}\nconst C: i64 = 2;"
+            ),
+            (vec![3, 1, 0], vec![])
+        );
+    }
+
+    #[test]
+    fn test_constant_cycles() {
+        let (_, cycles) = analyse_source(
+            "const A: i64 = B;\nconst B: i64 = { C + A };\nconst C: i64 = 1;\nconst D: i64 = D + 1;\nconst E: () -> i64 = \\() => E();",
+        );
+
+        assert_eq!(
+            cycles,
+            vec![
+                (0, "Constant 'A' depends on itself: A -> B -> A".into()),
+                (3, "Constant 'D' depends on itself: D -> D".into()),
+            ]
+        );
+    }
+}
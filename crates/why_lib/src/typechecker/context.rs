@@ -1,14 +1,109 @@
-use super::scope::Scope;
+use std::{cell::RefCell, rc::Rc};
+
+use super::{
+    error::TypeMismatch,
+    inference::{Substitution, VariableKind},
+    scope::{Scope, ScopeSnapshot},
+    types::Type,
+};
 
 #[derive(Debug, Clone)]
 pub struct Context {
     pub scope: Scope,
+    substitution: Rc<RefCell<Substitution>>,
+}
+
+/// A copy of a [`Context`], see [`Context::snapshot`].
+#[derive(Debug)]
+pub struct ContextSnapshot {
+    scope: ScopeSnapshot,
+    substitution: Substitution,
+}
+
+impl Context {
+    /// Create a new type variable for a type which has to be inferred.
+    pub fn fresh_variable(&self) -> Type {
+        self.substitution.borrow_mut().fresh()
+    }
+
+    /// Create a new type variable for the type of a number literal, which is inferred from the
+    /// context the literal is used in.
+    pub fn fresh_literal(&self, kind: VariableKind) -> Type {
+        self.substitution.borrow_mut().fresh_of_kind(kind)
+    }
+
+    /// Number of type variables created so far. Pass it to [`Context::default_literals`] to
+    /// only default the literals checked afterwards.
+    pub fn variable_count(&self) -> usize {
+        self.substitution.borrow().variable_count()
+    }
+
+    /// Give number literals (checked since `variable_count` variables existed) whose type could
+    /// not be inferred from their context the default type `i64` or `f64`.
+    pub fn default_literals(&self, variable_count: usize) {
+        self.substitution
+            .borrow_mut()
+            .default_literals(variable_count);
+    }
+
+    /// Resolve the given type, where the types of number literals which are not inferred yet
+    /// are bound to their default type. A literal (not nested within another type) is bound to
+    /// `preferred` instead, if that is a compatible number type. This is meant for places which
+    /// need to know the exact type right away, e.g., casts.
+    pub fn resolve_literals(&self, type_id: &Type, preferred: Option<&Type>) -> Type {
+        let type_id = self.resolve(type_id);
+
+        let literal = self.substitution.borrow().is_literal(&type_id);
+        if literal && preferred.is_some_and(|preferred| self.unify(preferred, &type_id).is_ok()) {
+            return self.resolve(&type_id);
+        }
+
+        let mut substitution = self.substitution.borrow_mut();
+        let defaulted = substitution.resolve_with_defaults(&type_id);
+        substitution.unify(&type_id, &defaulted);
+        defaulted
+    }
+
+    /// Take a snapshot of the scope and all inferred types, e.g., to undo the effects of a
+    /// statement which does not typecheck.
+    pub fn snapshot(&self) -> ContextSnapshot {
+        ContextSnapshot {
+            scope: self.scope.snapshot(),
+            substitution: self.substitution.borrow().clone(),
+        }
+    }
+
+    /// Reset this context (and all contexts sharing its frames) to the given snapshot.
+    pub fn restore(&mut self, snapshot: ContextSnapshot) {
+        self.scope.restore(snapshot.scope);
+        *self.substitution.borrow_mut() = snapshot.substitution;
+    }
+
+    /// Replace all type variables which have already been inferred within the given type.
+    pub fn resolve(&self, type_id: &Type) -> Type {
+        self.substitution.borrow().resolve(type_id)
+    }
+
+    /// Make sure that two types are equal, inferring type variables along the way.
+    pub fn unify(&self, expected: &Type, actual: &Type) -> Result<(), TypeMismatch> {
+        if self.substitution.borrow_mut().unify(expected, actual) {
+            return Ok(());
+        }
+
+        let substitution = self.substitution.borrow();
+
+        Err(TypeMismatch {
+            expected: substitution.resolve_with_defaults(expected),
+            actual: substitution.resolve_with_defaults(actual),
+        })
+    }
 }
 
 impl Default for Context {
     fn default() -> Self {
         Context {
             scope: Scope::new(),
+            substitution: Rc::default(),
         }
     }
 }
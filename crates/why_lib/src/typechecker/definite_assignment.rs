@@ -0,0 +1,430 @@
+//! Definite assignment analysis of function bodies. Variables which are declared without a value
+//! (e.g., `let x: i64;`) have to be assigned on every path before they are used. Unless they
+//! are mutable, they must not be assigned more than once.
+
+use crate::{
+    lexer::Span,
+    parser::ast::{
+        Array, Assignment, BinaryExpression, Block, Constant, Declaration, Expression, Function,
+        Id, If, Initialisation, LValue, Lambda, LambdaParameter, Postfix, Prefix, Statement,
+        StructFieldInitialisation, StructInitialisation, WhileLoop,
+    },
+};
+
+use super::{
+    error::{ImmutableReassign, TypeCheckError, UninitialisedVariable},
+    flow::{self, Divergence},
+    TypeResult,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum State {
+    Unassigned,
+    /// Assigned on some, but not on all paths.
+    MaybeAssigned,
+    Assigned,
+}
+
+impl State {
+    /// State after paths with the given states come together.
+    fn join(self, other: State) -> State {
+        if self == other {
+            self
+        } else {
+            State::MaybeAssigned
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Variable {
+    mutable: bool,
+    declaration: Span,
+    state: State,
+}
+
+/// Variables in scope by their name. Only variables declared without a value are tracked, all
+/// other bindings are `None` (they are still needed, because they shadow tracked variables).
+#[derive(Clone, Debug, Default)]
+struct Analysis {
+    scopes: Vec<Vec<(String, Option<Variable>)>>,
+}
+
+/// Check that every variable of the given function body, which is declared without a value, is
+/// assigned before it is used.
+pub fn check<T: Clone + Divergence>(statements: &[Statement<T>]) -> TypeResult<()> {
+    Analysis::default().scoped(statements)
+}
+
+impl Analysis {
+    fn bind(&mut self, name: &str, variable: Option<Variable>) {
+        if self.scopes.is_empty() {
+            self.scopes.push(vec![]);
+        }
+
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push((name.to_string(), variable));
+        }
+    }
+
+    fn variable(&mut self, name: &str) -> Option<&mut Variable> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|(variable_name, _)| variable_name == name)
+            .and_then(|(_, variable)| variable.as_mut())
+    }
+
+    /// Merge the state of another path through the same code into this one.
+    fn join(&mut self, other: &Analysis) {
+        for (scope, other_scope) in self.scopes.iter_mut().zip(other.scopes.iter()) {
+            for ((_, variable), (_, other_variable)) in scope.iter_mut().zip(other_scope.iter()) {
+                if let (Some(variable), Some(other_variable)) = (variable, other_variable) {
+                    variable.state = variable.state.join(other_variable.state);
+                }
+            }
+        }
+    }
+
+    /// Analyse statements within their own scope.
+    fn scoped<T: Clone + Divergence>(&mut self, statements: &[Statement<T>]) -> TypeResult<()> {
+        self.scopes.push(vec![]);
+        let result = statements
+            .iter()
+            .try_for_each(|statement| self.statement(statement));
+        self.scopes.pop();
+
+        result
+    }
+
+    /// Analyse alternative branches of which exactly one is executed. Branches which do not
+    /// reach their end (e.g., because they return) do not influence the state afterwards.
+    fn branches<T: Clone + Divergence>(
+        &mut self,
+        branches: [&[Statement<T>]; 2],
+    ) -> TypeResult<()> {
+        let mut continuations = vec![];
+
+        for statements in branches {
+            let mut branch = self.clone();
+            branch.scoped(statements)?;
+
+            if flow::falls_through(statements) {
+                continuations.push(branch);
+            }
+        }
+
+        let mut continuations = continuations.into_iter();
+        if let Some(first) = continuations.next() {
+            *self = first;
+            for continuation in continuations {
+                self.join(&continuation);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn statement<T: Clone + Divergence>(&mut self, statement: &Statement<T>) -> TypeResult<()> {
+        match statement {
+            Statement::If(if_expression) => self.if_expression(if_expression),
+            Statement::WhileLoop(WhileLoop {
+                condition, block, ..
+            }) => {
+                self.expression(condition)?;
+
+                // the body might be executed again after it was executed once, so it is analysed
+                // a second time with the state of both cases combined
+                let mut first_iteration = self.clone();
+                let _ = first_iteration.scoped(&block.statements);
+
+                let mut iteration = self.clone();
+                iteration.join(&first_iteration);
+                iteration.expression(condition)?;
+                let mut end_of_iteration = iteration.clone();
+                end_of_iteration.scoped(&block.statements)?;
+
+                // the loop is left when the condition does not hold (anymore)
+                *self = iteration;
+                self.join(&end_of_iteration);
+
+                Ok(())
+            }
+            Statement::Initialization(Initialisation {
+                id,
+                mutable,
+                value,
+                position,
+                ..
+            }) => {
+                let variable = match value {
+                    Some(value) => {
+                        self.expression(value)?;
+                        None
+                    }
+                    None => Some(Variable {
+                        mutable: *mutable,
+                        declaration: position.clone(),
+                        state: State::Unassigned,
+                    }),
+                };
+
+                self.bind(&id.name, variable);
+                Ok(())
+            }
+            Statement::Constant(Constant { id, value, .. }) => {
+                self.expression(value)?;
+                self.bind(&id.name, None);
+                Ok(())
+            }
+            Statement::Assignment(Assignment {
+                lvalue,
+                rvalue,
+                position,
+                ..
+            }) => {
+                self.expression(rvalue)?;
+
+                if let LValue::Postfix(postfix) = lvalue {
+                    self.postfix(postfix)?;
+                }
+
+                let id = lvalue.get_original_variable_name();
+                let Some(variable) = self.variable(&id.name) else {
+                    return Ok(());
+                };
+
+                // a variable without a value can be assigned once, even if it is not mutable
+                let reassigned = match lvalue {
+                    LValue::Id(_) => variable.state != State::Unassigned,
+                    LValue::Postfix(_) => true,
+                };
+
+                if reassigned && !variable.mutable {
+                    return Err(TypeCheckError::ImmutableReassign(
+                        ImmutableReassign {
+                            variable_name: id.name.clone(),
+                            declaration: Some(variable.declaration.clone()),
+                        },
+                        position.clone(),
+                    ));
+                }
+
+                variable.state = State::Assigned;
+                Ok(())
+            }
+            Statement::Expression(expression)
+            | Statement::YieldingExpression(expression)
+            | Statement::Return(expression) => self.expression(expression),
+            // nested functions are analysed on their own
+            Statement::Function(Function { id, .. })
+            | Statement::Declaration(Declaration { name: id, .. }) => {
+                self.bind(&id.name, None);
+                Ok(())
+            }
+            Statement::Comment(..) | Statement::StructDeclaration(_) => Ok(()),
+        }
+    }
+
+    fn if_expression<T: Clone + Divergence>(&mut self, if_expression: &If<T>) -> TypeResult<()> {
+        let If {
+            condition,
+            statements,
+            else_statements,
+            ..
+        } = if_expression;
+
+        self.expression(condition)?;
+        self.branches([statements, else_statements])
+    }
+
+    fn expression<T: Clone + Divergence>(&mut self, expression: &Expression<T>) -> TypeResult<()> {
+        match expression {
+            Expression::Id(Id { name, position, .. }) => match self.variable(name) {
+                Some(Variable {
+                    state: State::Unassigned | State::MaybeAssigned,
+                    declaration,
+                    ..
+                }) => Err(TypeCheckError::UninitialisedVariable(
+                    UninitialisedVariable {
+                        variable_name: name.clone(),
+                        declaration: declaration.clone(),
+                    },
+                    position.clone(),
+                )),
+                _ => Ok(()),
+            },
+            Expression::Num(_)
+            | Expression::Character(_)
+            | Expression::AstString(_)
+            | Expression::Function(_) => Ok(()),
+            Expression::Lambda(Lambda {
+                parameters,
+                expression,
+                ..
+            }) => {
+                // the body of a lambda is executed later (if at all), so assignments within it do
+                // not count
+                let mut lambda = self.clone();
+                lambda.scopes.push(vec![]);
+                for LambdaParameter { name, .. } in parameters {
+                    lambda.bind(&name.name, None);
+                }
+
+                lambda.expression(expression)
+            }
+            Expression::If(if_expression) => self.if_expression(if_expression),
+            Expression::Block(Block { statements, .. }) => self.scoped(statements),
+            Expression::Parens(expression)
+            | Expression::Prefix(Prefix::Negation {
+                expr: expression, ..
+            })
+            | Expression::Prefix(Prefix::Minus {
+                expr: expression, ..
+            }) => self.expression(expression),
+            Expression::Postfix(postfix) => self.postfix(postfix),
+            Expression::Binary(binary) => {
+                let BinaryExpression { left, right, .. } = binary.as_ref();
+
+                self.expression(left)?;
+                self.expression(right)
+            }
+            Expression::Array(Array::Literal { values, .. }) => {
+                values.iter().try_for_each(|value| self.expression(value))
+            }
+            Expression::Array(Array::Default {
+                initial_value,
+                length,
+                ..
+            }) => {
+                self.expression(initial_value)?;
+                self.expression(length)
+            }
+            Expression::StructInitialisation(StructInitialisation { fields, .. }) => fields
+                .iter()
+                .try_for_each(|StructFieldInitialisation { value, .. }| self.expression(value)),
+            Expression::Interpolation(interpolation) => interpolation
+                .expressions()
+                .try_for_each(|expression| self.expression(expression)),
+        }
+    }
+
+    fn postfix<T: Clone + Divergence>(&mut self, postfix: &Postfix<T>) -> TypeResult<()> {
+        match postfix {
+            Postfix::Call { expr, args, .. } => {
+                self.expression(expr)?;
+                args.iter().try_for_each(|arg| self.expression(arg))
+            }
+            Postfix::Index { expr, index, .. } => {
+                self.expression(expr)?;
+                self.expression(index)
+            }
+            Postfix::PropertyAccess { expr, .. }
+            | Postfix::OptionalPropertyAccess { expr, .. }
+            | Postfix::Propagate { expr, .. }
+            | Postfix::Cast { expr, .. } => self.expression(expr),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lexer::Lexer,
+        parser::{ast::TopLevelStatement, parse},
+    };
+
+    use super::*;
+
+    fn check_function(input: &str) -> TypeResult<()> {
+        let tokens = Lexer::new(input).lex().expect("should lex");
+        let mut statements = parse(&mut tokens.into()).expect("should parse");
+
+        let TopLevelStatement::Function(function) = statements.remove(0) else {
+            panic!("expected a function");
+        };
+        check(&function.statements)
+    }
+
+    fn error_code(input: &str) -> Option<&'static str> {
+        check_function(input).err().map(|e| e.code())
+    }
+
+    #[test]
+    fn test_assigned_before_use() {
+        assert_eq!(
+            error_code("fn f(): i64 {\n    let x: i64;\n    x = 42;\n    x\n}"),
+            None
+        );
+        assert_eq!(
+            error_code("fn f(): i64 {\n    let x: i64;\n    x\n}"),
+            Some("E0027")
+        );
+        assert_eq!(
+            error_code("fn f(): i64 {\n    let x: i64;\n    x = x + 1;\n    x\n}"),
+            Some("E0027")
+        );
+    }
+
+    #[test]
+    fn test_branches() {
+        assert_eq!(
+            error_code("fn f(c: bool): i64 {\n    let x: i64;\n    if (c) {\n        x = 1;\n    } else {\n        x = 2;\n    }\n    x\n}"),
+            None
+        );
+        assert_eq!(
+            error_code("fn f(c: bool): i64 {\n    let x: i64;\n    if (c) {\n        x = 1;\n    }\n    x\n}"),
+            Some("E0027")
+        );
+        // branches which return do not have to assign
+        assert_eq!(
+            error_code("fn f(c: bool): i64 {\n    let x: i64;\n    if (c) {\n        x = 1;\n    } else {\n        return 0;\n    }\n    x\n}"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_loops() {
+        assert_eq!(
+            error_code("fn f(c: bool): i64 {\n    let mut x: i64;\n    while (c) {\n        x = 1;\n    }\n    x\n}"),
+            Some("E0027")
+        );
+        assert_eq!(
+            error_code(
+                "fn f(c: bool): void {\n    let x: i64;\n    while (c) {\n        x = 1;\n    }\n}"
+            ),
+            Some("E0011")
+        );
+        assert_eq!(
+            error_code("fn f(c: bool): void {\n    let mut x: i64;\n    while (c) {\n        x = 1;\n    }\n}"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_single_assignment() {
+        assert_eq!(
+            error_code("fn f(): i64 {\n    let x: i64;\n    x = 1;\n    x = 2;\n    x\n}"),
+            Some("E0011")
+        );
+        assert_eq!(
+            error_code("fn f(): i64 {\n    let mut x: i64;\n    x = 1;\n    x = 2;\n    x\n}"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_shadowing_and_lambdas() {
+        assert_eq!(
+            error_code("fn f(): i64 {\n    let x: i64;\n    let g = \\(x) => x;\n    {\n        let x = 1;\n        x\n    }\n}"),
+            None
+        );
+        assert_eq!(
+            error_code(
+                "fn f(): i64 {\n    let x: i64;\n    let g = \\() => x;\n    x = 1;\n    x\n}"
+            ),
+            Some("E0027")
+        );
+    }
+}
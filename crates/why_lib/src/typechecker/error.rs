@@ -1,6 +1,10 @@
 use std::{error::Error, fmt::Display};
 
-use crate::{lexer::Span, parser::ast::TypeName};
+use crate::{
+    diagnostic::{Diagnostic, Fix},
+    lexer::Span,
+    parser::ast::{SpansMut, TypeName},
+};
 
 use super::types::Type;
 
@@ -18,15 +22,65 @@ pub enum TypeCheckError {
     MissingMainFunction(MissingMainFunction),
     InvalidMainSignature(InvalidMainSignature, Span),
     UnsupportedBinaryOperation(UnsupportedBinaryOperation, Span),
+    UnknownAttribute(UnknownAttribute, Span),
+    InvalidTestSignature(InvalidTestSignature, Span),
+    MissingReturn(MissingReturn, Span),
+    UninitialisedVariable(UninitialisedVariable, Span),
+    AmbiguousType(AmbiguousType, Span),
+    InvalidInterpolation(InvalidInterpolation, Span),
+    InvalidCast(InvalidCast, Span),
+    IntegerOverflow(IntegerOverflow, Span),
+    DivisionByZero(DivisionByZero, Span),
+    NonConstantArrayLength(NonConstantArrayLength, Span),
+    IndexOutOfBounds(IndexOutOfBounds, Span),
+    InvalidPropagation(InvalidPropagation, Span),
+    UnsignedNegation(UnsignedNegation, Span),
+    NonResultPropagation(NonResultPropagation, Span),
+    PropertyOfOptional(PropertyOfOptional, Span),
+    CyclicConstant(CyclicConstant, Span),
 }
 
 impl Display for TypeCheckError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.span().to_string(self.err()).as_str())
+        f.write_str(&self.diagnostic().to_string())
     }
 }
 
 impl TypeCheckError {
+    /// Stable code identifying the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TypeCheckError::TypeMismatch(..) => "E0003",
+            TypeCheckError::UndefinedVariable(..) => "E0004",
+            TypeCheckError::UndefinedType(..) => "E0005",
+            TypeCheckError::MissingInitialisationType(..) => "E0006",
+            TypeCheckError::InvalidConstantType(..) => "E0007",
+            TypeCheckError::RedefinedConstant(..) => "E0008",
+            TypeCheckError::RedefinedFunction(..) => "E0009",
+            TypeCheckError::RedefinedMethod(..) => "E0010",
+            TypeCheckError::ImmutableReassign(..) => "E0011",
+            TypeCheckError::MissingMainFunction(..) => "E0012",
+            TypeCheckError::InvalidMainSignature(..) => "E0013",
+            TypeCheckError::UnsupportedBinaryOperation(..) => "E0014",
+            TypeCheckError::UnknownAttribute(..) => "E0016",
+            TypeCheckError::InvalidTestSignature(..) => "E0017",
+            TypeCheckError::MissingReturn(..) => "E0026",
+            TypeCheckError::UninitialisedVariable(..) => "E0027",
+            TypeCheckError::AmbiguousType(..) => "E0028",
+            TypeCheckError::InvalidInterpolation(..) => "E0029",
+            TypeCheckError::InvalidCast(..) => "E0030",
+            TypeCheckError::IntegerOverflow(..) => "E0031",
+            TypeCheckError::DivisionByZero(..) => "E0032",
+            TypeCheckError::NonConstantArrayLength(..) => "E0033",
+            TypeCheckError::IndexOutOfBounds(..) => "E0034",
+            TypeCheckError::InvalidPropagation(..) => "E0035",
+            TypeCheckError::UnsignedNegation(..) => "E0037",
+            TypeCheckError::NonResultPropagation(..) => "E0038",
+            TypeCheckError::PropertyOfOptional(..) => "E0039",
+            TypeCheckError::CyclicConstant(..) => "E0040",
+        }
+    }
+
     pub fn span(&self) -> Span {
         match self {
             TypeCheckError::TypeMismatch(_, span) => span.clone(),
@@ -41,6 +95,210 @@ impl TypeCheckError {
             TypeCheckError::MissingMainFunction(_) => Span::default(),
             TypeCheckError::InvalidMainSignature(_, span) => span.clone(),
             TypeCheckError::UnsupportedBinaryOperation(_, span) => span.clone(),
+            TypeCheckError::UnknownAttribute(_, span) => span.clone(),
+            TypeCheckError::InvalidTestSignature(_, span) => span.clone(),
+            TypeCheckError::MissingReturn(_, span) => span.clone(),
+            TypeCheckError::UninitialisedVariable(_, span) => span.clone(),
+            TypeCheckError::AmbiguousType(_, span) => span.clone(),
+            TypeCheckError::InvalidInterpolation(_, span) => span.clone(),
+            TypeCheckError::InvalidCast(_, span) => span.clone(),
+            TypeCheckError::IntegerOverflow(_, span) => span.clone(),
+            TypeCheckError::DivisionByZero(_, span) => span.clone(),
+            TypeCheckError::NonConstantArrayLength(_, span) => span.clone(),
+            TypeCheckError::IndexOutOfBounds(_, span) => span.clone(),
+            TypeCheckError::InvalidPropagation(_, span) => span.clone(),
+            TypeCheckError::UnsignedNegation(_, span) => span.clone(),
+            TypeCheckError::NonResultPropagation(_, span) => span.clone(),
+            TypeCheckError::PropertyOfOptional(_, span) => span.clone(),
+            TypeCheckError::CyclicConstant(_, span) => span.clone(),
+        }
+    }
+
+    /// Full diagnostic of this error, including labels, notes and the fix (if there is one).
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::error(self.code(), self.err());
+        let span = self.span();
+
+        let diagnostic = match self {
+            TypeCheckError::TypeMismatch(mismatch, _) if mismatch.recursive_type().is_some() => {
+                diagnostic.with_primary(span, Some("this has a type containing itself".into()))
+            }
+            TypeCheckError::TypeMismatch(TypeMismatch { expected, actual }, _) => diagnostic
+                .with_primary(
+                    span,
+                    Some(format!("expected '{expected}', found '{actual}'")),
+                ),
+            TypeCheckError::UndefinedVariable(..) => {
+                diagnostic.with_primary(span, Some("not found in this scope".into()))
+            }
+            TypeCheckError::UndefinedType(..) => {
+                diagnostic.with_primary(span, Some("not defined".into()))
+            }
+            TypeCheckError::MissingInitialisationType(..) => diagnostic
+                .with_primary(span, Some("type could not be inferred".into()))
+                .with_note("consider adding a type annotation"),
+            TypeCheckError::ImmutableReassign(ImmutableReassign { declaration, .. }, _) => {
+                let diagnostic = diagnostic.with_primary(span, Some("cannot assign twice".into()));
+
+                match declaration {
+                    Some(declaration) => {
+                        diagnostic.with_secondary(declaration.clone(), "declared as immutable here")
+                    }
+                    None => diagnostic,
+                }
+            }
+            TypeCheckError::MissingMainFunction(..) => {
+                diagnostic.with_note("every program needs an entry point 'fn main(): void'")
+            }
+            TypeCheckError::UnsupportedBinaryOperation(
+                UnsupportedBinaryOperation {
+                    operands: (left, right),
+                },
+                _,
+            ) => {
+                diagnostic.with_primary(span, Some(format!("operands are '{left}' and '{right}'")))
+            }
+            TypeCheckError::UnknownAttribute(..) => diagnostic
+                .with_primary(span, None)
+                .with_note("the only supported attribute is '#[test]'"),
+            TypeCheckError::InvalidTestSignature(..) => diagnostic
+                .with_primary(span, None)
+                .with_note("test functions have the signature 'fn name(): void'"),
+            TypeCheckError::MissingReturn(
+                MissingReturn {
+                    return_type,
+                    return_type_position,
+                    ..
+                },
+                _,
+            ) => diagnostic
+                .with_primary(span, Some("the end of the function is reachable".into()))
+                .with_secondary(
+                    return_type_position.clone(),
+                    format!("expected '{return_type}' because of this return type"),
+                ),
+            TypeCheckError::UninitialisedVariable(UninitialisedVariable { declaration, .. }, _) => {
+                diagnostic
+                    .with_primary(span, Some("used before it is assigned".into()))
+                    .with_secondary(declaration.clone(), "declared without a value here")
+                    .with_note("a variable has to be assigned on every path before it is used")
+            }
+            TypeCheckError::AmbiguousType(AmbiguousType { inferred }, _) => diagnostic
+                .with_primary(span, Some(format!("only inferred '{inferred}' so far")))
+                .with_note("consider adding a type annotation"),
+            TypeCheckError::InvalidInterpolation(InvalidInterpolation { type_id }, _) => diagnostic
+                .with_primary(span, Some(format!("this has type '{type_id}'")))
+                .with_note("only numbers, bool, char and str can be interpolated"),
+            TypeCheckError::InvalidCast(InvalidCast { from, .. }, _) => diagnostic
+                .with_primary(span, Some(format!("this has type '{from}'")))
+                .with_note("'as' converts between numbers and char, and from bool to integers"),
+            TypeCheckError::IntegerOverflow(IntegerOverflow { type_id, .. }, _) => {
+                let range = match type_id {
+                    Type::Integer(integer_type) => {
+                        format!("{}..={}", integer_type.min(), integer_type.max())
+                    }
+                    _ => "its range".into(),
+                };
+
+                diagnostic
+                    .with_primary(span, Some(format!("this has type '{type_id}'")))
+                    .with_note(format!("'{type_id}' can only hold values in {range}"))
+            }
+            TypeCheckError::DivisionByZero(..) => {
+                diagnostic.with_primary(span, Some("the divisor evaluates to zero".into()))
+            }
+            TypeCheckError::NonConstantArrayLength(..) => diagnostic
+                .with_primary(span, Some("not known at compile time".into()))
+                .with_note("array lengths can only use literals, constants and operations on them"),
+            TypeCheckError::IndexOutOfBounds(IndexOutOfBounds { length, .. }, _) => {
+                diagnostic.with_primary(span, Some(format!("the length is {length}")))
+            }
+            TypeCheckError::InvalidPropagation(InvalidPropagation { return_type }, _) => diagnostic
+                .with_primary(span, Some(format!("the function returns '{return_type}'")))
+                .with_note(
+                    "'?' can only be used in functions which return a result, e.g., 'T ! E'",
+                ),
+            TypeCheckError::UnsignedNegation(UnsignedNegation { type_id }, _) => diagnostic
+                .with_primary(span, Some(format!("this has type '{type_id}'")))
+                .with_note("only signed integers and floating point numbers can be negated"),
+            TypeCheckError::NonResultPropagation(NonResultPropagation { type_id }, _) => diagnostic
+                .with_primary(span, Some(format!("this has type '{type_id}'")))
+                .with_note("'?' unwraps a result, e.g., 'T ! E', or returns its error"),
+            TypeCheckError::PropertyOfOptional(PropertyOfOptional { type_id, .. }, _) => diagnostic
+                .with_primary(span, Some(format!("this has type '{type_id}'")))
+                .with_note("use '?.' or check that the value is '!= none' first"),
+            TypeCheckError::CyclicConstant(..) => diagnostic
+                .with_primary(span, Some("its value can not be evaluated".into()))
+                .with_note("the value of a constant can not use the constant itself"),
+            _ => diagnostic.with_primary(span, None),
+        };
+
+        diagnostic.with_suggestion(self.fix())
+    }
+
+    /// Suggested change to the source code which resolves this error (if there is an obvious
+    /// one).
+    pub fn fix(&self) -> Option<Fix> {
+        match self {
+            TypeCheckError::ImmutableReassign(
+                ImmutableReassign {
+                    variable_name,
+                    declaration: Some(declaration),
+                },
+                _,
+            ) => {
+                // the declaration starts with the `let` keyword
+                Some(Fix {
+                    message: format!("Make '{variable_name}' mutable"),
+                    span: Span {
+                        end: declaration.start + 3,
+                        ..declaration.clone()
+                    },
+                    replacement: "let mut".into(),
+                })
+            }
+            TypeCheckError::MissingInitialisationType(
+                MissingInitialisationType {
+                    variable_name,
+                    variable_position,
+                    inferred_type: Some(inferred_type),
+                },
+                _,
+            ) => Some(Fix {
+                message: format!("Add type annotation to '{variable_name}'"),
+                span: Span {
+                    start: variable_position.end,
+                    ..variable_position.clone()
+                },
+                // parts of the type which could not be inferred are left as `_` for the user to
+                // complete
+                replacement: format!(": {inferred_type}"),
+            }),
+            TypeCheckError::UndefinedVariable(
+                UndefinedVariable {
+                    similar_name: Some(similar_name),
+                    ..
+                },
+                span,
+            ) => Some(Fix {
+                message: format!("Replace with '{similar_name}'"),
+                span: span.clone(),
+                replacement: similar_name.clone(),
+            }),
+            TypeCheckError::MissingMainFunction(MissingMainFunction { end_of_source }) => {
+                let replacement = if end_of_source.start == 0 {
+                    "fn main(): void {\n}\n"
+                } else {
+                    "\n\nfn main(): void {\n}\n"
+                };
+
+                Some(Fix {
+                    message: "Add main function".into(),
+                    span: end_of_source.clone(),
+                    replacement: replacement.into(),
+                })
+            }
+            _ => None,
         }
     }
 
@@ -58,6 +316,88 @@ impl TypeCheckError {
             TypeCheckError::MissingMainFunction(e) => Box::new(e.clone()),
             TypeCheckError::InvalidMainSignature(e, _) => Box::new(e.clone()),
             TypeCheckError::UnsupportedBinaryOperation(e, _) => Box::new(e.clone()),
+            TypeCheckError::UnknownAttribute(e, _) => Box::new(e.clone()),
+            TypeCheckError::InvalidTestSignature(e, _) => Box::new(e.clone()),
+            TypeCheckError::MissingReturn(e, _) => Box::new(e.clone()),
+            TypeCheckError::UninitialisedVariable(e, _) => Box::new(e.clone()),
+            TypeCheckError::AmbiguousType(e, _) => Box::new(e.clone()),
+            TypeCheckError::InvalidInterpolation(e, _) => Box::new(e.clone()),
+            TypeCheckError::InvalidCast(e, _) => Box::new(e.clone()),
+            TypeCheckError::IntegerOverflow(e, _) => Box::new(e.clone()),
+            TypeCheckError::DivisionByZero(e, _) => Box::new(e.clone()),
+            TypeCheckError::NonConstantArrayLength(e, _) => Box::new(e.clone()),
+            TypeCheckError::IndexOutOfBounds(e, _) => Box::new(e.clone()),
+            TypeCheckError::InvalidPropagation(e, _) => Box::new(e.clone()),
+            TypeCheckError::UnsignedNegation(e, _) => Box::new(e.clone()),
+            TypeCheckError::NonResultPropagation(e, _) => Box::new(e.clone()),
+            TypeCheckError::PropertyOfOptional(e, _) => Box::new(e.clone()),
+            TypeCheckError::CyclicConstant(e, _) => Box::new(e.clone()),
+        }
+    }
+}
+
+impl SpansMut for TypeCheckError {
+    fn spans_mut(&mut self, f: &mut impl FnMut(&mut Span)) {
+        match self {
+            TypeCheckError::UndefinedType(UndefinedType { type_name }, span) => {
+                type_name.spans_mut(f);
+                f(span);
+            }
+            TypeCheckError::MissingInitialisationType(
+                MissingInitialisationType {
+                    variable_position, ..
+                },
+                span,
+            ) => {
+                f(variable_position);
+                f(span);
+            }
+            TypeCheckError::ImmutableReassign(ImmutableReassign { declaration, .. }, span) => {
+                declaration.spans_mut(f);
+                f(span);
+            }
+            TypeCheckError::MissingMainFunction(MissingMainFunction { end_of_source }) => {
+                f(end_of_source)
+            }
+            TypeCheckError::MissingReturn(
+                MissingReturn {
+                    return_type_position,
+                    ..
+                },
+                span,
+            ) => {
+                f(return_type_position);
+                f(span);
+            }
+            TypeCheckError::UninitialisedVariable(
+                UninitialisedVariable { declaration, .. },
+                span,
+            ) => {
+                f(declaration);
+                f(span);
+            }
+            TypeCheckError::TypeMismatch(_, span)
+            | TypeCheckError::UndefinedVariable(_, span)
+            | TypeCheckError::InvalidConstantType(_, span)
+            | TypeCheckError::RedefinedConstant(_, span)
+            | TypeCheckError::RedefinedFunction(_, span)
+            | TypeCheckError::RedefinedMethod(_, span)
+            | TypeCheckError::InvalidMainSignature(_, span)
+            | TypeCheckError::UnsupportedBinaryOperation(_, span)
+            | TypeCheckError::UnknownAttribute(_, span)
+            | TypeCheckError::InvalidTestSignature(_, span)
+            | TypeCheckError::AmbiguousType(_, span)
+            | TypeCheckError::InvalidInterpolation(_, span)
+            | TypeCheckError::InvalidCast(_, span)
+            | TypeCheckError::IntegerOverflow(_, span)
+            | TypeCheckError::DivisionByZero(_, span)
+            | TypeCheckError::NonConstantArrayLength(_, span)
+            | TypeCheckError::IndexOutOfBounds(_, span)
+            | TypeCheckError::InvalidPropagation(_, span)
+            | TypeCheckError::UnsignedNegation(_, span)
+            | TypeCheckError::NonResultPropagation(_, span)
+            | TypeCheckError::PropertyOfOptional(_, span)
+            | TypeCheckError::CyclicConstant(_, span) => f(span),
         }
     }
 }
@@ -70,12 +410,27 @@ pub struct TypeMismatch {
     pub actual: Type,
 }
 
+impl TypeMismatch {
+    /// The type an unconstrained variable would have to be bound to, if the mismatch stems from
+    /// that variable occurring within the type (e.g., in `x(x)`).
+    fn recursive_type(&self) -> Option<&Type> {
+        match (&self.expected, &self.actual) {
+            (Type::Variable(_), Type::Variable(_)) => None,
+            (Type::Variable(_), other) | (other, Type::Variable(_)) => Some(other),
+            _ => None,
+        }
+    }
+}
+
 impl Display for TypeMismatch {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!(
-            "Expected type '{:?}' but got '{:?}'",
-            self.expected, self.actual
-        ))
+        match self.recursive_type() {
+            Some(other) => f.write_fmt(format_args!("Type '{other}' would have to contain itself")),
+            None => f.write_fmt(format_args!(
+                "Expected type '{}' but got '{}'",
+                self.expected, self.actual
+            )),
+        }
     }
 }
 
@@ -84,6 +439,8 @@ impl Error for TypeMismatch {}
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct UndefinedVariable {
     pub variable_name: String,
+    /// Name of a variable in scope which is spelled similarly.
+    pub similar_name: Option<String>,
 }
 
 impl Display for UndefinedVariable {
@@ -91,7 +448,13 @@ impl Display for UndefinedVariable {
         f.write_fmt(format_args!(
             "Tried to access undefined variable {}",
             self.variable_name
-        ))
+        ))?;
+
+        if let Some(similar_name) = &self.similar_name {
+            f.write_fmt(format_args!(" (did you mean '{similar_name}'?)"))?;
+        }
+
+        Ok(())
     }
 }
 
@@ -111,7 +474,12 @@ impl Display for UndefinedType {
 impl Error for UndefinedType {}
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
-pub struct MissingInitialisationType;
+pub struct MissingInitialisationType {
+    pub variable_name: String,
+    pub variable_position: Span,
+    /// The part of the type which could be inferred (if any)
+    pub inferred_type: Option<Type>,
+}
 
 impl Display for MissingInitialisationType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -178,7 +546,7 @@ pub struct RedefinedMethod {
 impl Display for RedefinedMethod {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
-            "Method '{}' is already defined for type '{:?}'",
+            "Method '{}' is already defined for type '{}'",
             self.function_name, self.type_id
         ))
     }
@@ -189,6 +557,8 @@ impl Error for RedefinedMethod {}
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct ImmutableReassign {
     pub variable_name: String,
+    /// Position of the `let` statement declaring the variable (if it was declared by one).
+    pub declaration: Option<Span>,
 }
 
 impl Display for ImmutableReassign {
@@ -203,7 +573,10 @@ impl Display for ImmutableReassign {
 impl Error for ImmutableReassign {}
 
 #[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
-pub struct MissingMainFunction;
+pub struct MissingMainFunction {
+    /// End of the checked source, where a main function could be added.
+    pub end_of_source: Span,
+}
 
 impl Display for MissingMainFunction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -236,10 +609,259 @@ impl Display for UnsupportedBinaryOperation {
         } = self;
 
         f.write_fmt(format_args!(
-            "This binary operation is not supported for types '{:?}' and '{:?}'",
+            "This binary operation is not supported for types '{}' and '{}'",
             left, right
         ))
     }
 }
 
 impl Error for UnsupportedBinaryOperation {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct UnknownAttribute {
+    pub attribute_name: String,
+}
+
+impl Display for UnknownAttribute {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("Unknown attribute '{}'", self.attribute_name))
+    }
+}
+
+impl Error for UnknownAttribute {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InvalidTestSignature {
+    pub function_name: String,
+}
+
+impl Display for InvalidTestSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Test function '{}' must not accept any arguments and must return void",
+            self.function_name
+        ))
+    }
+}
+
+impl Error for InvalidTestSignature {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MissingReturn {
+    pub function_name: String,
+    pub return_type: Type,
+    pub return_type_position: Span,
+}
+
+impl Display for MissingReturn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Function '{}' does not return a value on every path",
+            self.function_name
+        ))
+    }
+}
+
+impl Error for MissingReturn {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct UninitialisedVariable {
+    pub variable_name: String,
+    /// Position of the `let` statement declaring the variable without a value.
+    pub declaration: Span,
+}
+
+impl Display for UninitialisedVariable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Variable '{}' is used before it is assigned",
+            self.variable_name
+        ))
+    }
+}
+
+impl Error for UninitialisedVariable {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AmbiguousType {
+    /// What is known about the type so far (with `_` for the parts which are still unknown).
+    pub inferred: Type,
+}
+
+impl Display for AmbiguousType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Type of this expression must be known at this point")
+    }
+}
+
+impl Error for AmbiguousType {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InvalidInterpolation {
+    pub type_id: Type,
+}
+
+impl Display for InvalidInterpolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Values of type '{}' can not be interpolated into a string",
+            self.type_id
+        ))
+    }
+}
+
+impl Error for InvalidInterpolation {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InvalidCast {
+    pub from: Type,
+    pub to: Type,
+}
+
+impl Display for InvalidCast {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Can not cast a value of type '{}' to '{}'",
+            self.from, self.to
+        ))
+    }
+}
+
+impl Error for InvalidCast {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IntegerOverflow {
+    pub value: i128,
+    pub type_id: Type,
+}
+
+impl Display for IntegerOverflow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Value {} does not fit into type '{}'",
+            self.value, self.type_id
+        ))
+    }
+}
+
+impl Error for IntegerOverflow {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct DivisionByZero;
+
+impl Display for DivisionByZero {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Division by zero in constant expression")
+    }
+}
+
+impl Error for DivisionByZero {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct NonConstantArrayLength;
+
+impl Display for NonConstantArrayLength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("The length of an array has to be a constant")
+    }
+}
+
+impl Error for NonConstantArrayLength {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct IndexOutOfBounds {
+    pub index: i128,
+    pub length: usize,
+}
+
+impl Display for IndexOutOfBounds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Index {} is out of bounds for array of length {}",
+            self.index, self.length
+        ))
+    }
+}
+
+impl Error for IndexOutOfBounds {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct InvalidPropagation {
+    /// Return type of the function the error would be returned from.
+    pub return_type: Type,
+}
+
+impl Display for InvalidPropagation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("The '?' operator can only be used in a function which returns a result")
+    }
+}
+
+impl Error for InvalidPropagation {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct UnsignedNegation {
+    /// Unsigned integer type of the negated value.
+    pub type_id: Type,
+}
+
+impl Display for UnsignedNegation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Cannot negate unsigned type '{}'",
+            self.type_id
+        ))
+    }
+}
+
+impl Error for UnsignedNegation {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct NonResultPropagation {
+    /// Type of the value the `?` is applied to.
+    pub type_id: Type,
+}
+
+impl Display for NonResultPropagation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("The '?' operator can only be applied to a result")
+    }
+}
+
+impl Error for NonResultPropagation {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PropertyOfOptional {
+    pub property_name: String,
+    /// Optional type of the value the property is accessed on.
+    pub type_id: Type,
+}
+
+impl Display for PropertyOfOptional {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Cannot access property '{}' of optional '{}'",
+            self.property_name, self.type_id
+        ))
+    }
+}
+
+impl Error for PropertyOfOptional {}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CyclicConstant {
+    /// Names of the constants forming the cycle, starting and ending with the same constant.
+    pub cycle: Vec<String>,
+}
+
+impl Display for CyclicConstant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "Constant '{}' depends on itself: {}",
+            self.cycle.first().map(String::as_str).unwrap_or_default(),
+            self.cycle.join(" -> ")
+        ))
+    }
+}
+
+impl Error for CyclicConstant {}
@@ -0,0 +1,287 @@
+//! Control flow analysis of function bodies. The statements of a body are turned into a graph,
+//! whose edges point from every statement to the statements which might be executed next. This
+//! graph is used to find out whether every path through a function returns and which statements
+//! can never be executed.
+
+use crate::{
+    lexer::Span,
+    parser::ast::{Block, Expression, Function, If, Statement, WhileLoop},
+};
+
+use super::{types::Type, TypeInformation};
+
+/// Node every path through the body starts at.
+const ENTRY: usize = 0;
+
+/// Node every path through the body ends at, either by returning or by reaching the end of the
+/// body.
+const EXIT: usize = 1;
+
+/// Information attached to an expression which tells whether evaluating it never finishes, e.g.,
+/// because it calls `panic`.
+pub trait Divergence {
+    fn diverges(&self) -> bool;
+}
+
+/// Without types, every expression is assumed to finish.
+impl Divergence for () {
+    fn diverges(&self) -> bool {
+        false
+    }
+}
+
+impl Divergence for TypeInformation {
+    fn diverges(&self) -> bool {
+        self.resolved_type() == Some(Type::Never)
+    }
+}
+
+struct ControlFlowGraph<'a, T> {
+    /// Statement of every node. The entry and the exit do not belong to any statement.
+    statements: Vec<Option<&'a Statement<T>>>,
+    successors: Vec<Vec<usize>>,
+    /// Node which is executed right before a node in the source, i.e., the previous statement
+    /// of the same block or the statement containing the block.
+    previous: Vec<Option<usize>>,
+    /// Nodes after which the end of the body is reached without returning.
+    fallthrough: Vec<usize>,
+}
+
+impl<'a, T: Clone + Divergence> ControlFlowGraph<'a, T> {
+    fn new(statements: &'a [Statement<T>]) -> Self {
+        let mut graph = ControlFlowGraph {
+            statements: vec![None, None],
+            successors: vec![vec![], vec![]],
+            previous: vec![None, None],
+            fallthrough: vec![],
+        };
+
+        let fallthrough = graph.sequence(statements, vec![ENTRY], None);
+        for node in fallthrough.iter() {
+            graph.successors[*node].push(EXIT);
+        }
+        graph.fallthrough = fallthrough;
+
+        graph
+    }
+
+    fn add_node(&mut self, statement: &'a Statement<T>, previous: Option<usize>) -> usize {
+        self.statements.push(Some(statement));
+        self.successors.push(vec![]);
+        self.previous.push(previous);
+
+        self.statements.len() - 1
+    }
+
+    /// Add the given statements, which are executed after the given predecessors. Returns the
+    /// nodes after which execution continues behind the statements.
+    fn sequence(
+        &mut self,
+        statements: &'a [Statement<T>],
+        mut predecessors: Vec<usize>,
+        mut previous: Option<usize>,
+    ) -> Vec<usize> {
+        for statement in statements {
+            // comments are never executed
+            if let Statement::Comment(..) = statement {
+                continue;
+            }
+
+            let node = self.add_node(statement, previous);
+            for predecessor in predecessors {
+                self.successors[predecessor].push(node);
+            }
+
+            predecessors = match statement {
+                Statement::Return(_) => {
+                    self.successors[node].push(EXIT);
+                    vec![]
+                }
+                // the program stops (e.g., by calling `panic`), so nothing after it is executed
+                Statement::Expression(expression) | Statement::YieldingExpression(expression)
+                    if expression.get_info().diverges() =>
+                {
+                    vec![]
+                }
+                Statement::If(if_expression)
+                | Statement::Expression(Expression::If(if_expression))
+                | Statement::YieldingExpression(Expression::If(if_expression)) => {
+                    let If {
+                        statements,
+                        else_statements,
+                        ..
+                    } = if_expression;
+
+                    let mut continuations = self.sequence(statements, vec![node], Some(node));
+                    continuations.extend(self.sequence(else_statements, vec![node], Some(node)));
+                    continuations
+                }
+                Statement::WhileLoop(WhileLoop { block, .. }) => {
+                    for continuation in self.sequence(&block.statements, vec![node], Some(node)) {
+                        self.successors[continuation].push(node);
+                    }
+
+                    // the loop is left as soon as the condition does not hold anymore
+                    vec![node]
+                }
+                Statement::Expression(Expression::Block(Block { statements, .. }))
+                | Statement::YieldingExpression(Expression::Block(Block { statements, .. })) => {
+                    self.sequence(statements, vec![node], Some(node))
+                }
+                _ => vec![node],
+            };
+            previous = Some(node);
+        }
+
+        predecessors
+    }
+
+    fn reachable(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.statements.len()];
+        let mut pending = vec![ENTRY];
+
+        while let Some(node) = pending.pop() {
+            if reachable[node] {
+                continue;
+            }
+
+            reachable[node] = true;
+            pending.extend(self.successors[node].iter().copied());
+        }
+
+        reachable
+    }
+
+    fn nodes(&self) -> impl Iterator<Item = &'a Statement<T>> + '_ {
+        self.statements.iter().flatten().copied()
+    }
+}
+
+/// Whether the end of the given statements can be reached without returning.
+pub fn falls_through<T: Clone + Divergence>(statements: &[Statement<T>]) -> bool {
+    let graph = ControlFlowGraph::new(statements);
+    let reachable = graph.reachable();
+
+    graph.fallthrough.iter().any(|node| reachable[*node])
+}
+
+/// All expressions returned by the given statements, including the ones of nested blocks (but
+/// not the ones of nested functions).
+pub fn returns<T: Clone + Divergence>(statements: &[Statement<T>]) -> Vec<&Expression<T>> {
+    ControlFlowGraph::new(statements)
+        .nodes()
+        .filter_map(|statement| match statement {
+            Statement::Return(expression) => Some(expression),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Positions of statements within the given function (and functions nested in it) which can never
+/// be executed. Of consecutive unreachable statements only the first one is reported.
+pub fn unreachable_code<T: Clone + Divergence>(function: &Function<T>) -> Vec<Span> {
+    let graph = ControlFlowGraph::new(&function.statements);
+    let reachable = graph.reachable();
+
+    let mut unreachable = vec![];
+
+    for (node, statement) in graph.statements.iter().enumerate() {
+        let Some(statement) = statement else {
+            continue;
+        };
+
+        if !reachable[node] && graph.previous[node].is_none_or(|previous| reachable[previous]) {
+            unreachable.push(statement.position());
+        }
+
+        if let Statement::Function(function) = statement {
+            unreachable.extend(unreachable_code(function));
+        }
+    }
+
+    unreachable
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lexer::Lexer,
+        parser::{ast::TopLevelStatement, parse},
+    };
+
+    use super::*;
+
+    fn function(input: &str) -> Function<()> {
+        let tokens = Lexer::new(input).lex().expect("should lex");
+        let mut statements = parse(&mut tokens.into()).expect("should parse");
+
+        let TopLevelStatement::Function(function) = statements.remove(0) else {
+            panic!("expected a function");
+        };
+        function
+    }
+
+    fn unreachable_lines(input: &str) -> Vec<usize> {
+        unreachable_code(&function(input))
+            .iter()
+            .map(|span| span.start_line_col().0 + 1)
+            .collect()
+    }
+
+    #[test]
+    fn test_falls_through() {
+        let cases = [
+            ("fn f(): void {}", true),
+            ("fn f(): i64 {\n    return 42;\n}", false),
+            ("fn f(x: i64): i64 {\n    if (x < 0) {\n        return 0;\n    }\n}", true),
+            (
+                "fn f(x: i64): i64 {\n    if (x < 0) {\n        return 0;\n    } else {\n        return 1;\n    }\n}",
+                false,
+            ),
+            (
+                "fn f(x: i64): i64 {\n    while (x < 0) {\n        return 0;\n    }\n}",
+                true,
+            ),
+            ("fn f(): i64 {\n    {\n        return 0;\n    };\n}", false),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(
+                falls_through(&function(input).statements),
+                expected,
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_returns() {
+        let function = function(
+            "fn f(x: i64): i64 {\n    if (x < 0) {\n        return 0;\n    }\n    while (x > 10) {\n        return 10;\n    }\n    fn g(): i64 {\n        return 1;\n    }\n    return x;\n}",
+        );
+
+        assert_eq!(returns(&function.statements).len(), 3);
+    }
+
+    #[test]
+    fn test_unreachable_code() {
+        assert_eq!(
+            unreachable_lines("fn f(): i64 {\n    return 1;\n    let x = 2;\n    let y = 3;\n}"),
+            vec![3]
+        );
+
+        assert_eq!(
+            unreachable_lines(
+                "fn f(x: i64): i64 {\n    if (x < 0) {\n        return 0;\n        x = 1;\n    } else {\n        return 1;\n    }\n    x\n}"
+            ),
+            vec![4, 8]
+        );
+
+        assert_eq!(
+            unreachable_lines(
+                "fn f(x: i64): i64 {\n    while (x < 0) {\n        return 0;\n    }\n    fn g(): i64 {\n        return 1;\n        2\n    }\n    x\n}"
+            ),
+            vec![7]
+        );
+    }
+}
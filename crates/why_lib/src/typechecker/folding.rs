@@ -0,0 +1,532 @@
+//! Evaluation of constant expressions (e.g., `60 * 60 * 24` or `N as usize * 2`, where `N` is a
+//! constant) at compile time. This makes sure that every constant value fits into its inferred
+//! type and does not divide by zero, so it has to happen after the types of all literals have
+//! been inferred.
+
+use std::cmp::Ordering;
+
+use crate::{
+    lexer::Span,
+    parser::ast::{
+        Array, Assignment, AstString, BinaryExpression, BinaryOperator, Block, Character, Constant,
+        Expression, Function, Id, If, Initialisation, LValue, Lambda, Num, Postfix, Prefix,
+        Statement, StructInitialisation, WhileLoop,
+    },
+};
+
+use super::{
+    context::Context,
+    error::{
+        DivisionByZero, IntegerOverflow, NonConstantArrayLength, TypeCheckError, TypeMismatch,
+    },
+    types::Type,
+    TypeInformation, TypeResult, ValidatedTypeInformation,
+};
+
+/// Value of an expression which is known at compile time.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    Integer(i128),
+    FloatingPoint(f64),
+    Boolean(bool),
+    Character(char),
+    String(String),
+}
+
+impl ConstValue {
+    /// Convert this value with `as`, just like the interpreter does at runtime.
+    fn convert(self, target: &Type) -> Self {
+        match (self, target) {
+            (ConstValue::Integer(value), Type::Integer(integer_type)) => {
+                ConstValue::Integer(integer_type.wrap(value))
+            }
+            (ConstValue::Integer(value), Type::FloatingPoint(float_type)) => {
+                ConstValue::FloatingPoint(float_type.round(value as f64))
+            }
+            (ConstValue::FloatingPoint(value), Type::Integer(integer_type)) => {
+                ConstValue::Integer((value as i128).clamp(integer_type.min(), integer_type.max()))
+            }
+            (ConstValue::FloatingPoint(value), Type::FloatingPoint(float_type)) => {
+                ConstValue::FloatingPoint(float_type.round(value))
+            }
+            (ConstValue::Character(value), Type::Integer(integer_type)) => {
+                ConstValue::Integer(integer_type.wrap(u32::from(value).into()))
+            }
+            (ConstValue::Boolean(value), Type::Integer(_)) => ConstValue::Integer(value.into()),
+            (ConstValue::Integer(value), Type::Character) => ConstValue::Character(
+                u32::try_from(value)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .unwrap_or(char::REPLACEMENT_CHARACTER),
+            ),
+            (value, _) => value,
+        }
+    }
+
+    /// Whether this value is represented exactly after converting it with `as`, e.g., `3 as f64`,
+    /// but not `1.5 as i64` or `300 as u8`.
+    pub fn converts_exactly(&self, target: &Type) -> bool {
+        match (self, self.clone().convert(target)) {
+            (ConstValue::Integer(value), ConstValue::FloatingPoint(converted)) => {
+                converted as i128 == *value
+            }
+            (ConstValue::FloatingPoint(value), ConstValue::Integer(converted)) => {
+                converted as f64 == *value && *value as i128 == converted
+            }
+            (ConstValue::FloatingPoint(value), ConstValue::FloatingPoint(converted)) => {
+                converted == *value || (converted.is_nan() && value.is_nan())
+            }
+            (ConstValue::Character(value), ConstValue::Integer(converted)) => {
+                i128::from(u32::from(*value)) == converted
+            }
+            (ConstValue::Integer(value), ConstValue::Character(converted)) => {
+                i128::from(u32::from(converted)) == *value
+            }
+            (ConstValue::Boolean(_), ConstValue::Integer(_)) => true,
+            (value, converted) => *value == converted,
+        }
+    }
+}
+
+/// Information about the nodes of a typed (or validated) AST, which is needed to evaluate
+/// constant expressions.
+pub trait ConstantInformation: Clone {
+    /// Type of the node (as far as it is known).
+    fn constant_type(&self) -> Option<Type>;
+
+    /// Value of the constant with the given name, if it is known at compile time.
+    fn constant_value(&self, name: &str) -> Option<ConstValue>;
+}
+
+impl ConstantInformation for TypeInformation {
+    fn constant_type(&self) -> Option<Type> {
+        self.resolved_type()
+    }
+
+    fn constant_value(&self, name: &str) -> Option<ConstValue> {
+        self.context.scope.get_constant_value(name)
+    }
+}
+
+impl ConstantInformation for ValidatedTypeInformation {
+    fn constant_type(&self) -> Option<Type> {
+        Some(self.type_id.clone())
+    }
+
+    fn constant_value(&self, name: &str) -> Option<ConstValue> {
+        self.context.scope.get_constant_value(name)
+    }
+}
+
+/// Value of an expression which only consists of literals, constants and operations on them.
+/// Returns `None`, if the expression is not constant.
+pub fn evaluate<T: ConstantInformation>(
+    expression: &Expression<T>,
+) -> TypeResult<Option<ConstValue>> {
+    let value = match expression {
+        Expression::Num(Num::Integer(value, info, position)) => {
+            ConstValue::Integer(fits(i128::from(*value), info, position)?)
+        }
+        Expression::Num(Num::FloatingPoint(value, info, _)) => {
+            ConstValue::FloatingPoint(round(*value, info))
+        }
+        Expression::Character(Character { character, .. }) => ConstValue::Character(*character),
+        Expression::AstString(AstString { value, .. }) => ConstValue::String(value.clone()),
+        Expression::Id(Id { name, info, .. }) => return Ok(info.constant_value(name)),
+        Expression::Parens(inner) => return evaluate(inner),
+        Expression::Prefix(Prefix::Minus { expr, position }) => {
+            let value = match expr.as_ref() {
+                // `-128` is a valid `i8`, even though `128` is not
+                Expression::Num(Num::Integer(value, ..)) => ConstValue::Integer(i128::from(*value)),
+                expr => match evaluate(expr)? {
+                    Some(value) => value,
+                    None => return Ok(None),
+                },
+            };
+
+            match value {
+                ConstValue::Integer(value) => {
+                    ConstValue::Integer(fits(-value, &expr.get_info(), position)?)
+                }
+                ConstValue::FloatingPoint(value) => ConstValue::FloatingPoint(-value),
+                _ => return Ok(None),
+            }
+        }
+        Expression::Prefix(Prefix::Negation { expr, .. }) => match evaluate(expr)? {
+            Some(ConstValue::Boolean(value)) => ConstValue::Boolean(!value),
+            _ => return Ok(None),
+        },
+        Expression::Binary(binary) => return self::binary(binary),
+        Expression::Postfix(Postfix::Cast { expr, info, .. }) => {
+            let (Some(value), Some(target)) = (evaluate(expr)?, info.constant_type()) else {
+                return Ok(None);
+            };
+            value.convert(&target)
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(value))
+}
+
+/// Length of an array (e.g., `N * 2` in `[0; N * 2]`), which has to be a constant integer that
+/// is not negative. Literals within the length are `usize` by default.
+pub fn array_length(length: &Expression<TypeInformation>, ctx: &Context) -> TypeResult<usize> {
+    let length_type = { length.get_info().type_id.borrow() }
+        .as_ref()
+        .map(|type_id| ctx.resolve_literals(type_id, Some(&Type::USIZE)));
+
+    match length_type {
+        Some(Type::Integer(_)) => {}
+        Some(actual) => {
+            return Err(TypeCheckError::TypeMismatch(
+                TypeMismatch {
+                    expected: Type::USIZE,
+                    actual,
+                },
+                length.position(),
+            ))
+        }
+        None => {
+            return Err(TypeCheckError::NonConstantArrayLength(
+                NonConstantArrayLength,
+                length.position(),
+            ))
+        }
+    }
+
+    match evaluate(length)? {
+        Some(ConstValue::Integer(value)) => usize::try_from(value).map_err(|_| {
+            TypeCheckError::IntegerOverflow(
+                IntegerOverflow {
+                    value,
+                    type_id: Type::USIZE,
+                },
+                length.position(),
+            )
+        }),
+        _ => Err(TypeCheckError::NonConstantArrayLength(
+            NonConstantArrayLength,
+            length.position(),
+        )),
+    }
+}
+
+fn binary<T: ConstantInformation>(binary: &BinaryExpression<T>) -> TypeResult<Option<ConstValue>> {
+    let BinaryExpression {
+        left,
+        right,
+        operator,
+        info,
+        position,
+    } = binary;
+
+    let (Some(left_value), Some(right_value)) = (evaluate(left)?, evaluate(right)?) else {
+        return Ok(None);
+    };
+
+    let value = match (operator, left_value, right_value) {
+        (BinaryOperator::Equals, left, right) => ConstValue::Boolean(left == right),
+        (BinaryOperator::NotEquals, left, right) => ConstValue::Boolean(left != right),
+        // constants are never `none`
+        (BinaryOperator::Coalesce, left, _) => left,
+        (
+            BinaryOperator::GreaterThan
+            | BinaryOperator::LessThan
+            | BinaryOperator::GreaterOrEqual
+            | BinaryOperator::LessOrEqual,
+            left,
+            right,
+        ) => {
+            // comparisons with NaN are always false
+            let result = compare(&left, &right).is_some_and(|ordering| match operator {
+                BinaryOperator::GreaterThan => ordering.is_gt(),
+                BinaryOperator::LessThan => ordering.is_lt(),
+                BinaryOperator::GreaterOrEqual => ordering.is_ge(),
+                _ => ordering.is_le(),
+            });
+            ConstValue::Boolean(result)
+        }
+        (BinaryOperator::Divide, ConstValue::Integer(_), ConstValue::Integer(0)) => {
+            return Err(TypeCheckError::DivisionByZero(
+                DivisionByZero,
+                right.position(),
+            ))
+        }
+        (operator, ConstValue::Integer(left), ConstValue::Integer(right)) => {
+            // values beyond i128 saturate, which is out of range for every integer type anyway
+            let value = match operator {
+                BinaryOperator::Add => left.saturating_add(right),
+                BinaryOperator::Substract => left.saturating_sub(right),
+                BinaryOperator::Multiply => left.saturating_mul(right),
+                _ => left.saturating_div(right),
+            };
+
+            ConstValue::Integer(fits(value, info, position)?)
+        }
+        (operator, ConstValue::FloatingPoint(left), ConstValue::FloatingPoint(right)) => {
+            let value = match operator {
+                BinaryOperator::Add => left + right,
+                BinaryOperator::Substract => left - right,
+                BinaryOperator::Multiply => left * right,
+                _ => left / right,
+            };
+
+            ConstValue::FloatingPoint(round(value, info))
+        }
+        (BinaryOperator::Add, ConstValue::String(left), ConstValue::String(right)) => {
+            ConstValue::String(format!("{left}{right}"))
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(value))
+}
+
+fn compare(left: &ConstValue, right: &ConstValue) -> Option<Ordering> {
+    match (left, right) {
+        (ConstValue::Integer(l), ConstValue::Integer(r)) => l.partial_cmp(r),
+        (ConstValue::FloatingPoint(l), ConstValue::FloatingPoint(r)) => l.partial_cmp(r),
+        (ConstValue::Boolean(l), ConstValue::Boolean(r)) => l.partial_cmp(r),
+        (ConstValue::Character(l), ConstValue::Character(r)) => l.partial_cmp(r),
+        (ConstValue::String(l), ConstValue::String(r)) => l.partial_cmp(r),
+        _ => None,
+    }
+}
+
+fn fits(value: i128, info: &impl ConstantInformation, position: &Span) -> TypeResult<i128> {
+    match info.constant_type() {
+        Some(Type::Integer(integer_type)) if !integer_type.contains(value) => {
+            Err(TypeCheckError::IntegerOverflow(
+                IntegerOverflow {
+                    value,
+                    type_id: Type::Integer(integer_type),
+                },
+                position.clone(),
+            ))
+        }
+        _ => Ok(value),
+    }
+}
+
+fn round(value: f64, info: &impl ConstantInformation) -> f64 {
+    match info.constant_type() {
+        Some(Type::FloatingPoint(float_type)) => float_type.round(value),
+        _ => value,
+    }
+}
+
+/// Make sure that every constant expression in the given statements can be evaluated, i.e., that
+/// it fits into its type and does not divide by zero.
+pub fn check(statements: &[Statement<TypeInformation>]) -> TypeResult<()> {
+    for statement in statements {
+        match statement {
+            Statement::Function(function) => self::function(function)?,
+            Statement::If(if_expression) => self::if_expression(if_expression)?,
+            Statement::WhileLoop(WhileLoop {
+                condition, block, ..
+            }) => {
+                expression(condition)?;
+                check(&block.statements)?;
+            }
+            Statement::Initialization(Initialisation { value, .. }) => {
+                if let Some(value) = value {
+                    expression(value)?;
+                }
+            }
+            Statement::Constant(Constant { value, .. })
+            | Statement::Expression(value)
+            | Statement::YieldingExpression(value)
+            | Statement::Return(value) => expression(value)?,
+            Statement::Assignment(Assignment { lvalue, rvalue, .. }) => {
+                if let LValue::Postfix(postfix) = lvalue {
+                    self::postfix(postfix)?;
+                }
+                expression(rvalue)?;
+            }
+            Statement::Declaration(_) | Statement::StructDeclaration(_) => {}
+            Statement::Comment(..) => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn function(function: &Function<TypeInformation>) -> TypeResult<()> {
+    check(&function.statements)
+}
+
+fn if_expression(if_expression: &If<TypeInformation>) -> TypeResult<()> {
+    let If {
+        condition,
+        statements,
+        else_statements,
+        ..
+    } = if_expression;
+
+    expression(condition)?;
+    check(statements)?;
+    check(else_statements)
+}
+
+/// Check the given expression (if it is constant) or all of its constant subexpressions.
+pub fn expression(expression: &Expression<TypeInformation>) -> TypeResult<()> {
+    if evaluate(expression)?.is_some() {
+        return Ok(());
+    }
+
+    match expression {
+        Expression::Id(_)
+        | Expression::Num(_)
+        | Expression::Character(_)
+        | Expression::AstString(_) => Ok(()),
+        Expression::Function(function) => self::function(function),
+        Expression::Lambda(Lambda { expression, .. })
+        | Expression::Parens(expression)
+        | Expression::Prefix(Prefix::Negation {
+            expr: expression, ..
+        })
+        | Expression::Prefix(Prefix::Minus {
+            expr: expression, ..
+        }) => self::expression(expression),
+        Expression::If(if_expression) => self::if_expression(if_expression),
+        Expression::Block(Block { statements, .. }) => check(statements),
+        Expression::Postfix(postfix) => self::postfix(postfix),
+        Expression::Binary(binary) => {
+            self::expression(&binary.left)?;
+            self::expression(&binary.right)
+        }
+        Expression::Array(Array::Literal { values, .. }) => {
+            values.iter().try_for_each(self::expression)
+        }
+        Expression::Array(Array::Default {
+            initial_value,
+            length,
+            ..
+        }) => {
+            self::expression(initial_value)?;
+            self::expression(length)
+        }
+        Expression::StructInitialisation(StructInitialisation { fields, .. }) => fields
+            .iter()
+            .try_for_each(|field| self::expression(&field.value)),
+        Expression::Interpolation(interpolation) => {
+            interpolation.expressions().try_for_each(self::expression)
+        }
+    }
+}
+
+fn postfix(postfix: &Postfix<TypeInformation>) -> TypeResult<()> {
+    match postfix {
+        Postfix::Call { expr, args, .. } => {
+            expression(expr)?;
+            args.iter().try_for_each(expression)
+        }
+        Postfix::Index { expr, index, .. } => {
+            expression(expr)?;
+            expression(index)
+        }
+        Postfix::PropertyAccess { expr, .. }
+        | Postfix::OptionalPropertyAccess { expr, .. }
+        | Postfix::Propagate { expr, .. }
+        | Postfix::Cast { expr, .. } => expression(expr),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lexer::Lexer,
+        parser::parse,
+        typechecker::{
+            error::{DivisionByZero, IntegerOverflow, TypeCheckError},
+            types::{IntegerType, Type},
+            TypeChecker,
+        },
+    };
+
+    fn errors_of(input: &str) -> Vec<TypeCheckError> {
+        let tokens = Lexer::new(input).lex().expect("should lex");
+        let statements = parse(&mut tokens.into()).expect("should parse");
+
+        TypeChecker::new(statements)
+            .check_all()
+            .err()
+            .unwrap_or_default()
+    }
+
+    fn overflows_of(input: &str) -> Vec<(i128, Type)> {
+        errors_of(input)
+            .into_iter()
+            .map(|error| match error {
+                TypeCheckError::IntegerOverflow(IntegerOverflow { value, type_id }, _) => {
+                    (value, type_id)
+                }
+                error => panic!("unexpected error {error:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_constants_in_range() {
+        assert_eq!(
+            overflows_of(
+                "fn main(): void {\n    let a: u8 = 255;\n    let b: i8 = -128;\n    let c: u16 = 255 * 256 + 255;\n    let d = 9223372036854775807;\n}"
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(
+            overflows_of("fn main(): void {\n    let a: u8 = 256;\n}"),
+            vec![(256, Type::Integer(IntegerType::U8))]
+        );
+        assert_eq!(
+            overflows_of("fn main(): void {\n    let a: i8 = 100 + 100;\n}"),
+            vec![(200, Type::Integer(IntegerType::I8))]
+        );
+        assert_eq!(
+            overflows_of("fn main(): void {\n    let a: u32 = 1 - 2;\n}"),
+            vec![(-1, Type::Integer(IntegerType::U32))]
+        );
+        assert_eq!(
+            overflows_of("fn main(): void {\n    let a = 9223372036854775808;\n}"),
+            vec![(9223372036854775808, Type::I64)]
+        );
+    }
+
+    #[test]
+    fn test_constant_references() {
+        assert_eq!(
+            overflows_of("const A: u8 = 200;\nconst B: u8 = A + 100;\n\nfn main(): void {}"),
+            vec![(300, Type::Integer(IntegerType::U8))]
+        );
+        assert_eq!(
+            overflows_of(
+                "fn main(): void {\n    const A: i8 = 8;\n    let b = A * 16;\n    let c = A * 15;\n}"
+            ),
+            vec![(128, Type::Integer(IntegerType::I8))]
+        );
+        assert_eq!(
+            overflows_of("fn main(): void {\n    let a: i8 = 8;\n    let b = a * 16;\n}"),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_division_by_zero() {
+        assert_eq!(
+            errors_of("const ZERO: i64 = 2 - 2;\n\nfn main(): void {\n    let a = 1 / ZERO;\n}")
+                .into_iter()
+                .map(|error| matches!(error, TypeCheckError::DivisionByZero(DivisionByZero, _)))
+                .collect::<Vec<_>>(),
+            vec![true]
+        );
+        assert_eq!(
+            errors_of("fn main(): void {\n    let zero = 0;\n    let a = 1 / zero;\n    let b = 1.0 / 0.0;\n}"),
+            vec![]
+        );
+    }
+}
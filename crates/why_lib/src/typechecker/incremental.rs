@@ -0,0 +1,538 @@
+//! Incremental typechecking of a file whose content changes over time (e.g., while it is edited
+//! in an editor).
+//!
+//! The results of the previous run are memoised per toplevel item:
+//!
+//! - the context after the shallow check of all items is reused as long as no signature (i.e.,
+//!   everything an item exposes to other items) changed,
+//! - the full check of an item is reused as long as neither its source nor the signatures of the
+//!   items it (transitively) refers to changed.
+//!
+//! Dependencies are tracked by name, so editing the body of a function only checks that function
+//! again, while changing its signature checks every item which mentions it. Reused results are
+//! moved to the current position of their item.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use crate::{
+    cst::{Parse, SyntaxElement, SyntaxKind, SyntaxNode},
+    parser::ast::{
+        Constant, Declaration, Function, Instance, SpansMut, StructDeclaration, TopLevelStatement,
+    },
+    source_map::FileId,
+};
+
+use super::{
+    check_order, constants, context::Context, folding, shallow_check_order, ShallowCheck,
+    TypeCheckError, TypeCheckable, TypeChecker, TypeInformation, TypeResult,
+};
+
+#[derive(Debug, Default)]
+pub struct IncrementalChecker {
+    shallow: Option<ShallowCheckResult>,
+    checked: HashMap<u64, CheckResult>,
+    rechecked: usize,
+}
+
+#[derive(Debug)]
+struct ShallowCheckResult {
+    /// Hash of the signatures of all items.
+    key: u64,
+    context: Context,
+    /// Errors together with the index of the item causing them and the position of that item.
+    errors: Vec<(usize, usize, TypeCheckError)>,
+}
+
+#[derive(Debug)]
+struct CheckResult {
+    /// Position of the checked item.
+    offset: usize,
+    result: TypeResult<TopLevelStatement<TypeInformation>>,
+}
+
+/// Everything needed to decide whether a toplevel item has to be checked again.
+#[derive(Debug)]
+struct Item {
+    /// Index of the statement within all toplevel statements.
+    index: usize,
+    /// Start of the item within the source.
+    offset: usize,
+    /// Hash of the complete source of the item.
+    content: u64,
+    /// Hash of everything other items can see of this item.
+    signature: u64,
+    /// Names this item makes available to other items.
+    declares: Vec<String>,
+    /// All identifiers used within the item.
+    uses: BTreeSet<String>,
+    /// Identifiers used within the signature of the item.
+    signature_uses: BTreeSet<String>,
+}
+
+impl IncrementalChecker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of items which were actually checked (instead of reused) during the last check.
+    pub fn rechecked(&self) -> usize {
+        self.rechecked
+    }
+
+    /// All items which typechecked during the last check, in the order they appear in the source.
+    /// Unlike the result of [IncrementalChecker::check], this includes them even if other items
+    /// do not typecheck.
+    pub fn checked_items(&self) -> Vec<TopLevelStatement<TypeInformation>> {
+        let mut items = self
+            .checked
+            .values()
+            .filter_map(|checked| Some((checked.offset, checked.result.as_ref().ok()?)))
+            .collect::<Vec<_>>();
+        items.sort_by_key(|(offset, _)| *offset);
+
+        items
+            .into_iter()
+            .map(|(_, statement)| statement.clone())
+            .collect()
+    }
+
+    /// Check a parsed file, reusing everything from the previous check which is not affected by
+    /// the changes in between. The result is the same as the one of [TypeChecker::check_all].
+    pub fn check(
+        &mut self,
+        parse: &Parse,
+    ) -> Result<Vec<TopLevelStatement<TypeInformation>>, Vec<TypeCheckError>> {
+        let items = parse
+            .statements
+            .iter()
+            .enumerate()
+            .filter(|(_, statement)| !matches!(statement, TopLevelStatement::Comment(..)))
+            .map(|(index, statement)| Item::new(index, statement, &parse.syntax))
+            .collect::<Vec<_>>();
+
+        let file = parse.syntax.span().file;
+        let (mut context, mut errors) = self.shallow_check(file, &parse.statements, &items);
+
+        let mut declared_by: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (i, item) in items.iter().enumerate() {
+            for name in &item.declares {
+                declared_by.entry(name).or_default().push(i);
+            }
+        }
+
+        let mut previous = std::mem::take(&mut self.checked);
+        let mut occurrences: HashMap<u64, usize> = HashMap::new();
+        let items_by_index = items
+            .iter()
+            .map(|item| (item.index, item))
+            .collect::<HashMap<_, _>>();
+        let mut results = vec![];
+        self.rechecked = 0;
+
+        // cycles of constants depend on several items, so they are found on every check
+        let mut cycles = constants::analyse(&parse.statements)
+            .cycles
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        for index in check_order(&parse.statements) {
+            if let Some(e) = cycles.remove(&index) {
+                results.push((index, Err(e)));
+                continue;
+            }
+
+            let statement = &parse.statements[index];
+            let Some(item) = items_by_index.get(&index) else {
+                results.push((index, statement.clone().check(&mut context)));
+                continue;
+            };
+
+            // identical items (e.g., duplicated functions) still need their own results
+            let key = item.key(&Self::dependencies(item, &items, &declared_by));
+            let occurrence = occurrences.entry(key).or_default();
+            let key = hash(&(key, *occurrence));
+            *occurrence += 1;
+
+            let result = match previous.remove(&key) {
+                Some(mut cached) => {
+                    let delta = item.offset as isize - cached.offset as isize;
+                    match &mut cached.result {
+                        Ok(statement) => statement.relocate(file, delta),
+                        Err(e) => e.relocate(file, delta),
+                    }
+                    cached.offset = item.offset;
+
+                    // the values of constants are only stored in the scope by checking them
+                    if let Ok(TopLevelStatement::Constant(constant)) = &cached.result {
+                        if let Ok(Some(value)) = folding::evaluate(&constant.value) {
+                            context.scope.set_constant_value(&constant.id.name, value);
+                        }
+                    }
+
+                    cached
+                }
+                None => {
+                    self.rechecked += 1;
+
+                    // a failing statement might leave some scopes behind, so we restore the stack
+                    let snapshot = context.snapshot();
+                    let result = statement.clone().check(&mut context);
+                    if result.is_err() {
+                        context.restore(snapshot);
+                    }
+
+                    CheckResult {
+                        offset: item.offset,
+                        result,
+                    }
+                }
+            };
+
+            results.push((index, result.result.clone()));
+            self.checked.insert(key, result);
+        }
+
+        results.sort_by_key(|(index, _)| *index);
+
+        let mut checked = vec![];
+        for (_, result) in results {
+            match result {
+                Ok(statement) => checked.push(statement),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        let mut main_checker = TypeChecker {
+            context,
+            statements: parse.statements.clone(),
+        };
+        if let Err(e) = main_checker.check_main_function() {
+            errors.push(e);
+        }
+
+        if errors.is_empty() {
+            Ok(checked)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Run the shallow check of all items (or reuse its result, if no signature changed).
+    fn shallow_check(
+        &mut self,
+        file: FileId,
+        statements: &[TopLevelStatement<()>],
+        items: &[Item],
+    ) -> (Context, Vec<TypeCheckError>) {
+        let key = hash(&items.iter().map(|item| item.signature).collect::<Vec<_>>());
+
+        match &mut self.shallow {
+            Some(shallow) if shallow.key == key => {
+                for (i, offset, e) in shallow.errors.iter_mut() {
+                    e.relocate(file, items[*i].offset as isize - *offset as isize);
+                    *offset = items[*i].offset;
+                }
+            }
+            _ => {
+                let mut context = Context::default();
+                let mut errors = vec![];
+
+                let items_by_index = items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| (item.index, (i, item)))
+                    .collect::<HashMap<_, _>>();
+
+                for index in shallow_check_order(statements) {
+                    let Some(&(i, item)) = items_by_index.get(&index) else {
+                        continue;
+                    };
+
+                    if let Err(e) = statements[index].shallow_check(&mut context) {
+                        errors.push((i, item.offset, e));
+                    }
+                }
+
+                self.shallow = Some(ShallowCheckResult {
+                    key,
+                    context,
+                    errors,
+                });
+            }
+        }
+
+        let shallow = self.shallow.as_ref().expect("shallow check should be done");
+        (
+            shallow.context.clone(),
+            shallow.errors.iter().map(|(_, _, e)| e.clone()).collect(),
+        )
+    }
+
+    /// Signatures of all items the given item (transitively) depends on, i.e., of all items
+    /// declaring a name it uses and of everything their signatures use in turn.
+    fn dependencies(
+        item: &Item,
+        items: &[Item],
+        declared_by: &HashMap<&str, Vec<usize>>,
+    ) -> Vec<u64> {
+        let mut visited = BTreeSet::new();
+        let mut names = item.uses.iter().collect::<Vec<_>>();
+
+        while let Some(name) = names.pop() {
+            for &i in declared_by.get(name.as_str()).into_iter().flatten() {
+                if visited.insert(i) {
+                    names.extend(&items[i].signature_uses);
+                }
+            }
+        }
+
+        visited.into_iter().map(|i| items[i].signature).collect()
+    }
+}
+
+impl Item {
+    fn new(index: usize, statement: &TopLevelStatement<()>, root: &SyntaxNode) -> Item {
+        let declares = match statement {
+            TopLevelStatement::Comment(..) => vec![],
+            TopLevelStatement::Function(Function { id, .. })
+            | TopLevelStatement::Constant(Constant { id, .. }) => vec![id.name.clone()],
+            TopLevelStatement::Declaration(Declaration { name, .. }) => vec![name.name.clone()],
+            TopLevelStatement::StructDeclaration(StructDeclaration { id, fields, .. }) => {
+                std::iter::once(&id.name)
+                    .chain(fields.iter().map(|field| &field.name.name))
+                    .cloned()
+                    .collect()
+            }
+            TopLevelStatement::Instance(Instance {
+                functions,
+                declarations,
+                ..
+            }) => functions
+                .iter()
+                .map(|function| function.id.name.clone())
+                .chain(
+                    declarations
+                        .iter()
+                        .map(|declaration| declaration.id.name.clone()),
+                )
+                .collect(),
+        };
+
+        let start = statement_start(statement);
+        let node = root
+            .children()
+            .into_iter()
+            .find(|node| node.span().start <= start && start < node.span().end);
+
+        let Some(node) = node else {
+            // without its source the item can not be reused, so it is checked every time
+            return Item {
+                index,
+                offset: start,
+                content: hash(&(index, format!("{statement:?}"))),
+                signature: hash(&format!("{statement:?}")),
+                declares,
+                uses: BTreeSet::new(),
+                signature_uses: BTreeSet::new(),
+            };
+        };
+
+        let mut signature = vec![];
+        collect_signature(&node, &mut signature);
+
+        let identifiers = |tokens: &[(SyntaxKind, String)]| {
+            tokens
+                .iter()
+                .filter(|(kind, _)| *kind == SyntaxKind::Id)
+                .map(|(_, text)| text.clone())
+                .collect::<BTreeSet<_>>()
+        };
+
+        let tokens = node
+            .tokens()
+            .into_iter()
+            .map(|token| (token.kind(), token.text().to_string()))
+            .collect::<Vec<_>>();
+
+        Item {
+            index,
+            offset: node.span().start,
+            content: hash(&node.text()),
+            signature: hash(&signature),
+            declares,
+            uses: identifiers(&tokens),
+            signature_uses: identifiers(&signature),
+        }
+    }
+
+    /// Key of the full check of this item, given the items it depends on.
+    fn key(&self, dependencies: &[u64]) -> u64 {
+        hash(&(self.content, dependencies))
+    }
+}
+
+/// Collect all (non-trivia) tokens of the signature of an item, i.e., everything except the
+/// bodies of functions. The values of constants are part of their signature, since other items
+/// can use them at compile time (e.g., as the length of an array).
+fn collect_signature(node: &SyntaxNode, tokens: &mut Vec<(SyntaxKind, String)>) {
+    for element in node.children_with_tokens() {
+        match element {
+            SyntaxElement::Node(child) => collect_signature(&child, tokens),
+            SyntaxElement::Token(token) if token.is_trivia() => {}
+            SyntaxElement::Token(token) => {
+                if node.kind() == SyntaxKind::Function && token.kind() == SyntaxKind::LBrace {
+                    return;
+                }
+                tokens.push((token.kind(), token.text().to_string()));
+            }
+        }
+    }
+}
+
+fn statement_start(statement: &TopLevelStatement<()>) -> usize {
+    match statement {
+        TopLevelStatement::Comment(..) => 0,
+        TopLevelStatement::Function(Function { position, .. })
+        | TopLevelStatement::Constant(Constant { position, .. })
+        | TopLevelStatement::Declaration(Declaration { position, .. })
+        | TopLevelStatement::StructDeclaration(StructDeclaration { position, .. })
+        | TopLevelStatement::Instance(Instance { position, .. }) => position.start,
+    }
+}
+
+fn hash(value: &impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cst::parse_file,
+        lexer::{Lexer, Span},
+        parser::parse,
+        source_map::SourceMap,
+    };
+
+    use super::*;
+
+    const SOURCE: &str = "fn a(): i64 {\n    1\n}\n\nfn b(): i64 {\n    a()\n}\n\nfn c(): i64 {\n    'c'\n}\n\nfn main(): void {}";
+
+    fn parse_source(name: &str, source: &str) -> Parse {
+        parse_file(SourceMap::add_named(name, source)).expect("should parse")
+    }
+
+    /// Result of checking the source from scratch.
+    fn check_all(source: &str) -> Result<Vec<String>, Vec<TypeCheckError>> {
+        let tokens = Lexer::new(source).lex().expect("should lex");
+        let statements = parse(&mut tokens.into()).expect("should parse");
+        TypeChecker::new(statements).check_all().map(spans)
+    }
+
+    fn spans(mut statements: Vec<TopLevelStatement<TypeInformation>>) -> Vec<String> {
+        let mut spans = vec![];
+        statements.spans_mut(&mut |span| spans.push(format!("{}..{}", span.start, span.end)));
+        spans
+    }
+
+    fn error_spans(errors: &[TypeCheckError]) -> Vec<(&'static str, usize, usize)> {
+        errors
+            .iter()
+            .map(|e| {
+                let Span { start, end, .. } = e.span();
+                (e.code(), start, end)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reuse_unchanged_items() {
+        let mut checker = IncrementalChecker::new();
+
+        let errors = checker
+            .check(&parse_source("test_reuse_unchanged_items", SOURCE))
+            .expect_err("should fail");
+        assert_eq!(checker.rechecked(), 4);
+        assert_eq!(
+            error_spans(&errors),
+            error_spans(&check_all(SOURCE).unwrap_err())
+        );
+
+        // nothing changed
+        checker
+            .check(&parse_source("test_reuse_unchanged_items", SOURCE))
+            .expect_err("should fail");
+        assert_eq!(checker.rechecked(), 0);
+    }
+
+    #[test]
+    fn test_edit_function_body() {
+        let mut checker = IncrementalChecker::new();
+        checker
+            .check(&parse_source("test_edit_function_body", SOURCE))
+            .expect_err("should fail");
+
+        // only the edited function is checked again, everything behind it moves
+        let source = SOURCE.replace("    1\n", "    40 + 2\n");
+        let errors = checker
+            .check(&parse_source("test_edit_function_body", &source))
+            .expect_err("should fail");
+        assert_eq!(checker.rechecked(), 1);
+        assert_eq!(
+            error_spans(&errors),
+            error_spans(&check_all(&source).unwrap_err())
+        );
+
+        // fixing the error in c yields the same typed AST as a check from scratch
+        let source = source.replace("'c'", "3");
+        let checked = checker
+            .check(&parse_source("test_edit_function_body", &source))
+            .expect("should typecheck");
+        assert_eq!(checker.rechecked(), 1);
+        assert_eq!(spans(checked), check_all(&source).unwrap());
+    }
+
+    #[test]
+    fn test_edit_signature() {
+        let mut checker = IncrementalChecker::new();
+        checker
+            .check(&parse_source("test_edit_signature", SOURCE))
+            .expect_err("should fail");
+
+        // a and everything using it are checked again
+        let source = SOURCE.replace("fn a(): i64 {\n    1\n}", "fn a(): f64 {\n    1.0\n}");
+        let errors = checker
+            .check(&parse_source("test_edit_signature", &source))
+            .expect_err("should fail");
+        assert_eq!(checker.rechecked(), 2);
+        assert_eq!(
+            error_spans(&errors),
+            error_spans(&check_all(&source).unwrap_err())
+        );
+    }
+
+    #[test]
+    fn test_edit_constant_value() {
+        let source = "const N: i64 = 4;\n\nfn a(): i64 {\n    1\n}\n\nfn b(): void {\n    let xs = [0; N];\n}\n\nfn main(): void {}";
+
+        let mut checker = IncrementalChecker::new();
+        checker
+            .check(&parse_source("test_edit_constant_value", source))
+            .expect("should typecheck");
+
+        // the value of a constant is visible to the items using it
+        let source = source.replace("4;", "0 - 1;");
+        let errors = checker
+            .check(&parse_source("test_edit_constant_value", &source))
+            .expect_err("should fail");
+        assert_eq!(checker.rechecked(), 2);
+        assert_eq!(
+            error_spans(&errors),
+            error_spans(&check_all(&source).unwrap_err())
+        );
+    }
+}
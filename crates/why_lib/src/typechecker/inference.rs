@@ -0,0 +1,391 @@
+//! Unification based inference of types which are not annotated, e.g., the parameters of lambdas.
+//! Such types start out as type variables ([`Type::Variable`]) which are bound to other types
+//! whenever two types have to be equal, e.g., both operands of an addition.
+
+use crate::parser::ast::{Expression, If, Initialisation, Statement, WhileLoop};
+
+use super::{
+    error::{MissingInitialisationType, TypeCheckError},
+    types::Type,
+    TypeInformation, TypeResult,
+};
+
+/// Which types a type variable can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariableKind {
+    Any,
+    /// Type of an integer literal, which can become any integer type.
+    Integer,
+    /// Type of a floating point literal, which can become any floating point type.
+    FloatingPoint,
+}
+
+impl VariableKind {
+    fn admits(&self, type_id: &Type) -> bool {
+        match self {
+            VariableKind::Any => true,
+            VariableKind::Integer => matches!(type_id, Type::Integer(_)),
+            VariableKind::FloatingPoint => matches!(type_id, Type::FloatingPoint(_)),
+        }
+    }
+
+    /// Kind of a variable which has to satisfy both kinds.
+    fn merge(self, other: VariableKind) -> Option<VariableKind> {
+        match (self, other) {
+            (VariableKind::Any, kind) | (kind, VariableKind::Any) => Some(kind),
+            (left, right) if left == right => Some(left),
+            _ => None,
+        }
+    }
+
+    /// Type a variable of this kind gets, if nothing else is inferred for it.
+    fn default_type(&self) -> Option<Type> {
+        match self {
+            VariableKind::Any => None,
+            VariableKind::Integer => Some(Type::I64),
+            VariableKind::FloatingPoint => Some(Type::F64),
+        }
+    }
+}
+
+/// The types type variables are bound to. It is shared by all contexts of a typechecker, so
+/// bindings made while checking an expression are visible everywhere.
+#[derive(Debug, Default, Clone)]
+pub struct Substitution {
+    bindings: Vec<Option<Type>>,
+    kinds: Vec<VariableKind>,
+}
+
+impl Substitution {
+    /// Create a new type variable which is not bound to any type yet.
+    pub fn fresh(&mut self) -> Type {
+        self.fresh_of_kind(VariableKind::Any)
+    }
+
+    /// Create a new type variable which can only be bound to types of the given kind.
+    pub fn fresh_of_kind(&mut self, kind: VariableKind) -> Type {
+        self.bindings.push(None);
+        self.kinds.push(kind);
+        Type::Variable(self.bindings.len() - 1)
+    }
+
+    /// Number of type variables created so far.
+    pub fn variable_count(&self) -> usize {
+        self.bindings.len()
+    }
+
+    /// Bind every unbound variable of a number literal (created at or after `first`) to its
+    /// default type, i.e., `i64` or `f64`.
+    pub fn default_literals(&mut self, first: usize) {
+        for variable in first..self.bindings.len() {
+            if self.bindings[variable].is_none() {
+                self.bindings[variable] = self.kinds[variable].default_type();
+            }
+        }
+    }
+
+    /// Like [`Substitution::resolve`], but variables of number literals which are not bound yet
+    /// are replaced by their default type (without binding them), e.g., for error messages.
+    pub fn resolve_with_defaults(&self, type_id: &Type) -> Type {
+        let resolved = self.resolve(type_id);
+        match resolved {
+            Type::Variable(variable) => self.kinds[variable]
+                .default_type()
+                .unwrap_or(Type::Variable(variable)),
+            Type::Reference(inner) => Type::Reference(Box::new(self.resolve_with_defaults(&inner))),
+            Type::Array(inner) => Type::Array(Box::new(self.resolve_with_defaults(&inner))),
+            Type::FixedArray(inner, length) => {
+                Type::FixedArray(Box::new(self.resolve_with_defaults(&inner)), length)
+            }
+            Type::Optional(inner) => Type::optional(self.resolve_with_defaults(&inner)),
+            Type::Result(value, error) => Type::Result(
+                Box::new(self.resolve_with_defaults(&value)),
+                Box::new(self.resolve_with_defaults(&error)),
+            ),
+            Type::Tuple(types) => Type::Tuple(
+                types
+                    .iter()
+                    .map(|t| self.resolve_with_defaults(t))
+                    .collect(),
+            ),
+            Type::Function {
+                params,
+                return_value,
+            } => Type::Function {
+                params: params
+                    .iter()
+                    .map(|t| self.resolve_with_defaults(t))
+                    .collect(),
+                return_value: Box::new(self.resolve_with_defaults(&return_value)),
+            },
+            resolved => resolved,
+        }
+    }
+
+    /// Whether the given (resolved) type is the variable of a number literal.
+    pub fn is_literal(&self, type_id: &Type) -> bool {
+        match type_id {
+            Type::Variable(variable) => self.kinds[*variable] != VariableKind::Any,
+            _ => false,
+        }
+    }
+
+    /// Replace every bound type variable within the given type by the type it is bound to.
+    pub fn resolve(&self, type_id: &Type) -> Type {
+        match type_id {
+            Type::Variable(variable) => match self.bindings.get(*variable) {
+                Some(Some(bound)) => self.resolve(bound),
+                _ => type_id.clone(),
+            },
+            Type::Reference(inner) => Type::Reference(Box::new(self.resolve(inner))),
+            Type::Array(inner) => Type::Array(Box::new(self.resolve(inner))),
+            Type::FixedArray(inner, length) => {
+                Type::FixedArray(Box::new(self.resolve(inner)), *length)
+            }
+            Type::Optional(inner) => Type::optional(self.resolve(inner)),
+            Type::Result(value, error) => {
+                Type::Result(Box::new(self.resolve(value)), Box::new(self.resolve(error)))
+            }
+            Type::Tuple(types) => Type::Tuple(types.iter().map(|t| self.resolve(t)).collect()),
+            Type::Function {
+                params,
+                return_value,
+            } => Type::Function {
+                params: params.iter().map(|t| self.resolve(t)).collect(),
+                return_value: Box::new(self.resolve(return_value)),
+            },
+            _ => type_id.clone(),
+        }
+    }
+
+    /// Bind type variables, such that both types become equal. Returns `false`, if this is not
+    /// possible, e.g., because the types have a different shape. Since a fixed-size array is
+    /// accepted where a slice is expected (but not vice versa), `left` is the expected type.
+    pub fn unify(&mut self, left: &Type, right: &Type) -> bool {
+        let left = self.resolve(left);
+        let right = self.resolve(right);
+
+        match (&left, &right) {
+            // an expression which never produces a value can be used in place of any value
+            (Type::Never, _) | (_, Type::Never) => true,
+            (Type::Variable(l), Type::Variable(r)) if l == r => true,
+            (Type::Variable(variable), Type::Variable(other_variable)) => {
+                let Some(kind) = self.kinds[*variable].merge(self.kinds[*other_variable]) else {
+                    return false;
+                };
+
+                self.kinds[*other_variable] = kind;
+                self.bindings[*variable] = Some(right.clone());
+                true
+            }
+            (Type::Variable(variable), other) | (other, Type::Variable(variable)) => {
+                // a variable can not be bound to a type containing itself (e.g., `x(x)`)
+                if other.contains_variable(*variable) || !self.kinds[*variable].admits(other) {
+                    return false;
+                }
+
+                self.bindings[*variable] = Some(other.clone());
+                true
+            }
+            (Type::Reference(l), Type::Reference(r))
+            | (Type::Optional(l), Type::Optional(r))
+            | (Type::Array(l), Type::Array(r))
+            // fixed-size arrays can be used as slices, but not the other way around
+            | (Type::Array(l), Type::FixedArray(r, _)) => self.unify(l, r),
+            (Type::FixedArray(l, l_length), Type::FixedArray(r, r_length))
+                if l_length == r_length =>
+            {
+                self.unify(l, r)
+            }
+            (Type::Result(l_value, l_error), Type::Result(r_value, r_error)) => {
+                self.unify(l_value, r_value) && self.unify(l_error, r_error)
+            }
+            (Type::Tuple(l), Type::Tuple(r)) if l.len() == r.len() => {
+                l.iter().zip(r.iter()).all(|(l, r)| self.unify(l, r))
+            }
+            (
+                Type::Function {
+                    params: l_params,
+                    return_value: l_return_value,
+                },
+                Type::Function {
+                    params: r_params,
+                    return_value: r_return_value,
+                },
+            ) if l_params.len() == r_params.len() => {
+                l_params
+                    .iter()
+                    .zip(r_params.iter())
+                    .all(|(l, r)| self.unify(l, r))
+                    && self.unify(l_return_value, r_return_value)
+            }
+            _ => left == right,
+        }
+    }
+}
+
+/// Make sure that the types of all variables declared (without an annotation) in the given
+/// statements have been inferred. This has to happen after the whole function has been checked,
+/// since a variable can be constrained by any of its uses.
+pub fn check_resolved(statements: &[Statement<TypeInformation>]) -> TypeResult<()> {
+    for statement in statements {
+        match statement {
+            Statement::Initialization(Initialisation {
+                id,
+                type_name: None,
+                position,
+                ..
+            }) => {
+                let Some(type_id) = id.info.resolved_type() else {
+                    continue;
+                };
+
+                if type_id.has_variables() {
+                    return Err(TypeCheckError::MissingInitialisationType(
+                        MissingInitialisationType {
+                            variable_name: id.name.clone(),
+                            variable_position: id.position.clone(),
+                            inferred_type: Some(type_id),
+                        },
+                        position.clone(),
+                    ));
+                }
+            }
+            Statement::If(if_expression)
+            | Statement::Expression(Expression::If(if_expression))
+            | Statement::YieldingExpression(Expression::If(if_expression)) => {
+                let If {
+                    statements,
+                    else_statements,
+                    ..
+                } = if_expression;
+
+                check_resolved(statements)?;
+                check_resolved(else_statements)?;
+            }
+            Statement::WhileLoop(WhileLoop { block, .. })
+            | Statement::Expression(Expression::Block(block))
+            | Statement::YieldingExpression(Expression::Block(block)) => {
+                check_resolved(&block.statements)?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::typechecker::types::Type;
+
+    use super::Substitution;
+
+    #[test]
+    fn test_unify_variables() {
+        let mut substitution = Substitution::default();
+
+        let x = substitution.fresh();
+        let y = substitution.fresh();
+
+        assert!(substitution.unify(&x, &y));
+        assert!(substitution.unify(&y, &Type::I64));
+
+        assert_eq!(substitution.resolve(&x), Type::I64);
+        assert!(!substitution.unify(&x, &Type::F64));
+    }
+
+    #[test]
+    fn test_unify_functions() {
+        let mut substitution = Substitution::default();
+
+        let param = substitution.fresh();
+        let return_value = substitution.fresh();
+
+        let function = Type::Function {
+            params: vec![param.clone()],
+            return_value: Box::new(return_value.clone()),
+        };
+
+        assert!(substitution.unify(
+            &function,
+            &Type::Function {
+                params: vec![Type::Array(Box::new(Type::Character))],
+                return_value: Box::new(Type::Boolean),
+            }
+        ));
+        assert_eq!(
+            substitution.resolve(&function),
+            Type::Function {
+                params: vec![Type::Array(Box::new(Type::Character))],
+                return_value: Box::new(Type::Boolean),
+            }
+        );
+
+        // different arity
+        assert!(!substitution.unify(
+            &function,
+            &Type::Function {
+                params: vec![],
+                return_value: Box::new(Type::Boolean),
+            }
+        ));
+    }
+
+    #[test]
+    fn test_unify_arrays() {
+        let mut substitution = Substitution::default();
+
+        let slice = Type::Array(Box::new(Type::I64));
+        let fixed_array = |length| Type::FixedArray(Box::new(Type::I64), length);
+
+        // fixed-size arrays can be used as slices, but not the other way around
+        assert!(substitution.unify(&slice, &fixed_array(2)));
+        assert!(!substitution.unify(&fixed_array(2), &slice));
+
+        assert!(substitution.unify(&fixed_array(2), &fixed_array(2)));
+        assert!(!substitution.unify(&fixed_array(2), &fixed_array(3)));
+    }
+
+    #[test]
+    fn test_unify_optionals() {
+        let mut substitution = Substitution::default();
+
+        let x = substitution.fresh();
+
+        assert!(substitution.unify(&Type::optional(x.clone()), &Type::optional(Type::I64)));
+        assert_eq!(substitution.resolve(&x), Type::I64);
+
+        // values have to be wrapped explicitly using `some`
+        assert!(!substitution.unify(&Type::optional(Type::I64), &Type::I64));
+    }
+
+    #[test]
+    fn test_unify_results() {
+        let mut substitution = Substitution::default();
+
+        let x = substitution.fresh();
+        let result = |value, error| Type::Result(Box::new(value), Box::new(error));
+
+        assert!(substitution.unify(
+            &result(Type::I64, x.clone()),
+            &result(Type::I64, Type::String)
+        ));
+        assert_eq!(substitution.resolve(&x), Type::String);
+
+        assert!(!substitution.unify(
+            &result(Type::I64, Type::String),
+            &result(Type::String, Type::I64)
+        ));
+    }
+
+    #[test]
+    fn test_occurs_check() {
+        let mut substitution = Substitution::default();
+
+        let x = substitution.fresh();
+
+        assert!(!substitution.unify(&x, &Type::Array(Box::new(x.clone()))));
+        assert_eq!(substitution.resolve(&x), x);
+    }
+}
@@ -0,0 +1,75 @@
+use super::{context::Context, types::Type};
+
+/// Functions which are built into the language. They are only used if their name is not shadowed
+/// by a user defined function or variable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Intrinsic {
+    /// `assert(condition)` fails if the condition does not hold.
+    Assert,
+    /// `assert_eq(left, right)` fails if both values are not equal.
+    AssertEq,
+    /// `some(value)` wraps a value into an optional value.
+    Some,
+    /// `ok(value)` wraps a value into a successful result.
+    Ok,
+    /// `err(error)` wraps an error into a failed result.
+    Err,
+    /// `panic(message)` stops the program with the given message. It never returns, so it can be
+    /// used wherever a value is expected.
+    Panic,
+}
+
+/// Name of the missing optional value. Like intrinsics, it can be shadowed by user defined names.
+pub const NONE: &str = "none";
+
+impl Intrinsic {
+    pub fn from_name(name: &str) -> Option<Intrinsic> {
+        match name {
+            "assert" => Some(Intrinsic::Assert),
+            "assert_eq" => Some(Intrinsic::AssertEq),
+            "some" => Some(Intrinsic::Some),
+            "ok" => Some(Intrinsic::Ok),
+            "err" => Some(Intrinsic::Err),
+            "panic" => Some(Intrinsic::Panic),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Intrinsic::Assert => "assert",
+            Intrinsic::AssertEq => "assert_eq",
+            Intrinsic::Some => "some",
+            Intrinsic::Ok => "ok",
+            Intrinsic::Err => "err",
+            Intrinsic::Panic => "panic",
+        }
+    }
+
+    /// Type of this intrinsic when called with arguments of the given types. `assert_eq`, `some`,
+    /// `ok` and `err` are generic, so their parameters get the type of the first argument. The
+    /// other half of a result is inferred from its uses.
+    pub fn signature(&self, args: &[Type], ctx: &mut Context) -> Type {
+        let operand = args.first().cloned().unwrap_or(Type::Unknown);
+
+        let (params, return_value) = match self {
+            Intrinsic::Assert => (vec![Type::Boolean], Type::Void),
+            Intrinsic::AssertEq => (vec![operand.clone(), operand], Type::Void),
+            Intrinsic::Some => (vec![operand.clone()], Type::optional(operand)),
+            Intrinsic::Ok => (
+                vec![operand.clone()],
+                Type::Result(Box::new(operand), Box::new(ctx.fresh_variable())),
+            ),
+            Intrinsic::Err => (
+                vec![operand.clone()],
+                Type::Result(Box::new(ctx.fresh_variable()), Box::new(operand)),
+            ),
+            Intrinsic::Panic => (vec![Type::String], Type::Never),
+        };
+
+        Type::Function {
+            params,
+            return_value: Box::new(return_value),
+        }
+    }
+}
@@ -1,17 +1,40 @@
+mod bounds;
+mod casts;
+mod constants;
 mod context;
+mod definite_assignment;
 mod error;
+mod flow;
+mod folding;
+mod incremental;
+mod inference;
+mod intrinsic;
+mod propagation;
 mod scope;
 mod typed_ast;
 mod types;
+mod usage;
+mod warning;
 
+use crate::diagnostic::Diagnostic;
 use crate::lexer::Span;
-use crate::parser::ast::TopLevelStatement;
+use crate::parser::ast::{
+    Constant, Declaration, Function, Instance, Statement, StructDeclaration, TopLevelStatement,
+};
+use crate::source_map::SourceMap;
 use error::{InvalidMainSignature, MissingMainFunction};
 use std::fmt::{Display, Formatter};
-use std::{cell::RefCell, error::Error, fmt::Debug, rc::Rc};
+use std::{cell::RefCell, collections::HashMap, error::Error, fmt::Debug, rc::Rc};
 
+pub use self::bounds::{bounds_check, BoundsCheck};
+pub use self::constants::constant_order;
+use self::context::Context;
 pub use self::error::TypeCheckError;
-use self::{context::Context, types::Type};
+pub use self::folding::{ConstValue, ConstantInformation};
+pub use self::incremental::IncrementalChecker;
+pub use self::intrinsic::{Intrinsic, NONE};
+pub use self::types::{FloatType, IntegerType, Type};
+pub use self::warning::TypeCheckWarning;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TypeInformation {
@@ -23,9 +46,17 @@ impl TypeInformation {
     pub fn has_type(&self) -> bool {
         self.type_id.borrow().is_some()
     }
+
+    /// The type of this node with all type variables replaced which have been inferred so far.
+    pub fn resolved_type(&self) -> Option<Type> {
+        self.type_id
+            .borrow()
+            .as_ref()
+            .map(|type_id| self.context.resolve(type_id))
+    }
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ValidatedTypeInformation {
     pub type_id: Type,
     #[serde(skip)]
@@ -34,14 +65,13 @@ pub struct ValidatedTypeInformation {
 
 impl TypeInformation {
     fn validate(self, position: &Span) -> Result<ValidatedTypeInformation, TypeValidationError> {
-        let TypeInformation { type_id, context } = self;
-        let verified_type_information = if let Some(type_id) = type_id.borrow().clone() {
-            Ok(ValidatedTypeInformation { type_id, context })
-        } else {
-            Err(TypeValidationError(position.clone()))
-        };
-
-        verified_type_information
+        match self.resolved_type() {
+            Some(type_id) if !type_id.has_variables() => Ok(ValidatedTypeInformation {
+                type_id,
+                context: self.context,
+            }),
+            _ => Err(TypeValidationError(position.clone())),
+        }
     }
 }
 
@@ -51,6 +81,11 @@ pub struct TypeValidationError(Span);
 impl TypeValidationError {
     const MESSAGE: &'static str = "Type must be known at compile time!";
 
+    /// Stable code identifying this kind of error.
+    pub fn code(&self) -> &'static str {
+        "E0015"
+    }
+
     pub fn span(&self) -> Span {
         self.0.clone()
     }
@@ -58,11 +93,17 @@ impl TypeValidationError {
     pub fn err(&self) -> String {
         Self::MESSAGE.to_string()
     }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        Diagnostic::error(self.code(), Self::MESSAGE)
+            .with_primary(self.span(), Some("type could not be inferred".into()))
+            .with_note("consider adding a type annotation")
+    }
 }
 
 impl Display for TypeValidationError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(self.0.to_string(Self::MESSAGE).as_str())
+        f.write_str(&self.diagnostic().to_string())
     }
 }
 
@@ -80,8 +121,6 @@ trait TypeCheckable {
     type Typed;
 
     fn check(self, ctx: &mut Context) -> TypeResult<Self::Typed>;
-
-    fn revert(this: &Self::Typed) -> Self;
 }
 
 trait ShallowCheck {
@@ -113,24 +152,8 @@ impl TypeChecker {
     }
 
     fn shallow_check(&mut self) -> TypeResult<()> {
-        let struct_declarations = self
-            .statements
-            .iter()
-            .filter(|stm| matches!(stm, TopLevelStatement::StructDeclaration(_)))
-            .collect::<Vec<_>>();
-
-        let other_tl_statements = self
-            .statements
-            .iter()
-            .filter(|stm| !matches!(stm, TopLevelStatement::StructDeclaration(_)))
-            .collect::<Vec<_>>();
-
-        for s in struct_declarations.iter() {
-            s.shallow_check(&mut self.context)?;
-        }
-
-        for s in other_tl_statements.iter() {
-            s.shallow_check(&mut self.context)?;
+        for index in shallow_check_order(&self.statements) {
+            self.statements[index].shallow_check(&mut self.context)?;
         }
 
         Ok(())
@@ -139,22 +162,162 @@ impl TypeChecker {
     pub fn check(mut self) -> TypeResult<Vec<TopLevelStatement<TypeInformation>>> {
         self.shallow_check()?;
 
+        if let Some((_, e)) = constants::analyse(&self.statements)
+            .cycles
+            .into_iter()
+            .next()
+        {
+            return Err(e);
+        }
+
         let mut checked = vec![];
 
-        for stm in self.statements.iter() {
-            checked.push(stm.clone().check(&mut self.context)?);
+        for index in check_order(&self.statements) {
+            let statement = self.statements[index].clone().check(&mut self.context)?;
+            checked.push((index, statement));
         }
 
         self.check_main_function()?;
 
-        Ok(checked)
+        checked.sort_by_key(|(index, _)| *index);
+        Ok(checked
+            .into_iter()
+            .map(|(_, statement)| statement)
+            .collect())
+    }
+
+    /// Check all statements, but instead of stopping at the first error, collect the errors of
+    /// every toplevel statement.
+    pub fn check_all(
+        mut self,
+    ) -> Result<Vec<TopLevelStatement<TypeInformation>>, Vec<TypeCheckError>> {
+        let (checked, mut errors) = self.check_statements();
+
+        if let Err(e) = self.check_main_function() {
+            errors.push(e);
+        }
+
+        if errors.is_empty() {
+            Ok(checked)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Like [`TypeChecker::check_all`], but without requiring a main function, e.g., for files
+    /// which only contain tests.
+    pub fn check_all_without_main(
+        mut self,
+    ) -> Result<Vec<TopLevelStatement<TypeInformation>>, Vec<TypeCheckError>> {
+        let (checked, errors) = self.check_statements();
+
+        if errors.is_empty() {
+            Ok(checked)
+        } else {
+            Err(errors)
+        }
+    }
+
+    fn check_statements(
+        &mut self,
+    ) -> (Vec<TopLevelStatement<TypeInformation>>, Vec<TypeCheckError>) {
+        let mut errors = vec![];
+
+        for index in shallow_check_order(&self.statements) {
+            if let Err(e) = self.statements[index].shallow_check(&mut self.context) {
+                errors.push(e);
+            }
+        }
+
+        let mut results = vec![];
+        let mut cycles = constants::analyse(&self.statements)
+            .cycles
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        for index in check_order(&self.statements) {
+            if let Some(e) = cycles.remove(&index) {
+                results.push((index, Err(e)));
+                continue;
+            }
+
+            // a failing statement might leave some scopes behind, so we restore the stack
+            let snapshot = self.context.snapshot();
+
+            let result = self.statements[index].clone().check(&mut self.context);
+            if result.is_err() {
+                self.context.restore(snapshot);
+            }
+            results.push((index, result));
+        }
+
+        results.sort_by_key(|(index, _)| *index);
+
+        let mut checked = vec![];
+        for (_, result) in results {
+            match result {
+                Ok(stm) => checked.push(stm),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        (checked, errors)
+    }
+
+    /// Check a single toplevel statement against everything checked by this typechecker before.
+    /// If the statement does not typecheck, the context is left untouched. This is meant for
+    /// interactive sessions, which do not need a main function.
+    pub fn check_top_level_statement(
+        &mut self,
+        statement: TopLevelStatement<()>,
+    ) -> TypeResult<TopLevelStatement<TypeInformation>> {
+        let snapshot = self.context.snapshot();
+
+        let checked = statement
+            .shallow_check(&mut self.context)
+            .and_then(|_| statement.clone().check(&mut self.context));
+
+        match checked {
+            Ok(checked) => {
+                self.statements.push(statement);
+                Ok(checked)
+            }
+            Err(e) => {
+                self.context.restore(snapshot);
+                Err(e)
+            }
+        }
+    }
+
+    /// Check a single statement as if it was part of the global scope, e.g., a variable
+    /// declaration or an expression. Like with [`TypeChecker::check_top_level_statement`],
+    /// the context is left untouched if the statement does not typecheck.
+    pub fn check_statement(
+        &mut self,
+        statement: Statement<()>,
+    ) -> TypeResult<Statement<TypeInformation>> {
+        let snapshot = self.context.snapshot();
+        let first_variable = self.context.variable_count();
+
+        statement
+            .check(&mut self.context)
+            .and_then(|statement| {
+                self.context.default_literals(first_variable);
+                folding::check(std::slice::from_ref(&statement))?;
+                Ok(statement)
+            })
+            .inspect_err(|_| {
+                self.context.restore(snapshot);
+            })
     }
 
     fn check_main_function(&mut self) -> Result<(), TypeCheckError> {
         let main = self.context.scope.resolve_name("main");
 
         let Some(main) = main else {
-            return Err(TypeCheckError::MissingMainFunction(MissingMainFunction));
+            return Err(TypeCheckError::MissingMainFunction(MissingMainFunction {
+                end_of_source: self.end_of_source(),
+            }));
         };
 
         let main = { main.borrow().clone().unwrap() };
@@ -164,8 +327,7 @@ impl TypeChecker {
                 params,
                 return_value,
             } => {
-                if !params.is_empty()
-                    && (*return_value != Type::Void || *return_value != Type::Integer)
+                if !params.is_empty() && (*return_value != Type::Void || *return_value != Type::I64)
                 {
                     // TODO: we need to return the correct span of the main function for better
                     // error display
@@ -175,12 +337,62 @@ impl TypeChecker {
                     ));
                 }
             }
-            _ => return Err(TypeCheckError::MissingMainFunction(MissingMainFunction)),
+            _ => {
+                return Err(TypeCheckError::MissingMainFunction(MissingMainFunction {
+                    end_of_source: self.end_of_source(),
+                }))
+            }
         }
 
         Ok(())
     }
 
+    /// Get an (empty) span at the end of the source of the checked statements.
+    fn end_of_source(&self) -> Span {
+        let Some(file) = self
+            .statements
+            .iter()
+            .find_map(|statement| match statement {
+                TopLevelStatement::Comment(..) => None,
+                TopLevelStatement::Function(Function { position, .. })
+                | TopLevelStatement::Constant(Constant { position, .. })
+                | TopLevelStatement::Declaration(Declaration { position, .. })
+                | TopLevelStatement::StructDeclaration(StructDeclaration { position, .. })
+                | TopLevelStatement::Instance(Instance { position, .. }) => Some(position.file),
+            })
+        else {
+            return Span::default();
+        };
+
+        let end = SourceMap::get(file)
+            .map(|source| source.source().trim_end().len())
+            .unwrap_or_default();
+
+        Span::new(file, end, end)
+    }
+
+    /// Collect the warnings of checked statements, e.g., about code which is never executed or
+    /// bindings which are never used. The warnings are ordered by their position.
+    pub fn warnings(statements: &[TopLevelStatement<TypeInformation>]) -> Vec<TypeCheckWarning> {
+        let mut warnings = statements
+            .iter()
+            .flat_map(|statement| match statement {
+                TopLevelStatement::Function(function) => vec![function],
+                TopLevelStatement::Instance(Instance { functions, .. }) => {
+                    functions.iter().collect()
+                }
+                _ => vec![],
+            })
+            .flat_map(flow::unreachable_code)
+            .map(TypeCheckWarning::UnreachableCode)
+            .chain(usage::unused_bindings(statements))
+            .chain(casts::lossy_casts(statements))
+            .collect::<Vec<_>>();
+
+        warnings.sort_by_key(|warning| warning.span().start);
+        warnings
+    }
+
     pub fn validate(
         statements: Vec<TopLevelStatement<TypeInformation>>,
     ) -> Result<Vec<TopLevelStatement<ValidatedTypeInformation>>, TypeValidationError> {
@@ -192,4 +404,734 @@ impl TypeChecker {
 
         Ok(validated)
     }
+
+    /// Validate a single statement, which was checked by [`TypeChecker::check_statement`].
+    pub fn validate_statement(
+        statement: Statement<TypeInformation>,
+    ) -> Result<Statement<ValidatedTypeInformation>, TypeValidationError> {
+        statement.validate()
+    }
+}
+
+/// Indices of the given statements in the order in which they are checked. Constants come
+/// first (after the constants they use), so their values are known wherever they are used
+/// (e.g., as the length of an array).
+fn check_order<T>(statements: &[TopLevelStatement<T>]) -> Vec<usize> {
+    let mut order = constant_order(statements);
+    order.extend(
+        statements
+            .iter()
+            .enumerate()
+            .filter(|(_, statement)| !matches!(statement, TopLevelStatement::Constant(_)))
+            .map(|(index, _)| index),
+    );
+    order
+}
+
+/// Indices of the given statements in the order in which they are shallow checked. Structs have
+/// to be known before anything else, and constants before any signature which might use their
+/// value (e.g., `fn sum(values: [i64; N])`).
+fn shallow_check_order<T>(statements: &[TopLevelStatement<T>]) -> Vec<usize> {
+    let rank = |statement: &TopLevelStatement<T>| match statement {
+        TopLevelStatement::StructDeclaration(_) => 0,
+        TopLevelStatement::Constant(_) => 1,
+        _ => 2,
+    };
+
+    let mut order = (0..statements.len()).collect::<Vec<_>>();
+    order.sort_by_key(|&index| rank(&statements[index]));
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        lexer::Lexer,
+        parser::{parse, parse_statements},
+    };
+
+    use super::{error::TypeMismatch, *};
+
+    fn checker(input: &str) -> TypeChecker {
+        let tokens = Lexer::new(input).lex().expect("should lex");
+        TypeChecker::new(parse(&mut tokens.into()).expect("should parse"))
+    }
+
+    #[test]
+    fn test_check_all_collects_errors() {
+        let errors = checker(
+            "fn foo(): i64 {\n    bar\n}\n\nfn baz(): i64 {\n    'a'\n}\n\nfn main(): void {}",
+        )
+        .check_all()
+        .expect_err("should fail");
+
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec!["E0004", "E0003"]
+        );
+    }
+
+    #[test]
+    fn test_check_all_reports_missing_main() {
+        let errors = checker("fn foo(): i64 {\n    bar\n}")
+            .check_all()
+            .expect_err("should fail");
+
+        assert!(matches!(
+            errors.as_slice(),
+            [
+                TypeCheckError::UndefinedVariable(..),
+                TypeCheckError::MissingMainFunction(..)
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_incremental_checks() {
+        let mut checker = TypeChecker::default();
+
+        let tokens = Lexer::new("fn inc(x: i64): i64 {\n    x + 1\n}")
+            .lex()
+            .expect("should lex");
+        for statement in parse(&mut tokens.into()).expect("should parse") {
+            checker
+                .check_top_level_statement(statement)
+                .expect("should typecheck");
+        }
+
+        let tokens = Lexer::new("let y = inc(41); y").lex().expect("should lex");
+        let statements = parse_statements(&mut tokens.into()).expect("should parse");
+        let checked = statements
+            .into_iter()
+            .map(|statement| checker.check_statement(statement))
+            .collect::<Result<Vec<_>, _>>()
+            .expect("should typecheck");
+
+        assert_eq!(
+            *checked.last().unwrap().get_info().unwrap().type_id.borrow(),
+            Some(Type::I64)
+        );
+
+        // failing statements do not leave anything behind
+        let tokens = Lexer::new("let z = unknown;").lex().expect("should lex");
+        let statement = parse_statements(&mut tokens.into())
+            .expect("should parse")
+            .remove(0);
+        assert!(checker.check_statement(statement).is_err());
+        assert!(checker.context.scope.resolve_name("z").is_none());
+    }
+
+    #[test]
+    fn test_test_attributes() {
+        let errors = checker("#[test]\nfn valid(): void {}\n\n#[tset]\nfn typo(): void {}\n\n#[test]\nfn invalid(x: i64): i64 {\n    x\n}")
+            .check_all_without_main()
+            .expect_err("should fail");
+
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec!["E0016", "E0017"]
+        );
+    }
+
+    #[test]
+    fn test_intrinsics() {
+        checker("fn main(): void {\n    assert(1 < 2);\n    assert_eq('a', 'b');\n}")
+            .check_all()
+            .expect("should typecheck");
+
+        let errors = checker("fn main(): void {\n    assert(1);\n    assert_eq(1, 1.0);\n}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec!["E0003"]
+        );
+
+        // user defined functions shadow intrinsics
+        checker(
+            "fn assert(x: i64): i64 {\n    x\n}\n\nfn main(): void {\n    let x = assert(42);\n}",
+        )
+        .check_all()
+        .expect("should typecheck");
+    }
+
+    #[test]
+    fn test_returns_on_every_path() {
+        checker("fn sign(x: i64): i64 {\n    if (x < 0) {\n        return 0 - 1;\n    } else {\n        return 1;\n    }\n}\n\nfn main(): void {}")
+            .check_all()
+            .expect("should typecheck");
+
+        let input = "fn sign(x: i64): i64 {\n    if (x < 0) {\n        return 0 - 1;\n    }\n}\n\nfn main(): void {}";
+        let errors = checker(input).check_all().expect_err("should fail");
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec!["E0026"]
+        );
+        assert_eq!(errors[0].span().start_line_col(), (4, 0));
+
+        let errors = checker("fn sign(x: i64): i64 {\n    while (x < 0) {\n        return 'a';\n    }\n    1\n}\n\nfn main(): void {}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors
+                .iter()
+                .map(|e| (e.code(), e.span().start_line_col()))
+                .collect::<Vec<_>>(),
+            vec![("E0003", (2, 15))]
+        );
+    }
+
+    #[test]
+    fn test_declaration_spans() {
+        // errors about a whole function cover it up to its closing brace
+        let errors =
+            checker("fn foo(): void {}\n\nfn foo(): i64 {\n    42\n}\n\nfn main(): void {}")
+                .check_all()
+                .expect_err("should fail");
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec!["E0009"]
+        );
+        assert_eq!(errors[0].span().start_line_col(), (2, 0));
+        assert_eq!(errors[0].span().end_line_col(), (4, 1));
+
+        // a missing return points at the closing brace, which is the end of the function
+        let errors = checker("fn foo(x: i64): i64 {\n    if (x > 0) {\n        return x;\n    }\n  }\n\nfn main(): void {}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec!["E0026"]
+        );
+        assert_eq!(errors[0].span().start_line_col(), (4, 2));
+        assert_eq!(errors[0].span().end_line_col(), (4, 3));
+    }
+
+    #[test]
+    fn test_warnings() {
+        let tokens = Lexer::new(
+            "fn foo(): i64 {\n    return 42;\n    43\n}\n\nfn main(): void {\n    return main();\n    let _x = foo();\n    let _y = foo();\n}",
+        )
+        .lex()
+        .expect("should lex");
+        let checked = TypeChecker::new(parse(&mut tokens.into()).expect("should parse"))
+            .check_all()
+            .expect("should typecheck");
+
+        assert_eq!(
+            TypeChecker::warnings(&checked)
+                .iter()
+                .map(|warning| (warning.code(), warning.span().start_line_col()))
+                .collect::<Vec<_>>(),
+            vec![("W0001", (2, 4)), ("W0001", (7, 4))]
+        );
+    }
+
+    #[test]
+    fn test_unused_bindings() {
+        let tokens = Lexer::new(
+            "fn used(x: i64, unused: i64, _ignored: i64): i64 {\n    let mut y = x;\n    let mut z = 0;\n    z = y;\n    let w = 1;\n    z\n}\n\nfn dead(): i64 {\n    also_dead()\n}\n\nfn also_dead(): i64 {\n    dead()\n}\n\nfn main(): void {\n    let _ = used(1, 2, 3);\n}",
+        )
+        .lex()
+        .expect("should lex");
+        let checked = TypeChecker::new(parse(&mut tokens.into()).expect("should parse"))
+            .check_all()
+            .expect("should typecheck");
+
+        assert_eq!(
+            TypeChecker::warnings(&checked)
+                .iter()
+                .map(|warning| (warning.code(), warning.span().start_line_col()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("W0004", (0, 16)),
+                ("W0003", (1, 12)),
+                ("W0002", (4, 8)),
+                ("W0005", (8, 3)),
+                ("W0005", (12, 3)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_uninitialised_variables() {
+        checker("fn main(): void {\n    let x: i64;\n    x = 42;\n    let _y = x;\n}")
+            .check_all()
+            .expect("should typecheck");
+
+        let errors = checker("fn main(): void {\n    let x: i64;\n    let _y = x;\n}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec!["E0027"]
+        );
+
+        // variables without a value need a type annotation
+        let errors = checker("fn main(): void {\n    let x;\n}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec!["E0006"]
+        );
+    }
+
+    #[test]
+    fn test_diagnostics() {
+        let errors = checker("fn main(): void {\n    let x = 42;\n    x = 43;\n}")
+            .check_all()
+            .expect_err("should fail");
+
+        let diagnostic = errors[0].diagnostic();
+        assert_eq!(diagnostic.code, "E0011");
+        assert_eq!(
+            diagnostic
+                .labels
+                .iter()
+                .map(|label| (
+                    label.span.start_line_col(),
+                    label.message.as_deref(),
+                    label.primary
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ((2, 4), Some("cannot assign twice"), true),
+                ((1, 4), Some("declared as immutable here"), false)
+            ]
+        );
+        assert_eq!(
+            diagnostic.suggestions,
+            errors[0].fix().into_iter().collect::<Vec<_>>()
+        );
+
+        // types are written the same way in the message as in the labels
+        let errors = checker("fn main(): void {\n    let a: u8 = 5;\n    let b: i64 = a;\n}")
+            .check_all()
+            .expect_err("should fail");
+
+        let diagnostic = errors[0].diagnostic();
+        assert_eq!(diagnostic.message, "Expected type 'i64' but got 'u8'");
+        assert_eq!(
+            diagnostic.labels[0].message.as_deref(),
+            Some("expected 'i64', found 'u8'")
+        );
+    }
+
+    #[test]
+    fn test_fixes() {
+        let errors = checker(
+            "fn foo(): i64 {\n    let x = 42;\n    x = 43;\n    let counter = 1;\n    countr\n}",
+        )
+        .check_all()
+        .expect_err("should fail");
+
+        let fixes = errors
+            .iter()
+            .map(|e| {
+                e.fix()
+                    .map(|fix| (fix.message, fix.span.start_line_col(), fix.replacement))
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            fixes,
+            vec![
+                Some(("Make 'x' mutable".into(), (1, 4), "let mut".into())),
+                Some((
+                    "Add main function".into(),
+                    (5, 1),
+                    "\n\nfn main(): void {\n}\n".into()
+                )),
+            ]
+        );
+
+        let errors = checker(
+            "fn foo(): i64 {\n    let counter = 1;\n    countr\n}\n\nfn main(): void {\n    let f = \\(x) => x;\n}",
+        )
+        .check_all()
+        .expect_err("should fail");
+
+        let fixes = errors
+            .iter()
+            .map(|e| {
+                e.fix()
+                    .map(|fix| (fix.message, fix.span.start_line_col(), fix.replacement))
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            fixes,
+            vec![
+                Some(("Replace with 'counter'".into(), (2, 4), "counter".into())),
+                Some((
+                    "Add type annotation to 'f'".into(),
+                    (6, 9),
+                    ": (_) -> _".into()
+                )),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lambda_inference() {
+        let input = "struct Point {\n    x: i64;\n}\n\nfn main(): void {\n    let inc = \\(x) => x + 1;\n    let apply = \\(f, x) => f(x);\n    let first = \\(xs) => xs[0];\n    let get_x: (Point) -> i64 = \\(p) => p.x;\n    let scale = \\(x) => x;\n    assert_eq(apply(inc, first([1, 2])), get_x(Point { x: 2 }));\n    assert_eq(scale(1.5), 1.5);\n}";
+        let checked = checker(input).check_all().expect("should typecheck");
+        let validated = TypeChecker::validate(checked).expect("should validate");
+
+        let TopLevelStatement::Function(main) = &validated[1] else {
+            panic!("expected main function");
+        };
+
+        let types = main
+            .statements
+            .iter()
+            .filter_map(|statement| match statement {
+                Statement::Initialization(init) => Some(init.id.info.type_id.to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            types,
+            vec![
+                "(i64) -> i64",
+                "((i64) -> i64, i64) -> i64",
+                "([i64]) -> i64",
+                "(Point) -> i64",
+                "(f64) -> f64"
+            ]
+        );
+
+        let errors = checker("fn main(): void {\n    let inc = \\(x) => x + 1;\n    inc(1.5);\n}")
+            .check_all()
+            .expect_err("should fail");
+        assert!(matches!(
+            errors.as_slice(),
+            [TypeCheckError::TypeMismatch(
+                TypeMismatch {
+                    expected: Type::I64,
+                    actual: Type::F64
+                },
+                _
+            )]
+        ));
+    }
+
+    #[test]
+    fn test_ambiguous_lambdas() {
+        let errors = checker(
+            "fn main(): void {\n    let id = \\(x) => x;\n    let get_x = \\(p) => p.x;\n}",
+        )
+        .check_all()
+        .expect_err("should fail");
+
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec!["E0028"]
+        );
+        assert_eq!(errors[0].span().start_line_col(), (2, 24));
+
+        let errors = checker("fn main(): void {\n    let id = \\(x) => x;\n}")
+            .check_all()
+            .expect_err("should fail");
+
+        assert_eq!(
+            errors
+                .iter()
+                .map(|e| e.fix().map(|fix| fix.replacement))
+                .collect::<Vec<_>>(),
+            vec![Some(": (_) -> _".into())]
+        );
+
+        // the fix inserts the inferred part of the type, which still parses, and leaves the
+        // placeholders for the user to complete
+        let input = "fn main(): void {\n    let id = \\(x) => x;\n}";
+        let errors = checker(input).check_all().expect_err("should fail");
+        let fix = errors[0].fix().expect("should have a fix");
+
+        let mut fixed = input.to_string();
+        fixed.replace_range(fix.span.start..fix.span.end, &fix.replacement);
+        assert_eq!(
+            fixed,
+            "fn main(): void {\n    let id: (_) -> _ = \\(x) => x;\n}"
+        );
+        let tokens = Lexer::new(&fixed).lex().expect("should lex");
+        parse(&mut tokens.into()).expect("should parse");
+
+        let completed = fixed.replace('_', "i64");
+        checker(&completed).check_all().expect("should typecheck");
+
+        // without any inferred type, there is nothing to insert
+        let errors = checker("fn main(): void {\n    let x;\n}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec!["E0006"]
+        );
+        assert!(errors[0].fix().is_none());
+    }
+
+    #[test]
+    fn test_casts() {
+        let errors = checker(
+            "fn main(): void {\n    let a = 1 as f64 + 1.5;\n    let b = \"42\" as i64;\n}",
+        )
+        .check_all()
+        .expect_err("should fail");
+
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec!["E0030"]
+        );
+        assert_eq!(errors[0].span().start_line_col(), (2, 12));
+        assert_eq!(
+            errors[0].err().to_string(),
+            "Can not cast a value of type 'str' to 'i64'"
+        );
+
+        let errors = checker("fn main(): void {\n    let f = \\(x) => x as f64;\n}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec!["E0028"]
+        );
+    }
+
+    #[test]
+    fn test_unsigned_negation() {
+        checker("fn main(): void {\n    let a: i8 = 5;\n    let b = -a;\n    let c = -1.5;\n}")
+            .check_all()
+            .expect("should typecheck");
+
+        let errors = checker("fn main(): void {\n    let a: u8 = 5;\n    let b = -a;\n}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors.iter().map(|e| e.code()).collect::<Vec<_>>(),
+            vec!["E0037"]
+        );
+        assert_eq!(errors[0].span().start_line_col(), (2, 13));
+        assert_eq!(
+            errors[0].err().to_string(),
+            "Cannot negate unsigned type 'u8'"
+        );
+    }
+
+    #[test]
+    fn test_array_lengths() {
+        checker(
+            "fn main(): void {\n    const L: usize = 2;\n    let a = [0; N * 2];\n    let b = [1.5; L + 1];\n}\n\nconst N: i64 = 4 * 8;",
+        )
+        .check_all()
+        .expect("should typecheck");
+
+        let errors = checker("fn main(): void {\n    let n = 4;\n    let a = [0; n];\n}\n\nfn foo(): void {\n    let a = [0; 0 - 1];\n}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors.iter().map(|error| error.code()).collect::<Vec<_>>(),
+            vec!["E0033", "E0031"]
+        );
+    }
+
+    #[test]
+    fn test_constant_dependencies() {
+        // constants are checked after the constants they use
+        checker("const A: usize = B * 2;\nconst B: usize = 2;\n\nfn main(): void {\n    let a = [0; A];\n}")
+            .check_all()
+            .expect("should typecheck");
+
+        let errors = checker("const A: i64 = B;\nconst B: i64 = A;\n\nfn main(): void {}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| (error.code(), error.span().start_line_col()))
+                .collect::<Vec<_>>(),
+            vec![("E0040", (0, 6))]
+        );
+        assert_eq!(
+            errors[0].err().to_string(),
+            "Constant 'A' depends on itself: A -> B -> A"
+        );
+
+        let tokens = Lexer::new("const A: i64 = A;\n\nfn main(): void {}")
+            .lex()
+            .expect("should lex");
+        let error = TypeChecker::new(parse(&mut tokens.into()).expect("should parse"))
+            .check()
+            .expect_err("should fail");
+        assert_eq!(error.code(), "E0040");
+    }
+
+    #[test]
+    fn test_fixed_arrays() {
+        checker(
+            "fn first(values: [i64; N]): i64 {\n    values[0]\n}\n\nfn sum(values: [i64]): i64 {\n    values[0] + values[1]\n}\n\nfn main(): void {\n    let a = [1, 2, 3];\n    first(a);\n    sum(a);\n    let b: [i64] = [0; N];\n}\n\nconst N: usize = 3;",
+        )
+        .check_all()
+        .expect("should typecheck");
+
+        let errors = checker("fn main(): void {\n    let mut a = [1, 2];\n    a[2] = 42;\n}\n\nfn foo(values: [i64; 3]): void {}\n\nfn bar(): void {\n    foo([1, 2]);\n}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors.iter().map(|error| error.code()).collect::<Vec<_>>(),
+            vec!["E0034", "E0003"]
+        );
+        assert_eq!(errors[0].span().start_line_col(), (2, 6));
+        assert_eq!(
+            errors[0].err().to_string(),
+            "Index 2 is out of bounds for array of length 2"
+        );
+    }
+
+    #[test]
+    fn test_optionals() {
+        checker(
+            "struct Point {\n    x: i64;\n}\n\nfn find(x: i64): Point? {\n    if (x > 0) {\n        return some(Point { x: x });\n    }\n    none\n}\n\nfn main(): void {\n    let a: i64 = find(1)?.x ?? 0;\n    let b = find(2);\n    if (b != none) {\n        let c: i64 = b.x;\n    }\n    let d: i64? = b?.x ?? some(3);\n}",
+        )
+        .check_all()
+        .expect("should typecheck");
+
+        // mutable variables are not narrowed, since they might be set to `none`
+        let errors = checker("fn main(): void {\n    let mut a: i64? = some(1);\n    if (a != none) {\n        a + 1;\n    }\n}\n\nfn foo(a: i64?): void {\n    let b: i64 = a ?? 1.5;\n}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors.iter().map(|error| error.code()).collect::<Vec<_>>(),
+            vec!["E0014", "E0003"]
+        );
+        assert_eq!(errors[1].span().start_line_col(), (8, 22));
+    }
+
+    #[test]
+    fn test_properties_of_optionals() {
+        let errors = checker("struct Point {\n    x: i64;\n}\n\nfn main(): void {\n    let p: Point? = none;\n    let x = p.x;\n}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| (error.code(), error.span().start_line_col()))
+                .collect::<Vec<_>>(),
+            vec![("E0039", (6, 12))]
+        );
+        assert_eq!(
+            errors[0].err().to_string(),
+            "Cannot access property 'x' of optional 'Point?'"
+        );
+    }
+
+    #[test]
+    fn test_results() {
+        checker(
+            "fn digit(c: char): i64 ! str {\n    if (c >= '0') {\n        return ok(c as i64 - '0' as i64);\n    }\n    err(\"not a digit\")\n}\n\nfn pair(a: char, b: char): i64 ! str {\n    let tens = digit(a)?;\n    ok(tens * 10 + digit(b)?)\n}\n\nfn main(): void {\n    let x: i64 = pair('4', '2') ?? 0;\n    let inc = \\(c) => ok(digit(c)? + 1);\n    assert(inc('1') == ok(2));\n}",
+        )
+        .check_all()
+        .expect("should typecheck");
+
+        let errors = checker("fn digit(c: char): i64 ! str {\n    err(\"no\")\n}\n\nfn wrong(): i64 ! i64 {\n    ok(digit('1')?)\n}\n\nfn main(): void {\n    digit('2')?;\n}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors.iter().map(|error| error.code()).collect::<Vec<_>>(),
+            vec!["E0003", "E0035"]
+        );
+        assert_eq!(errors[1].span().start_line_col(), (9, 9));
+    }
+
+    #[test]
+    fn test_panics() {
+        checker("fn first(values: [i64]): i64 ! str {\n    if (values[0] > 0) {\n        return ok(values[0]);\n    }\n    panic(\"boom\");\n}\n\nfn second(): i64 ! str {\n    panic(\"boom\")\n}\n\nfn main(): void {\n    let a: i64 = first([1, 2]) ?? 0;\n    let b = second();\n}")
+            .check_all()
+            .expect("should typecheck");
+
+        // nothing after a panic is executed
+        let tokens = Lexer::new("fn main(): void {\n    panic(\"boom\");\n    let _x = 1;\n}")
+            .lex()
+            .expect("should lex");
+        let checked = TypeChecker::new(parse(&mut tokens.into()).expect("should parse"))
+            .check_all()
+            .expect("should typecheck");
+        assert_eq!(
+            TypeChecker::warnings(&checked)
+                .iter()
+                .map(|warning| (warning.code(), warning.span().start_line_col()))
+                .collect::<Vec<_>>(),
+            vec![("W0001", (2, 4))]
+        );
+    }
+
+    #[test]
+    fn test_propagating_non_results() {
+        let errors = checker("fn foo(): i64 ! str {\n    let a = [1, 2];\n    ok(a?)\n}\n\nfn bar(): i64 ! str {\n    ok(1?)\n}\n\nfn main(): void {}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| (error.code(), error.span().start_line_col()))
+                .collect::<Vec<_>>(),
+            vec![("E0038", (2, 7)), ("E0038", (6, 7))]
+        );
+
+        let diagnostic = errors[0].diagnostic();
+        assert_eq!(
+            diagnostic.message,
+            "The '?' operator can only be applied to a result"
+        );
+        assert_eq!(
+            diagnostic.labels[0].message.as_deref(),
+            Some("this has type '[i64; 2]'")
+        );
+    }
+
+    #[test]
+    fn test_self_application() {
+        let errors = checker("fn main(): void {\n    let f = \\(x) => x(x);\n}")
+            .check_all()
+            .expect_err("should fail");
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| (error.code(), error.err().to_string()))
+                .collect::<Vec<_>>(),
+            vec![(
+                "E0003",
+                "Type '(_) -> _' would have to contain itself".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_blocks_ending_with_comments() {
+        let checked = checker(
+            "fn main(): void {\n    if (1 == 1) {\n        let a = 1;\n        // a\n    } else {\n        let b = 2;\n    }\n    let f = \\() => {\n        let c = 3;\n        // c\n    };\n    f();\n}",
+        )
+        .check_all()
+        .expect("should typecheck");
+
+        let TopLevelStatement::Function(main) = &checked[0] else {
+            panic!("expected main function");
+        };
+        let Statement::If(if_statement) = &main.statements[0] else {
+            panic!("expected if statement");
+        };
+
+        let comment = if_statement.statements.last().unwrap();
+        assert!(comment.get_info().is_none());
+        assert_eq!(comment.position().start_line_col(), (3, 8));
+    }
+
+    #[test]
+    fn test_check_all_ok() {
+        let checked = checker("fn main(): void {}").check_all();
+
+        assert!(checked.is_ok());
+    }
 }
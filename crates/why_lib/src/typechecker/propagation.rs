@@ -0,0 +1,194 @@
+//! Check of the `?` operator, which returns the error of a result from the enclosing function (or
+//! lambda). The error has to fit the result type this function returns.
+
+use crate::{
+    lexer::Span,
+    parser::ast::{
+        Array, Assignment, Block, Constant, Expression, If, Initialisation, LValue, Postfix,
+        Prefix, Statement, StructInitialisation, WhileLoop,
+    },
+};
+
+use super::{
+    context::Context,
+    error::{InvalidPropagation, TypeCheckError},
+    types::Type,
+    TypeInformation, TypeResult,
+};
+
+/// Result which is unwrapped by a `?`, together with the position of the whole expression.
+type Propagation<'a> = (&'a Expression<TypeInformation>, Span);
+
+/// Check every `?` within the given statements (but not within nested functions and lambdas)
+/// against the return type of the function they belong to.
+pub fn check(
+    statements: &[Statement<TypeInformation>],
+    return_type: &Type,
+    ctx: &Context,
+) -> TypeResult<()> {
+    let mut propagations = vec![];
+    self::statements(statements, &mut propagations);
+
+    check_all(propagations, return_type, ctx)
+}
+
+/// Check every `?` within the body of a lambda against its return type.
+pub fn check_lambda(
+    body: &Expression<TypeInformation>,
+    return_type: &Type,
+    ctx: &Context,
+) -> TypeResult<()> {
+    let mut propagations = vec![];
+    self::expression(body, &mut propagations);
+
+    check_all(propagations, return_type, ctx)
+}
+
+fn check_all(propagations: Vec<Propagation>, return_type: &Type, ctx: &Context) -> TypeResult<()> {
+    for (expr, position) in propagations {
+        let Some(Type::Result(_, error)) = expr.get_info().resolved_type() else {
+            continue;
+        };
+
+        // the return type of a lambda might not be inferred yet
+        let expected = match ctx.resolve(return_type) {
+            variable @ Type::Variable(_) => {
+                let result = Type::Result(Box::new(ctx.fresh_variable()), error);
+                ctx.unify(&variable, &result)
+                    .map_err(|mismatch| TypeCheckError::TypeMismatch(mismatch, position))?;
+                continue;
+            }
+            Type::Result(_, expected) => expected,
+            return_type => {
+                return Err(TypeCheckError::InvalidPropagation(
+                    InvalidPropagation { return_type },
+                    position,
+                ));
+            }
+        };
+
+        ctx.unify(&expected, &error)
+            .map_err(|mismatch| TypeCheckError::TypeMismatch(mismatch, position))?;
+    }
+
+    Ok(())
+}
+
+fn statements<'a>(
+    statements: &'a [Statement<TypeInformation>],
+    propagations: &mut Vec<Propagation<'a>>,
+) {
+    for statement in statements {
+        match statement {
+            Statement::If(if_expression) => self::if_expression(if_expression, propagations),
+            Statement::WhileLoop(WhileLoop {
+                condition, block, ..
+            }) => {
+                expression(condition, propagations);
+                self::statements(&block.statements, propagations);
+            }
+            Statement::Initialization(Initialisation { value, .. }) => {
+                if let Some(value) = value {
+                    expression(value, propagations);
+                }
+            }
+            Statement::Constant(Constant { value, .. })
+            | Statement::Expression(value)
+            | Statement::YieldingExpression(value)
+            | Statement::Return(value) => expression(value, propagations),
+            Statement::Assignment(Assignment { lvalue, rvalue, .. }) => {
+                if let LValue::Postfix(postfix) = lvalue {
+                    self::postfix(postfix, propagations);
+                }
+                expression(rvalue, propagations);
+            }
+            // nested functions are checked on their own
+            Statement::Function(_)
+            | Statement::Declaration(_)
+            | Statement::StructDeclaration(_)
+            | Statement::Comment(..) => {}
+        }
+    }
+}
+
+fn if_expression<'a>(
+    if_expression: &'a If<TypeInformation>,
+    propagations: &mut Vec<Propagation<'a>>,
+) {
+    let If {
+        condition,
+        statements,
+        else_statements,
+        ..
+    } = if_expression;
+
+    expression(condition, propagations);
+    self::statements(statements, propagations);
+    self::statements(else_statements, propagations);
+}
+
+fn expression<'a>(
+    expression: &'a Expression<TypeInformation>,
+    propagations: &mut Vec<Propagation<'a>>,
+) {
+    match expression {
+        // nested functions and lambdas are checked on their own
+        Expression::Id(_)
+        | Expression::Num(_)
+        | Expression::Character(_)
+        | Expression::AstString(_)
+        | Expression::Function(_)
+        | Expression::Lambda(_) => {}
+        Expression::Parens(expression)
+        | Expression::Prefix(Prefix::Negation {
+            expr: expression, ..
+        })
+        | Expression::Prefix(Prefix::Minus {
+            expr: expression, ..
+        }) => self::expression(expression, propagations),
+        Expression::If(if_expression) => self::if_expression(if_expression, propagations),
+        Expression::Block(Block { statements, .. }) => self::statements(statements, propagations),
+        Expression::Postfix(postfix) => self::postfix(postfix, propagations),
+        Expression::Binary(binary) => {
+            self::expression(&binary.left, propagations);
+            self::expression(&binary.right, propagations);
+        }
+        Expression::Array(Array::Literal { values, .. }) => values
+            .iter()
+            .for_each(|value| self::expression(value, propagations)),
+        Expression::Array(Array::Default {
+            initial_value,
+            length,
+            ..
+        }) => {
+            self::expression(initial_value, propagations);
+            self::expression(length, propagations);
+        }
+        Expression::StructInitialisation(StructInitialisation { fields, .. }) => fields
+            .iter()
+            .for_each(|field| self::expression(&field.value, propagations)),
+        Expression::Interpolation(interpolation) => interpolation
+            .expressions()
+            .for_each(|expression| self::expression(expression, propagations)),
+    }
+}
+
+fn postfix<'a>(postfix: &'a Postfix<TypeInformation>, propagations: &mut Vec<Propagation<'a>>) {
+    match postfix {
+        Postfix::Call { expr, args, .. } => {
+            expression(expr, propagations);
+            args.iter().for_each(|arg| expression(arg, propagations));
+        }
+        Postfix::Index { expr, index, .. } => {
+            expression(expr, propagations);
+            expression(index, propagations);
+        }
+        Postfix::Propagate { expr, position, .. } => {
+            expression(expr, propagations);
+            propagations.push((expr, expr.position().merge(position)));
+        }
+        Postfix::PropertyAccess { expr, .. }
+        | Postfix::OptionalPropertyAccess { expr, .. }
+        | Postfix::Cast { expr, .. } => expression(expr, propagations),
+    }
+}
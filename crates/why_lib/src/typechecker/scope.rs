@@ -1,14 +1,18 @@
 use std::{cell::RefCell, collections::HashMap, fmt::Display, rc::Rc};
 
-use crate::parser::ast::Expression;
+use crate::{lexer::Span, parser::ast::Expression};
 
-use super::{error::TypeCheckError, types::Type, TypeInformation, TypedConstruct};
+use super::{
+    error::TypeCheckError, folding::ConstValue, types::Type, TypeInformation, TypedConstruct,
+};
 
 #[derive(Clone)]
 struct StoredVariable {
     value: Expression<TypeInformation>,
     type_id: Rc<RefCell<Option<Type>>>,
     mutable: bool,
+    /// Position of the statement declaring this variable (if any)
+    declaration: Option<Span>,
 }
 
 // TODO: this should probably store the location (i.e, span) for all variables, constants and types
@@ -22,6 +26,8 @@ pub struct Frame {
     types: HashMap<String, Type>,
     /// All constants available in this frame
     constants: HashMap<String, Type>,
+    /// Values of all constants in this frame, which could be evaluated at compile time
+    values: HashMap<String, ConstValue>,
 }
 
 impl std::fmt::Debug for Frame {
@@ -42,12 +48,15 @@ impl std::fmt::Debug for Frame {
                 &self.constants.iter().collect::<HashMap<_, _>>(),
             )
             .field("types", &self.types)
+            .field("values", &self.values)
             .finish()
     }
 }
 
 type StackFrame = Rc<RefCell<Frame>>;
 
+type VariableType = Rc<RefCell<Option<Type>>>;
+
 #[derive(Clone, Debug)]
 pub struct Scope {
     stacks: Vec<StackFrame>,
@@ -113,11 +122,60 @@ impl Display for MethodAddError {
 
 impl std::error::Error for MethodAddError {}
 
+/// A copy of everything a scope holds, which can be restored via [`Scope::restore`].
+#[derive(Debug)]
+pub struct ScopeSnapshot {
+    frames: Vec<(StackFrame, Frame)>,
+    /// Types of all variables at the time of the snapshot, since they are updated in place
+    variable_types: Vec<(VariableType, Option<Type>)>,
+    methods: HashMap<Type, HashMap<String, Type>>,
+}
+
 impl Scope {
     pub fn new() -> Scope {
         Self::default()
     }
 
+    /// Take a snapshot of this scope. Clones of this scope share their frames, so restoring the
+    /// snapshot also affects them.
+    pub fn snapshot(&self) -> ScopeSnapshot {
+        let frames = self
+            .stacks
+            .iter()
+            .map(|frame| (frame.clone(), frame.borrow().clone()))
+            .collect::<Vec<_>>();
+
+        let variable_types = frames
+            .iter()
+            .flat_map(|(_, frame)| frame.variables.values())
+            .map(|variable| (variable.type_id.clone(), variable.type_id.borrow().clone()))
+            .collect();
+
+        ScopeSnapshot {
+            frames,
+            variable_types,
+            methods: self.methods.borrow().clone(),
+        }
+    }
+
+    /// Reset this scope to the state of the given snapshot.
+    pub fn restore(&mut self, snapshot: ScopeSnapshot) {
+        self.stacks = snapshot
+            .frames
+            .into_iter()
+            .map(|(stack_frame, frame)| {
+                *stack_frame.borrow_mut() = frame;
+                stack_frame
+            })
+            .collect();
+
+        for (type_id, value) in snapshot.variable_types {
+            *type_id.borrow_mut() = value;
+        }
+
+        *self.methods.borrow_mut() = snapshot.methods;
+    }
+
     pub fn enter_scope(&mut self) {
         self.stacks.push(StackFrame::default())
     }
@@ -131,6 +189,27 @@ impl Scope {
         name: impl ToString,
         value: Expression<TypeInformation>,
         mutable: bool,
+    ) -> Result<(), VariableAddError> {
+        self.insert_variable(name, value, mutable, None)
+    }
+
+    /// Add a variable which is declared by a statement (e.g., a `let`) at the given position.
+    pub fn declare_variable(
+        &mut self,
+        name: impl ToString,
+        value: Expression<TypeInformation>,
+        mutable: bool,
+        declaration: Span,
+    ) -> Result<(), VariableAddError> {
+        self.insert_variable(name, value, mutable, Some(declaration))
+    }
+
+    fn insert_variable(
+        &mut self,
+        name: impl ToString,
+        value: Expression<TypeInformation>,
+        mutable: bool,
+        declaration: Option<Span>,
     ) -> Result<(), VariableAddError> {
         let name = name.to_string();
 
@@ -146,6 +225,7 @@ impl Scope {
                     value,
                     type_id,
                     mutable,
+                    declaration,
                 },
             )
         });
@@ -185,6 +265,56 @@ impl Scope {
             })
     }
 
+    /// Get the position of the statement which declared a variable.
+    pub fn get_variable_declaration(&self, name: impl ToString) -> Option<Span> {
+        let name = name.to_string();
+        self.stacks
+            .iter()
+            .rev()
+            .find(|scope| scope.borrow().variables.contains_key(&name))
+            .and_then(|scope| {
+                scope
+                    .borrow()
+                    .variables
+                    .get(&name)
+                    .and_then(|StoredVariable { declaration, .. }| declaration.clone())
+            })
+    }
+
+    /// Find the name of a variable or constant in scope which is spelled similarly to the given
+    /// name, i.e., which only differs in a few characters. Both names have to share at least one
+    /// character, so very short names (e.g., `x` and `y`) are never similar.
+    pub fn get_similar_name(&self, name: impl ToString) -> Option<String> {
+        let name = name.to_string();
+        let length = name.chars().count();
+        let max_distance = (length / 3).max(1);
+
+        let mut candidates = self
+            .stacks
+            .iter()
+            .flat_map(|scope| {
+                let scope = scope.borrow();
+                scope
+                    .variables
+                    .keys()
+                    .chain(scope.constants.keys())
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .filter(|candidate| *candidate != name)
+            .map(|candidate| (edit_distance(&name, &candidate), candidate))
+            .filter(|(distance, candidate)| {
+                *distance <= max_distance && *distance < length.min(candidate.chars().count())
+            })
+            .collect::<Vec<_>>();
+
+        candidates.sort();
+        candidates
+            .into_iter()
+            .next()
+            .map(|(_, candidate)| candidate)
+    }
+
     pub fn update_variable(
         &mut self,
         name: impl ToString,
@@ -273,6 +403,29 @@ impl Scope {
         Ok(())
     }
 
+    /// Remember the value of an already added constant (if it was evaluated at compile time).
+    pub fn set_constant_value(&mut self, name: impl ToString, value: ConstValue) {
+        let name = name.to_string();
+        if let Some(scope) = self
+            .stacks
+            .iter()
+            .rev()
+            .find(|scope| scope.borrow().constants.contains_key(&name))
+        {
+            scope.borrow_mut().values.insert(name, value);
+        }
+    }
+
+    /// Value of the constant with the given name, if it is known at compile time.
+    pub fn get_constant_value(&self, name: impl ToString) -> Option<ConstValue> {
+        let name = name.to_string();
+        self.stacks
+            .iter()
+            .rev()
+            .find(|scope| scope.borrow().constants.contains_key(&name))
+            .and_then(|scope| scope.borrow().values.get(&name).cloned())
+    }
+
     pub fn resolve_name(&mut self, name: impl ToString) -> Option<Rc<RefCell<Option<Type>>>> {
         let name = name.to_string();
         self.get_constant(&name)
@@ -338,14 +491,43 @@ impl Scope {
             }
         }
 
-        self.methods
-            .borrow()
+        let methods = self.methods.borrow();
+        let method = methods
             .get(&type_id)
             .and_then(|methods| methods.get(&property_name))
-            .cloned()
+            .cloned();
+
+        // fixed-size arrays share the methods of slices
+        match (method, type_id) {
+            (Some(method), _) => Some(method),
+            (None, Type::FixedArray(inner, _)) => methods
+                .get(&Type::Array(inner))
+                .and_then(|methods| methods.get(&property_name))
+                .cloned(),
+            (None, _) => None,
+        }
     }
 }
 
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
 #[cfg(test)]
 mod tests {
     use std::{cell::RefCell, rc::Rc};
@@ -371,7 +553,7 @@ mod tests {
         let expression = Expression::Id(Id {
             name: "foo".into(),
             info: TypeInformation {
-                type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                type_id: Rc::new(RefCell::new(Some(Type::I64))),
                 context: Context::default(),
             },
             position: Span::default(),
@@ -383,7 +565,7 @@ mod tests {
 
         assert_eq!(
             scope.get_variable("foo"),
-            Some(Rc::new(RefCell::new(Some(Type::Integer))))
+            Some(Rc::new(RefCell::new(Some(Type::I64))))
         );
     }
 
@@ -394,7 +576,7 @@ mod tests {
         let expression = Expression::Id(Id {
             name: "foo".into(),
             info: TypeInformation {
-                type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                type_id: Rc::new(RefCell::new(Some(Type::I64))),
                 context: Context::default(),
             },
             position: Span::default(),
@@ -423,7 +605,7 @@ mod tests {
         let expression = Expression::Id(Id {
             name: "foo".into(),
             info: TypeInformation {
-                type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                type_id: Rc::new(RefCell::new(Some(Type::I64))),
                 context: Context::default(),
             },
             position: Span::default(),
@@ -438,7 +620,7 @@ mod tests {
 
         assert_eq!(
             scope.get_variable("foo"),
-            Some(Rc::new(RefCell::new(Some(Type::Integer))))
+            Some(Rc::new(RefCell::new(Some(Type::I64))))
         );
 
         scope.exit_scope();
@@ -452,7 +634,7 @@ mod tests {
         let expression = Expression::Id(Id {
             name: "foo".into(),
             info: TypeInformation {
-                type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                type_id: Rc::new(RefCell::new(Some(Type::I64))),
                 context: Context::default(),
             },
             position: Span::default(),
@@ -472,4 +654,38 @@ mod tests {
         assert_eq!(foo, Rc::new(RefCell::new(None)));
         assert_eq!(bar, Rc::new(RefCell::new(None)));
     }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(super::edit_distance("foo", "foo"), 0);
+        assert_eq!(super::edit_distance("foo", "fo"), 1);
+        assert_eq!(super::edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn test_similar_name() {
+        let mut scope = Scope::new();
+
+        let expression = Expression::Id(Id {
+            name: "counter".into(),
+            info: TypeInformation {
+                type_id: Rc::new(RefCell::new(Some(Type::I64))),
+                context: Context::default(),
+            },
+            position: Span::default(),
+        });
+
+        scope
+            .add_variable("counter", expression.clone(), false)
+            .expect("something went wrong");
+        scope
+            .add_variable("f", expression, false)
+            .expect("something went wrong");
+
+        assert_eq!(scope.get_similar_name("countr"), Some("counter".into()));
+        assert_eq!(scope.get_similar_name("foo"), None);
+        // single characters have nothing in common with each other
+        assert_eq!(scope.get_similar_name("y"), None);
+        assert_eq!(scope.get_similar_name("fo"), None);
+    }
 }
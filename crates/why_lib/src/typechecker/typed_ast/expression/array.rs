@@ -4,10 +4,8 @@ use crate::typechecker::{TypeValidationError, TypedConstruct, ValidatedTypeInfor
 use crate::{
     parser::ast::Array,
     typechecker::{
-        context::Context,
-        error::{TypeCheckError, TypeMismatch},
-        types::Type,
-        TypeCheckable, TypeInformation, TypeResult,
+        context::Context, error::TypeCheckError, folding, types::Type, TypeCheckable,
+        TypeInformation, TypeResult,
     },
 };
 
@@ -26,6 +24,7 @@ impl TypeCheckable for Array<()> {
                     checked_values.push(value.check(ctx)?);
                 }
 
+                let length = checked_values.len();
                 let type_id = checked_values.first().map(|val| val.get_info().type_id);
 
                 if let Some(type_id) = &type_id {
@@ -33,15 +32,9 @@ impl TypeCheckable for Array<()> {
                     for value in checked_values.iter() {
                         let value_type = { value.get_info().type_id.borrow() }.clone();
                         if let (Some(type_id), Some(value_type)) = (&type_id, value_type) {
-                            if *type_id != value_type {
-                                return Err(TypeCheckError::TypeMismatch(
-                                    TypeMismatch {
-                                        expected: type_id.clone(),
-                                        actual: value_type,
-                                    },
-                                    value.position(),
-                                ));
-                            }
+                            ctx.unify(type_id, &value_type).map_err(|mismatch| {
+                                TypeCheckError::TypeMismatch(mismatch, value.position())
+                            })?;
                         }
                     }
                 }
@@ -58,7 +51,7 @@ impl TypeCheckable for Array<()> {
                                     type_id
                                         .borrow()
                                         .clone()
-                                        .map(|type_id| Type::Array(Box::new(type_id))),
+                                        .map(|type_id| Type::FixedArray(Box::new(type_id), length)),
                                 ))
                             },
                         ),
@@ -74,22 +67,26 @@ impl TypeCheckable for Array<()> {
                 ..
             } => {
                 let initial_value = initial_value.check(ctx)?;
-
-                // TODO: This should be an expression which evaluates to an integer
-                // FIXME: This currently allows for FloatingPoint lengths
                 let length = length.check(ctx)?;
 
+                let length_value = folding::array_length(&length, ctx)?;
+
                 let type_id = { initial_value.get_info().type_id.borrow() }.clone();
 
                 Ok(Array::Default {
                     initial_value: Box::new(initial_value),
-                    length,
+                    length: Box::new(length),
                     info: TypeInformation {
                         type_id: type_id.map_or(
                             // TODO: which one?
                             Rc::new(RefCell::new(None)),
                             // Rc::new(RefCell::new(Some(Type::Array(Box::new(Type::Unknown))))),
-                            |type_id| Rc::new(RefCell::new(Some(Type::Array(Box::new(type_id))))),
+                            |type_id| {
+                                Rc::new(RefCell::new(Some(Type::FixedArray(
+                                    Box::new(type_id),
+                                    length_value,
+                                ))))
+                            },
                         ),
                         context,
                     },
@@ -98,29 +95,6 @@ impl TypeCheckable for Array<()> {
             }
         }
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        match this {
-            Array::Literal {
-                values, position, ..
-            } => Array::Literal {
-                values: values.iter().map(TypeCheckable::revert).collect(),
-                info: (),
-                position: position.clone(),
-            },
-            Array::Default {
-                initial_value,
-                length,
-                position,
-                ..
-            } => Array::Default {
-                initial_value: Box::new(TypeCheckable::revert(initial_value.as_ref())),
-                length: TypeCheckable::revert(length),
-                info: (),
-                position: position.clone(),
-            },
-        }
-    }
 }
 
 impl TypedConstruct for Array<TypeInformation> {
@@ -135,7 +109,7 @@ impl TypedConstruct for Array<TypeInformation> {
                 position,
             } => Ok(Array::Default {
                 initial_value: Box::new(initial_value.validate()?),
-                length: length.validate()?,
+                length: Box::new(length.validate()?),
                 info: info.validate(&position)?,
                 position,
             }),
@@ -167,7 +141,7 @@ mod tests {
 
     use crate::{
         lexer::Span,
-        parser::ast::{Array, Expression, Num},
+        parser::ast::{Array, Expression, Num, Prefix},
         typechecker::{
             context::Context,
             error::{TypeCheckError, TypeMismatch},
@@ -204,10 +178,11 @@ mod tests {
         };
 
         let arr = arr.check(&mut ctx)?;
+        ctx.default_literals(0);
 
         assert_eq!(
-            arr.get_info().type_id,
-            Rc::new(RefCell::new(Some(Type::Array(Box::new(Type::Integer)))))
+            arr.get_info().resolved_type(),
+            Some(Type::FixedArray(Box::new(Type::I64), 1))
         );
         Ok(())
     }
@@ -226,12 +201,11 @@ mod tests {
         };
 
         let arr = arr.check(&mut ctx)?;
+        ctx.default_literals(0);
 
         assert_eq!(
-            arr.get_info().type_id,
-            Rc::new(RefCell::new(Some(Type::Array(Box::new(
-                Type::FloatingPoint
-            )))))
+            arr.get_info().resolved_type(),
+            Some(Type::FixedArray(Box::new(Type::F64), 2))
         );
         Ok(())
     }
@@ -255,8 +229,8 @@ mod tests {
             res,
             Err(TypeCheckError::TypeMismatch(
                 TypeMismatch {
-                    expected: Type::Integer,
-                    actual: Type::FloatingPoint
+                    expected: Type::I64,
+                    actual: Type::F64
                 },
                 Span::default()
             ))
@@ -270,18 +244,61 @@ mod tests {
 
         let arr = Array::Default {
             initial_value: Box::new(Expression::Num(Num::Integer(42, (), Span::default()))),
-            length: Num::Integer(10, (), Span::default()),
+            length: Box::new(Expression::Num(Num::Integer(10, (), Span::default()))),
             info: (),
             position: Span::default(),
         };
 
         let arr = arr.check(&mut ctx)?;
+        ctx.default_literals(0);
 
         assert_eq!(
-            arr.get_info().type_id,
-            Rc::new(RefCell::new(Some(Type::Array(Box::new(Type::Integer)))))
+            arr.get_info().resolved_type(),
+            Some(Type::FixedArray(Box::new(Type::I64), 10))
         );
 
         Ok(())
     }
+
+    #[test]
+    fn test_error_on_invalid_default_length() {
+        let mut ctx = Context::default();
+
+        let arr = Array::Default {
+            initial_value: Box::new(Expression::Num(Num::Integer(42, (), Span::default()))),
+            length: Box::new(Expression::Num(Num::FloatingPoint(
+                1.5,
+                (),
+                Span::default(),
+            ))),
+            info: (),
+            position: Span::default(),
+        };
+
+        assert_eq!(
+            arr.check(&mut ctx),
+            Err(TypeCheckError::TypeMismatch(
+                TypeMismatch {
+                    expected: Type::USIZE,
+                    actual: Type::F64
+                },
+                Span::default()
+            ))
+        );
+
+        let arr = Array::Default {
+            initial_value: Box::new(Expression::Num(Num::Integer(42, (), Span::default()))),
+            length: Box::new(Expression::Prefix(Prefix::Minus {
+                expr: Box::new(Expression::Num(Num::Integer(1, (), Span::default()))),
+                position: Span::default(),
+            })),
+            info: (),
+            position: Span::default(),
+        };
+
+        assert!(matches!(
+            arr.check(&mut ctx),
+            Err(TypeCheckError::IntegerOverflow(..))
+        ));
+    }
 }
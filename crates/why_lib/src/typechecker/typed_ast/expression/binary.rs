@@ -3,7 +3,7 @@ use std::{cell::RefCell, rc::Rc};
 use crate::typechecker::error::UnsupportedBinaryOperation;
 use crate::typechecker::{TypeValidationError, TypedConstruct, ValidatedTypeInformation};
 use crate::{
-    parser::ast::{BinaryExpression, BinaryOperator},
+    parser::ast::{BinaryExpression, BinaryOperator, Expression},
     typechecker::{
         context::Context, error::TypeCheckError, types::Type, TypeCheckable, TypeInformation,
         TypeResult,
@@ -27,26 +27,66 @@ impl TypeCheckable for BinaryExpression<()> {
         let left = left.check(ctx)?;
         let right = right.check(ctx)?;
 
-        let left_type = { left.get_info().type_id.borrow() }.clone();
-        let right_type = { right.get_info().type_id.borrow() }.clone();
+        if operator == BinaryOperator::Coalesce {
+            let type_id = coalesce(&left, &right, ctx)?;
+
+            return Ok(BinaryExpression {
+                left,
+                right,
+                operator,
+                info: TypeInformation {
+                    type_id: Rc::new(RefCell::new(type_id)),
+                    context,
+                },
+                position,
+            });
+        }
+
+        let left_type = left.get_info().resolved_type();
+        let right_type = right.get_info().resolved_type();
 
         let compount_type = if let (Some(left_type), Some(right_type)) = (left_type, right_type) {
-            if !left_type.does_eq(&right_type) {
+            // operands whose types are not inferred yet get the type of the other operand
+            let compatible = if left_type.has_variables() || right_type.has_variables() {
+                ctx.unify(&left_type, &right_type).is_ok()
+            } else {
+                left_type.does_eq(&right_type)
+            };
+
+            if !compatible {
                 return Err(TypeCheckError::UnsupportedBinaryOperation(
                     UnsupportedBinaryOperation {
-                        operands: (left_type, right_type),
+                        operands: (
+                            ctx.resolve_literals(&left_type, None),
+                            ctx.resolve_literals(&right_type, None),
+                        ),
                     },
                     position,
                 ));
             }
-            Some(left_type)
+            Some(ctx.resolve(&left_type))
         } else {
             None
         };
 
         if let Some(t) = &compount_type {
+            let comparison = !matches!(
+                operator,
+                BinaryOperator::Add
+                    | BinaryOperator::Substract
+                    | BinaryOperator::Multiply
+                    | BinaryOperator::Divide
+            );
+
             match t {
-                Type::Integer | Type::FloatingPoint | Type::Boolean => {}
+                Type::Integer(_) | Type::FloatingPoint(_) | Type::Boolean | Type::Variable(_) => {}
+                // strings can be concatenated and compared, characters only compared
+                Type::String if comparison || operator == BinaryOperator::Add => {}
+                Type::Character if comparison => {}
+                // optional values and results can only be compared for equality (e.g., with
+                // `none`)
+                Type::Optional(_) | Type::Result(..)
+                    if matches!(operator, BinaryOperator::Equals | BinaryOperator::NotEquals) => {}
                 _ => {
                     return Err(TypeCheckError::UnsupportedBinaryOperation(
                         UnsupportedBinaryOperation {
@@ -64,10 +104,12 @@ impl TypeCheckable for BinaryExpression<()> {
             | BinaryOperator::Multiply
             | BinaryOperator::Divide => compount_type,
             BinaryOperator::Equals
+            | BinaryOperator::NotEquals
             | BinaryOperator::GreaterThan
             | BinaryOperator::LessThan
             | BinaryOperator::GreaterOrEqual
             | BinaryOperator::LessOrEqual => Some(Type::Boolean),
+            BinaryOperator::Coalesce => unreachable!("coalescing is checked separately"),
         };
 
         Ok(BinaryExpression {
@@ -81,24 +123,44 @@ impl TypeCheckable for BinaryExpression<()> {
             position,
         })
     }
+}
 
-    fn revert(this: &Self::Typed) -> Self {
-        let BinaryExpression {
-            left,
-            right,
-            operator,
-            position,
-            ..
-        } = this;
+/// Type of `left ?? right`: `left` has to be optional and `right` a default of its inner type (or
+/// an optional value itself, in which case the result stays optional). If `left` is a result,
+/// `right` is the default in case of an error.
+fn coalesce(
+    left: &Expression<TypeInformation>,
+    right: &Expression<TypeInformation>,
+    ctx: &mut Context,
+) -> TypeResult<Option<Type>> {
+    let (Some(left_type), Some(right_type)) = (
+        left.get_info().resolved_type(),
+        right.get_info().resolved_type(),
+    ) else {
+        return Ok(None);
+    };
 
-        BinaryExpression {
-            left: TypeCheckable::revert(left),
-            right: TypeCheckable::revert(right),
-            operator: *operator,
-            info: (),
-            position: position.clone(),
-        }
+    if let Type::Result(value, _) = ctx.resolve(&left_type) {
+        ctx.unify(&value, &right_type)
+            .map_err(|mismatch| TypeCheckError::TypeMismatch(mismatch, right.position()))?;
+
+        return Ok(Some(ctx.resolve(&value)));
     }
+
+    let inner = ctx.fresh_variable();
+    let optional = Type::Optional(Box::new(inner.clone()));
+    ctx.unify(&optional, &left_type)
+        .map_err(|mismatch| TypeCheckError::TypeMismatch(mismatch, left.position()))?;
+
+    let type_id = match ctx.resolve(&right_type) {
+        Type::Optional(_) => optional,
+        _ => inner,
+    };
+
+    ctx.unify(&type_id, &right_type)
+        .map_err(|mismatch| TypeCheckError::TypeMismatch(mismatch, right.position()))?;
+
+    Ok(Some(ctx.resolve(&type_id)))
 }
 
 impl TypedConstruct for BinaryExpression<TypeInformation> {
@@ -131,10 +193,10 @@ mod tests {
 
     use crate::{
         lexer::Span,
-        parser::ast::{BinaryExpression, BinaryOperator, Expression, Num},
+        parser::ast::{AstString, BinaryExpression, BinaryOperator, Character, Expression, Num},
         typechecker::{
             context::Context,
-            error::{TypeCheckError, TypeMismatch, UnsupportedBinaryOperation},
+            error::{TypeCheckError, UnsupportedBinaryOperation},
             types::Type,
             TypeCheckable,
         },
@@ -152,8 +214,9 @@ mod tests {
         };
 
         let exp = exp.check(&mut ctx)?;
+        ctx.default_literals(0);
 
-        assert_eq!(exp.info.type_id, Rc::new(RefCell::new(Some(Type::Integer))));
+        assert_eq!(exp.info.resolved_type(), Some(Type::I64));
 
         Ok(())
     }
@@ -193,7 +256,94 @@ mod tests {
             res,
             Err(TypeCheckError::UnsupportedBinaryOperation(
                 UnsupportedBinaryOperation {
-                    operands: (Type::Integer, Type::FloatingPoint)
+                    operands: (Type::I64, Type::F64)
+                },
+                Span::default()
+            ))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_concatenation_and_comparison() -> Result<()> {
+        let string = |value: &str| {
+            Expression::AstString(AstString {
+                value: value.into(),
+                info: (),
+                position: Span::default(),
+            })
+        };
+
+        for (operator, type_id) in [
+            (BinaryOperator::Add, Type::String),
+            (BinaryOperator::Equals, Type::Boolean),
+            (BinaryOperator::LessThan, Type::Boolean),
+        ] {
+            let mut ctx = Context::default();
+            let exp = BinaryExpression {
+                left: string("foo"),
+                right: string("bar"),
+                operator,
+                info: (),
+                position: Span::default(),
+            };
+
+            let exp = exp.check(&mut ctx)?;
+
+            assert_eq!(exp.info.type_id, Rc::new(RefCell::new(Some(type_id))));
+        }
+
+        let mut ctx = Context::default();
+        let exp = BinaryExpression {
+            left: string("foo"),
+            right: string("bar"),
+            operator: BinaryOperator::Multiply,
+            info: (),
+            position: Span::default(),
+        };
+
+        assert!(exp.check(&mut ctx).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_character_comparison() -> Result<()> {
+        let character = |character| {
+            Expression::Character(Character {
+                character,
+                info: (),
+                position: Span::default(),
+            })
+        };
+
+        let mut ctx = Context::default();
+        let exp = BinaryExpression {
+            left: character('a'),
+            right: character('b'),
+            operator: BinaryOperator::GreaterOrEqual,
+            info: (),
+            position: Span::default(),
+        };
+
+        let exp = exp.check(&mut ctx)?;
+        assert_eq!(exp.info.type_id, Rc::new(RefCell::new(Some(Type::Boolean))));
+
+        let mut ctx = Context::default();
+        let exp = BinaryExpression {
+            left: character('a'),
+            right: character('b'),
+            operator: BinaryOperator::Add,
+            info: (),
+            position: Span::default(),
+        };
+
+        assert_eq!(
+            exp.check(&mut ctx),
+            Err(TypeCheckError::UnsupportedBinaryOperation(
+                UnsupportedBinaryOperation {
+                    operands: (Type::Character, Type::Character)
                 },
                 Span::default()
             ))
@@ -2,7 +2,7 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::typechecker::{TypeValidationError, TypedConstruct, ValidatedTypeInformation};
 use crate::{
-    parser::ast::Block,
+    parser::ast::{Block, Statement},
     typechecker::{context::Context, types::Type, TypeCheckable, TypeInformation, TypeResult},
 };
 
@@ -23,9 +23,11 @@ impl TypeCheckable for Block<()> {
             checked_statements.push(stmt.check(ctx)?);
         }
 
+        // a block ending with a comment does not yield a value either
         let type_id = checked_statements
             .last()
-            .map(|last| last.get_info().type_id)
+            .and_then(Statement::get_info)
+            .map(|info| info.type_id)
             .unwrap_or(Rc::new(RefCell::new(Some(Type::Void))));
 
         Ok(Block {
@@ -34,20 +36,6 @@ impl TypeCheckable for Block<()> {
             position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let Block {
-            statements,
-            position,
-            ..
-        } = this;
-
-        Block {
-            statements: statements.iter().map(TypeCheckable::revert).collect(),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for Block<TypeInformation> {
@@ -115,11 +103,9 @@ mod tests {
         };
 
         let block = block.check(&mut ctx)?;
+        ctx.default_literals(0);
 
-        assert_eq!(
-            block.info.type_id,
-            Rc::new(RefCell::new(Some(Type::Integer)))
-        );
+        assert_eq!(block.info.resolved_type(), Some(Type::I64));
 
         Ok(())
     }
@@ -26,20 +26,6 @@ impl TypeCheckable for Character<()> {
             },
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let Character {
-            character,
-            position,
-            ..
-        } = this;
-
-        Character {
-            character: *character,
-            position: position.clone(),
-            info: (),
-        }
-    }
 }
 
 impl TypedConstruct for Character<TypeInformation> {
@@ -2,12 +2,16 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::typechecker::{TypeValidationError, ValidatedTypeInformation};
 use crate::{
+    lexer::Span,
     parser::ast::{Expression, Function, FunctionParameter, Id, Statement},
     typechecker::{
         context::Context,
+        definite_assignment,
         error::{
-            RedefinedConstant, RedefinedFunction, TypeCheckError, TypeMismatch, UndefinedType,
+            InvalidTestSignature, MissingReturn, RedefinedConstant, RedefinedFunction,
+            TypeCheckError, UnknownAttribute,
         },
+        flow, folding, inference, propagation,
         types::Type,
         ShallowCheck, TypeCheckable, TypeInformation, TypeResult, TypedConstruct,
     },
@@ -20,7 +24,10 @@ impl TypeCheckable for Function<()> {
         // at start of function, enter scope
         ctx.scope.enter_scope();
 
+        let first_variable = ctx.variable_count();
+
         let Function {
+            attributes,
             id,
             parameters,
             return_type,
@@ -42,15 +49,7 @@ impl TypeCheckable for Function<()> {
             param_types.push(param_type);
         }
 
-        let Ok(return_type_id) = Type::try_from((&return_type, &*ctx)) else {
-            let position = return_type.position();
-            return Err(TypeCheckError::UndefinedType(
-                UndefinedType {
-                    type_name: return_type,
-                },
-                position,
-            ));
-        };
+        let return_type_id = Type::from_annotation(&return_type, ctx)?;
 
         let mut checked_statements = vec![];
 
@@ -58,24 +57,46 @@ impl TypeCheckable for Function<()> {
             checked_statements.push(stmt.check(ctx)?);
         }
 
+        definite_assignment::check(&checked_statements)?;
+
+        // returns which are not the last statement are checked here, the last statement is
+        // checked below (where the type of a return can also be inferred from the signature)
+        for expression in flow::returns(&checked_statements) {
+            let return_value_type = expression.get_info().type_id.borrow().clone();
+            let Some(return_value_type) = return_value_type else {
+                continue;
+            };
+
+            ctx.unify(&return_type_id, &return_value_type)
+                .map_err(|mismatch| {
+                    TypeCheckError::TypeMismatch(mismatch, expression.position())
+                })?;
+        }
+
+        // errors returned by `?` have to fit the return type as well
+        propagation::check(&checked_statements, &return_type_id, ctx)?;
+
+        let falls_through = flow::falls_through(&checked_statements);
+
         match checked_statements.last_mut() {
             Some(
                 last_stmt @ Statement::YieldingExpression(_) | last_stmt @ Statement::Return(_),
             ) => {
-                let last_stmt_type = last_stmt.get_info().type_id.clone();
+                let Some(TypeInformation {
+                    type_id: last_stmt_type,
+                    ..
+                }) = last_stmt.get_info()
+                else {
+                    unreachable!()
+                };
                 let inner = { last_stmt_type.borrow().clone() };
 
                 match inner {
                     Some(inner_type) => {
-                        if inner_type != return_type_id {
-                            return Err(TypeCheckError::TypeMismatch(
-                                TypeMismatch {
-                                    expected: return_type_id,
-                                    actual: inner_type.clone(),
-                                },
-                                last_stmt.position(),
-                            ));
-                        }
+                        ctx.unify(&return_type_id, &inner_type)
+                            .map_err(|mismatch| {
+                                TypeCheckError::TypeMismatch(mismatch, last_stmt.position())
+                            })?;
                     }
                     None if return_type_id == Type::Void => {}
                     None => {
@@ -84,17 +105,31 @@ impl TypeCheckable for Function<()> {
                 }
             }
             _ if return_type_id == Type::Void => {}
+            _ if !falls_through => {}
             _ => {
-                return Err(TypeCheckError::TypeMismatch(
-                    TypeMismatch {
-                        expected: return_type_id,
-                        actual: Type::Void,
+                // point at the closing brace of the function
+                let end_of_function = Span {
+                    start: position.end.saturating_sub(1),
+                    ..position.clone()
+                };
+
+                return Err(TypeCheckError::MissingReturn(
+                    MissingReturn {
+                        function_name: id.name,
+                        return_type: return_type_id,
+                        return_type_position: return_type.position(),
                     },
-                    return_type.position(),
+                    end_of_function,
                 ));
             }
         }
 
+        // literals whose type has not been inferred from their uses (including the returns) get
+        // their default type now
+        ctx.default_literals(first_variable);
+        inference::check_resolved(&checked_statements)?;
+        folding::check(&checked_statements)?;
+
         ctx.scope.exit_scope();
 
         let function_type_id = Type::Function {
@@ -116,6 +151,7 @@ impl TypeCheckable for Function<()> {
         };
 
         let func = Function {
+            attributes,
             id: id.clone(),
             parameters: checked_parameters,
             return_type,
@@ -126,26 +162,6 @@ impl TypeCheckable for Function<()> {
 
         Ok(func)
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let Function {
-            id,
-            parameters,
-            return_type,
-            statements,
-            position,
-            ..
-        } = this;
-
-        Function {
-            id: TypeCheckable::revert(id),
-            parameters: parameters.iter().map(TypeCheckable::revert).collect(),
-            return_type: return_type.to_owned(),
-            statements: statements.iter().map(TypeCheckable::revert).collect(),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl Function<()> {
@@ -161,26 +177,12 @@ impl Function<()> {
         let mut param_types = vec![];
 
         for FunctionParameter { type_name, .. } in parameters.iter() {
-            let Ok(param_type) = Type::try_from((type_name, ctx)) else {
-                return Err(TypeCheckError::UndefinedType(
-                    UndefinedType {
-                        type_name: type_name.clone(),
-                    },
-                    type_name.position(),
-                ));
-            };
+            let param_type = Type::from_annotation(type_name, ctx)?;
 
             param_types.push(param_type);
         }
 
-        let Ok(return_type) = Type::try_from((return_type, ctx)) else {
-            return Err(TypeCheckError::UndefinedType(
-                UndefinedType {
-                    type_name: return_type.clone(),
-                },
-                return_type.position(),
-            ));
-        };
+        let return_type = Type::from_annotation(return_type, ctx)?;
 
         Ok(Type::Function {
             params: param_types,
@@ -191,10 +193,46 @@ impl Function<()> {
 
 impl ShallowCheck for Function<()> {
     fn shallow_check(&self, ctx: &mut Context) -> TypeResult<()> {
-        let Function { id, position, .. } = self;
+        let Function {
+            attributes,
+            id,
+            position,
+            ..
+        } = self;
 
         let type_id = self.simple_shallow_check(&*ctx)?;
 
+        for attribute in attributes {
+            match attribute.name.as_str() {
+                "test" => {
+                    let Type::Function {
+                        params,
+                        return_value,
+                    } = &type_id
+                    else {
+                        unreachable!()
+                    };
+
+                    if !params.is_empty() || **return_value != Type::Void {
+                        return Err(TypeCheckError::InvalidTestSignature(
+                            InvalidTestSignature {
+                                function_name: id.name.clone(),
+                            },
+                            id.position.clone(),
+                        ));
+                    }
+                }
+                name => {
+                    return Err(TypeCheckError::UnknownAttribute(
+                        UnknownAttribute {
+                            attribute_name: name.to_string(),
+                        },
+                        attribute.position.clone(),
+                    ));
+                }
+            }
+        }
+
         if ctx.scope.add_constant(&id.name, type_id).is_err() {
             return Err(TypeCheckError::RedefinedFunction(
                 RedefinedFunction {
@@ -213,6 +251,7 @@ impl TypedConstruct for Function<TypeInformation> {
 
     fn validate(self) -> Result<Self::Validated, TypeValidationError> {
         let Function {
+            attributes,
             id,
             parameters,
             return_type,
@@ -232,6 +271,7 @@ impl TypedConstruct for Function<TypeInformation> {
         }
 
         Ok(Function {
+            attributes,
             id: id.validate()?,
             parameters: validated_parameters,
             return_type,
@@ -297,22 +337,6 @@ impl TypeCheckable for FunctionParameter<()> {
             position: param_position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let FunctionParameter {
-            name,
-            type_name,
-            position,
-            ..
-        } = this;
-
-        FunctionParameter {
-            name: TypeCheckable::revert(name),
-            type_name: type_name.to_owned(),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for FunctionParameter<TypeInformation> {
@@ -375,7 +399,7 @@ mod tests {
         assert_eq!(
             param.info,
             TypeInformation {
-                type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                type_id: Rc::new(RefCell::new(Some(Type::I64))),
                 context: Context::default(),
             }
         );
@@ -383,14 +407,14 @@ mod tests {
         assert_eq!(
             param.name.info,
             TypeInformation {
-                type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                type_id: Rc::new(RefCell::new(Some(Type::I64))),
                 context: Context::default(),
             }
         );
 
         assert_eq!(
             ctx.scope.resolve_name("foo"),
-            Some(Rc::new(RefCell::new(Some(Type::Integer))))
+            Some(Rc::new(RefCell::new(Some(Type::I64))))
         );
 
         Ok(())
@@ -401,6 +425,7 @@ mod tests {
         let mut ctx = Context::default();
 
         let func = Function {
+            attributes: vec![],
             id: Id {
                 name: "foo".into(),
                 info: (),
@@ -432,8 +457,8 @@ mod tests {
                 name: "foo".into(),
                 info: TypeInformation {
                     type_id: Rc::new(RefCell::new(Some(Type::Function {
-                        params: vec![Type::FloatingPoint],
-                        return_value: Box::new(Type::Integer)
+                        params: vec![Type::F64],
+                        return_value: Box::new(Type::I64)
                     }))),
                     context: Context::default(),
                 },
@@ -445,8 +470,8 @@ mod tests {
             func.info,
             TypeInformation {
                 type_id: Rc::new(RefCell::new(Some(Type::Function {
-                    params: vec![Type::FloatingPoint],
-                    return_value: Box::new(Type::Integer)
+                    params: vec![Type::F64],
+                    return_value: Box::new(Type::I64)
                 }))),
                 context: Context::default(),
             }
@@ -459,6 +484,7 @@ mod tests {
         let mut ctx = Context::default();
 
         let func = Function {
+            attributes: vec![],
             id: Id {
                 name: "foo".into(),
                 info: (),
@@ -489,8 +515,8 @@ mod tests {
         assert_eq!(
             type_id,
             Some(Rc::new(RefCell::new(Some(Type::Function {
-                params: vec![Type::FloatingPoint],
-                return_value: Box::new(Type::Integer)
+                params: vec![Type::F64],
+                return_value: Box::new(Type::I64)
             }))))
         );
         Ok(())
@@ -501,6 +527,7 @@ mod tests {
         let mut ctx = Context::default();
 
         let func = Function {
+            attributes: vec![],
             id: Id {
                 name: "foo".into(),
                 info: (),
@@ -522,7 +549,7 @@ mod tests {
             Err(TypeCheckError::TypeMismatch(
                 TypeMismatch {
                     expected: Type::Void,
-                    actual: Type::Integer
+                    actual: Type::I64
                 },
                 Span::default()
             ))
@@ -536,6 +563,7 @@ mod tests {
         let mut ctx = Context::default();
 
         let func = Function {
+            attributes: vec![],
             id: Id {
                 name: "foo".into(),
                 info: (),
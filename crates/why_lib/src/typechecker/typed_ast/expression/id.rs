@@ -1,10 +1,13 @@
+use std::{cell::RefCell, rc::Rc};
+
 use crate::typechecker::{TypeValidationError, ValidatedTypeInformation};
 use crate::{
     parser::ast::Id,
     typechecker::{
         context::Context,
         error::{TypeCheckError, UndefinedVariable},
-        TypeCheckable, TypeInformation, TypeResult, TypedConstruct,
+        types::Type,
+        TypeCheckable, TypeInformation, TypeResult, TypedConstruct, NONE,
     },
 };
 
@@ -15,8 +18,22 @@ impl TypeCheckable for Id<()> {
         let Id { name, position, .. } = self;
 
         let Some(type_id) = ctx.scope.resolve_name(&name) else {
+            if name == NONE {
+                return Ok(Id {
+                    name,
+                    info: TypeInformation {
+                        type_id: Rc::new(RefCell::new(Some(Type::Optional(Box::new(
+                            ctx.fresh_variable(),
+                        ))))),
+                        context: ctx.clone(),
+                    },
+                    position,
+                });
+            }
+
             return Err(TypeCheckError::UndefinedVariable(
                 UndefinedVariable {
+                    similar_name: ctx.scope.get_similar_name(&name),
                     variable_name: name,
                 },
                 position,
@@ -32,16 +49,6 @@ impl TypeCheckable for Id<()> {
             position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let Id { name, position, .. } = this;
-
-        Id {
-            name: name.to_owned(),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for Id<TypeInformation> {
@@ -86,7 +93,7 @@ mod tests {
                 Expression::Id(Id {
                     name: "foo".into(),
                     info: TypeInformation {
-                        type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                        type_id: Rc::new(RefCell::new(Some(Type::I64))),
                         context: Context::default(),
                     },
                     position: Span::default(),
@@ -117,7 +124,7 @@ mod tests {
                 Expression::Id(Id {
                     name: "foo".into(),
                     info: TypeInformation {
-                        type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                        type_id: Rc::new(RefCell::new(Some(Type::I64))),
                         context: Context::default(),
                     },
                     position: Span::default(),
@@ -137,7 +144,7 @@ mod tests {
         assert_eq!(
             id.info,
             TypeInformation {
-                type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                type_id: Rc::new(RefCell::new(Some(Type::I64))),
                 context: Context::default(),
             }
         );
@@ -161,7 +168,8 @@ mod tests {
             res,
             Err(TypeCheckError::UndefinedVariable(
                 UndefinedVariable {
-                    variable_name: "foo".into()
+                    variable_name: "foo".into(),
+                    similar_name: None,
                 },
                 Span::default()
             ))
@@ -173,7 +181,7 @@ mod tests {
     #[test]
     fn test_retrival_of_constant() -> Result<(), Box<dyn Error>> {
         let mut ctx = Context::default();
-        ctx.scope.add_constant("foo", Type::Integer)?;
+        ctx.scope.add_constant("foo", Type::I64)?;
 
         let id = Id {
             name: "foo".into(),
@@ -183,7 +191,7 @@ mod tests {
 
         let id = id.check(&mut ctx)?;
 
-        assert_eq!(id.info.type_id, Rc::new(RefCell::new(Some(Type::Integer))));
+        assert_eq!(id.info.type_id, Rc::new(RefCell::new(Some(Type::I64))));
 
         Ok(())
     }
@@ -2,12 +2,10 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::typechecker::{TypeValidationError, TypedConstruct, ValidatedTypeInformation};
 use crate::{
-    parser::ast::If,
+    parser::ast::{BinaryExpression, BinaryOperator, Expression, Id, If, Statement},
     typechecker::{
-        context::Context,
-        error::{TypeCheckError, TypeMismatch},
-        types::Type,
-        TypeCheckable, TypeInformation, TypeResult,
+        context::Context, error::TypeCheckError, types::Type, TypeCheckable, TypeInformation,
+        TypeResult, NONE,
     },
 };
 
@@ -27,50 +25,47 @@ impl TypeCheckable for If<()> {
 
         let condition = condition.check(ctx)?;
 
-        match &*condition.get_info().type_id.borrow() {
-            Some(Type::Boolean) => {}
-            Some(other) => {
-                return Err(TypeCheckError::TypeMismatch(
-                    TypeMismatch {
-                        expected: Type::Boolean,
-                        actual: other.clone(),
-                    },
-                    condition.position(),
-                ))
-            }
-            _ => {}
-        };
-
-        let mut checked_statements = vec![];
-
-        for statement in statements.into_iter() {
-            checked_statements.push(statement.check(ctx)?);
+        if let Some(condition_type) = { condition.get_info().type_id.borrow() }.clone() {
+            ctx.unify(&Type::Boolean, &condition_type)
+                .map_err(|mismatch| TypeCheckError::TypeMismatch(mismatch, condition.position()))?;
         }
 
-        let mut checked_else_statements = vec![];
+        let narrowing = narrowing(&condition, ctx);
 
-        for statement in else_statements.into_iter() {
-            checked_else_statements.push(statement.check(ctx)?);
-        }
+        let checked_statements = check_branch(
+            statements,
+            narrowing
+                .as_ref()
+                .filter(|narrowing| narrowing.in_then_branch),
+            ctx,
+        )?;
+
+        let checked_else_statements = check_branch(
+            else_statements,
+            narrowing
+                .as_ref()
+                .filter(|narrowing| !narrowing.in_then_branch),
+            ctx,
+        )?;
 
         let type_id = match (checked_statements.last(), checked_else_statements.last()) {
             (Some(first), Some(last)) => {
-                let first_type = { first.get_info().type_id.borrow().clone() };
-                let last_type = { last.get_info().type_id.borrow().clone() };
+                // branches ending with a comment do not yield a value
+                let branch_type =
+                    |statement: &Statement<TypeInformation>| match statement.get_info() {
+                        Some(info) => info.type_id.borrow().clone(),
+                        None => Some(Type::Void),
+                    };
+                let first_type = branch_type(first);
+                let last_type = branch_type(last);
 
                 // check, if types of if and else match
                 match (first_type, last_type) {
                     (Some(first_type), Some(last_type)) => {
                         // if they do not match, we have a fucky wucky
-                        if first_type != last_type {
-                            return Err(TypeCheckError::TypeMismatch(
-                                TypeMismatch {
-                                    expected: first_type,
-                                    actual: last_type,
-                                },
-                                last.position(),
-                            ));
-                        }
+                        ctx.unify(&first_type, &last_type).map_err(|mismatch| {
+                            TypeCheckError::TypeMismatch(mismatch, last.position())
+                        })?;
                         // otherwise (e.g., in case of both being None), we simply return the type
                         // of the if branch
                         Rc::new(RefCell::new(Some(first_type)))
@@ -90,24 +85,93 @@ impl TypeCheckable for If<()> {
             position,
         })
     }
+}
 
-    fn revert(this: &Self::Typed) -> Self {
-        let If {
-            condition,
-            statements,
-            else_statements,
-            position,
-            ..
-        } = this;
+/// A variable of an optional type which is known to hold a value within one branch of an `if`.
+struct Narrowing {
+    name: String,
+    inner: Type,
+    in_then_branch: bool,
+}
 
-        If {
-            condition: Box::new(TypeCheckable::revert(condition.as_ref())),
-            statements: statements.iter().map(TypeCheckable::revert).collect(),
-            else_statements: else_statements.iter().map(TypeCheckable::revert).collect(),
-            info: (),
-            position: position.clone(),
-        }
+/// Find the variable narrowed by a condition like `x != none` (or `x == none` for the else
+/// branch). Only immutable variables are narrowed, since a mutable variable might be set to
+/// `none` within the branch.
+fn narrowing(condition: &Expression<TypeInformation>, ctx: &mut Context) -> Option<Narrowing> {
+    let Expression::Binary(binary) = condition else {
+        return None;
+    };
+
+    let BinaryExpression {
+        left,
+        right,
+        operator,
+        ..
+    } = binary.as_ref();
+
+    let in_then_branch = match operator {
+        BinaryOperator::NotEquals => true,
+        BinaryOperator::Equals => false,
+        _ => return None,
+    };
+
+    let Id { name, info, .. } = match (left, right) {
+        (Expression::Id(id), Expression::Id(other)) if other.name == NONE => id,
+        (Expression::Id(other), Expression::Id(id)) if other.name == NONE => id,
+        _ => return None,
+    };
+
+    // `none` might be shadowed by a user defined name
+    if ctx.scope.resolve_name(NONE).is_some() {
+        return None;
+    }
+
+    if ctx.scope.is_variable_mutable(name) != Some(false) {
+        return None;
+    }
+
+    match info.resolved_type() {
+        Some(Type::Optional(inner)) => Some(Narrowing {
+            name: name.clone(),
+            inner: *inner,
+            in_then_branch,
+        }),
+        _ => None,
+    }
+}
+
+/// Check the statements of a branch, in which the narrowed variable (if any) has its inner type.
+fn check_branch(
+    statements: Vec<Statement<()>>,
+    narrowing: Option<&Narrowing>,
+    ctx: &mut Context,
+) -> TypeResult<Vec<Statement<TypeInformation>>> {
+    if let Some(Narrowing { name, inner, .. }) = narrowing {
+        ctx.scope.enter_scope();
+        let _ = ctx.scope.add_variable(
+            name,
+            Expression::Id(Id {
+                name: name.clone(),
+                info: TypeInformation {
+                    type_id: Rc::new(RefCell::new(Some(inner.clone()))),
+                    context: ctx.clone(),
+                },
+                position: Default::default(),
+            }),
+            false,
+        );
     }
+
+    let checked_statements = statements
+        .into_iter()
+        .map(|statement| statement.check(ctx))
+        .collect::<TypeResult<Vec<_>>>();
+
+    if narrowing.is_some() {
+        ctx.scope.exit_scope();
+    }
+
+    checked_statements
 }
 
 impl TypedConstruct for If<TypeInformation> {
@@ -202,7 +266,7 @@ mod tests {
             Expression::Id(Id {
                 name: "foo".into(),
                 info: TypeInformation {
-                    type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                    type_id: Rc::new(RefCell::new(Some(Type::I64))),
                     context: ctx.clone(),
                 },
                 position: Span::default(),
@@ -229,7 +293,7 @@ mod tests {
             Err(TypeCheckError::TypeMismatch(
                 TypeMismatch {
                     expected: Type::Boolean,
-                    actual: Type::Integer
+                    actual: Type::I64
                 },
                 Span::default()
             ))
@@ -258,7 +322,7 @@ mod tests {
             Expression::Id(Id {
                 name: "bar".into(),
                 info: TypeInformation {
-                    type_id: Rc::new(RefCell::new(Some(Type::FloatingPoint))),
+                    type_id: Rc::new(RefCell::new(Some(Type::F64))),
                     context: ctx.clone(),
                 },
                 position: Span::default(),
@@ -270,7 +334,7 @@ mod tests {
             Expression::Id(Id {
                 name: "baz".into(),
                 info: TypeInformation {
-                    type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                    type_id: Rc::new(RefCell::new(Some(Type::I64))),
                     context: ctx.clone(),
                 },
                 position: Span::default(),
@@ -304,8 +368,8 @@ mod tests {
             result,
             Err(TypeCheckError::TypeMismatch(
                 TypeMismatch {
-                    expected: Type::FloatingPoint,
-                    actual: Type::Integer
+                    expected: Type::F64,
+                    actual: Type::I64
                 },
                 Span::default()
             ))
@@ -334,7 +398,7 @@ mod tests {
             Expression::Id(Id {
                 name: "bar".into(),
                 info: TypeInformation {
-                    type_id: Rc::new(RefCell::new(Some(Type::FloatingPoint))),
+                    type_id: Rc::new(RefCell::new(Some(Type::F64))),
                     context: ctx.clone(),
                 },
                 position: Span::default(),
@@ -0,0 +1,167 @@
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    parser::ast::{Interpolation, InterpolationSegment},
+    typechecker::{
+        context::Context,
+        error::{AmbiguousType, InvalidInterpolation, TypeCheckError},
+        types::Type,
+        TypeCheckable, TypeInformation, TypeResult, TypeValidationError, TypedConstruct,
+        ValidatedTypeInformation,
+    },
+};
+
+impl TypeCheckable for Interpolation<()> {
+    type Typed = Interpolation<TypeInformation>;
+
+    fn check(self, ctx: &mut Context) -> TypeResult<Self::Typed> {
+        let context = ctx.clone();
+        let Interpolation {
+            segments, position, ..
+        } = self;
+
+        let mut checked_segments = vec![];
+
+        for segment in segments {
+            let expression = match segment {
+                InterpolationSegment::Text(text) => {
+                    checked_segments.push(InterpolationSegment::Text(text));
+                    continue;
+                }
+                InterpolationSegment::Expression(expression) => expression.check(ctx)?,
+            };
+
+            let type_id = { expression.get_info().type_id.borrow() }
+                .as_ref()
+                .map(|type_id| ctx.resolve_literals(type_id, None));
+
+            if let Some(type_id) = type_id {
+                match type_id {
+                    Type::Integer(_)
+                    | Type::FloatingPoint(_)
+                    | Type::Boolean
+                    | Type::Character
+                    | Type::String => {}
+                    type_id if type_id.has_variables() => {
+                        return Err(TypeCheckError::AmbiguousType(
+                            AmbiguousType { inferred: type_id },
+                            expression.position(),
+                        ));
+                    }
+                    type_id => {
+                        return Err(TypeCheckError::InvalidInterpolation(
+                            InvalidInterpolation { type_id },
+                            expression.position(),
+                        ));
+                    }
+                }
+            }
+
+            checked_segments.push(InterpolationSegment::Expression(expression));
+        }
+
+        Ok(Interpolation {
+            segments: checked_segments,
+            info: TypeInformation {
+                type_id: Rc::new(RefCell::new(Some(Type::String))),
+                context,
+            },
+            position,
+        })
+    }
+}
+
+impl TypedConstruct for Interpolation<TypeInformation> {
+    type Validated = Interpolation<ValidatedTypeInformation>;
+
+    fn validate(self) -> Result<Self::Validated, TypeValidationError> {
+        let Interpolation {
+            segments,
+            info,
+            position,
+        } = self;
+
+        let mut validated_segments = vec![];
+        for segment in segments {
+            validated_segments.push(match segment {
+                InterpolationSegment::Text(text) => InterpolationSegment::Text(text),
+                InterpolationSegment::Expression(expression) => {
+                    InterpolationSegment::Expression(expression.validate()?)
+                }
+            });
+        }
+
+        Ok(Interpolation {
+            segments: validated_segments,
+            info: info.validate(&position)?,
+            position,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::{
+        lexer::Span,
+        parser::ast::{Array, Expression, Interpolation, InterpolationSegment, Num},
+        typechecker::{
+            context::Context,
+            error::{InvalidInterpolation, TypeCheckError},
+            types::Type,
+            TypeCheckable,
+        },
+    };
+
+    #[test]
+    fn test_interpolation() -> Result<()> {
+        let mut ctx = Context::default();
+
+        let interpolation = Interpolation {
+            segments: vec![
+                InterpolationSegment::Text("answer: ".into()),
+                InterpolationSegment::Expression(Expression::Num(Num::Integer(
+                    42,
+                    (),
+                    Span::default(),
+                ))),
+            ],
+            info: (),
+            position: Span::default(),
+        };
+
+        let interpolation = interpolation.check(&mut ctx)?;
+
+        assert_eq!(interpolation.info.resolved_type(), Some(Type::String));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_on_invalid_interpolation() {
+        let mut ctx = Context::default();
+
+        let interpolation = Interpolation {
+            segments: vec![InterpolationSegment::Expression(Expression::Array(
+                Array::Literal {
+                    values: vec![Expression::Num(Num::Integer(42, (), Span::default()))],
+                    info: (),
+                    position: Span::default(),
+                },
+            ))],
+            info: (),
+            position: Span::default(),
+        };
+
+        assert_eq!(
+            interpolation.check(&mut ctx),
+            Err(TypeCheckError::InvalidInterpolation(
+                InvalidInterpolation {
+                    type_id: Type::FixedArray(Box::new(Type::I64), 1)
+                },
+                Span::default()
+            ))
+        );
+    }
+}
@@ -5,7 +5,8 @@ use crate::{
     parser::ast::{Expression, Id, Lambda, LambdaParameter},
     typechecker::{
         context::Context,
-        error::{RedefinedConstant, TypeCheckError, TypeMismatch},
+        error::{RedefinedConstant, TypeCheckError},
+        propagation,
         types::Type,
         TypeCheckable, TypeInformation, TypeResult, TypedConstruct,
     },
@@ -15,6 +16,19 @@ impl TypeCheckable for Lambda<()> {
     type Typed = Lambda<TypeInformation>;
 
     fn check(self, ctx: &mut Context) -> TypeResult<Self::Typed> {
+        self.check_expecting(ctx, None)
+    }
+}
+
+impl Lambda<()> {
+    /// Check this lambda, where the type it is used as might already be known (e.g., from an
+    /// annotation or the parameter of a called function). Otherwise, the types of the parameters
+    /// and the return value are inferred from the body and the uses of this lambda.
+    pub fn check_expecting(
+        self,
+        ctx: &mut Context,
+        expected: Option<&Type>,
+    ) -> TypeResult<Lambda<TypeInformation>> {
         let Lambda {
             parameters,
             expression,
@@ -27,147 +41,74 @@ impl TypeCheckable for Lambda<()> {
         ctx.scope.enter_scope();
 
         let mut checked_parameters = vec![];
+        let mut param_types = vec![];
 
         for param in parameters.into_iter() {
-            checked_parameters.push(param.check(ctx)?);
+            let param = param.check(ctx)?;
+            param_types.push(
+                { param.info.type_id.borrow() }
+                    .clone()
+                    .unwrap_or(Type::Unknown),
+            );
+            checked_parameters.push(param);
+        }
+
+        let return_value = ctx.fresh_variable();
+
+        let type_id = Type::Function {
+            params: param_types,
+            return_value: Box::new(return_value.clone()),
+        };
+
+        // the expected type has to be known before checking the body, since the body might
+        // access properties of the parameters
+        if let Some(expected) = expected {
+            ctx.unify(expected, &type_id)
+                .map_err(|mismatch| TypeCheckError::TypeMismatch(mismatch, position.clone()))?;
         }
 
         let checked_expression = expression.check(ctx)?;
 
+        if let Some(expression_type) = { checked_expression.get_info().type_id.borrow() }.clone() {
+            ctx.unify(&return_value, &expression_type)
+                .map_err(|mismatch| {
+                    TypeCheckError::TypeMismatch(mismatch, checked_expression.position())
+                })?;
+        }
+
+        propagation::check_lambda(&checked_expression, &return_value, ctx)?;
+
         ctx.scope.exit_scope();
 
         Ok(Lambda {
             parameters: checked_parameters,
             expression: Box::new(checked_expression),
             info: TypeInformation {
-                type_id: Rc::new(RefCell::new(None)),
+                type_id: Rc::new(RefCell::new(Some(type_id))),
                 context,
             },
             position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let Lambda {
-            parameters,
-            expression,
-            position,
-            ..
-        } = this;
-
-        Lambda {
-            parameters: parameters.iter().map(TypeCheckable::revert).collect(),
-            expression: Box::new(TypeCheckable::revert(expression.as_ref())),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for Lambda<TypeInformation> {
     type Validated = Lambda<ValidatedTypeInformation>;
 
     fn update_type(&mut self, type_id: Type) -> Result<(), TypeCheckError> {
-        let err = Err(TypeCheckError::TypeMismatch(
-            TypeMismatch {
-                expected: Type::Function {
-                    params: vec![Type::Unknown; self.parameters.len()],
-                    return_value: Box::new(Type::Unknown),
-                },
-                actual: type_id.clone(),
-            },
-            self.position.clone(),
-        ));
-
-        // check, if we have function
-        let Type::Function {
-            params,
-            return_value,
-        } = type_id.clone()
-        else {
-            return err;
-        };
-
-        if let Some(current_type) = self.info.type_id.borrow().as_ref() {
-            if *current_type == type_id {
-                return Ok(());
+        let current_type = { self.info.type_id.borrow() }.clone();
+
+        match current_type {
+            Some(current_type) => self
+                .info
+                .context
+                .unify(&type_id, &current_type)
+                .map_err(|mismatch| TypeCheckError::TypeMismatch(mismatch, self.position.clone())),
+            None => {
+                *self.info.type_id.borrow_mut() = Some(type_id);
+                Ok(())
             }
-
-            // TODO: maybe use different error for this
-            return Err(TypeCheckError::TypeMismatch(
-                TypeMismatch {
-                    expected: current_type.clone(),
-                    actual: type_id,
-                },
-                self.position.clone(),
-            ));
-        }
-
-        // check for correct arity
-        if params.len() != self.parameters.len() {
-            return err;
         }
-
-        // clone context to mess nothing up
-        let mut ctx = self.info.context.clone();
-
-        ctx.scope.enter_scope();
-
-        // enter all parameters with their respective types into the scope
-        for (i, t) in params.iter().enumerate() {
-            let name = &self.parameters[i].name.name;
-            let position = &self.parameters[i].name.position;
-
-            if ctx
-                .scope
-                .add_variable(
-                    name,
-                    Expression::Id(Id {
-                        name: name.clone(),
-                        info: TypeInformation {
-                            type_id: Rc::new(RefCell::new(Some(t.clone()))),
-                            context: ctx.clone(),
-                        },
-                        position: position.clone(),
-                    }),
-                    false,
-                )
-                .is_err()
-            {
-                return Err(TypeCheckError::RedefinedConstant(
-                    RedefinedConstant {
-                        constant_name: name.to_string(),
-                    },
-                    position.clone(),
-                ));
-            }
-        }
-
-        // check (the reverted) expression
-        let expr =
-            <Expression<()> as TypeCheckable>::revert(self.expression.as_ref()).check(&mut ctx)?;
-
-        // check, if return types match
-        if let Some(expr_type) = expr.get_info().type_id.borrow_mut().as_ref() {
-            if *expr_type != *return_value {
-                return Err(TypeCheckError::TypeMismatch(
-                    TypeMismatch {
-                        expected: expr_type.clone(),
-                        actual: *return_value.clone(),
-                    },
-                    expr.position(),
-                ));
-            }
-        }
-
-        // update types of parameters accordingly
-        for (i, t) in params.iter().enumerate() {
-            self.parameters[i].update_type(t.to_owned())?;
-        }
-
-        self.info.type_id = Rc::new(RefCell::new(Some(type_id)));
-
-        Ok(())
     }
 
     fn validate(self) -> Result<Self::Validated, TypeValidationError> {
@@ -208,7 +149,7 @@ impl TypeCheckable for LambdaParameter<()> {
             ..
         } = name;
 
-        let type_id = Rc::new(RefCell::new(None));
+        let type_id = Rc::new(RefCell::new(Some(ctx.fresh_variable())));
 
         let id = Id {
             name,
@@ -241,16 +182,6 @@ impl TypeCheckable for LambdaParameter<()> {
             position: param_position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let LambdaParameter { name, position, .. } = this;
-
-        LambdaParameter {
-            name: TypeCheckable::revert(name),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for LambdaParameter<TypeInformation> {
@@ -285,7 +216,10 @@ mod tests {
 
     use crate::{
         lexer::Span,
-        parser::ast::{Expression, Id, Initialisation, Lambda, LambdaParameter, Num, TypeName},
+        parser::ast::{
+            BinaryExpression, BinaryOperator, Expression, Id, Initialisation, Lambda,
+            LambdaParameter, Num, TypeName,
+        },
         typechecker::{context::Context, types::Type, TypeCheckable, TypeInformation},
     };
 
@@ -311,13 +245,13 @@ mod tests {
                 name: Id {
                     name: "foo".into(),
                     info: TypeInformation {
-                        type_id: Rc::new(RefCell::new(None)),
+                        type_id: Rc::new(RefCell::new(Some(Type::Variable(0)))),
                         context: Context::default(),
                     },
                     position: Span::default(),
                 },
                 info: TypeInformation {
-                    type_id: Rc::new(RefCell::new(None)),
+                    type_id: Rc::new(RefCell::new(Some(Type::Variable(0)))),
                     context: Context::default(),
                 },
                 position: Span::default(),
@@ -345,7 +279,7 @@ mod tests {
 
         assert_eq!(
             ctx.scope.resolve_name("foo"),
-            Some(Rc::new(RefCell::new(None)))
+            Some(Rc::new(RefCell::new(Some(Type::Variable(0)))))
         );
 
         Ok(())
@@ -371,19 +305,75 @@ mod tests {
                 expression: Box::new(Expression::Num(Num::Integer(
                     42,
                     TypeInformation {
-                        type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                        type_id: Rc::new(RefCell::new(Some(Type::Variable(1)))),
                         context: Context::default(),
                     },
                     Span::default()
                 ))),
                 info: TypeInformation {
-                    type_id: Rc::new(RefCell::new(None)),
+                    type_id: Rc::new(RefCell::new(Some(Type::Function {
+                        params: vec![],
+                        return_value: Box::new(Type::Variable(0)),
+                    }))),
                     context: Context::default(),
                 },
                 position: Span::default(),
             }
         );
 
+        ctx.default_literals(0);
+        assert_eq!(
+            lambda.info.resolved_type(),
+            Some(Type::Function {
+                params: vec![],
+                return_value: Box::new(Type::I64),
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parameter_inference_from_body() -> Result<(), Box<dyn Error>> {
+        let mut ctx = Context::default();
+
+        let lambda = Lambda {
+            parameters: vec![LambdaParameter {
+                name: Id {
+                    name: "x".into(),
+                    info: (),
+                    position: Span::default(),
+                },
+                info: (),
+                position: Span::default(),
+            }],
+            expression: Box::new(Expression::Binary(Box::new(BinaryExpression {
+                left: Expression::Id(Id {
+                    name: "x".into(),
+                    info: (),
+                    position: Span::default(),
+                }),
+                right: Expression::Num(Num::FloatingPoint(1.5, (), Span::default())),
+                operator: BinaryOperator::Add,
+                info: (),
+                position: Span::default(),
+            }))),
+            info: (),
+            position: Span::default(),
+        };
+
+        let lambda = lambda.check(&mut ctx)?;
+        ctx.default_literals(0);
+
+        assert_eq!(
+            lambda.info.resolved_type(),
+            Some(Type::Function {
+                params: vec![Type::F64],
+                return_value: Box::new(Type::F64),
+            })
+        );
+        assert_eq!(lambda.parameters[0].info.resolved_type(), Some(Type::F64));
+
         Ok(())
     }
 
@@ -403,7 +393,7 @@ mod tests {
                 return_type: Box::new(TypeName::Literal("i64".into(), Span::default())),
                 position: Span::default(),
             }),
-            value: Expression::Lambda(Lambda {
+            value: Some(Expression::Lambda(Lambda {
                 parameters: vec![LambdaParameter {
                     name: Id {
                         name: "x".into(),
@@ -420,7 +410,7 @@ mod tests {
                 })),
                 info: (),
                 position: Span::default(),
-            }),
+            })),
             info: (),
             position: Span::default(),
         };
@@ -430,8 +420,8 @@ mod tests {
         ctx.scope.update_variable(
             "foo",
             Type::Function {
-                params: vec![Type::Integer],
-                return_value: Box::new(Type::Integer),
+                params: vec![Type::I64],
+                return_value: Box::new(Type::I64),
             },
         )?;
 
@@ -440,8 +430,8 @@ mod tests {
             .update_variable(
                 "foo",
                 Type::Function {
-                    params: vec![Type::FloatingPoint],
-                    return_value: Box::new(Type::Integer),
+                    params: vec![Type::F64],
+                    return_value: Box::new(Type::I64),
                 },
             )
             .is_err());
@@ -5,6 +5,7 @@ mod character;
 mod function;
 mod id;
 mod if_expression;
+mod interpolation;
 mod lambda;
 mod num;
 mod postfix;
@@ -42,31 +43,9 @@ impl TypeCheckable for Expression<()> {
             Expression::StructInitialisation(init) => {
                 Ok(Expression::StructInitialisation(init.check(ctx)?))
             }
-        }
-    }
-
-    fn revert(this: &Self::Typed) -> Self {
-        match this {
-            Expression::Id(id) => Expression::Id(TypeCheckable::revert(id)),
-            Expression::Num(num) => Expression::Num(TypeCheckable::revert(num)),
-            Expression::Character(character) => {
-                Expression::Character(TypeCheckable::revert(character))
+            Expression::Interpolation(interpolation) => {
+                Ok(Expression::Interpolation(interpolation.check(ctx)?))
             }
-            Expression::AstString(string) => Expression::AstString(TypeCheckable::revert(string)),
-            Expression::Function(func) => Expression::Function(TypeCheckable::revert(func)),
-            Expression::Lambda(lambda) => Expression::Lambda(TypeCheckable::revert(lambda)),
-            Expression::If(if_exp) => Expression::If(TypeCheckable::revert(if_exp)),
-            Expression::Block(block) => Expression::Block(TypeCheckable::revert(block)),
-            Expression::Parens(exp) => {
-                Expression::Parens(Box::new(TypeCheckable::revert(exp.as_ref())))
-            }
-            Expression::Postfix(post) => Expression::Postfix(TypeCheckable::revert(post)),
-            Expression::Prefix(pref) => Expression::Prefix(TypeCheckable::revert(pref)),
-            Expression::Binary(bin) => {
-                Expression::Binary(Box::new(TypeCheckable::revert(bin.as_ref())))
-            }
-            Expression::Array(arr) => Expression::Array(TypeCheckable::revert(arr)),
-            Expression::StructInitialisation(_) => todo!(),
         }
     }
 }
@@ -90,6 +69,7 @@ impl TypedConstruct for Expression<TypeInformation> {
             Expression::Binary(_) => unreachable!(),
             Expression::Array(_) => unreachable!(),
             Expression::StructInitialisation(_) => unreachable!(),
+            Expression::Interpolation(_) => unreachable!(),
         }
     }
 
@@ -111,6 +91,9 @@ impl TypedConstruct for Expression<TypeInformation> {
             Expression::StructInitialisation(struct_initialisation) => Ok(
                 Expression::StructInitialisation(struct_initialisation.validate()?),
             ),
+            Expression::Interpolation(interpolation) => {
+                Ok(Expression::Interpolation(interpolation.validate()?))
+            }
         }
     }
 }
@@ -4,19 +4,22 @@ use crate::typechecker::{TypeValidationError, ValidatedTypeInformation};
 use crate::{
     parser::ast::Num,
     typechecker::{
-        context::Context, types::Type, TypeCheckable, TypeInformation, TypeResult, TypedConstruct,
+        context::Context, inference::VariableKind, TypeCheckable, TypeInformation, TypeResult,
+        TypedConstruct,
     },
 };
 
 impl TypeCheckable for Num<()> {
     type Typed = Num<TypeInformation>;
 
+    /// The exact type of a number literal depends on its context, e.g., `let x: u8 = 42;`. If it
+    /// is not constrained at all, it becomes an `i64` or `f64`.
     fn check(self, ctx: &mut Context) -> TypeResult<Self::Typed> {
         match self {
             Num::Integer(val, _, position) => Ok(Num::Integer(
                 val,
                 TypeInformation {
-                    type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                    type_id: Rc::new(RefCell::new(Some(ctx.fresh_literal(VariableKind::Integer)))),
                     context: ctx.clone(),
                 },
                 position,
@@ -24,20 +27,15 @@ impl TypeCheckable for Num<()> {
             Num::FloatingPoint(val, _, position) => Ok(Num::FloatingPoint(
                 val,
                 TypeInformation {
-                    type_id: Rc::new(RefCell::new(Some(Type::FloatingPoint))),
+                    type_id: Rc::new(RefCell::new(Some(
+                        ctx.fresh_literal(VariableKind::FloatingPoint),
+                    ))),
                     context: ctx.clone(),
                 },
                 position,
             )),
         }
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        match this {
-            Num::Integer(val, _, pos) => Num::Integer(*val, (), pos.clone()),
-            Num::FloatingPoint(val, _, pos) => Num::FloatingPoint(*val, (), pos.clone()),
-        }
-    }
 }
 
 impl TypedConstruct for Num<TypeInformation> {
@@ -57,39 +55,62 @@ impl TypedConstruct for Num<TypeInformation> {
 
 #[cfg(test)]
 mod tests {
-    use std::{cell::RefCell, error::Error, rc::Rc};
+    use std::error::Error;
 
     use crate::{
         lexer::Span,
         parser::ast::Num,
-        typechecker::{context::Context, types::Type, TypeCheckable},
+        typechecker::{
+            context::Context,
+            types::{IntegerType, Type},
+            TypeCheckable,
+        },
     };
 
     #[test]
     fn test_check_integer() -> Result<(), Box<dyn Error>> {
-        let Num::Integer(num, info, ..) =
-            Num::Integer(42, (), Span::default()).check(&mut Context::default())?
-        else {
+        let ctx = &mut Context::default();
+        let Num::Integer(num, info, ..) = Num::Integer(42, (), Span::default()).check(ctx)? else {
             unreachable!()
         };
 
         assert_eq!(num, 42);
-        assert_eq!(info.type_id, Rc::new(RefCell::new(Some(Type::Integer))));
+        assert_eq!(info.resolved_type(), Some(Type::Variable(0)));
+
+        ctx.default_literals(0);
+        assert_eq!(info.resolved_type(), Some(Type::I64));
         Ok(())
     }
 
     #[test]
     fn test_check_floatingpoint() -> Result<(), Box<dyn Error>> {
+        let ctx = &mut Context::default();
         let Num::FloatingPoint(num, info, ..) =
-            Num::FloatingPoint(42.0, (), Span::default()).check(&mut Context::default())?
+            Num::FloatingPoint(42.0, (), Span::default()).check(ctx)?
         else {
             unreachable!()
         };
 
         assert_eq!(num, 42.0);
+
+        ctx.default_literals(0);
+        assert_eq!(info.resolved_type(), Some(Type::F64));
+        Ok(())
+    }
+
+    #[test]
+    fn test_literal_type_from_context() -> Result<(), Box<dyn Error>> {
+        let ctx = &mut Context::default();
+        let num = Num::Integer(42, (), Span::default()).check(ctx)?;
+        let type_id = num.get_info().resolved_type().unwrap();
+
+        assert!(ctx.unify(&Type::F64, &type_id).is_err());
+        assert!(ctx.unify(&Type::Integer(IntegerType::U8), &type_id).is_ok());
+
+        ctx.default_literals(0);
         assert_eq!(
-            info.type_id,
-            Rc::new(RefCell::new(Some(Type::FloatingPoint)))
+            num.get_info().resolved_type(),
+            Some(Type::Integer(IntegerType::U8))
         );
         Ok(())
     }
@@ -2,12 +2,16 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::typechecker::{TypeValidationError, ValidatedTypeInformation};
 use crate::{
-    parser::ast::{Id, Postfix},
+    parser::ast::{Expression, Id, Num, Postfix},
     typechecker::{
+        bounds_check,
         context::Context,
-        error::{TypeCheckError, TypeMismatch, UndefinedVariable},
+        error::{
+            AmbiguousType, IndexOutOfBounds, InvalidCast, NonResultPropagation, PropertyOfOptional,
+            TypeCheckError, TypeMismatch, UndefinedVariable,
+        },
         types::Type,
-        TypeCheckable, TypeInformation, TypeResult, TypedConstruct,
+        BoundsCheck, Intrinsic, TypeCheckable, TypeInformation, TypeResult, TypedConstruct,
     },
 };
 
@@ -23,13 +27,40 @@ impl TypeCheckable for Postfix<()> {
                 position,
                 ..
             } => {
-                let expr = expr.check(ctx)?;
+                // intrinsics can be shadowed by user defined names
+                let intrinsic = match expr.as_ref() {
+                    Expression::Id(Id { name, .. }) if ctx.scope.resolve_name(name).is_none() => {
+                        Intrinsic::from_name(name)
+                    }
+                    _ => None,
+                };
 
-                let expr_type_id = { expr.get_info().type_id.borrow() }.clone();
+                let expr = match (*expr, intrinsic) {
+                    (Expression::Id(Id { name, position, .. }), Some(_)) => Expression::Id(Id {
+                        name,
+                        info: TypeInformation {
+                            type_id: Rc::new(RefCell::new(None)),
+                            context: context.clone(),
+                        },
+                        position,
+                    }),
+                    (expr, _) => expr.check(ctx)?,
+                };
+
+                // lambdas passed to a function get the types of its parameters
+                let param_types = match expr.get_info().resolved_type() {
+                    Some(Type::Function { params, .. }) if params.len() == args.len() => params,
+                    _ => vec![],
+                };
 
                 let mut checked_args = vec![];
-                for arg in args.into_iter() {
-                    checked_args.push(arg.check(ctx)?);
+                for (i, arg) in args.into_iter().enumerate() {
+                    checked_args.push(match arg {
+                        Expression::Lambda(lambda) => {
+                            Expression::Lambda(lambda.check_expecting(ctx, param_types.get(i))?)
+                        }
+                        arg => arg.check(ctx)?,
+                    });
                 }
 
                 let arg_types = checked_args
@@ -41,11 +72,38 @@ impl TypeCheckable for Postfix<()> {
                     })
                     .collect::<Vec<_>>();
 
+                if let Some(intrinsic) = intrinsic {
+                    *expr.get_info().type_id.borrow_mut() =
+                        Some(intrinsic.signature(&arg_types, ctx));
+                }
+
+                let mut expr_type_id = expr.get_info().resolved_type();
+
                 let expected_type = Type::Function {
                     params: arg_types.clone(),
                     return_value: Box::new(Type::Unknown),
                 };
 
+                // calling a value whose type is not inferred yet makes it a function
+                if let Some(variable @ Type::Variable(_)) = &expr_type_id {
+                    let function = Type::Function {
+                        params: arg_types
+                            .iter()
+                            .map(|arg_type| match arg_type {
+                                Type::Unknown => ctx.fresh_variable(),
+                                arg_type => arg_type.clone(),
+                            })
+                            .collect(),
+                        return_value: Box::new(ctx.fresh_variable()),
+                    };
+
+                    ctx.unify(&function, variable).map_err(|mismatch| {
+                        TypeCheckError::TypeMismatch(mismatch, position.clone())
+                    })?;
+
+                    expr_type_id = Some(function);
+                }
+
                 let type_id = match &expr_type_id {
                     Some(
                         expr_type_id @ Type::Function {
@@ -69,15 +127,12 @@ impl TypeCheckable for Postfix<()> {
                             let expected = params[i].clone();
                             let actual = arg_types[i].clone();
 
-                            if actual != expected {
-                                if actual == Type::Unknown {
-                                    arg.update_type(expected)?;
-                                } else {
-                                    return Err(TypeCheckError::TypeMismatch(
-                                        TypeMismatch { expected, actual },
-                                        arg.position(),
-                                    ));
-                                }
+                            if actual == Type::Unknown {
+                                arg.update_type(expected)?;
+                            } else {
+                                ctx.unify(&expected, &actual).map_err(|mismatch| {
+                                    TypeCheckError::TypeMismatch(mismatch, arg.position())
+                                })?;
                             }
                         }
 
@@ -111,17 +166,51 @@ impl TypeCheckable for Postfix<()> {
                 let expr = expr.check(ctx)?;
                 let index = index.check(ctx)?;
 
-                let expr_type = { expr.get_info().type_id.borrow() }.clone();
-                let index_type = { index.get_info().type_id.borrow() }.clone();
+                let mut expr_type = expr.get_info().resolved_type();
+                let mut index_type = index.get_info().resolved_type();
+
+                // values whose type is not inferred yet are used as arrays of some type...
+                if let Some(variable @ Type::Variable(_)) = &expr_type {
+                    let array = Type::Array(Box::new(ctx.fresh_variable()));
+                    ctx.unify(&array, variable).map_err(|mismatch| {
+                        TypeCheckError::TypeMismatch(mismatch, expr.position())
+                    })?;
+                    expr_type = Some(array);
+                }
+
+                // ...and as indices (e.g., integer literals) when used as an index
+                if let Some(variable @ Type::Variable(_)) = &index_type {
+                    ctx.unify(&Type::USIZE, variable).map_err(|mismatch| {
+                        TypeCheckError::TypeMismatch(mismatch, index.position())
+                    })?;
+                    index_type = Some(Type::USIZE);
+                }
+
+                // constant indices into fixed-size arrays are checked right away
+                if let Some(array_type) = &expr_type {
+                    if let BoundsCheck::OutOfBounds {
+                        index: value,
+                        length,
+                    } = bounds_check(array_type, &index)
+                    {
+                        return Err(TypeCheckError::IndexOutOfBounds(
+                            IndexOutOfBounds {
+                                index: value,
+                                length,
+                            },
+                            index.position(),
+                        ));
+                    }
+                }
 
-                // check, if expr is callable and if index is an integer
+                // check, if expr is callable and if index is a usize
                 let type_id = match (expr_type, index_type) {
                     // all good
-                    (Some(Type::Array(inner)), Some(Type::Integer)) => {
+                    (Some(Type::Array(inner) | Type::FixedArray(inner, _)), Some(Type::USIZE)) => {
                         Rc::new(RefCell::new(Some(*inner)))
                     }
                     // Nope - not callable
-                    (Some(expr_type), Some(Type::Integer)) => {
+                    (Some(expr_type), Some(Type::USIZE)) => {
                         return Err(TypeCheckError::TypeMismatch(
                             TypeMismatch {
                                 expected: Type::Array(Box::new(Type::Unknown)),
@@ -130,11 +219,11 @@ impl TypeCheckable for Postfix<()> {
                             expr.position(),
                         ));
                     }
-                    // Not index with an integer
+                    // Not index with a usize
                     (Some(_), Some(index_type)) => {
                         return Err(TypeCheckError::TypeMismatch(
                             TypeMismatch {
-                                expected: Type::Integer,
+                                expected: Type::USIZE,
                                 actual: index_type,
                             },
                             index.position(),
@@ -165,16 +254,40 @@ impl TypeCheckable for Postfix<()> {
                     ..
                 } = property;
 
-                let expr_type = { expr.get_info().type_id.borrow() }.clone();
+                // methods can be called on literals, e.g., `42.double()`
+                let expr_type = { expr.get_info().type_id.borrow() }
+                    .as_ref()
+                    .map(|type_id| ctx.resolve_literals(type_id, None));
 
                 let type_id = match expr_type {
+                    // properties can not be used to infer a type, since several structs might
+                    // have a property with this name
+                    Some(type_id) if type_id.has_variables() => {
+                        return Err(TypeCheckError::AmbiguousType(
+                            AmbiguousType { inferred: type_id },
+                            expr.position(),
+                        ));
+                    }
                     Some(type_id) => {
-                        match ctx.scope.resolve_property_for_type(type_id, &property_name) {
+                        match ctx
+                            .scope
+                            .resolve_property_for_type(type_id.clone(), &property_name)
+                        {
                             Some(type_id) => Some(type_id),
+                            None if matches!(type_id, Type::Optional(_)) => {
+                                return Err(TypeCheckError::PropertyOfOptional(
+                                    PropertyOfOptional {
+                                        property_name,
+                                        type_id,
+                                    },
+                                    expr.position(),
+                                ));
+                            }
                             None => {
                                 return Err(TypeCheckError::UndefinedVariable(
                                     UndefinedVariable {
                                         variable_name: property_name.clone(),
+                                        similar_name: None,
                                     },
                                     property_position,
                                 ));
@@ -200,44 +313,150 @@ impl TypeCheckable for Postfix<()> {
                     position,
                 })
             }
-        }
-    }
-
-    fn revert(this: &Self::Typed) -> Self {
-        match this {
-            Postfix::Call {
-                expr,
-                args,
-                position,
-                ..
-            } => Postfix::Call {
-                expr: Box::new(TypeCheckable::revert(expr.as_ref())),
-                args: args.iter().map(TypeCheckable::revert).collect(),
-                info: (),
-                position: position.clone(),
-            },
-            Postfix::Index {
+            Postfix::OptionalPropertyAccess {
                 expr,
-                index,
+                property,
                 position,
                 ..
-            } => Postfix::Index {
-                expr: Box::new(TypeCheckable::revert(expr.as_ref())),
-                index: Box::new(TypeCheckable::revert(index.as_ref())),
-                info: (),
-                position: position.clone(),
-            },
-            Postfix::PropertyAccess {
+            } => {
+                let expr = expr.check(ctx)?;
+
+                let Id {
+                    name: property_name,
+                    position: property_position,
+                    ..
+                } = property;
+
+                let inner = ctx.fresh_variable();
+                if let Some(type_id) = expr.get_info().resolved_type() {
+                    ctx.unify(&Type::Optional(Box::new(inner.clone())), &type_id)
+                        .map_err(|mismatch| {
+                            TypeCheckError::TypeMismatch(mismatch, expr.position())
+                        })?;
+                }
+
+                let type_id = match ctx.resolve(&inner) {
+                    type_id if type_id.has_variables() => {
+                        return Err(TypeCheckError::AmbiguousType(
+                            AmbiguousType { inferred: type_id },
+                            expr.position(),
+                        ));
+                    }
+                    type_id => match ctx.scope.resolve_property_for_type(type_id, &property_name) {
+                        // the property is missing as well, if the value is
+                        Some(type_id) => Type::optional(type_id),
+                        None => {
+                            return Err(TypeCheckError::UndefinedVariable(
+                                UndefinedVariable {
+                                    variable_name: property_name.clone(),
+                                    similar_name: None,
+                                },
+                                property_position,
+                            ));
+                        }
+                    },
+                };
+
+                Ok(Postfix::OptionalPropertyAccess {
+                    expr: Box::new(expr),
+                    property: Id {
+                        name: property_name,
+                        position: property_position,
+                        info: TypeInformation {
+                            type_id: Rc::new(RefCell::new(Some(type_id.clone()))),
+                            context: context.clone(),
+                        },
+                    },
+                    info: TypeInformation {
+                        type_id: Rc::new(RefCell::new(Some(type_id))),
+                        context,
+                    },
+                    position,
+                })
+            }
+            Postfix::Propagate { expr, position, .. } => {
+                let expr = expr.check(ctx)?;
+
+                // whether the error fits the enclosing function is checked by the function
+                let value = ctx.fresh_variable();
+                let result = Type::Result(Box::new(value.clone()), Box::new(ctx.fresh_variable()));
+                if let Some(type_id) = expr.get_info().resolved_type() {
+                    // the fresh halves of the result fit anything, so only non-results fail here
+                    ctx.unify(&result, &type_id)
+                        .map_err(|TypeMismatch { actual, .. }| {
+                            TypeCheckError::NonResultPropagation(
+                                NonResultPropagation { type_id: actual },
+                                expr.position(),
+                            )
+                        })?;
+                }
+
+                Ok(Postfix::Propagate {
+                    expr: Box::new(expr),
+                    info: TypeInformation {
+                        type_id: Rc::new(RefCell::new(Some(value))),
+                        context,
+                    },
+                    position,
+                })
+            }
+            Postfix::Cast {
                 expr,
-                property,
+                target,
                 position,
                 ..
-            } => Postfix::PropertyAccess {
-                expr: Box::new(TypeCheckable::revert(expr.as_ref())),
-                property: TypeCheckable::revert(property),
-                info: (),
-                position: position.clone(),
-            },
+            } => {
+                let expr = expr.check(ctx)?;
+                let target_type = Type::try_from((&target, &*ctx))?;
+
+                // a literal gets the target type if its value fits, e.g., `255 as u8`; anything
+                // else keeps its own type, so a cast never constrains the type of a variable
+                let preferred = match (&expr, &target_type) {
+                    (Expression::Num(Num::Integer(value, ..)), Type::Integer(integer_type))
+                        if integer_type.contains(i128::from(*value)) =>
+                    {
+                        Some(&target_type)
+                    }
+                    (Expression::Num(Num::FloatingPoint(..)), Type::FloatingPoint(_)) => {
+                        Some(&target_type)
+                    }
+                    _ => None,
+                };
+
+                let type_id = { expr.get_info().type_id.borrow() }
+                    .as_ref()
+                    .map(|type_id| ctx.resolve_literals(type_id, preferred));
+
+                if let Some(type_id) = type_id {
+                    // which conversion is used depends on the type of the value
+                    if type_id.has_variables() {
+                        return Err(TypeCheckError::AmbiguousType(
+                            AmbiguousType { inferred: type_id },
+                            expr.position(),
+                        ));
+                    }
+
+                    if type_id.conversion(&target_type).is_none() {
+                        return Err(TypeCheckError::InvalidCast(
+                            InvalidCast {
+                                from: type_id,
+                                to: target_type,
+                            },
+                            expr.position().merge(&position),
+                        ));
+                    }
+                }
+
+                Ok(Postfix::Cast {
+                    expr: Box::new(expr),
+                    target,
+                    info: TypeInformation {
+                        type_id: Rc::new(RefCell::new(Some(target_type))),
+                        context,
+                    },
+                    position,
+                })
+            }
         }
     }
 }
@@ -287,6 +506,37 @@ impl TypedConstruct for Postfix<TypeInformation> {
                 info: info.validate(&position)?,
                 position,
             }),
+            Postfix::OptionalPropertyAccess {
+                expr,
+                property,
+                info,
+                position,
+            } => Ok(Postfix::OptionalPropertyAccess {
+                expr: Box::new(expr.validate()?),
+                property: property.validate()?,
+                info: info.validate(&position)?,
+                position,
+            }),
+            Postfix::Propagate {
+                expr,
+                info,
+                position,
+            } => Ok(Postfix::Propagate {
+                expr: Box::new(expr.validate()?),
+                info: info.validate(&position)?,
+                position,
+            }),
+            Postfix::Cast {
+                expr,
+                target,
+                info,
+                position,
+            } => Ok(Postfix::Cast {
+                expr: Box::new(expr.validate()?),
+                target,
+                info: info.validate(&position)?,
+                position,
+            }),
         }
     }
 }
@@ -316,7 +566,7 @@ mod tests {
             "foo",
             Type::Function {
                 params: vec![],
-                return_value: Box::new(Type::Integer),
+                return_value: Box::new(Type::I64),
             },
         )?;
 
@@ -335,7 +585,7 @@ mod tests {
 
         assert_eq!(
             call.get_info().type_id,
-            Rc::new(RefCell::new(Some(Type::Integer)))
+            Rc::new(RefCell::new(Some(Type::I64)))
         );
 
         Ok(())
@@ -348,12 +598,12 @@ mod tests {
         ctx.scope.add_constant(
             "foo",
             Type::Function {
-                params: vec![Type::FloatingPoint, Type::Integer],
-                return_value: Box::new(Type::Integer),
+                params: vec![Type::F64, Type::I64],
+                return_value: Box::new(Type::I64),
             },
         )?;
 
-        ctx.scope.add_constant("bar", Type::FloatingPoint)?;
+        ctx.scope.add_constant("bar", Type::F64)?;
 
         let call = Postfix::Call {
             expr: Box::new(Expression::Id(Id {
@@ -377,7 +627,7 @@ mod tests {
 
         assert_eq!(
             call.get_info().type_id,
-            Rc::new(RefCell::new(Some(Type::Integer)))
+            Rc::new(RefCell::new(Some(Type::I64)))
         );
 
         Ok(())
@@ -390,12 +640,12 @@ mod tests {
         ctx.scope.add_constant(
             "foo",
             Type::Function {
-                params: vec![Type::Integer, Type::FloatingPoint],
-                return_value: Box::new(Type::Integer),
+                params: vec![Type::I64, Type::F64],
+                return_value: Box::new(Type::I64),
             },
         )?;
 
-        ctx.scope.add_constant("bar", Type::FloatingPoint)?;
+        ctx.scope.add_constant("bar", Type::F64)?;
 
         let call = Postfix::Call {
             expr: Box::new(Expression::Id(Id {
@@ -421,8 +671,8 @@ mod tests {
             result,
             Err(TypeCheckError::TypeMismatch(
                 TypeMismatch {
-                    expected: Type::Integer,
-                    actual: Type::FloatingPoint
+                    expected: Type::I64,
+                    actual: Type::F64
                 },
                 Span::default()
             ))
@@ -436,7 +686,7 @@ mod tests {
         let mut ctx = Context::default();
 
         ctx.scope
-            .add_constant("foo", Type::Array(Box::new(Type::Integer)))?;
+            .add_constant("foo", Type::Array(Box::new(Type::I64)))?;
 
         let index = Postfix::Index {
             expr: Box::new(Expression::Id(Id {
@@ -453,7 +703,7 @@ mod tests {
 
         assert_eq!(
             index.get_info().type_id,
-            Rc::new(RefCell::new(Some(Type::Integer)))
+            Rc::new(RefCell::new(Some(Type::I64)))
         );
 
         Ok(())
@@ -464,10 +714,10 @@ mod tests {
         let mut ctx = Context::default();
 
         ctx.scope
-            .add_constant("foo", Type::Array(Box::new(Type::Integer)))?;
+            .add_constant("foo", Type::Array(Box::new(Type::I64)))?;
 
         ctx.scope
-            .add_constant("bar", Type::Array(Box::new(Type::FloatingPoint)))?;
+            .add_constant("bar", Type::Array(Box::new(Type::F64)))?;
 
         let index = Postfix::Index {
             expr: Box::new(Expression::Id(Id {
@@ -490,8 +740,8 @@ mod tests {
             res,
             Err(TypeCheckError::TypeMismatch(
                 TypeMismatch {
-                    expected: Type::Integer,
-                    actual: Type::Array(Box::new(Type::FloatingPoint))
+                    expected: Type::USIZE,
+                    actual: Type::Array(Box::new(Type::F64))
                 },
                 Span::default()
             ))
@@ -506,7 +756,7 @@ mod tests {
 
         ctx.scope.add_constant(
             "foo",
-            Type::Struct("Foo".into(), vec![("bar".into(), Type::Integer)]),
+            Type::Struct("Foo".into(), vec![("bar".into(), Type::I64)]),
         )?;
 
         let access = Postfix::PropertyAccess {
@@ -528,7 +778,7 @@ mod tests {
 
         assert_eq!(
             access.get_info().type_id,
-            Rc::new(RefCell::new(Some(Type::Integer)))
+            Rc::new(RefCell::new(Some(Type::I64)))
         );
 
         Ok(())
@@ -544,7 +794,7 @@ mod tests {
                 "Foo".into(),
                 vec![(
                     "bar".into(),
-                    Type::Struct("Bar".into(), vec![("baz".into(), Type::FloatingPoint)]),
+                    Type::Struct("Bar".into(), vec![("baz".into(), Type::F64)]),
                 )],
             ),
         )?;
@@ -577,7 +827,7 @@ mod tests {
 
         assert_eq!(
             access.get_info().type_id,
-            Rc::new(RefCell::new(Some(Type::FloatingPoint)))
+            Rc::new(RefCell::new(Some(Type::F64)))
         );
 
         Ok(())
@@ -589,7 +839,7 @@ mod tests {
 
         ctx.scope.add_constant(
             "foo",
-            Type::Struct("Foo".into(), vec![("bar".into(), Type::Integer)]),
+            Type::Struct("Foo".into(), vec![("bar".into(), Type::I64)]),
         )?;
 
         let access = Postfix::PropertyAccess {
@@ -614,6 +864,7 @@ mod tests {
             Err(TypeCheckError::UndefinedVariable(
                 UndefinedVariable {
                     variable_name: "baz".into(),
+                    similar_name: None,
                 },
                 Span::default()
             ))
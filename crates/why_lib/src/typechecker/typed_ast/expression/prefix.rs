@@ -3,7 +3,7 @@ use crate::{
     parser::ast::Prefix,
     typechecker::{
         context::Context,
-        error::{TypeCheckError, TypeMismatch},
+        error::{TypeCheckError, TypeMismatch, UnsignedNegation},
         types::Type,
         TypeCheckable, TypeInformation, TypeResult,
     },
@@ -24,15 +24,9 @@ impl TypeCheckable for Prefix<()> {
 
                 // check if we actually have a boolean type
                 if let Some(type_id) = type_id {
-                    if type_id != Type::Boolean {
-                        return Err(TypeCheckError::TypeMismatch(
-                            TypeMismatch {
-                                expected: Type::Boolean,
-                                actual: type_id,
-                            },
-                            expr.position(),
-                        ));
-                    }
+                    ctx.unify(&Type::Boolean, &type_id).map_err(|mismatch| {
+                        TypeCheckError::TypeMismatch(mismatch, expr.position())
+                    })?;
                 }
 
                 Ok(Prefix::Negation {
@@ -49,15 +43,24 @@ impl TypeCheckable for Prefix<()> {
                 let type_id = type_id_ref.borrow().clone();
 
                 // check if we actually have a numeric type
-                if let Some(type_id) = type_id {
-                    if type_id != Type::Integer && type_id != Type::FloatingPoint {
-                        return Err(TypeCheckError::TypeMismatch(
-                            TypeMismatch {
-                                expected: Type::Integer,
-                                actual: type_id,
-                            },
-                            expr.position(),
-                        ));
+                if let Some(type_id) = type_id.map(|type_id| ctx.resolve(&type_id)) {
+                    match type_id {
+                        Type::Integer(integer_type) if !integer_type.is_signed() => {
+                            return Err(TypeCheckError::UnsignedNegation(
+                                UnsignedNegation { type_id },
+                                expr.position(),
+                            ));
+                        }
+                        Type::Integer(_) | Type::FloatingPoint(_) | Type::Variable(_) => {}
+                        type_id => {
+                            return Err(TypeCheckError::TypeMismatch(
+                                TypeMismatch {
+                                    expected: Type::I64,
+                                    actual: type_id,
+                                },
+                                expr.position(),
+                            ));
+                        }
                     }
                 }
 
@@ -68,19 +71,6 @@ impl TypeCheckable for Prefix<()> {
             }
         }
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        match this {
-            Prefix::Negation { expr, position } => Prefix::Negation {
-                expr: Box::new(TypeCheckable::revert(expr.as_ref())),
-                position: position.clone(),
-            },
-            Prefix::Minus { expr, position } => Prefix::Minus {
-                expr: Box::new(TypeCheckable::revert(expr.as_ref())),
-                position: position.clone(),
-            },
-        }
-    }
 }
 
 impl TypedConstruct for Prefix<TypeInformation> {
@@ -127,11 +117,9 @@ mod tests {
         };
 
         let pref = pref.check(&mut ctx)?;
+        ctx.default_literals(0);
 
-        assert_eq!(
-            pref.get_info().type_id,
-            Rc::new(RefCell::new(Some(Type::Integer)))
-        );
+        assert_eq!(pref.get_info().resolved_type(), Some(Type::I64));
         Ok(())
     }
 
@@ -180,7 +168,7 @@ mod tests {
             res,
             Err(TypeCheckError::TypeMismatch(
                 TypeMismatch {
-                    expected: Type::Integer,
+                    expected: Type::I64,
                     actual: Type::Boolean
                 },
                 Span::default()
@@ -205,7 +193,7 @@ mod tests {
             Err(TypeCheckError::TypeMismatch(
                 TypeMismatch {
                     expected: Type::Boolean,
-                    actual: Type::Integer
+                    actual: Type::I64
                 },
                 Span::default()
             ))
@@ -24,18 +24,6 @@ impl TypeCheckable for AstString<()> {
             },
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let AstString {
-            value, position, ..
-        } = this;
-
-        AstString {
-            value: value.clone(),
-            position: position.clone(),
-            info: (),
-        }
-    }
 }
 
 impl TypedConstruct for AstString<TypeInformation> {
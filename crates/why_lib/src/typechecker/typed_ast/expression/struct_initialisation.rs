@@ -6,7 +6,7 @@ use crate::{
     parser::ast::{Id, StructFieldInitialisation, StructInitialisation, TypeName},
     typechecker::{
         context::Context,
-        error::{TypeCheckError, TypeMismatch, UndefinedType, UndefinedVariable},
+        error::{TypeCheckError, UndefinedType, UndefinedVariable},
         types::Type,
         TypeCheckable, TypeInformation, TypeResult, TypedConstruct,
     },
@@ -62,6 +62,7 @@ impl TypeCheckable for StructInitialisation<()> {
                 return Err(TypeCheckError::UndefinedVariable(
                     UndefinedVariable {
                         variable_name: format!("{name}.{struct_field_name}"),
+                        similar_name: None,
                     },
                     struct_position,
                 ));
@@ -76,15 +77,13 @@ impl TypeCheckable for StructInitialisation<()> {
 
             match initialised_field_type {
                 Some(field_type) => {
-                    if field_type != *struct_field_type {
-                        return Err(TypeCheckError::TypeMismatch(
-                            TypeMismatch {
-                                expected: struct_field_type.clone(),
-                                actual: field_type,
-                            },
-                            initialised_field.position,
-                        ));
-                    }
+                    ctx.unify(struct_field_type, &field_type)
+                        .map_err(|mismatch| {
+                            TypeCheckError::TypeMismatch(
+                                mismatch,
+                                initialised_field.position.clone(),
+                            )
+                        })?;
                 }
                 None => {
                     initialised_field
@@ -117,26 +116,6 @@ impl TypeCheckable for StructInitialisation<()> {
             position: struct_position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let StructInitialisation {
-            id,
-            fields,
-            position,
-            ..
-        } = this;
-
-        StructInitialisation {
-            id: Id {
-                name: id.name.clone(),
-                info: (),
-                position: id.position.clone(),
-            },
-            fields: fields.iter().map(TypeCheckable::revert).collect(),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for StructInitialisation<TypeInformation> {
@@ -200,26 +179,6 @@ impl TypeCheckable for StructFieldInitialisation<()> {
             position: struct_position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let StructFieldInitialisation {
-            name,
-            value,
-            position,
-            ..
-        } = this;
-
-        StructFieldInitialisation {
-            name: Id {
-                name: name.name.clone(),
-                info: (),
-                position: name.position.clone(),
-            },
-            value: TypeCheckable::revert(value),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for StructFieldInitialisation<TypeInformation> {
@@ -334,6 +293,7 @@ mod tests {
 
         dec.shallow_check(&mut ctx)?;
         dec.check(&mut ctx)?;
+        ctx.default_literals(0);
 
         let init = StructInitialisation {
             id: Id {
@@ -373,22 +333,13 @@ mod tests {
             init.info.type_id,
             Rc::new(RefCell::new(Some(Type::Struct(
                 "Foo".into(),
-                vec![
-                    ("bar".into(), Type::Integer),
-                    ("baz".into(), Type::FloatingPoint)
-                ]
+                vec![("bar".into(), Type::I64), ("baz".into(), Type::F64)]
             ))))
         );
 
-        assert_eq!(
-            init.fields[0].info.type_id,
-            Rc::new(RefCell::new(Some(Type::Integer)))
-        );
+        assert_eq!(init.fields[0].info.resolved_type(), Some(Type::I64));
 
-        assert_eq!(
-            init.fields[1].info.type_id,
-            Rc::new(RefCell::new(Some(Type::FloatingPoint)))
-        );
+        assert_eq!(init.fields[1].info.resolved_type(), Some(Type::F64));
 
         Ok(())
     }
@@ -431,6 +382,7 @@ mod tests {
 
         dec.shallow_check(&mut ctx)?;
         dec.check(&mut ctx)?;
+        ctx.default_literals(0);
 
         let init = StructInitialisation {
             id: Id {
@@ -470,22 +422,13 @@ mod tests {
             init.info.type_id,
             Rc::new(RefCell::new(Some(Type::Struct(
                 "Foo".into(),
-                vec![
-                    ("bar".into(), Type::Integer),
-                    ("baz".into(), Type::FloatingPoint)
-                ]
+                vec![("bar".into(), Type::I64), ("baz".into(), Type::F64)]
             ))))
         );
 
-        assert_eq!(
-            init.fields[0].info.type_id,
-            Rc::new(RefCell::new(Some(Type::Integer)))
-        );
+        assert_eq!(init.fields[0].info.resolved_type(), Some(Type::I64));
 
-        assert_eq!(
-            init.fields[1].info.type_id,
-            Rc::new(RefCell::new(Some(Type::FloatingPoint)))
-        );
+        assert_eq!(init.fields[1].info.resolved_type(), Some(Type::F64));
 
         Ok(())
     }
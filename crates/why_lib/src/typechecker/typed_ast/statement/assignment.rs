@@ -3,7 +3,7 @@ use crate::{
     parser::ast::{Assignment, LValue},
     typechecker::{
         context::Context,
-        error::{ImmutableReassign, TypeCheckError, TypeMismatch},
+        error::{ImmutableReassign, TypeCheckError},
         TypeCheckable, TypeInformation, TypeResult, TypedConstruct,
     },
 };
@@ -24,6 +24,7 @@ impl TypeCheckable for Assignment<()> {
         if let Some(false) = ctx.scope.is_variable_mutable(&name) {
             return Err(TypeCheckError::ImmutableReassign(
                 ImmutableReassign {
+                    declaration: ctx.scope.get_variable_declaration(&name),
                     variable_name: name,
                 },
                 position,
@@ -40,15 +41,10 @@ impl TypeCheckable for Assignment<()> {
 
         match (variable_type_id, rvalue_type_id) {
             (Some(variable_type_id), Some(rvalue_type_id)) => {
-                if variable_type_id != rvalue_type_id {
-                    return Err(TypeCheckError::TypeMismatch(
-                        TypeMismatch {
-                            expected: variable_type_id,
-                            actual: rvalue_type_id,
-                        },
-                        rvalue.position(),
-                    ));
-                }
+                ctx.unify(&variable_type_id, &rvalue_type_id)
+                    .map_err(|mismatch| {
+                        TypeCheckError::TypeMismatch(mismatch, rvalue.position())
+                    })?;
             }
             (Some(variable_type_id), None) => {
                 rvalue.update_type(variable_type_id.clone())?;
@@ -68,22 +64,6 @@ impl TypeCheckable for Assignment<()> {
             position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let Assignment {
-            lvalue: id,
-            rvalue,
-            position,
-            ..
-        } = this;
-
-        Assignment {
-            lvalue: TypeCheckable::revert(id),
-            rvalue: TypeCheckable::revert(rvalue),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for Assignment<TypeInformation> {
@@ -115,13 +95,6 @@ impl TypeCheckable for LValue<()> {
             LValue::Postfix(postfix) => Ok(LValue::Postfix(postfix.check(ctx)?)),
         }
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        match this {
-            LValue::Id(id) => LValue::Id(TypeCheckable::revert(id)),
-            LValue::Postfix(postfix) => LValue::Postfix(TypeCheckable::revert(postfix)),
-        }
-    }
 }
 
 impl TypedConstruct for LValue<TypeInformation> {
@@ -163,7 +136,7 @@ mod tests {
             Expression::Id(Id {
                 name: "foo".into(),
                 info: TypeInformation {
-                    type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                    type_id: Rc::new(RefCell::new(Some(Type::I64))),
                     context: ctx.clone(),
                 },
                 position: Span::default(),
@@ -195,7 +168,7 @@ mod tests {
             Expression::Id(Id {
                 name: "foo".into(),
                 info: TypeInformation {
-                    type_id: Rc::new(RefCell::new(Some(Type::FloatingPoint))),
+                    type_id: Rc::new(RefCell::new(Some(Type::F64))),
                     context: ctx.clone(),
                 },
                 position: Span::default(),
@@ -220,8 +193,8 @@ mod tests {
             result,
             Err(TypeCheckError::TypeMismatch(
                 TypeMismatch {
-                    expected: Type::FloatingPoint,
-                    actual: Type::Integer
+                    expected: Type::F64,
+                    actual: Type::I64
                 },
                 Span::default()
             ))
@@ -238,7 +211,7 @@ mod tests {
             Expression::Id(Id {
                 name: "foo".into(),
                 info: TypeInformation {
-                    type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                    type_id: Rc::new(RefCell::new(Some(Type::I64))),
                     context: ctx.clone(),
                 },
                 position: Span::default(),
@@ -263,7 +236,8 @@ mod tests {
             result,
             Err(TypeCheckError::ImmutableReassign(
                 ImmutableReassign {
-                    variable_name: "foo".into()
+                    variable_name: "foo".into(),
+                    declaration: None,
                 },
                 Span::default()
             ))
@@ -293,7 +267,8 @@ mod tests {
             result,
             Err(TypeCheckError::UndefinedVariable(
                 UndefinedVariable {
-                    variable_name: "foo".into()
+                    variable_name: "foo".into(),
+                    similar_name: None,
                 },
                 Span::default()
             ))
@@ -308,7 +283,7 @@ mod tests {
 
         ctx.scope.add_type(
             "Foo",
-            Type::Struct("Foo".to_string(), vec![("bar".to_string(), Type::Integer)]),
+            Type::Struct("Foo".to_string(), vec![("bar".to_string(), Type::I64)]),
         )?;
 
         ctx.scope.add_variable(
@@ -319,7 +294,7 @@ mod tests {
                     info: TypeInformation {
                         type_id: Rc::new(RefCell::new(Some(Type::Struct(
                             "Foo".to_string(),
-                            vec![("bar".to_string(), Type::Integer)],
+                            vec![("bar".to_string(), Type::I64)],
                         )))),
                         context: ctx.clone(),
                     },
@@ -329,7 +304,7 @@ mod tests {
                     name: Id {
                         name: "bar".into(),
                         info: TypeInformation {
-                            type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                            type_id: Rc::new(RefCell::new(Some(Type::I64))),
                             context: ctx.clone(),
                         },
                         position: Span::default(),
@@ -337,13 +312,13 @@ mod tests {
                     value: Expression::Num(Num::Integer(
                         1337,
                         TypeInformation {
-                            type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                            type_id: Rc::new(RefCell::new(Some(Type::I64))),
                             context: ctx.clone(),
                         },
                         Span::default(),
                     )),
                     info: TypeInformation {
-                        type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                        type_id: Rc::new(RefCell::new(Some(Type::I64))),
                         context: ctx.clone(),
                     },
                     position: Span::default(),
@@ -351,7 +326,7 @@ mod tests {
                 info: TypeInformation {
                     type_id: Rc::new(RefCell::new(Some(Type::Struct(
                         "Foo".to_string(),
-                        vec![("bar".to_string(), Type::Integer)],
+                        vec![("bar".to_string(), Type::I64)],
                     )))),
                     context: ctx.clone(),
                 },
@@ -390,7 +365,7 @@ mod tests {
 
         ctx.scope.add_type(
             "Foo",
-            Type::Struct("Foo".to_string(), vec![("bar".to_string(), Type::Integer)]),
+            Type::Struct("Foo".to_string(), vec![("bar".to_string(), Type::I64)]),
         )?;
 
         ctx.scope.add_variable(
@@ -401,7 +376,7 @@ mod tests {
                     info: TypeInformation {
                         type_id: Rc::new(RefCell::new(Some(Type::Struct(
                             "Foo".to_string(),
-                            vec![("bar".to_string(), Type::Integer)],
+                            vec![("bar".to_string(), Type::I64)],
                         )))),
                         context: ctx.clone(),
                     },
@@ -411,7 +386,7 @@ mod tests {
                     name: Id {
                         name: "bar".into(),
                         info: TypeInformation {
-                            type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                            type_id: Rc::new(RefCell::new(Some(Type::I64))),
                             context: ctx.clone(),
                         },
                         position: Span::default(),
@@ -419,13 +394,13 @@ mod tests {
                     value: Expression::Num(Num::Integer(
                         1337,
                         TypeInformation {
-                            type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                            type_id: Rc::new(RefCell::new(Some(Type::I64))),
                             context: ctx.clone(),
                         },
                         Span::default(),
                     )),
                     info: TypeInformation {
-                        type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                        type_id: Rc::new(RefCell::new(Some(Type::I64))),
                         context: ctx.clone(),
                     },
                     position: Span::default(),
@@ -433,7 +408,7 @@ mod tests {
                 info: TypeInformation {
                     type_id: Rc::new(RefCell::new(Some(Type::Struct(
                         "Foo".to_string(),
-                        vec![("bar".to_string(), Type::Integer)],
+                        vec![("bar".to_string(), Type::I64)],
                     )))),
                     context: ctx.clone(),
                 },
@@ -2,10 +2,11 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::typechecker::{TypeValidationError, ValidatedTypeInformation};
 use crate::{
-    parser::ast::{Constant, Id},
+    parser::ast::{Constant, Expression, Id},
     typechecker::{
         context::Context,
-        error::{InvalidConstantType, RedefinedConstant, TypeCheckError, TypeMismatch},
+        error::{InvalidConstantType, RedefinedConstant, TypeCheckError},
+        folding,
         types::Type,
         ShallowCheck, TypeCheckable, TypeInformation, TypeResult, TypedConstruct,
     },
@@ -24,6 +25,7 @@ impl TypeCheckable for Constant<()> {
         } = self;
 
         let context = ctx.clone();
+        let first_variable = ctx.variable_count();
 
         let Id {
             name,
@@ -31,10 +33,6 @@ impl TypeCheckable for Constant<()> {
             ..
         } = id;
 
-        let mut value = value.check(ctx)?;
-
-        let info = value.get_info();
-
         let Ok(type_id) = Type::try_from((&type_name, &*ctx)) else {
             return Err(TypeCheckError::InvalidConstantType(
                 InvalidConstantType {
@@ -44,21 +42,24 @@ impl TypeCheckable for Constant<()> {
             ));
         };
 
+        let mut value = match value {
+            Expression::Lambda(lambda) => {
+                Expression::Lambda(lambda.check_expecting(ctx, Some(&type_id))?)
+            }
+            value => value.check(ctx)?,
+        };
+
+        let info = value.get_info();
+
         {
             let inner = info.type_id.clone();
             let mut inner = inner.borrow_mut();
 
             match inner.as_ref() {
                 Some(inner_type) => {
-                    if type_id != *inner_type {
-                        return Err(TypeCheckError::TypeMismatch(
-                            TypeMismatch {
-                                expected: type_id,
-                                actual: inner_type.clone(),
-                            },
-                            value.position(),
-                        ));
-                    }
+                    ctx.unify(&type_id, inner_type).map_err(|mismatch| {
+                        TypeCheckError::TypeMismatch(mismatch, value.position())
+                    })?;
                 }
                 // oups - no value of associated expression
                 None => {
@@ -71,6 +72,12 @@ impl TypeCheckable for Constant<()> {
             }
         }
 
+        ctx.default_literals(first_variable);
+        match folding::evaluate(&value)? {
+            Some(constant_value) => ctx.scope.set_constant_value(&name, constant_value),
+            None => folding::expression(&value)?,
+        }
+
         Ok(Constant {
             id: Id {
                 name,
@@ -86,24 +93,6 @@ impl TypeCheckable for Constant<()> {
             position: const_position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let Constant {
-            id,
-            type_name,
-            value,
-            position,
-            ..
-        } = this;
-
-        Constant {
-            id: TypeCheckable::revert(id),
-            type_name: type_name.clone(),
-            value: TypeCheckable::revert(value),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for Constant<TypeInformation> {
@@ -130,7 +119,12 @@ impl TypedConstruct for Constant<TypeInformation> {
 
 impl ShallowCheck for Constant<()> {
     fn shallow_check(&self, ctx: &mut Context) -> TypeResult<()> {
-        let Constant { id, type_name, .. } = self;
+        let Constant {
+            id,
+            type_name,
+            value,
+            ..
+        } = self;
 
         let name = id.name.clone();
 
@@ -152,6 +146,12 @@ impl ShallowCheck for Constant<()> {
             ));
         };
 
+        // evaluate the value right away, so it can already be used in signatures (e.g., as the
+        // length of an array type); errors are reported once the constant is actually checked
+        if !matches!(value, Expression::Lambda(_) | Expression::Function(_)) {
+            let _ = self.clone().check(&mut ctx.clone());
+        }
+
         Ok(())
     }
 }
@@ -189,15 +189,13 @@ mod tests {
 
         constant.shallow_check(&mut ctx)?;
         let constant = constant.check(&mut ctx)?;
+        ctx.default_literals(0);
 
-        assert_eq!(
-            constant.id.info.type_id,
-            Rc::new(RefCell::new(Some(Type::Integer)))
-        );
+        assert_eq!(constant.id.info.resolved_type(), Some(Type::I64));
 
         assert_eq!(
             ctx.scope.resolve_name("foo"),
-            Some(Rc::new(RefCell::new(Some(Type::Integer))))
+            Some(Rc::new(RefCell::new(Some(Type::I64))))
         );
 
         Ok(())
@@ -235,7 +233,7 @@ mod tests {
     #[test]
     fn test_error_on_redefinition() -> Result<(), Box<dyn Error>> {
         let mut ctx = Context::default();
-        ctx.scope.add_constant("foo", Type::Integer)?;
+        ctx.scope.add_constant("foo", Type::I64)?;
 
         let constant = Constant {
             id: Id {
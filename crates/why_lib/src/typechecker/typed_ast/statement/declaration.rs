@@ -3,7 +3,7 @@ use crate::{
     parser::ast::{Declaration, Id},
     typechecker::{
         context::Context,
-        error::{RedefinedConstant, TypeCheckError, UndefinedType},
+        error::{RedefinedConstant, TypeCheckError},
         types::Type,
         ShallowCheck, TypeCheckable, TypeInformation, TypeResult, TypedConstruct,
     },
@@ -28,13 +28,7 @@ impl TypeCheckable for Declaration<()> {
             ..
         } = name;
 
-        let Ok(type_id) = Type::try_from((&type_name, &*ctx)) else {
-            let position = type_name.position();
-            return Err(TypeCheckError::UndefinedType(
-                UndefinedType { type_name },
-                position,
-            ));
-        };
+        let type_id = Type::from_annotation(&type_name, ctx)?;
 
         let type_id = Rc::new(RefCell::new(Some(type_id)));
 
@@ -57,22 +51,6 @@ impl TypeCheckable for Declaration<()> {
             position: dec_position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let Declaration {
-            name,
-            type_name,
-            position,
-            ..
-        } = this;
-
-        Declaration {
-            name: TypeCheckable::revert(name),
-            type_name: type_name.to_owned(),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for Declaration<TypeInformation> {
@@ -101,15 +79,7 @@ impl ShallowCheck for Declaration<()> {
             name, type_name, ..
         } = self;
 
-        let Ok(type_id) = Type::try_from((type_name, &*ctx)) else {
-            let position = type_name.position();
-            return Err(TypeCheckError::UndefinedType(
-                UndefinedType {
-                    type_name: type_name.clone(),
-                },
-                position,
-            ));
-        };
+        let type_id = Type::from_annotation(type_name, ctx)?;
 
         if ctx.scope.add_constant(&name.name, type_id).is_err() {
             return Err(TypeCheckError::RedefinedConstant(
@@ -179,7 +149,7 @@ mod tests {
 
         let var = ctx.scope.resolve_name("foo");
 
-        assert_eq!(var, Some(Rc::new(RefCell::new(Some(Type::Integer)))));
+        assert_eq!(var, Some(Rc::new(RefCell::new(Some(Type::I64)))));
 
         Ok(())
     }
@@ -203,7 +173,7 @@ mod tests {
 
         assert_eq!(
             dec.name.info.type_id,
-            Rc::new(RefCell::new(Some(Type::Integer)))
+            Rc::new(RefCell::new(Some(Type::I64)))
         );
         assert_eq!(dec.info.type_id, Rc::new(RefCell::new(Some(Type::Void))));
 
@@ -2,13 +2,11 @@ use std::{cell::RefCell, rc::Rc};
 
 use crate::typechecker::{TypeValidationError, ValidatedTypeInformation};
 use crate::{
-    parser::ast::{Id, Initialisation},
+    lexer::Span,
+    parser::ast::{Expression, Id, Initialisation, TypeName},
     typechecker::{
         context::Context,
-        error::{
-            MissingInitialisationType, RedefinedConstant, TypeCheckError, TypeMismatch,
-            UndefinedType,
-        },
+        error::{MissingInitialisationType, RedefinedConstant, TypeCheckError, UndefinedType},
         types::Type,
         TypeCheckable, TypeInformation, TypeResult, TypedConstruct,
     },
@@ -35,58 +33,73 @@ impl TypeCheckable for Initialisation<()> {
             ..
         } = id;
 
-        let mut value = value.check(ctx)?;
+        let Some(value) = value else {
+            return declare_uninitialised(
+                ctx,
+                Id {
+                    name,
+                    info: (),
+                    position: id_position,
+                },
+                mutable,
+                type_name,
+                init_position,
+            );
+        };
+
+        // is the annotated type actually a valid type?
+        let annotated_type = match &type_name {
+            Some(type_name) => Some(Type::from_annotation(type_name, ctx)?),
+            None => None,
+        };
+
+        // lambdas need the annotated type to be able to use their parameters in their body
+        let mut value = match value {
+            Expression::Lambda(lambda) => {
+                Expression::Lambda(lambda.check_expecting(ctx, annotated_type.as_ref())?)
+            }
+            value => value.check(ctx)?,
+        };
 
         let info = value.get_info();
 
         // check for annotated type
-        if let Some(type_name) = type_name.clone() {
-            // is it actually a valid type?
-            if let Ok(type_id) = Type::try_from((&type_name, &*ctx)) {
-                // check of type of associated expression
-                let inner = info.type_id.clone();
-                let inner = inner.borrow_mut().clone();
-
-                match inner.as_ref() {
-                    // we have a type...
-                    Some(inner_type) => {
-                        // check, if they are equal
-                        if type_id != *inner_type {
-                            return Err(TypeCheckError::TypeMismatch(
-                                TypeMismatch {
-                                    expected: type_id,
-                                    actual: inner_type.clone(),
-                                },
-                                value.position(),
-                            ));
-                        }
-                    }
-                    // oups - no value of associated expression
-                    None => {
-                        // update type of underlying expression
-                        value.update_type(type_id.clone())?;
-
-                        // ...and the type of enclosed in the information
-                        *info.type_id.borrow_mut() = Some(type_id);
-                    }
+        if let Some(type_id) = annotated_type {
+            // check of type of associated expression
+            let inner = info.type_id.clone();
+            let inner = inner.borrow_mut().clone();
+
+            match inner.as_ref() {
+                // we have a type...
+                Some(inner_type) => {
+                    // check, if they are equal
+                    ctx.unify(&type_id, inner_type).map_err(|mismatch| {
+                        TypeCheckError::TypeMismatch(mismatch, value.position())
+                    })?;
+                }
+                // oups - no value of associated expression
+                None => {
+                    // update type of underlying expression
+                    value.update_type(type_id.clone())?;
+
+                    // ...and the type of enclosed in the information
+                    *info.type_id.borrow_mut() = Some(type_id);
                 }
-            } else {
-                let position = type_name.position();
-                return Err(TypeCheckError::UndefinedType(
-                    UndefinedType { type_name },
-                    position,
-                ));
             }
         } else if !info.has_type() {
             return Err(TypeCheckError::MissingInitialisationType(
-                MissingInitialisationType,
+                MissingInitialisationType {
+                    variable_name: name,
+                    variable_position: id_position,
+                    inferred_type: None,
+                },
                 init_position,
             ));
         }
 
         if ctx
             .scope
-            .add_variable(&name, value.clone(), mutable)
+            .declare_variable(&name, value.clone(), mutable, init_position.clone())
             .is_err()
         {
             return Err(TypeCheckError::RedefinedConstant(
@@ -105,7 +118,7 @@ impl TypeCheckable for Initialisation<()> {
             },
             mutable,
             type_name,
-            value,
+            value: Some(value),
             info: TypeInformation {
                 type_id: Rc::new(RefCell::new(Some(Type::Void))),
                 context,
@@ -113,26 +126,6 @@ impl TypeCheckable for Initialisation<()> {
             position: init_position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let Initialisation {
-            id,
-            mutable,
-            type_name,
-            value,
-            position,
-            ..
-        } = this;
-
-        Initialisation {
-            id: TypeCheckable::revert(id),
-            mutable: *mutable,
-            type_name: type_name.to_owned(),
-            value: TypeCheckable::revert(value),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for Initialisation<TypeInformation> {
@@ -152,13 +145,79 @@ impl TypedConstruct for Initialisation<TypeInformation> {
             id: id.validate()?,
             mutable,
             type_name,
-            value: value.validate()?,
+            value: value.map(TypedConstruct::validate).transpose()?,
             info: info.validate(&position)?,
             position,
         })
     }
 }
 
+/// Declare a variable without an initial value. Such a variable needs an annotated type and
+/// can be assigned once, even if it is not mutable (which is verified by the definite assignment
+/// analysis of the enclosing function).
+fn declare_uninitialised(
+    ctx: &mut Context,
+    id: Id<()>,
+    mutable: bool,
+    type_name: Option<TypeName>,
+    position: Span,
+) -> TypeResult<Initialisation<TypeInformation>> {
+    let context = ctx.clone();
+
+    let Some(type_name) = type_name else {
+        return Err(TypeCheckError::MissingInitialisationType(
+            MissingInitialisationType {
+                variable_name: id.name,
+                variable_position: id.position,
+                inferred_type: None,
+            },
+            position,
+        ));
+    };
+
+    let Ok(type_id) = Type::try_from((&type_name, &*ctx)) else {
+        let position = type_name.position();
+        return Err(TypeCheckError::UndefinedType(
+            UndefinedType { type_name },
+            position,
+        ));
+    };
+
+    let id = Id {
+        name: id.name,
+        info: TypeInformation {
+            type_id: Rc::new(RefCell::new(Some(type_id))),
+            context: context.clone(),
+        },
+        position: id.position,
+    };
+
+    if ctx
+        .scope
+        .declare_variable(&id.name, Expression::Id(id.clone()), true, position.clone())
+        .is_err()
+    {
+        return Err(TypeCheckError::RedefinedConstant(
+            RedefinedConstant {
+                constant_name: id.name,
+            },
+            id.position,
+        ));
+    };
+
+    Ok(Initialisation {
+        id,
+        mutable,
+        type_name: Some(type_name),
+        value: None,
+        info: TypeInformation {
+            type_id: Rc::new(RefCell::new(Some(Type::Void))),
+            context,
+        },
+        position,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use std::{cell::RefCell, error::Error, rc::Rc};
@@ -166,7 +225,7 @@ mod tests {
     use crate::typechecker::error::MissingInitialisationType;
     use crate::{
         lexer::Span,
-        parser::ast::{Expression, Id, Initialisation, Lambda, Num, TypeName},
+        parser::ast::{Array, Expression, Id, Initialisation, Num, TypeName},
         typechecker::{
             context::Context,
             error::{TypeCheckError, TypeMismatch},
@@ -187,7 +246,7 @@ mod tests {
             },
             mutable: false,
             type_name: None,
-            value: Expression::Num(Num::Integer(42, (), Span::default())),
+            value: Some(Expression::Num(Num::Integer(42, (), Span::default()))),
             info: (),
             position: Span::default(),
         }
@@ -198,14 +257,14 @@ mod tests {
         assert!(init.type_name.is_none());
         assert_eq!(
             init.value,
-            Expression::Num(Num::Integer(
+            Some(Expression::Num(Num::Integer(
                 42,
                 TypeInformation {
-                    type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                    type_id: Rc::new(RefCell::new(Some(Type::Variable(0)))),
                     context: Context::default(),
                 },
                 Span::default()
-            ))
+            )))
         );
 
         Ok(())
@@ -223,7 +282,7 @@ mod tests {
             },
             mutable: false,
             type_name: None,
-            value: Expression::Num(Num::Integer(42, (), Span::default())),
+            value: Some(Expression::Num(Num::Integer(42, (), Span::default()))),
             info: (),
             position: Span::default(),
         };
@@ -232,7 +291,10 @@ mod tests {
 
         let var = ctx.scope.resolve_name("foo");
 
-        assert_eq!(var, Some(Rc::new(RefCell::new(Some(Type::Integer)))));
+        assert_eq!(var, Some(Rc::new(RefCell::new(Some(Type::Variable(0))))));
+
+        ctx.default_literals(0);
+        assert_eq!(ctx.resolve(&Type::Variable(0)), Type::I64);
 
         Ok(())
     }
@@ -249,7 +311,7 @@ mod tests {
             },
             mutable: false,
             type_name: None,
-            value: Expression::Num(Num::Integer(42, (), Span::default())),
+            value: Some(Expression::Num(Num::Integer(42, (), Span::default()))),
             info: (),
             position: Span::default(),
         };
@@ -263,13 +325,9 @@ mod tests {
                 context: Context::default(),
             }
         );
-        assert_eq!(
-            init.id.info,
-            TypeInformation {
-                type_id: Rc::new(RefCell::new(Some(Type::Integer))),
-                context: Context::default(),
-            }
-        );
+
+        ctx.default_literals(0);
+        assert_eq!(init.id.info.resolved_type(), Some(Type::I64));
 
         Ok(())
     }
@@ -286,7 +344,7 @@ mod tests {
             },
             mutable: false,
             type_name: Some(TypeName::Literal("f64".into(), Span::default())),
-            value: Expression::Num(Num::Integer(42, (), Span::default())),
+            value: Some(Expression::Num(Num::Integer(42, (), Span::default()))),
             info: (),
             position: Span::default(),
         };
@@ -296,8 +354,8 @@ mod tests {
             init,
             Err(TypeCheckError::TypeMismatch(
                 TypeMismatch {
-                    expected: Type::FloatingPoint,
-                    actual: Type::Integer
+                    expected: Type::F64,
+                    actual: Type::I64
                 },
                 Span::default()
             ))
@@ -316,12 +374,11 @@ mod tests {
             },
             mutable: false,
             type_name: None,
-            value: Expression::Lambda(Lambda {
-                parameters: vec![],
-                expression: Box::new(Expression::Num(Num::Integer(42, (), Span::default()))),
+            value: Some(Expression::Array(Array::Literal {
+                values: vec![],
                 info: (),
                 position: Span::default(),
-            }),
+            })),
             info: (),
             position: Span::default(),
         };
@@ -331,7 +388,11 @@ mod tests {
         assert_eq!(
             res,
             Err(TypeCheckError::MissingInitialisationType(
-                MissingInitialisationType,
+                MissingInitialisationType {
+                    variable_name: "foo".into(),
+                    variable_position: Span::default(),
+                    inferred_type: None,
+                },
                 Span::default()
             ))
         );
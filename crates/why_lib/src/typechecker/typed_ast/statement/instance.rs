@@ -63,24 +63,6 @@ impl TypeCheckable for Instance<()> {
             position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let Instance {
-            name,
-            functions,
-            position,
-            declarations,
-            ..
-        } = this;
-
-        Instance {
-            name: name.clone(),
-            functions: functions.iter().map(TypeCheckable::revert).collect(),
-            declarations: declarations.iter().map(TypeCheckable::revert).collect(),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl ShallowCheck for Instance<()> {
@@ -279,6 +261,7 @@ mod tests {
         let inst = Instance {
             name: TypeName::Literal("i64".into(), Span::default()),
             functions: vec![Function {
+                attributes: vec![],
                 id: Id {
                     name: "bar".into(),
                     info: (),
@@ -306,12 +289,13 @@ mod tests {
             Instance {
                 name: TypeName::Literal("i64".into(), Span::default()),
                 functions: vec![Function {
+                    attributes: vec![],
                     id: Id {
                         name: "bar".into(),
                         info: TypeInformation {
                             type_id: Rc::new(RefCell::new(Some(Type::Function {
                                 params: vec![],
-                                return_value: Box::new(Type::Integer)
+                                return_value: Box::new(Type::I64)
                             }))),
                             context: Context::default()
                         },
@@ -322,7 +306,7 @@ mod tests {
                     statements: vec![Statement::YieldingExpression(Expression::Id(Id {
                         name: "this".into(),
                         info: TypeInformation {
-                            type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                            type_id: Rc::new(RefCell::new(Some(Type::I64))),
                             context: Context::default()
                         },
                         position: Span::default(),
@@ -330,7 +314,7 @@ mod tests {
                     info: TypeInformation {
                         type_id: Rc::new(RefCell::new(Some(Type::Function {
                             params: vec![],
-                            return_value: Box::new(Type::Integer)
+                            return_value: Box::new(Type::I64)
                         }))),
                         context: Context::default()
                     },
@@ -353,12 +337,13 @@ mod tests {
         let mut ctx = Context::default();
         ctx.scope.add_type(
             "Foo",
-            Type::Struct("Foo".into(), vec![("baz".into(), Type::Integer)]),
+            Type::Struct("Foo".into(), vec![("baz".into(), Type::I64)]),
         )?;
 
         let inst = Instance {
             name: TypeName::Literal("Foo".into(), Span::default()),
             functions: vec![Function {
+                attributes: vec![],
                 id: Id {
                     name: "bar".into(),
                     info: (),
@@ -397,12 +382,13 @@ mod tests {
             Instance {
                 name: TypeName::Literal("Foo".into(), Span::default()),
                 functions: vec![Function {
+                    attributes: vec![],
                     id: Id {
                         name: "bar".into(),
                         info: TypeInformation {
                             type_id: Rc::new(RefCell::new(Some(Type::Function {
                                 params: vec![],
-                                return_value: Box::new(Type::Integer),
+                                return_value: Box::new(Type::I64),
                             }))),
                             context: Context::default(),
                         },
@@ -417,7 +403,7 @@ mod tests {
                                 info: TypeInformation {
                                     type_id: Rc::new(RefCell::new(Some(Type::Struct(
                                         "Foo".into(),
-                                        vec![("baz".into(), Type::Integer)],
+                                        vec![("baz".into(), Type::I64)],
                                     )))),
                                     context: Context::default(),
                                 },
@@ -426,13 +412,13 @@ mod tests {
                             property: Id {
                                 name: "baz".into(),
                                 info: TypeInformation {
-                                    type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                                    type_id: Rc::new(RefCell::new(Some(Type::I64))),
                                     context: Context::default(),
                                 },
                                 position: Span::default(),
                             },
                             info: TypeInformation {
-                                type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                                type_id: Rc::new(RefCell::new(Some(Type::I64))),
                                 context: Context::default(),
                             },
                             position: Span::default(),
@@ -441,7 +427,7 @@ mod tests {
                     info: TypeInformation {
                         type_id: Rc::new(RefCell::new(Some(Type::Function {
                             params: vec![],
-                            return_value: Box::new(Type::Integer),
+                            return_value: Box::new(Type::I64),
                         }))),
                         context: Context::default(),
                     },
@@ -464,12 +450,13 @@ mod tests {
         let mut ctx = Context::default();
         ctx.scope.add_type(
             "Foo",
-            Type::Struct("Foo".into(), vec![("baz".into(), Type::Integer)]),
+            Type::Struct("Foo".into(), vec![("baz".into(), Type::I64)]),
         )?;
 
         let inst = Instance {
             name: TypeName::Literal("Foo".into(), Span::default()),
             functions: vec![Function {
+                attributes: vec![],
                 id: Id {
                     name: "bar".into(),
                     info: (),
@@ -527,12 +514,13 @@ mod tests {
             Instance {
                 name: TypeName::Literal("Foo".into(), Span::default()),
                 functions: vec![Function {
+                    attributes: vec![],
                     id: Id {
                         name: "bar".into(),
                         info: TypeInformation {
                             type_id: Rc::new(RefCell::new(Some(Type::Function {
                                 params: vec![],
-                                return_value: Box::new(Type::Integer),
+                                return_value: Box::new(Type::I64),
                             }))),
                             context: Context::default(),
                         },
@@ -547,7 +535,7 @@ mod tests {
                                 info: TypeInformation {
                                     type_id: Rc::new(RefCell::new(Some(Type::Struct(
                                         "Foo".into(),
-                                        vec![("baz".into(), Type::Integer)],
+                                        vec![("baz".into(), Type::I64)],
                                     )))),
                                     context: Context::default(),
                                 },
@@ -556,13 +544,13 @@ mod tests {
                             property: Id {
                                 name: "baz".into(),
                                 info: TypeInformation {
-                                    type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                                    type_id: Rc::new(RefCell::new(Some(Type::I64))),
                                     context: Context::default(),
                                 },
                                 position: Span::default(),
                             },
                             info: TypeInformation {
-                                type_id: Rc::new(RefCell::new(Some(Type::Integer))),
+                                type_id: Rc::new(RefCell::new(Some(Type::I64))),
                                 context: Context::default(),
                             },
                             position: Span::default(),
@@ -571,7 +559,7 @@ mod tests {
                     info: TypeInformation {
                         type_id: Rc::new(RefCell::new(Some(Type::Function {
                             params: vec![],
-                            return_value: Box::new(Type::Integer),
+                            return_value: Box::new(Type::I64),
                         }))),
                         context: Context::default(),
                     },
@@ -582,11 +570,8 @@ mod tests {
                         name: "foo".into(),
                         info: TypeInformation {
                             type_id: Rc::new(RefCell::new(Some(Type::Function {
-                                params: vec![
-                                    Type::Integer,
-                                    Type::Tuple(vec![Type::Integer, Type::FloatingPoint])
-                                ],
-                                return_value: Box::new(Type::Integer)
+                                params: vec![Type::I64, Type::Tuple(vec![Type::I64, Type::F64])],
+                                return_value: Box::new(Type::I64)
                             }))),
                             context: ctx.clone()
                         },
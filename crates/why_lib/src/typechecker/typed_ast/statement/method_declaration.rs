@@ -51,24 +51,6 @@ impl TypeCheckable for MethodDeclaration<()> {
             },
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let MethodDeclaration {
-            id,
-            parameter_types,
-            return_type,
-            position,
-            ..
-        } = this;
-
-        MethodDeclaration {
-            id: TypeCheckable::revert(id),
-            parameter_types: parameter_types.clone(),
-            return_type: return_type.clone(),
-            position: position.clone(),
-            info: (),
-        }
-    }
 }
 
 impl MethodDeclaration<()> {
@@ -219,11 +201,8 @@ mod tests {
                     name: "foo".into(),
                     info: TypeInformation {
                         type_id: Rc::new(RefCell::new(Some(Type::Function {
-                            params: vec![
-                                Type::Integer,
-                                Type::Tuple(vec![Type::Integer, Type::FloatingPoint])
-                            ],
-                            return_value: Box::new(Type::Integer)
+                            params: vec![Type::I64, Type::Tuple(vec![Type::I64, Type::F64])],
+                            return_value: Box::new(Type::I64)
                         }))),
                         context: ctx.clone()
                     },
@@ -25,7 +25,7 @@ impl TypeCheckable for TopLevelStatement<()> {
             TopLevelStatement::Constant(constant) => {
                 Ok(TopLevelStatement::Constant(constant.check(ctx)?))
             }
-            TopLevelStatement::Comment(c) => Ok(TopLevelStatement::Comment(c)),
+            TopLevelStatement::Comment(c, position) => Ok(TopLevelStatement::Comment(c, position)),
             TopLevelStatement::Declaration(dec) => {
                 Ok(TopLevelStatement::Declaration(dec.check(ctx)?))
             }
@@ -35,33 +35,12 @@ impl TypeCheckable for TopLevelStatement<()> {
             TopLevelStatement::Instance(inst) => Ok(TopLevelStatement::Instance(inst.check(ctx)?)),
         }
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        match this {
-            TopLevelStatement::Function(func) => {
-                TopLevelStatement::Function(TypeCheckable::revert(func))
-            }
-            TopLevelStatement::Constant(_) => {
-                unimplemented!("TypeCheckable::revert is not implemented for Constants")
-            }
-            TopLevelStatement::Comment(c) => TopLevelStatement::Comment(c.to_owned()),
-            TopLevelStatement::Declaration(dec) => {
-                TopLevelStatement::Declaration(TypeCheckable::revert(dec))
-            }
-            TopLevelStatement::StructDeclaration(dec) => {
-                TopLevelStatement::StructDeclaration(TypeCheckable::revert(dec))
-            }
-            TopLevelStatement::Instance(inst) => {
-                TopLevelStatement::Instance(TypeCheckable::revert(inst))
-            }
-        }
-    }
 }
 
 impl ShallowCheck for TopLevelStatement<()> {
     fn shallow_check(&self, ctx: &mut Context) -> TypeResult<()> {
         match self {
-            TopLevelStatement::Comment(_) => Ok(()),
+            TopLevelStatement::Comment(..) => Ok(()),
             TopLevelStatement::Function(inner) => inner.shallow_check(ctx),
             TopLevelStatement::Constant(inner) => inner.shallow_check(ctx),
             TopLevelStatement::Declaration(inner) => inner.shallow_check(ctx),
@@ -74,13 +53,13 @@ impl ShallowCheck for TopLevelStatement<()> {
 impl TypedConstruct for TopLevelStatement<TypeInformation> {
     type Validated = TopLevelStatement<ValidatedTypeInformation>;
 
-    fn update_type(&mut self, type_id: Type) -> TypeResult<()> {
+    fn update_type(&mut self, _type_id: Type) -> TypeResult<()> {
         unreachable!()
     }
 
     fn validate(self) -> Result<Self::Validated, TypeValidationError> {
         match self {
-            TopLevelStatement::Comment(c) => Ok(TopLevelStatement::Comment(c)),
+            TopLevelStatement::Comment(c, position) => Ok(TopLevelStatement::Comment(c, position)),
             TopLevelStatement::Function(function) => {
                 Ok(TopLevelStatement::Function(function.validate()?))
             }
@@ -109,41 +88,22 @@ impl TypeCheckable for Statement<()> {
             Statement::If(if_exp) => Ok(Statement::If(if_exp.check(ctx)?)),
             Statement::WhileLoop(while_l) => Ok(Statement::WhileLoop(while_l.check(ctx)?)),
             Statement::Initialization(init) => Ok(Statement::Initialization(init.check(ctx)?)),
-            Statement::Constant(constant) => Ok(Statement::Constant(constant.check(ctx)?)),
+            Statement::Constant(constant) => {
+                // unlike toplevel constants, local ones are only visible after their definition
+                constant.shallow_check(ctx)?;
+                Ok(Statement::Constant(constant.check(ctx)?))
+            }
             Statement::Assignment(assign) => Ok(Statement::Assignment(assign.check(ctx)?)),
             Statement::Expression(exp) => Ok(Statement::Expression(exp.check(ctx)?)),
             Statement::YieldingExpression(exp) => {
                 Ok(Statement::YieldingExpression(exp.check(ctx)?))
             }
             Statement::Return(exp) => Ok(Statement::Return(exp.check(ctx)?)),
-            Statement::Comment(c) => Ok(Statement::Comment(c)),
+            Statement::Comment(c, position) => Ok(Statement::Comment(c, position)),
             Statement::Declaration(dec) => Ok(Statement::Declaration(dec.check(ctx)?)),
             Statement::StructDeclaration(dec) => Ok(Statement::StructDeclaration(dec.check(ctx)?)),
         }
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        match this {
-            Statement::Function(func) => Statement::Function(TypeCheckable::revert(func)),
-            Statement::If(if_exp) => Statement::If(TypeCheckable::revert(if_exp)),
-            Statement::WhileLoop(while_l) => Statement::WhileLoop(TypeCheckable::revert(while_l)),
-            Statement::Initialization(init) => {
-                Statement::Initialization(TypeCheckable::revert(init))
-            }
-            Statement::Constant(_) => todo!(),
-            Statement::Assignment(assign) => Statement::Assignment(TypeCheckable::revert(assign)),
-            Statement::Expression(expr) => Statement::Expression(TypeCheckable::revert(expr)),
-            Statement::YieldingExpression(expr) => {
-                Statement::YieldingExpression(TypeCheckable::revert(expr))
-            }
-            Statement::Return(expr) => Statement::Return(TypeCheckable::revert(expr)),
-            Statement::Comment(c) => Statement::Comment(c.to_owned()),
-            Statement::Declaration(dec) => Statement::Declaration(TypeCheckable::revert(dec)),
-            Statement::StructDeclaration(dec) => {
-                Statement::StructDeclaration(TypeCheckable::revert(dec))
-            }
-        }
-    }
 }
 
 impl TypedConstruct for Statement<TypeInformation> {
@@ -160,7 +120,7 @@ impl TypedConstruct for Statement<TypeInformation> {
             Statement::Expression(expr) => expr.update_type(type_id),
             Statement::YieldingExpression(expr) => expr.update_type(type_id),
             Statement::Return(expr) => expr.update_type(type_id),
-            Statement::Comment(_) => Ok(()),
+            Statement::Comment(..) => Ok(()),
             Statement::Declaration(dec) => dec.update_type(type_id),
             Statement::StructDeclaration(dec) => dec.update_type(type_id),
         }
@@ -181,7 +141,7 @@ impl TypedConstruct for Statement<TypeInformation> {
                 Statement::YieldingExpression(yielding_expression.validate()?),
             ),
             Statement::Return(expression) => Ok(Statement::Return(expression.validate()?)),
-            Statement::Comment(comment) => Ok(Statement::Comment(comment)),
+            Statement::Comment(comment, position) => Ok(Statement::Comment(comment, position)),
             Statement::Declaration(declaration) => {
                 Ok(Statement::Declaration(declaration.validate()?))
             }
@@ -52,26 +52,6 @@ impl TypeCheckable for StructDeclaration<()> {
             position: struct_position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let StructDeclaration {
-            id,
-            fields,
-            position,
-            ..
-        } = this;
-
-        StructDeclaration {
-            id: Id {
-                name: id.name.clone(),
-                info: (),
-                position: id.position.clone(),
-            },
-            fields: fields.iter().map(TypeCheckable::revert).collect::<Vec<_>>(),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for StructDeclaration<TypeInformation> {
@@ -168,26 +148,6 @@ impl TypeCheckable for StructFieldDeclaration<()> {
             position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let StructFieldDeclaration {
-            name,
-            type_name,
-            position,
-            ..
-        } = this;
-
-        StructFieldDeclaration {
-            name: Id {
-                name: name.name.clone(),
-                info: (),
-                position: name.position.clone(),
-            },
-            type_name: type_name.clone(),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for StructFieldDeclaration<TypeInformation> {
@@ -294,10 +254,7 @@ mod tests {
             ctx.scope.get_type("Foo"),
             Some(Type::Struct(
                 "Foo".into(),
-                vec![
-                    ("bar".into(), Type::Integer),
-                    ("baz".into(), Type::FloatingPoint)
-                ]
+                vec![("bar".into(), Type::I64), ("baz".into(), Type::F64)]
             ))
         );
 
@@ -365,7 +322,7 @@ mod tests {
                 "Foo".into(),
                 vec![
                     ("bar".into(), Type::Struct("BarStruct".into(), vec![])),
-                    ("baz".into(), Type::FloatingPoint)
+                    ("baz".into(), Type::F64)
                 ]
             ))
         );
@@ -4,10 +4,8 @@ use crate::typechecker::{TypeValidationError, TypedConstruct, ValidatedTypeInfor
 use crate::{
     parser::ast::WhileLoop,
     typechecker::{
-        context::Context,
-        error::{TypeCheckError, TypeMismatch},
-        types::Type,
-        TypeCheckable, TypeInformation, TypeResult,
+        context::Context, error::TypeCheckError, types::Type, TypeCheckable, TypeInformation,
+        TypeResult,
     },
 };
 
@@ -26,19 +24,10 @@ impl TypeCheckable for WhileLoop<()> {
 
         let condition = condition.check(ctx)?;
 
-        match &*condition.get_info().type_id.borrow() {
-            Some(Type::Boolean) => {}
-            Some(other) => {
-                return Err(TypeCheckError::TypeMismatch(
-                    TypeMismatch {
-                        expected: Type::Boolean,
-                        actual: other.clone(),
-                    },
-                    condition.position(),
-                ))
-            }
-            _ => {}
-        };
+        if let Some(condition_type) = { condition.get_info().type_id.borrow() }.clone() {
+            ctx.unify(&Type::Boolean, &condition_type)
+                .map_err(|mismatch| TypeCheckError::TypeMismatch(mismatch, condition.position()))?;
+        }
 
         Ok(WhileLoop {
             condition,
@@ -50,22 +39,6 @@ impl TypeCheckable for WhileLoop<()> {
             position,
         })
     }
-
-    fn revert(this: &Self::Typed) -> Self {
-        let WhileLoop {
-            condition,
-            block,
-            position,
-            ..
-        } = this;
-
-        WhileLoop {
-            condition: TypeCheckable::revert(condition),
-            block: TypeCheckable::revert(block),
-            info: (),
-            position: position.clone(),
-        }
-    }
 }
 
 impl TypedConstruct for WhileLoop<TypeInformation> {
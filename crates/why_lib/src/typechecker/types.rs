@@ -1,38 +1,295 @@
 use std::{borrow::Borrow, error::Error, fmt::Display};
 
-use crate::{lexer::Span, parser::ast::TypeName};
+use crate::{
+    lexer::Span,
+    parser::ast::{ArrayLength, TypeName},
+};
 
 use super::{
     context::Context,
-    error::{TypeCheckError, UndefinedType},
+    error::{NonConstantArrayLength, TypeCheckError, UndefinedType},
+    folding::ConstValue,
 };
 
-#[derive(Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Type {
-    Integer,
-    FloatingPoint,
+    Integer(IntegerType),
+    FloatingPoint(FloatType),
     Boolean,
     Character,
     String,
     Void,
     Unknown,
+    /// Type of expressions which never produce a value, e.g., calling `panic`. It fits wherever
+    /// a value of any type is expected.
+    Never,
     Reference(Box<Type>),
     Tuple(Vec<Type>),
+    /// Array of any length (i.e., a slice), e.g., `[i64]`.
     Array(Box<Type>),
+    /// Array whose length is known at compile time, e.g., `[i64; 4]`. It can be used wherever a
+    /// slice of the same element type is expected.
+    FixedArray(Box<Type>, usize),
+    /// Value which might be `none`, e.g., `i64?`. Optional types do not nest, i.e., `some(x)`
+    /// has the same type as `x`, if `x` is optional already.
+    Optional(Box<Type>),
+    /// Either a value or an error, e.g., `i64 ! str`.
+    Result(Box<Type>, Box<Type>),
     Struct(String, Vec<(String, Type)>),
     Function {
         params: Vec<Type>,
         return_value: Box<Type>,
     },
+    /// Placeholder for a type which is not known yet and has to be inferred, e.g., the type of a
+    /// lambda parameter.
+    Variable(usize),
+}
+
+/// Width and signedness of an integer type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum IntegerType {
+    I8,
+    I16,
+    I32,
+    I64,
+    U8,
+    U16,
+    U32,
+    U64,
+    /// Unsigned integer used for indices and lengths. It is as wide as `u64`.
+    Usize,
+}
+
+impl IntegerType {
+    /// Name of this type in source code, e.g., `u8`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            IntegerType::I8 => "i8",
+            IntegerType::I16 => "i16",
+            IntegerType::I32 => "i32",
+            IntegerType::I64 => "i64",
+            IntegerType::U8 => "u8",
+            IntegerType::U16 => "u16",
+            IntegerType::U32 => "u32",
+            IntegerType::U64 => "u64",
+            IntegerType::Usize => "usize",
+        }
+    }
+
+    /// Smallest value of this type.
+    pub fn min(&self) -> i128 {
+        match self {
+            IntegerType::I8 => i8::MIN.into(),
+            IntegerType::I16 => i16::MIN.into(),
+            IntegerType::I32 => i32::MIN.into(),
+            IntegerType::I64 => i64::MIN.into(),
+            IntegerType::U8
+            | IntegerType::U16
+            | IntegerType::U32
+            | IntegerType::U64
+            | IntegerType::Usize => 0,
+        }
+    }
+
+    /// Largest value of this type.
+    pub fn max(&self) -> i128 {
+        match self {
+            IntegerType::I8 => i8::MAX.into(),
+            IntegerType::I16 => i16::MAX.into(),
+            IntegerType::I32 => i32::MAX.into(),
+            IntegerType::I64 => i64::MAX.into(),
+            IntegerType::U8 => u8::MAX.into(),
+            IntegerType::U16 => u16::MAX.into(),
+            IntegerType::U32 => u32::MAX.into(),
+            IntegerType::U64 | IntegerType::Usize => u64::MAX.into(),
+        }
+    }
+
+    /// Whether the given value can be represented by this type.
+    pub fn contains(&self, value: i128) -> bool {
+        (self.min()..=self.max()).contains(&value)
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.min() < 0
+    }
+
+    /// Wrap the given value around into the range of this type (like `as` in Rust).
+    pub fn wrap(&self, value: i128) -> i128 {
+        let bits = match self {
+            IntegerType::I8 | IntegerType::U8 => 8,
+            IntegerType::I16 | IntegerType::U16 => 16,
+            IntegerType::I32 | IntegerType::U32 => 32,
+            IntegerType::I64 | IntegerType::U64 | IntegerType::Usize => 64,
+        };
+
+        let value = value.rem_euclid(1 << bits);
+        if value > self.max() {
+            value - (1 << bits)
+        } else {
+            value
+        }
+    }
+}
+
+/// Width of a floating point type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum FloatType {
+    F32,
+    F64,
+}
+
+impl FloatType {
+    /// Name of this type in source code, e.g., `f32`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FloatType::F32 => "f32",
+            FloatType::F64 => "f64",
+        }
+    }
+
+    /// Round the given value to the closest value of this type.
+    pub fn round(&self, value: f64) -> f64 {
+        match self {
+            FloatType::F32 => value as f32 as f64,
+            FloatType::F64 => value,
+        }
+    }
+
+    /// Largest magnitude up to which every integer can be represented exactly.
+    fn exact_integers(&self) -> i128 {
+        match self {
+            FloatType::F32 => 1 << f32::MANTISSA_DIGITS,
+            FloatType::F64 => 1 << f64::MANTISSA_DIGITS,
+        }
+    }
+}
+
+/// Kind of a conversion of a value to another type with `as`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conversion {
+    /// Every value is represented exactly, e.g., `'a' as i64`.
+    Lossless,
+    /// The value might change, e.g., `1.5 as i64` is truncated to `1`.
+    Lossy,
 }
 
 impl Type {
+    /// Type of integer literals whose type is not constrained otherwise.
+    pub const I64: Type = Type::Integer(IntegerType::I64);
+    /// Type of floating point literals whose type is not constrained otherwise.
+    pub const F64: Type = Type::FloatingPoint(FloatType::F64);
+    /// Type of indices into arrays.
+    pub const USIZE: Type = Type::Integer(IntegerType::Usize);
+
+    /// Optional type of the given type (which is the type itself, if it is optional already).
+    pub fn optional(inner: Type) -> Type {
+        match inner {
+            Type::Optional(_) => inner,
+            inner => Type::Optional(Box::new(inner)),
+        }
+    }
+
+    /// How a value of this type is converted to the given type with `as`. Returns `None`, if
+    /// there is no such conversion.
+    pub fn conversion(&self, target: &Type) -> Option<Conversion> {
+        let lossless = |lossless| {
+            if lossless {
+                Conversion::Lossless
+            } else {
+                Conversion::Lossy
+            }
+        };
+
+        match (self, target) {
+            (Type::Integer(from), Type::Integer(to)) => {
+                Some(lossless(to.min() <= from.min() && from.max() <= to.max()))
+            }
+            // e.g., large integers can not be represented exactly as floating point numbers
+            (Type::Integer(from), Type::FloatingPoint(to)) => Some(lossless(
+                -to.exact_integers() <= from.min() && from.max() <= to.exact_integers(),
+            )),
+            (Type::FloatingPoint(from), Type::FloatingPoint(to)) => {
+                Some(lossless(from == to || *to == FloatType::F64))
+            }
+            (Type::FloatingPoint(_), Type::Integer(_)) => Some(Conversion::Lossy),
+            (Type::Character, Type::Integer(to)) => {
+                Some(lossless(to.contains(u32::from(char::MAX).into())))
+            }
+            (Type::Integer(from), Type::Character) => Some(lossless(*from == IntegerType::U8)),
+            (Type::Character, Type::Character)
+            | (Type::Boolean, Type::Boolean)
+            | (Type::Boolean, Type::Integer(_)) => Some(Conversion::Lossless),
+            _ => None,
+        }
+    }
+
+    /// Type of an annotation (e.g., of a parameter). Unless the length of an array type is not
+    /// constant, any error is reported as the whole annotation being undefined.
+    pub fn from_annotation(type_name: &TypeName, ctx: &Context) -> Result<Type, TypeCheckError> {
+        Type::try_from((type_name, ctx)).map_err(|e| match e {
+            TypeCheckError::NonConstantArrayLength(..) => e,
+            _ => TypeCheckError::UndefinedType(
+                UndefinedType {
+                    type_name: type_name.clone(),
+                },
+                type_name.position(),
+            ),
+        })
+    }
+
+    /// Whether this type still contains type variables which have not been inferred.
+    pub fn has_variables(&self) -> bool {
+        match self {
+            Type::Variable(_) => true,
+            Type::Reference(inner)
+            | Type::Array(inner)
+            | Type::FixedArray(inner, _)
+            | Type::Optional(inner) => inner.has_variables(),
+            Type::Result(value, error) => value.has_variables() || error.has_variables(),
+            Type::Tuple(types) => types.iter().any(Type::has_variables),
+            Type::Function {
+                params,
+                return_value,
+            } => params.iter().any(Type::has_variables) || return_value.has_variables(),
+            _ => false,
+        }
+    }
+
+    /// Whether the given type variable occurs within this type.
+    pub fn contains_variable(&self, variable: usize) -> bool {
+        match self {
+            Type::Variable(other) => *other == variable,
+            Type::Reference(inner)
+            | Type::Array(inner)
+            | Type::FixedArray(inner, _)
+            | Type::Optional(inner) => inner.contains_variable(variable),
+            Type::Result(value, error) => {
+                value.contains_variable(variable) || error.contains_variable(variable)
+            }
+            Type::Tuple(types) => types.iter().any(|t| t.contains_variable(variable)),
+            Type::Function {
+                params,
+                return_value,
+            } => {
+                params.iter().any(|t| t.contains_variable(variable))
+                    || return_value.contains_variable(variable)
+            }
+            _ => false,
+        }
+    }
+
     pub fn does_eq(&self, other: &Self) -> bool {
         match (self, other) {
+            (Self::Integer(l0), Self::Integer(r0)) => l0 == r0,
+            (Self::FloatingPoint(l0), Self::FloatingPoint(r0)) => l0 == r0,
             (Self::Reference(l0), r0) => l0.as_ref() == r0,
             (l0, Self::Reference(r0)) => l0 == r0.as_ref(),
             (Self::Tuple(l0), Self::Tuple(r0)) => l0 == r0,
             (Self::Array(l0), Self::Array(r0)) => l0 == r0,
+            (Self::FixedArray(l0, l1), Self::FixedArray(r0, r1)) => l0 == r0 && l1 == r1,
+            (Self::Optional(l0), Self::Optional(r0)) => l0 == r0,
+            (Self::Result(l0, l1), Self::Result(r0, r1)) => l0 == r0 && l1 == r1,
             (Self::Struct(l0, l1), Self::Struct(r0, r1)) => l0 == r0 && l1 == r1,
             (
                 Self::Function {
@@ -44,36 +301,50 @@ impl Type {
                     return_value: r_return_value,
                 },
             ) => l_params == r_params && l_return_value == r_return_value,
+            (Self::Variable(l0), Self::Variable(r0)) => l0 == r0,
             _ => core::mem::discriminant(self) == core::mem::discriminant(other),
         }
     }
 }
 
-impl std::fmt::Debug for Type {
+/// Display a type the way it would be written in source code.
+impl Display for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let join = |types: &[Type]| {
+            types
+                .iter()
+                .map(|t| t.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        // types which would otherwise swallow a trailing `?` or `! E`
+        let nested = |t: &Type| match t {
+            Self::Function { .. } | Self::Result(..) => format!("({t})"),
+            t => t.to_string(),
+        };
+
         match self {
-            Self::Integer => write!(f, "Integer"),
-            Self::FloatingPoint => write!(f, "FloatingPoint"),
-            Self::Boolean => write!(f, "Boolean"),
-            Self::Void => write!(f, "Void"),
-            Self::Character => write!(f, "Character"),
-            Self::String => write!(f, "String"),
-            Self::Unknown => write!(f, "Unknown"),
-            Self::Reference(arg0) => f.debug_tuple("Reference").field(arg0).finish(),
-            Self::Tuple(arg0) => f.debug_tuple("Tuple").field(arg0).finish(),
-            Self::Array(arg0) => f.debug_tuple("Array").field(arg0).finish(),
-            Self::Struct(arg0, _) => f.write_fmt(format_args!("struct {arg0}")),
+            Self::Integer(integer_type) => write!(f, "{}", integer_type.name()),
+            Self::FloatingPoint(float_type) => write!(f, "{}", float_type.name()),
+            Self::Boolean => write!(f, "bool"),
+            Self::Void => write!(f, "void"),
+            Self::Character => write!(f, "char"),
+            Self::String => write!(f, "str"),
+            Self::Unknown => write!(f, "unknown"),
+            Self::Never => write!(f, "never"),
+            Self::Reference(inner) => write!(f, "&{inner}"),
+            Self::Tuple(types) => write!(f, "({})", join(types)),
+            Self::Array(inner) => write!(f, "[{inner}]"),
+            Self::FixedArray(inner, length) => write!(f, "[{inner}; {length}]"),
+            Self::Optional(inner) => write!(f, "{}?", nested(inner)),
+            Self::Result(value, error) => write!(f, "{} ! {}", nested(value), nested(error)),
+            Self::Struct(name, _) => write!(f, "{name}"),
             Self::Function {
                 params,
                 return_value,
-            } => f.write_fmt(format_args!(
-                "({}) -> {return_value:?}",
-                params
-                    .iter()
-                    .map(|i| format!("{i:?}"))
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            )),
+            } => write!(f, "({}) -> {return_value}", join(params)),
+            Self::Variable(_) => write!(f, "_"),
         }
     }
 }
@@ -86,7 +357,7 @@ pub struct TypeFromTypeNameError {
 impl Display for TypeFromTypeNameError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!(
-            "Failed to convert '{:?}' to a qualified type",
+            "Failed to convert '{}' to a qualified type",
             self.source
         ))
     }
@@ -115,8 +386,17 @@ where
         let value = value.into();
         match &value {
             TypeName::Literal(lit, span) => match lit.as_str() {
-                "i64" => Ok(Type::Integer),
-                "f64" => Ok(Type::FloatingPoint),
+                "i8" => Ok(Type::Integer(IntegerType::I8)),
+                "i16" => Ok(Type::Integer(IntegerType::I16)),
+                "i32" => Ok(Type::Integer(IntegerType::I32)),
+                "i64" => Ok(Type::Integer(IntegerType::I64)),
+                "u8" => Ok(Type::Integer(IntegerType::U8)),
+                "u16" => Ok(Type::Integer(IntegerType::U16)),
+                "u32" => Ok(Type::Integer(IntegerType::U32)),
+                "u64" => Ok(Type::Integer(IntegerType::U64)),
+                "usize" => Ok(Type::Integer(IntegerType::Usize)),
+                "f32" => Ok(Type::FloatingPoint(FloatType::F32)),
+                "f64" => Ok(Type::FloatingPoint(FloatType::F64)),
                 "void" => Ok(Type::Void),
                 "bool" => Ok(Type::Boolean),
                 "char" => Ok(Type::Character),
@@ -159,9 +439,35 @@ where
             TypeName::Array(inner, _) => {
                 Ok(Type::Array(Box::new((inner.borrow(), ctx).try_into()?)))
             }
+            TypeName::FixedArray(inner, length, _) => {
+                let length = match length {
+                    ArrayLength::Literal(length, _) => *length as usize,
+                    ArrayLength::Constant(name, position) => {
+                        match ctx.scope.get_constant_value(name) {
+                            Some(ConstValue::Integer(length)) if length >= 0 => length as usize,
+                            _ => {
+                                return Err(TypeCheckError::NonConstantArrayLength(
+                                    NonConstantArrayLength,
+                                    position.clone(),
+                                ))
+                            }
+                        }
+                    }
+                };
+
+                Ok(Type::FixedArray(
+                    Box::new((inner.borrow(), ctx).try_into()?),
+                    length,
+                ))
+            }
             TypeName::Reference(inner, _) => {
                 Ok(Type::Reference(Box::new((inner.borrow(), ctx).try_into()?)))
             }
+            TypeName::Optional(inner, _) => Ok(Type::optional((inner.borrow(), ctx).try_into()?)),
+            TypeName::Result(value, error, _) => Ok(Type::Result(
+                Box::new((value.borrow(), ctx).try_into()?),
+                Box::new((error.borrow(), ctx).try_into()?),
+            )),
         }
     }
 }
@@ -172,8 +478,13 @@ mod tests {
 
     use crate::{
         lexer::Span,
-        parser::ast::TypeName,
-        typechecker::{context::Context, types::Type},
+        parser::ast::{ArrayLength, TypeName},
+        typechecker::{
+            context::Context,
+            error::{NonConstantArrayLength, TypeCheckError},
+            folding::ConstValue,
+            types::{Conversion, FloatType, IntegerType, Type},
+        },
     };
 
     #[test]
@@ -182,32 +493,61 @@ mod tests {
 
         assert_eq!(
             Type::try_from((TypeName::Literal("i64".into(), Span::default()), &ctx)),
-            Ok(Type::Integer)
+            Ok(Type::I64)
         );
 
         assert_eq!(
             Type::try_from((TypeName::Literal("f64".into(), Span::default()), &ctx)),
-            Ok(Type::FloatingPoint)
+            Ok(Type::F64)
+        );
+    }
+
+    #[test]
+    fn test_sized_literals() {
+        let ctx = Context::default();
+
+        assert_eq!(
+            Type::try_from((TypeName::Literal("u8".into(), Span::default()), &ctx)),
+            Ok(Type::Integer(IntegerType::U8))
+        );
+        assert_eq!(
+            Type::try_from((TypeName::Literal("usize".into(), Span::default()), &ctx)),
+            Ok(Type::USIZE)
+        );
+        assert_eq!(
+            Type::try_from((TypeName::Literal("f32".into(), Span::default()), &ctx)),
+            Ok(Type::FloatingPoint(FloatType::F32))
         );
     }
 
     #[test]
     fn test_invalid_literal() {
         let ctx = Context::default();
-        assert!(Type::try_from((TypeName::Literal("f32".into(), Span::default()), &ctx)).is_err());
-        assert!(Type::try_from((TypeName::Literal("i32".into(), Span::default()), &ctx)).is_err());
+        assert!(Type::try_from((TypeName::Literal("i128".into(), Span::default()), &ctx)).is_err());
         assert!(Type::try_from((TypeName::Literal("foo".into(), Span::default()), &ctx)).is_err());
     }
 
+    #[test]
+    fn test_integer_ranges() {
+        assert!(IntegerType::I8.contains(-128));
+        assert!(!IntegerType::I8.contains(128));
+        assert!(IntegerType::U64.contains(u64::MAX.into()));
+        assert!(!IntegerType::U16.contains(-1));
+
+        assert_eq!(IntegerType::U8.wrap(256 + 42), 42);
+        assert_eq!(IntegerType::I8.wrap(255), -1);
+        assert_eq!(IntegerType::U32.wrap(-1), u32::MAX.into());
+    }
+
     #[test]
     fn test_custom_type() -> Result<(), Box<dyn Error>> {
         let mut ctx = Context::default();
         ctx.scope
-            .add_type("Foo", Type::Array(Box::new(Type::Integer)))?;
+            .add_type("Foo", Type::Array(Box::new(Type::I64)))?;
 
         assert_eq!(
             Type::try_from((TypeName::Literal("Foo".into(), Span::default()), &ctx)),
-            Ok(Type::Array(Box::new(Type::Integer)))
+            Ok(Type::Array(Box::new(Type::I64)))
         );
 
         Ok(())
@@ -225,7 +565,7 @@ mod tests {
                 ),
                 &ctx
             )),
-            Ok(Type::Reference(Box::new(Type::Integer)))
+            Ok(Type::Reference(Box::new(Type::I64)))
         );
     }
 
@@ -244,7 +584,7 @@ mod tests {
                 ),
                 &ctx
             )),
-            Ok(Type::Tuple(vec![Type::Integer, Type::FloatingPoint]))
+            Ok(Type::Tuple(vec![Type::I64, Type::F64]))
         )
     }
 
@@ -260,10 +600,102 @@ mod tests {
                 ),
                 &ctx
             )),
-            Ok(Type::Array(Box::new(Type::Integer)))
+            Ok(Type::Array(Box::new(Type::I64)))
         )
     }
 
+    #[test]
+    fn test_fixed_array() -> Result<(), Box<dyn Error>> {
+        let mut ctx = Context::default();
+        ctx.scope.add_constant("N", Type::USIZE)?;
+        ctx.scope.set_constant_value("N", ConstValue::Integer(3));
+        ctx.scope.add_constant("M", Type::USIZE)?;
+
+        let fixed_array = |length| {
+            TypeName::FixedArray(
+                Box::new(TypeName::Literal("i64".into(), Span::default())),
+                length,
+                Span::default(),
+            )
+        };
+
+        assert_eq!(
+            Type::try_from((fixed_array(ArrayLength::Literal(4, Span::default())), &ctx)),
+            Ok(Type::FixedArray(Box::new(Type::I64), 4))
+        );
+        assert_eq!(
+            Type::try_from((
+                fixed_array(ArrayLength::Constant("N".into(), Span::default())),
+                &ctx
+            )),
+            Ok(Type::FixedArray(Box::new(Type::I64), 3))
+        );
+        assert_eq!(
+            Type::try_from((
+                fixed_array(ArrayLength::Constant("M".into(), Span::default())),
+                &ctx
+            )),
+            Err(TypeCheckError::NonConstantArrayLength(
+                NonConstantArrayLength,
+                Span::default()
+            ))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_optional() {
+        let ctx = Context::default();
+
+        let optional = TypeName::Optional(
+            Box::new(TypeName::Literal("i64".into(), Span::default())),
+            Span::default(),
+        );
+
+        assert_eq!(
+            Type::try_from((optional, &ctx)),
+            Ok(Type::Optional(Box::new(Type::I64)))
+        );
+
+        // optional types do not nest
+        assert_eq!(
+            Type::optional(Type::optional(Type::I64)),
+            Type::Optional(Box::new(Type::I64))
+        );
+
+        assert_eq!(Type::optional(Type::I64).to_string(), "i64?");
+        assert_eq!(
+            Type::optional(Type::Function {
+                params: vec![],
+                return_value: Box::new(Type::Void)
+            })
+            .to_string(),
+            "(() -> void)?"
+        );
+    }
+
+    #[test]
+    fn test_result() {
+        let ctx = Context::default();
+
+        let result = TypeName::Result(
+            Box::new(TypeName::Literal("i64".into(), Span::default())),
+            Box::new(TypeName::Literal("str".into(), Span::default())),
+            Span::default(),
+        );
+
+        let result = Type::try_from((result, &ctx));
+        assert_eq!(
+            result,
+            Ok(Type::Result(Box::new(Type::I64), Box::new(Type::String)))
+        );
+
+        let result = result.unwrap();
+        assert_eq!(result.to_string(), "i64 ! str");
+        assert_eq!(Type::optional(result).to_string(), "(i64 ! str)?");
+    }
+
     #[test]
     fn test_function() {
         let ctx = Context::default();
@@ -280,9 +712,66 @@ mod tests {
         assert_eq!(
             Type::try_from((func, &ctx)),
             Ok(Type::Function {
-                params: vec![Type::Integer, Type::FloatingPoint],
-                return_value: Box::new(Type::FloatingPoint)
+                params: vec![Type::I64, Type::F64],
+                return_value: Box::new(Type::F64)
             })
         )
     }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(
+            Type::Character.conversion(&Type::I64),
+            Some(Conversion::Lossless)
+        );
+        assert_eq!(Type::F64.conversion(&Type::I64), Some(Conversion::Lossy));
+        assert_eq!(
+            Type::Integer(IntegerType::U8).conversion(&Type::Integer(IntegerType::I16)),
+            Some(Conversion::Lossless)
+        );
+        assert_eq!(
+            Type::Integer(IntegerType::I16).conversion(&Type::Integer(IntegerType::U16)),
+            Some(Conversion::Lossy)
+        );
+        assert_eq!(
+            Type::Integer(IntegerType::I32).conversion(&Type::F64),
+            Some(Conversion::Lossless)
+        );
+        assert_eq!(
+            Type::FloatingPoint(FloatType::F32).conversion(&Type::F64),
+            Some(Conversion::Lossless)
+        );
+        assert_eq!(
+            Type::Integer(IntegerType::U8).conversion(&Type::Character),
+            Some(Conversion::Lossless)
+        );
+        assert_eq!(Type::I64.conversion(&Type::Boolean), None);
+        assert_eq!(Type::String.conversion(&Type::String), None);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Type::I64.to_string(), "i64");
+        assert_eq!(Type::Integer(IntegerType::U16).to_string(), "u16");
+        assert_eq!(
+            Type::Reference(Box::new(Type::Array(Box::new(Type::Character)))).to_string(),
+            "&[char]"
+        );
+        assert_eq!(
+            Type::Function {
+                params: vec![Type::I64, Type::Struct("Foo".into(), vec![])],
+                return_value: Box::new(Type::Tuple(vec![Type::Boolean, Type::String])),
+            }
+            .to_string(),
+            "(i64, Foo) -> (bool, str)"
+        );
+        assert_eq!(
+            Type::Function {
+                params: vec![Type::Variable(0)],
+                return_value: Box::new(Type::I64),
+            }
+            .to_string(),
+            "(_) -> i64"
+        );
+    }
 }
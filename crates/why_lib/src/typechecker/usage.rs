@@ -0,0 +1,349 @@
+//! Analysis of which bindings of a program are actually used. Bindings whose name starts with an
+//! underscore are never reported.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    lexer::Span,
+    parser::ast::{
+        Array, Assignment, BinaryExpression, Block, Constant, Declaration, Expression, Function,
+        FunctionParameter, Id, If, Initialisation, Instance, LValue, Lambda, LambdaParameter,
+        Postfix, Prefix, Statement, StructFieldInitialisation, StructInitialisation,
+        TopLevelStatement, WhileLoop,
+    },
+};
+
+use super::TypeCheckWarning;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BindingKind {
+    Variable {
+        mutable: bool,
+    },
+    Parameter,
+    /// Bindings which are never reported, e.g., lambda parameters or nested functions.
+    Other,
+}
+
+#[derive(Debug, Clone)]
+struct Binding {
+    name: String,
+    position: Span,
+    kind: BindingKind,
+    used: bool,
+    mutated: bool,
+}
+
+#[derive(Debug, Default)]
+struct Usage {
+    scopes: Vec<Vec<Binding>>,
+    /// Toplevel items used by the item which is currently analysed.
+    globals: HashSet<String>,
+    warnings: Vec<TypeCheckWarning>,
+}
+
+/// Find unused variables, parameters and mutability as well as toplevel functions which are never
+/// called (directly or indirectly) from `main` or a test.
+pub fn unused_bindings<T: Clone>(statements: &[TopLevelStatement<T>]) -> Vec<TypeCheckWarning> {
+    let mut usage = Usage::default();
+
+    // toplevel items by the toplevel items they use
+    let mut dependencies = HashMap::new();
+    let mut roots = vec![];
+
+    for statement in statements {
+        match statement {
+            TopLevelStatement::Function(function) => {
+                usage.function(function);
+
+                let name = function.id.name.clone();
+                if name == "main" || function.has_attribute("test") {
+                    roots.push(name.clone());
+                }
+                dependencies.insert(name, std::mem::take(&mut usage.globals));
+            }
+            TopLevelStatement::Constant(Constant { id, value, .. }) => {
+                usage.expression(value);
+
+                roots.push(id.name.clone());
+                dependencies.insert(id.name.clone(), std::mem::take(&mut usage.globals));
+            }
+            TopLevelStatement::Instance(Instance {
+                name, functions, ..
+            }) => {
+                // methods can not be traced back to their calls, so they are always considered
+                // to be used
+                for function in functions {
+                    usage.function(function);
+                }
+
+                let name = format!("instance {name}");
+                roots.push(name.clone());
+                dependencies.insert(name, std::mem::take(&mut usage.globals));
+            }
+            TopLevelStatement::Comment(..)
+            | TopLevelStatement::Declaration(_)
+            | TopLevelStatement::StructDeclaration(_) => {}
+        }
+    }
+
+    let mut reachable = HashSet::new();
+    while let Some(name) = roots.pop() {
+        if !reachable.insert(name.clone()) {
+            continue;
+        }
+
+        if let Some(used) = dependencies.get(&name) {
+            roots.extend(used.iter().cloned());
+        }
+    }
+
+    for statement in statements {
+        let TopLevelStatement::Function(Function { id, .. }) = statement else {
+            continue;
+        };
+
+        if !reachable.contains(&id.name) && !id.name.starts_with('_') {
+            usage.warnings.push(TypeCheckWarning::DeadFunction(
+                id.name.clone(),
+                id.position.clone(),
+            ));
+        }
+    }
+
+    usage.warnings
+}
+
+impl Usage {
+    fn bind<T>(&mut self, id: &Id<T>, kind: BindingKind) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push(Binding {
+                name: id.name.clone(),
+                position: id.position.clone(),
+                kind,
+                used: false,
+                mutated: false,
+            });
+        }
+    }
+
+    fn binding(&mut self, name: &str) -> Option<&mut Binding> {
+        self.scopes
+            .iter_mut()
+            .rev()
+            .flat_map(|scope| scope.iter_mut().rev())
+            .find(|binding| binding.name == name)
+    }
+
+    fn use_name(&mut self, name: &str) {
+        match self.binding(name) {
+            Some(binding) => binding.used = true,
+            None => {
+                self.globals.insert(name.to_string());
+            }
+        }
+    }
+
+    fn enter_scope(&mut self) {
+        self.scopes.push(vec![]);
+    }
+
+    fn exit_scope(&mut self) {
+        let Some(scope) = self.scopes.pop() else {
+            return;
+        };
+
+        for Binding {
+            name,
+            position,
+            kind,
+            used,
+            mutated,
+        } in scope
+        {
+            if name.starts_with('_') {
+                continue;
+            }
+
+            let warning = match kind {
+                BindingKind::Variable { .. } if !used => TypeCheckWarning::UnusedVariable,
+                BindingKind::Variable { mutable: true } if !mutated => TypeCheckWarning::UnusedMut,
+                BindingKind::Parameter if !used => TypeCheckWarning::UnusedParameter,
+                _ => continue,
+            };
+
+            self.warnings.push(warning(name, position));
+        }
+    }
+
+    fn function<T: Clone>(&mut self, function: &Function<T>) {
+        let Function {
+            parameters,
+            statements,
+            ..
+        } = function;
+
+        self.enter_scope();
+        for FunctionParameter { name, .. } in parameters {
+            self.bind(name, BindingKind::Parameter);
+        }
+        self.statements(statements);
+        self.exit_scope();
+    }
+
+    fn block<T: Clone>(&mut self, statements: &[Statement<T>]) {
+        self.enter_scope();
+        self.statements(statements);
+        self.exit_scope();
+    }
+
+    fn statements<T: Clone>(&mut self, statements: &[Statement<T>]) {
+        for statement in statements {
+            self.statement(statement);
+        }
+    }
+
+    fn statement<T: Clone>(&mut self, statement: &Statement<T>) {
+        match statement {
+            Statement::Function(function) => {
+                self.bind(&function.id, BindingKind::Other);
+                self.function(function);
+            }
+            Statement::If(if_expression) => self.if_expression(if_expression),
+            Statement::WhileLoop(WhileLoop {
+                condition, block, ..
+            }) => {
+                self.expression(condition);
+                self.block(&block.statements);
+            }
+            Statement::Initialization(Initialisation {
+                id, mutable, value, ..
+            }) => {
+                if let Some(value) = value {
+                    self.expression(value);
+                }
+                self.bind(id, BindingKind::Variable { mutable: *mutable });
+            }
+            Statement::Constant(Constant { id, value, .. }) => {
+                self.expression(value);
+                self.bind(id, BindingKind::Other);
+            }
+            Statement::Assignment(Assignment { lvalue, rvalue, .. }) => {
+                self.expression(rvalue);
+
+                if let LValue::Postfix(postfix) = lvalue {
+                    self.postfix(postfix);
+                }
+
+                if let Some(binding) = self.binding(&lvalue.get_original_variable_name().name) {
+                    binding.mutated = true;
+                }
+            }
+            Statement::Expression(expression)
+            | Statement::YieldingExpression(expression)
+            | Statement::Return(expression) => self.expression(expression),
+            Statement::Declaration(Declaration { name, .. }) => self.bind(name, BindingKind::Other),
+            Statement::Comment(..) | Statement::StructDeclaration(_) => {}
+        }
+    }
+
+    fn if_expression<T: Clone>(&mut self, if_expression: &If<T>) {
+        let If {
+            condition,
+            statements,
+            else_statements,
+            ..
+        } = if_expression;
+
+        self.expression(condition);
+        self.block(statements);
+        self.block(else_statements);
+    }
+
+    fn expression<T: Clone>(&mut self, expression: &Expression<T>) {
+        match expression {
+            Expression::Id(Id { name, .. }) => self.use_name(name),
+            Expression::Num(_) | Expression::Character(_) | Expression::AstString(_) => {}
+            Expression::Function(function) => self.function(function),
+            Expression::Lambda(Lambda {
+                parameters,
+                expression,
+                ..
+            }) => {
+                self.enter_scope();
+                for LambdaParameter { name, .. } in parameters {
+                    self.bind(name, BindingKind::Other);
+                }
+                self.expression(expression);
+                self.exit_scope();
+            }
+            Expression::If(if_expression) => self.if_expression(if_expression),
+            Expression::Block(Block { statements, .. }) => self.block(statements),
+            Expression::Parens(expression)
+            | Expression::Prefix(Prefix::Negation {
+                expr: expression, ..
+            })
+            | Expression::Prefix(Prefix::Minus {
+                expr: expression, ..
+            }) => self.expression(expression),
+            Expression::Postfix(postfix) => self.postfix(postfix),
+            Expression::Binary(binary) => {
+                let BinaryExpression { left, right, .. } = binary.as_ref();
+
+                self.expression(left);
+                self.expression(right);
+            }
+            Expression::Array(Array::Literal { values, .. }) => {
+                for value in values {
+                    self.expression(value);
+                }
+            }
+            Expression::Array(Array::Default {
+                initial_value,
+                length,
+                ..
+            }) => {
+                self.expression(initial_value);
+                self.expression(length);
+            }
+            Expression::StructInitialisation(StructInitialisation { fields, .. }) => {
+                for StructFieldInitialisation { value, .. } in fields {
+                    self.expression(value);
+                }
+            }
+            Expression::Interpolation(interpolation) => {
+                for expression in interpolation.expressions() {
+                    self.expression(expression);
+                }
+            }
+        }
+    }
+
+    fn postfix<T: Clone>(&mut self, postfix: &Postfix<T>) {
+        match postfix {
+            Postfix::Call { expr, args, .. } => {
+                self.expression(expr);
+                for arg in args {
+                    self.expression(arg);
+                }
+
+                // a method might modify the instance it is called on
+                if let Expression::Postfix(Postfix::PropertyAccess { expr, .. }) = expr.as_ref() {
+                    if let Expression::Id(Id { name, .. }) = expr.as_ref() {
+                        if let Some(binding) = self.binding(name) {
+                            binding.mutated = true;
+                        }
+                    }
+                }
+            }
+            Postfix::Index { expr, index, .. } => {
+                self.expression(expr);
+                self.expression(index);
+            }
+            Postfix::PropertyAccess { expr, .. }
+            | Postfix::OptionalPropertyAccess { expr, .. }
+            | Postfix::Propagate { expr, .. }
+            | Postfix::Cast { expr, .. } => self.expression(expr),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::fmt::Display;
+
+use crate::{diagnostic::Diagnostic, lexer::Span};
+
+use super::Type;
+
+/// Problem in a program which typechecks, but is most likely not what was intended.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TypeCheckWarning {
+    /// A statement which can never be executed, e.g., because it follows a `return`.
+    UnreachableCode(Span),
+    UnusedVariable(String, Span),
+    /// A mutable variable which is never assigned after its initialisation.
+    UnusedMut(String, Span),
+    UnusedParameter(String, Span),
+    /// A toplevel function which is neither called from `main` nor from any test.
+    DeadFunction(String, Span),
+    /// A conversion with `as` which might change the value, e.g., from `f64` to `i64`.
+    LossyCast(Type, Type, Span),
+}
+
+impl TypeCheckWarning {
+    /// Stable code identifying the kind of this warning.
+    pub fn code(&self) -> &'static str {
+        match self {
+            TypeCheckWarning::UnreachableCode(..) => "W0001",
+            TypeCheckWarning::UnusedVariable(..) => "W0002",
+            TypeCheckWarning::UnusedMut(..) => "W0003",
+            TypeCheckWarning::UnusedParameter(..) => "W0004",
+            TypeCheckWarning::DeadFunction(..) => "W0005",
+            TypeCheckWarning::LossyCast(..) => "W0006",
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            TypeCheckWarning::UnreachableCode(span)
+            | TypeCheckWarning::UnusedVariable(_, span)
+            | TypeCheckWarning::UnusedMut(_, span)
+            | TypeCheckWarning::UnusedParameter(_, span)
+            | TypeCheckWarning::DeadFunction(_, span)
+            | TypeCheckWarning::LossyCast(_, _, span) => span.clone(),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            TypeCheckWarning::UnreachableCode(..) => "Unreachable code".into(),
+            TypeCheckWarning::UnusedVariable(name, _) => format!("Unused variable '{name}'"),
+            TypeCheckWarning::UnusedMut(name, _) => {
+                format!("Variable '{name}' does not need to be mutable")
+            }
+            TypeCheckWarning::UnusedParameter(name, _) => format!("Unused parameter '{name}'"),
+            TypeCheckWarning::DeadFunction(name, _) => format!("Function '{name}' is never used"),
+            TypeCheckWarning::LossyCast(from, to, _) => {
+                format!("Cast from '{from}' to '{to}' might change the value")
+            }
+        }
+    }
+
+    pub fn diagnostic(&self) -> Diagnostic {
+        let diagnostic = Diagnostic::warning(self.code(), self.message());
+        let span = self.span();
+
+        match self {
+            TypeCheckWarning::UnreachableCode(..) => diagnostic
+                .with_primary(span, Some("this statement is never executed".into()))
+                .with_note("every path before it returns"),
+            TypeCheckWarning::UnusedMut(..) => {
+                diagnostic.with_primary(span, Some("never assigned after its declaration".into()))
+            }
+            TypeCheckWarning::UnusedVariable(..)
+            | TypeCheckWarning::UnusedParameter(..)
+            | TypeCheckWarning::DeadFunction(..) => diagnostic
+                .with_primary(span, None)
+                .with_note("prefix the name with an underscore to silence this warning"),
+            TypeCheckWarning::LossyCast(from, to, _) => {
+                let reason = match (from, to) {
+                    (Type::Integer(_), Type::FloatingPoint(_)) => {
+                        "large integers can not be represented exactly".into()
+                    }
+                    (Type::FloatingPoint(_), Type::Integer(_)) => {
+                        "the fractional part is truncated and large values saturate".into()
+                    }
+                    (Type::FloatingPoint(_), _) => format!("the value is rounded to '{to}'"),
+                    (_, Type::Integer(_)) => format!("values out of range of '{to}' wrap around"),
+                    _ => "integers which are no valid code point become '\u{FFFD}'".into(),
+                };
+                diagnostic.with_primary(span, Some(reason))
+            }
+        }
+    }
+}
+
+impl Display for TypeCheckWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.diagnostic().to_string())
+    }
+}
@@ -1,8 +1,11 @@
 use anyhow::Result;
-use y_lang::{VCArgs, compile_file};
+use y_lang::{Command, VCArgs, compile_file, run_tests};
 
 fn main() -> Result<()> {
     let args = VCArgs::init();
 
-    compile_file(args)
+    match &args.command {
+        Some(Command::Test { paths }) => run_tests(&args, paths),
+        None => compile_file(args),
+    }
 }
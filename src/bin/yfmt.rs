@@ -0,0 +1,101 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::PathBuf,
+    process,
+};
+
+use anyhow::Result;
+use clap::Parser;
+use why_lib::formatter::{FormatConfig, format};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about)]
+pub struct FmtArgs {
+    /// The files to format. If none are given, the source is read from stdin and the formatted
+    /// source is written to stdout.
+    #[arg(index = 1)]
+    pub files: Vec<PathBuf>,
+
+    /// Do not write anything, but list all files which are not formatted and fail if there are
+    /// any.
+    #[arg(long)]
+    pub check: bool,
+
+    /// Number of spaces per level of indentation.
+    #[arg(long, default_value_t = 4)]
+    pub indent_width: usize,
+
+    /// Maximum width of a line.
+    #[arg(long, default_value_t = 100)]
+    pub max_width: usize,
+
+    /// Indent with tabs instead of spaces.
+    #[arg(long)]
+    pub use_tabs: bool,
+}
+
+fn main() -> Result<()> {
+    let args = FmtArgs::parse();
+
+    let config = FormatConfig {
+        indent_width: args.indent_width,
+        use_tabs: args.use_tabs,
+        max_width: args.max_width,
+    };
+
+    if args.files.is_empty() {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+
+        let formatted = match format(&input, &config) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("{e}");
+                process::exit(-1);
+            }
+        };
+
+        if args.check {
+            if formatted != input {
+                eprintln!("<stdin> is not formatted");
+                process::exit(1);
+            }
+        } else {
+            print!("{formatted}");
+        }
+
+        return Ok(());
+    }
+
+    let mut unformatted = false;
+
+    for file in &args.files {
+        let input = fs::read_to_string(file)?;
+
+        let formatted = match format(&input, &config) {
+            Ok(formatted) => formatted,
+            Err(e) => {
+                eprintln!("{}: {e}", file.display());
+                process::exit(-1);
+            }
+        };
+
+        if formatted == input {
+            continue;
+        }
+
+        if args.check {
+            println!("{}", file.display());
+            unformatted = true;
+        } else {
+            fs::write(file, formatted)?;
+        }
+    }
+
+    if unformatted {
+        process::exit(1);
+    }
+
+    Ok(())
+}
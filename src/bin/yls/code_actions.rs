@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use tower_lsp_server::lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, NumberOrString, Range, TextEdit,
+    Uri, WorkspaceEdit,
+};
+use why_lib::lexer::Lexer;
+use why_lib::parser;
+use why_lib::source_map::FileId;
+use why_lib::typechecker::TypeChecker;
+
+use crate::span_to_range;
+
+/// Offer quick fixes for all typechecker errors within the given range which have an obvious
+/// fix. Diagnostics reported by the client are attached to the fixes resolving them.
+pub fn code_actions(
+    uri: &Uri,
+    file: FileId,
+    range: Range,
+    diagnostics: &[Diagnostic],
+) -> Vec<CodeActionOrCommand> {
+    let Ok(tokens) = Lexer::for_file(file).lex() else {
+        return vec![];
+    };
+    let Ok(statements) = parser::parse(&mut tokens.into()) else {
+        return vec![];
+    };
+    let Err(errors) = TypeChecker::new(statements).check_all() else {
+        return vec![];
+    };
+
+    errors
+        .iter()
+        .filter(|error| {
+            let error_range = span_to_range(&error.span());
+            error_range.start <= range.end && range.start <= error_range.end
+        })
+        .filter_map(|error| {
+            let fix = error.fix()?;
+            let error_range = span_to_range(&error.span());
+            let code = NumberOrString::String(error.code().into());
+
+            let resolved = diagnostics
+                .iter()
+                .filter(|diagnostic| {
+                    diagnostic.range == error_range && diagnostic.code.as_ref() == Some(&code)
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+
+            let edit = TextEdit {
+                range: span_to_range(&fix.span),
+                new_text: fix.replacement,
+            };
+
+            Some(CodeActionOrCommand::CodeAction(CodeAction {
+                title: fix.message,
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: (!resolved.is_empty()).then_some(resolved),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(uri.clone(), vec![edit])])),
+                    ..Default::default()
+                }),
+                is_preferred: Some(true),
+                ..Default::default()
+            }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use tower_lsp_server::lsp_types::Position;
+
+    use why_lib::source_map::SourceMap;
+
+    use super::*;
+
+    fn actions(input: &str, range: Range) -> Vec<(String, TextEdit)> {
+        let uri = "file:///test.why".parse::<Uri>().unwrap();
+
+        code_actions(&uri, SourceMap::add(input), range, &[])
+            .into_iter()
+            .map(|action| {
+                let CodeActionOrCommand::CodeAction(action) = action else {
+                    unreachable!()
+                };
+                let edit = action.edit.unwrap().changes.unwrap().into_values().next();
+                let edit = edit.unwrap().remove(0);
+                (action.title, edit)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_make_mutable() {
+        let input = "fn main(): void {\n    let x = 42;\n    x = 43;\n}";
+        let range = Range::new(Position::new(2, 4), Position::new(2, 4));
+
+        assert_eq!(
+            actions(input, range),
+            vec![(
+                "Make 'x' mutable".into(),
+                TextEdit {
+                    range: Range::new(Position::new(1, 4), Position::new(1, 7)),
+                    new_text: "let mut".into(),
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn test_only_within_range() {
+        let input = "fn main(): void {\n    let x = 42;\n    x = 43;\n}";
+        let range = Range::new(Position::new(0, 0), Position::new(0, 2));
+
+        assert!(actions(input, range).is_empty());
+    }
+
+    #[test]
+    fn test_missing_main() {
+        let input = "fn foo(): void {\n}\n";
+        let range = Range::new(Position::new(0, 0), Position::new(0, 0));
+
+        assert_eq!(
+            actions(input, range),
+            vec![(
+                "Add main function".into(),
+                TextEdit {
+                    range: Range::new(Position::new(1, 1), Position::new(1, 1)),
+                    new_text: "\n\nfn main(): void {\n}\n".into(),
+                }
+            )]
+        );
+    }
+}
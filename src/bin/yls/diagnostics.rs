@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use tower_lsp_server::lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Uri};
+use tracing::error;
+use why_lib::cst::{self, SyntaxError};
+use why_lib::lexer::Span;
+use why_lib::parser::ParseError;
+use why_lib::source_map::FileId;
+use why_lib::typechecker::{IncrementalChecker, TypeChecker};
+
+use crate::span_to_range;
+
+/// Identifier of the diagnostics for a given document content. As long as the content of a
+/// document does not change, the diagnostics do not change either.
+pub fn result_id(input: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    input.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// A task running on the thread which owns the checkers, which are keyed by the URIs of their
+/// documents.
+type Job = Box<dyn FnOnce(&mut HashMap<String, IncrementalChecker>) + Send>;
+
+/// Incremental typecheckers of all documents checked so far, so only the toplevel items affected
+/// by changes of a document are typechecked again. The type information of a checker is shared
+/// via `Rc`, so the checkers live on a dedicated thread, which runs all jobs using them.
+#[derive(Debug)]
+pub struct Checkers {
+    jobs: Sender<Job>,
+}
+
+impl Default for Checkers {
+    fn default() -> Self {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+
+        thread::Builder::new()
+            .name("checkers".into())
+            .spawn(move || {
+                let mut checkers = HashMap::new();
+                for job in receiver {
+                    job(&mut checkers);
+                }
+            })
+            .expect("should spawn checker thread");
+
+        Checkers { jobs }
+    }
+}
+
+impl Checkers {
+    /// Run the given function with all checkers on the checker thread and wait for its result.
+    fn run<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&mut HashMap<String, IncrementalChecker>) -> T + Send + 'static,
+    ) -> T {
+        let (sender, receiver) = mpsc::channel();
+
+        self.jobs
+            .send(Box::new(move |checkers| {
+                let _ = sender.send(f(checkers));
+            }))
+            .expect("checker thread should be running");
+
+        receiver.recv().expect("checker thread should be running")
+    }
+
+    /// Run the given function with the checker of a document (which is created, if there is
+    /// none yet) and wait for its result.
+    pub fn with_checker<T: Send + 'static>(
+        &self,
+        uri: &Uri,
+        f: impl FnOnce(&mut IncrementalChecker) -> T + Send + 'static,
+    ) -> T {
+        let uri = uri.to_string();
+        self.run(move |checkers| f(checkers.entry(uri).or_default()))
+    }
+
+    /// Collect all diagnostics of the given document, reusing the checker of its previous check.
+    pub fn diagnostics(&self, uri: &Uri, file: FileId) -> Vec<Diagnostic> {
+        self.with_checker(uri, move |checker| diagnostics_for_code(checker, file))
+    }
+
+    /// Drop the checker of a document, e.g., after it has been closed.
+    pub fn remove(&self, uri: &Uri) {
+        let uri = uri.to_string();
+        self.run(move |checkers| {
+            checkers.remove(&uri);
+        })
+    }
+
+    #[cfg(test)]
+    fn rechecked(&self, uri: &Uri) -> Option<usize> {
+        let uri = uri.to_string();
+        self.run(move |checkers| checkers.get(&uri).map(IncrementalChecker::rechecked))
+    }
+}
+
+/// Run all stages of the compiler on the given input and collect every problem found. Only the
+/// toplevel items affected by changes since the last check of the checker are typechecked again.
+fn diagnostics_for_code(checker: &mut IncrementalChecker, file: FileId) -> Vec<Diagnostic> {
+    let parsed = match cst::parse_file(file) {
+        Ok(parsed) => parsed,
+        Err(SyntaxError::Lex(e)) => {
+            error!("LexError: {e}");
+            return vec![];
+        }
+        Err(SyntaxError::Parse(e)) => {
+            let code = e.code();
+            let ParseError { message, position } = e;
+            let position = position.unwrap_or_default();
+            return vec![diagnostic(message, &position, code)];
+        }
+    };
+
+    let checked = match checker.check(&parsed) {
+        Ok(checked) => checked,
+        Err(errors) => {
+            return errors
+                .iter()
+                .map(|e| diagnostic(e.err().to_string(), &e.span(), e.code()))
+                .collect();
+        }
+    };
+
+    let warnings = TypeChecker::warnings(&checked)
+        .into_iter()
+        .map(|warning| Diagnostic {
+            severity: Some(DiagnosticSeverity::WARNING),
+            ..diagnostic(warning.message(), &warning.span(), warning.code())
+        })
+        .collect::<Vec<_>>();
+
+    checked
+        .into_iter()
+        .filter_map(|statement| TypeChecker::validate(vec![statement]).err())
+        .map(|e| diagnostic(e.err(), &e.span(), e.code()))
+        .chain(warnings)
+        .collect()
+}
+
+fn diagnostic(message: String, span: &Span, code: &str) -> Diagnostic {
+    Diagnostic {
+        range: span_to_range(span),
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String(code.into())),
+        source: Some("yls".into()),
+        message,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use why_lib::source_map::SourceMap;
+
+    use super::*;
+
+    /// Diagnostics of a single document, which is checked from scratch.
+    fn diagnostics(input: &str) -> Vec<Diagnostic> {
+        let uri = "file:///test.why".parse::<Uri>().unwrap();
+        Checkers::default().diagnostics(&uri, SourceMap::add(input))
+    }
+
+    #[test]
+    fn test_result_id_is_stable() {
+        assert_eq!(
+            result_id("fn main(): void {}"),
+            result_id("fn main(): void {}")
+        );
+        assert_ne!(
+            result_id("fn main(): void {}"),
+            result_id("fn main(): i64 {}")
+        );
+    }
+
+    #[test]
+    fn test_no_diagnostics() {
+        assert!(diagnostics("fn main(): void {}").is_empty());
+    }
+
+    #[test]
+    fn test_multiple_diagnostics() {
+        let diagnostics = diagnostics(
+            "fn foo(): i64 {\n    bar\n}\n\nfn baz(): i64 {\n    'a'\n}\n\nfn main(): void {}",
+        );
+
+        assert_eq!(diagnostics.len(), 2);
+        assert!(
+            diagnostics
+                .iter()
+                .all(|d| d.severity == Some(DiagnosticSeverity::ERROR))
+        );
+        assert_eq!(
+            diagnostics[0].code,
+            Some(NumberOrString::String("E0004".into()))
+        );
+        assert_eq!(diagnostics[0].range.start.line, 1);
+        assert_eq!(
+            diagnostics[1].code,
+            Some(NumberOrString::String("E0003".into()))
+        );
+        assert_eq!(diagnostics[1].range.start.line, 5);
+    }
+
+    #[test]
+    fn test_changing_document() {
+        let checkers = Checkers::default();
+        let uri = "file:///test_changing_document.why".parse::<Uri>().unwrap();
+
+        let file = SourceMap::add_named(
+            uri.as_str(),
+            "fn foo(): i64 {\n    bar\n}\n\nfn main(): void {}",
+        );
+        assert_eq!(checkers.diagnostics(&uri, file)[0].range.start.line, 1);
+        assert_eq!(checkers.rechecked(&uri), Some(2));
+
+        // the checker of the previous check is reused, so only the moved items are checked again
+        let file = SourceMap::add_named(
+            uri.as_str(),
+            "// foo\n\nfn foo(): i64 {\n    bar\n}\n\nfn main(): void {}",
+        );
+        assert_eq!(checkers.diagnostics(&uri, file)[0].range.start.line, 3);
+
+        let file = SourceMap::add_named(
+            uri.as_str(),
+            "// foo\n\nfn foo(): i64 {\n    bar\n}\n\nfn main(): void {}",
+        );
+        assert_eq!(checkers.diagnostics(&uri, file)[0].range.start.line, 3);
+        assert_eq!(checkers.rechecked(&uri), Some(0));
+
+        let file = SourceMap::add_named(
+            uri.as_str(),
+            "fn foo(): i64 {\n    42\n}\n\nfn main(): void {\n    foo();\n}",
+        );
+        assert!(checkers.diagnostics(&uri, file).is_empty());
+
+        checkers.remove(&uri);
+        assert_eq!(checkers.rechecked(&uri), None);
+    }
+
+    #[test]
+    fn test_warnings() {
+        let diagnostics = diagnostics(
+            "fn foo(): i64 {\n    return 42;\n    43\n}\n\nfn main(): void {\n    foo();\n}",
+        );
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(
+            diagnostics[0].code,
+            Some(NumberOrString::String("W0001".into()))
+        );
+        assert_eq!(diagnostics[0].range.start.line, 2);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let diagnostics = diagnostics("let x = 42;");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].code,
+            Some(NumberOrString::String("E0002".into()))
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use tower_lsp_server::lsp_types::{FormattingOptions, Position, Range, TextEdit};
+use why_lib::formatter::{self, FormatConfig};
+
+/// Format a whole document according to the options of the client. The result replaces the
+/// entire document, or is empty if the document is formatted already.
+pub fn formatting(input: &str, options: &FormattingOptions) -> Option<Vec<TextEdit>> {
+    let config = FormatConfig {
+        indent_width: options.tab_size as usize,
+        use_tabs: !options.insert_spaces,
+        ..Default::default()
+    };
+
+    let formatted = formatter::format(input, &config).ok()?;
+    if formatted == input {
+        return Some(vec![]);
+    }
+
+    let last_line = input.rsplit('\n').next().unwrap_or_default();
+    let end = Position::new(
+        input.matches('\n').count() as u32,
+        last_line.encode_utf16().count() as u32,
+    );
+
+    Some(vec![TextEdit {
+        range: Range::new(Position::new(0, 0), end),
+        new_text: formatted,
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(tab_size: u32) -> FormattingOptions {
+        FormattingOptions {
+            tab_size,
+            insert_spaces: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_replaces_document() {
+        let input = "fn main(): void {\nlet x = 42;\n}";
+
+        assert_eq!(
+            formatting(input, &options(2)),
+            Some(vec![TextEdit {
+                range: Range::new(Position::new(0, 0), Position::new(2, 1)),
+                new_text: "fn main(): void {\n  let x = 42;\n}\n".into(),
+            }])
+        );
+    }
+
+    #[test]
+    fn test_formatted_document() {
+        let input = "fn main(): void {\n    let x = 42;\n}\n";
+
+        assert_eq!(formatting(input, &options(4)), Some(vec![]));
+    }
+
+    #[test]
+    fn test_invalid_document() {
+        assert_eq!(formatting("fn main(", &options(4)), None);
+    }
+}
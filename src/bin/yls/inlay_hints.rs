@@ -0,0 +1,171 @@
+use tower_lsp_server::lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, Range};
+use why_lib::cst;
+use why_lib::lexer::Span;
+use why_lib::parser::ast::*;
+use why_lib::source_map::FileId;
+use why_lib::typechecker::{IncrementalChecker, Type, TypeInformation};
+
+use crate::span_to_range;
+use crate::visit::{self, TypeHint, Visitor};
+
+/// Collects the inferred types of bindings without a type annotation.
+struct InlayHints {
+    range: Range,
+    hints: Vec<InlayHint>,
+}
+
+impl InlayHints {
+    fn add(&mut self, position: &Span, type_id: Option<Type>) {
+        let Some(type_id) = type_id else {
+            return;
+        };
+
+        if type_id == Type::Unknown || type_id.has_variables() {
+            return;
+        }
+
+        let position = span_to_range(position).end;
+        if position < self.range.start || position > self.range.end {
+            return;
+        }
+
+        self.hints.push(InlayHint {
+            position,
+            label: InlayHintLabel::String(format!(": {type_id}")),
+            kind: Some(InlayHintKind::TYPE),
+            text_edits: None,
+            tooltip: None,
+            padding_left: None,
+            padding_right: None,
+            data: None,
+        });
+    }
+}
+
+impl Visitor<TypeInformation> for InlayHints {
+    fn statement(&mut self, statement: &Statement<TypeInformation>) {
+        if let Statement::Initialization(Initialisation {
+            id,
+            type_name: None,
+            ..
+        }) = statement
+        {
+            self.add(&id.position, id.info.type_id());
+        }
+    }
+
+    fn expression(&mut self, expression: &Expression<TypeInformation>) {
+        if let Expression::Lambda(Lambda { parameters, .. }) = expression {
+            for LambdaParameter { name, info, .. } in parameters {
+                self.add(&name.position, info.type_id());
+            }
+        }
+    }
+}
+
+/// Compute type hints for all un-annotated `let` bindings and lambda parameters within the
+/// given range. Hints are available within every toplevel item which typechecks, even if other
+/// items of the document do not.
+pub fn inlay_hints(
+    checker: &mut IncrementalChecker,
+    file: FileId,
+    range: Range,
+) -> Option<Vec<InlayHint>> {
+    let parsed = cst::parse_file(file).ok()?;
+    // errors are reported as diagnostics, only the items which typecheck are of interest here
+    let _ = checker.check(&parsed);
+    let checked = checker.checked_items();
+
+    let mut hints = InlayHints {
+        range,
+        hints: vec![],
+    };
+    visit::walk(&checked, &mut hints);
+
+    Some(hints.hints)
+}
+
+#[cfg(test)]
+mod tests {
+    use tower_lsp_server::lsp_types::Position;
+
+    use why_lib::source_map::SourceMap;
+
+    use super::*;
+
+    fn whole_document() -> Range {
+        Range::new(Position::new(0, 0), Position::new(u32::MAX, 0))
+    }
+
+    fn labels(hints: &[InlayHint]) -> Vec<(u32, u32, String)> {
+        hints
+            .iter()
+            .map(|hint| {
+                let InlayHintLabel::String(label) = &hint.label else {
+                    unreachable!()
+                };
+                (hint.position.line, hint.position.character, label.clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_let_bindings() {
+        let input =
+            "fn main(): void {\n    let x = 42;\n    let y: f64 = 1.5;\n    let mut z = 'a';\n}";
+
+        let hints = inlay_hints(
+            &mut IncrementalChecker::new(),
+            SourceMap::add(input),
+            whole_document(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            labels(&hints),
+            vec![(1, 9, ": i64".into()), (3, 13, ": char".into())]
+        );
+    }
+
+    #[test]
+    fn test_lambda_parameters() {
+        let input = "fn apply(f: (i64, i64) -> i64): i64 {\n    f(1, 2)\n}\n\nfn main(): void {\n    apply(\\(a, b) => a + b);\n}";
+
+        let hints = inlay_hints(
+            &mut IncrementalChecker::new(),
+            SourceMap::add(input),
+            whole_document(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            labels(&hints),
+            vec![(5, 13, ": i64".into()), (5, 16, ": i64".into())]
+        );
+    }
+
+    #[test]
+    fn test_items_which_typecheck() {
+        let input = "fn broken(): i64 {\n    let a = 'a';\n    a + 1\n}\n\nfn main(): void {\n    let x = 42;\n}";
+
+        let hints = inlay_hints(
+            &mut IncrementalChecker::new(),
+            SourceMap::add(input),
+            whole_document(),
+        )
+        .unwrap();
+
+        assert_eq!(labels(&hints), vec![(6, 9, ": i64".into())]);
+    }
+
+    #[test]
+    fn test_range() {
+        let input = "fn main(): void {\n    let x = 42;\n    let y = 43;\n}";
+
+        let range = Range::new(Position::new(2, 0), Position::new(3, 0));
+        let hints =
+            inlay_hints(&mut IncrementalChecker::new(), SourceMap::add(input), range).unwrap();
+
+        assert_eq!(labels(&hints), vec![(2, 9, ": i64".into())]);
+    }
+}
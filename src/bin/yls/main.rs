@@ -0,0 +1,545 @@
+mod code_actions;
+mod diagnostics;
+mod formatting;
+mod inlay_hints;
+mod semantic_tokens;
+mod signature_help;
+mod symbols;
+mod visit;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tower_lsp_server::jsonrpc::{Error, Result};
+use tower_lsp_server::lsp_types::notification::PublishDiagnostics;
+use tower_lsp_server::lsp_types::*;
+use tower_lsp_server::{Client, LanguageServer, LspService, Server, UriExt};
+use tracing::error;
+use tracing_subscriber::{filter, layer::SubscriberExt, util::SubscriberInitExt};
+use why_lib::lexer::{self, Span};
+use why_lib::parser::{self, ast::TopLevelStatement};
+use why_lib::source_map::{FileId, SourceMap};
+
+/// Convert a span of the lexer to a range in an LSP document.
+pub fn span_to_range(span: &Span) -> Range {
+    let (start, end) = (span.start_utf16(), span.end_utf16());
+
+    Range {
+        start: Position {
+            line: start.0 as u32,
+            character: start.1 as u32,
+        },
+        end: Position {
+            line: end.0 as u32,
+            character: end.1 as u32,
+        },
+    }
+}
+
+#[derive(Debug)]
+struct Backend {
+    client: Client,
+    /// Content of all documents currently opened in the client.
+    documents: RwLock<HashMap<Uri, String>>,
+    /// Root directories of all folders in the current workspace.
+    workspace_folders: RwLock<Vec<PathBuf>>,
+    /// Whether the client requests diagnostics on its own instead of having them published.
+    pull_diagnostics: AtomicBool,
+    /// Incremental typecheckers of all documents, keyed by their URI.
+    checkers: diagnostics::Checkers,
+}
+
+impl Backend {
+    fn new(client: Client) -> Self {
+        Backend {
+            client,
+            documents: RwLock::default(),
+            workspace_folders: RwLock::default(),
+            pull_diagnostics: AtomicBool::default(),
+            checkers: diagnostics::Checkers::default(),
+        }
+    }
+
+    /// Get the content of a document. Opened documents are preferred over the file on disk.
+    fn get_document(&self, uri: &Uri) -> Option<String> {
+        if let Some(content) = self.documents.read().unwrap().get(uri) {
+            return Some(content.clone());
+        }
+
+        fs::read_to_string(uri.to_file_path()?).ok()
+    }
+
+    /// Add the current content of a document to the source map. Every document keeps its id
+    /// within the source map, even if its content changes.
+    fn load_document(&self, uri: &Uri) -> Option<FileId> {
+        let content = self.get_document(uri)?;
+        Some(SourceMap::add_named(uri.as_str(), content))
+    }
+
+    fn parse_document(&self, file: FileId) -> Option<Vec<TopLevelStatement<()>>> {
+        let lexed = lexer::Lexer::for_file(file).lex().ok()?;
+        parser::parse(&mut lexed.into()).ok()
+    }
+
+    /// Collect all `.why` files within the given directory (recursively).
+    fn collect_source_files(dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+
+            if path.is_dir() {
+                if !is_hidden && entry.file_name() != "target" {
+                    Self::collect_source_files(&path, files);
+                }
+            } else if path.extension().is_some_and(|ext| ext == "why") {
+                files.push(path);
+            }
+        }
+    }
+
+    fn workspace_files(&self) -> Vec<Uri> {
+        let mut files = vec![];
+
+        for folder in self.workspace_folders.read().unwrap().iter() {
+            Self::collect_source_files(folder, &mut files);
+        }
+
+        let mut uris = files
+            .iter()
+            .filter_map(Uri::from_file_path)
+            .collect::<Vec<_>>();
+
+        // documents opened in the client might live outside of the workspace
+        for uri in self.documents.read().unwrap().keys() {
+            if !uris.contains(uri) {
+                uris.push(uri.clone());
+            }
+        }
+
+        uris
+    }
+
+    /// Publish the diagnostics of a document, which belong to the given version of it (if known).
+    async fn check_diagnostics(&self, uri: Uri, version: Option<i32>) {
+        // clients supporting pull diagnostics ask for them on their own
+        if self.pull_diagnostics.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let path = uri.path().as_str();
+        if !path.ends_with(".why") {
+            return;
+        }
+
+        let diagnostics = self
+            .load_document(&uri)
+            .map(|file| self.checkers.diagnostics(&uri, file))
+            .unwrap_or_default();
+
+        self.client
+            .send_notification::<PublishDiagnostics>(PublishDiagnosticsParams {
+                uri: uri.clone(),
+                version,
+                diagnostics,
+            })
+            .await;
+    }
+
+    /// Create a diagnostic report for a document. If the document did not change since the
+    /// previous report, only the previous result id is confirmed.
+    fn diagnostic_report(
+        &self,
+        uri: &Uri,
+        previous_result_id: Option<&str>,
+    ) -> Option<DocumentDiagnosticReport> {
+        let file = self.load_document(uri)?;
+        let result_id = diagnostics::result_id(SourceMap::get(file)?.source());
+
+        if previous_result_id == Some(result_id.as_str()) {
+            return Some(DocumentDiagnosticReport::Unchanged(
+                RelatedUnchangedDocumentDiagnosticReport {
+                    related_documents: None,
+                    unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                        result_id,
+                    },
+                },
+            ));
+        }
+
+        Some(DocumentDiagnosticReport::Full(
+            RelatedFullDocumentDiagnosticReport {
+                related_documents: None,
+                full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                    result_id: Some(result_id),
+                    items: self.checkers.diagnostics(uri, file),
+                },
+            },
+        ))
+    }
+}
+
+impl LanguageServer for Backend {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        let pull_diagnostics = params
+            .capabilities
+            .text_document
+            .as_ref()
+            .is_some_and(|text_document| text_document.diagnostic.is_some());
+        self.pull_diagnostics
+            .store(pull_diagnostics, Ordering::Relaxed);
+
+        #[allow(deprecated)]
+        let folders = match (params.workspace_folders, params.root_uri) {
+            (Some(folders), _) => folders.into_iter().map(|folder| folder.uri).collect(),
+            (None, Some(root)) => vec![root],
+            (None, None) => vec![],
+        };
+
+        *self.workspace_folders.write().unwrap() = folders
+            .iter()
+            .filter_map(|uri| uri.to_file_path().map(|path| path.into_owned()))
+            .collect();
+
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                    TextDocumentSyncKind::FULL,
+                )),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                workspace_symbol_provider: Some(OneOf::Left(true)),
+                signature_help_provider: Some(SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".into(), ",".into()]),
+                    retrigger_characters: None,
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                }),
+                inlay_hint_provider: Some(OneOf::Left(true)),
+                document_formatting_provider: Some(OneOf::Left(true)),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+                        ..Default::default()
+                    },
+                )),
+                semantic_tokens_provider: Some(
+                    SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        SemanticTokensOptions {
+                            legend: semantic_tokens::legend(),
+                            range: Some(true),
+                            full: Some(SemanticTokensFullOptions::Bool(true)),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                diagnostic_provider: Some(DiagnosticServerCapabilities::Options(
+                    DiagnosticOptions {
+                        identifier: Some("ylsp".into()),
+                        inter_file_dependencies: false,
+                        workspace_diagnostics: true,
+                        ..Default::default()
+                    },
+                )),
+                workspace: Some(WorkspaceServerCapabilities {
+                    workspace_folders: Some(WorkspaceFoldersServerCapabilities {
+                        supported: Some(true),
+                        change_notifications: Some(OneOf::Left(true)),
+                    }),
+                    file_operations: Some(WorkspaceFileOperationsServerCapabilities {
+                        did_create: Some(FileOperationRegistrationOptions {
+                            filters: vec![FileOperationFilter {
+                                scheme: None,
+                                pattern: FileOperationPattern {
+                                    glob: "**/*.why".into(),
+                                    ..Default::default()
+                                },
+                            }],
+                        }),
+                        ..Default::default()
+                    }),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+    }
+
+    async fn initialized(&self, _: InitializedParams) {
+        self.client
+            .log_message(MessageType::ERROR, "server initialized!")
+            .await;
+    }
+
+    async fn shutdown(&self) -> Result<()> {
+        self.client
+            .log_message(MessageType::ERROR, "server shutdown!")
+            .await;
+        Ok(())
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        let DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri, text, version, ..
+            },
+        } = params;
+        self.documents.write().unwrap().insert(uri.clone(), text);
+        self.check_diagnostics(uri, Some(version)).await;
+    }
+
+    async fn did_change(&self, params: DidChangeTextDocumentParams) {
+        let DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier { uri, version },
+            content_changes,
+        } = params;
+
+        // we only support full synchronisation, so the last change contains the whole document
+        let Some(TextDocumentContentChangeEvent { text, .. }) = content_changes.into_iter().last()
+        else {
+            return;
+        };
+        self.documents.write().unwrap().insert(uri.clone(), text);
+
+        // only the items affected by the change are checked again, which is cheap enough to do
+        // on every change
+        self.check_diagnostics(uri, Some(version)).await;
+    }
+
+    async fn did_close(&self, params: DidCloseTextDocumentParams) {
+        let DidCloseTextDocumentParams {
+            text_document: TextDocumentIdentifier { uri },
+        } = params;
+        self.documents.write().unwrap().remove(&uri);
+        self.checkers.remove(&uri);
+    }
+
+    async fn did_save(&self, params: DidSaveTextDocumentParams) {
+        let DidSaveTextDocumentParams {
+            text_document: TextDocumentIdentifier { uri },
+            ..
+        } = params;
+        self.check_diagnostics(uri, None).await;
+    }
+
+    async fn did_create_files(&self, params: CreateFilesParams) {
+        error!("CREATED: {params:?}")
+    }
+
+    async fn did_change_workspace_folders(&self, params: DidChangeWorkspaceFoldersParams) {
+        let WorkspaceFoldersChangeEvent { added, removed } = params.event;
+
+        let to_path =
+            |folder: WorkspaceFolder| folder.uri.to_file_path().map(|path| path.into_owned());
+
+        let mut folders = self.workspace_folders.write().unwrap();
+        let removed = removed.into_iter().filter_map(to_path).collect::<Vec<_>>();
+        folders.retain(|folder| !removed.contains(folder));
+        folders.extend(added.into_iter().filter_map(to_path));
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let Some(statements) = self
+            .load_document(&params.text_document.uri)
+            .and_then(|file| self.parse_document(file))
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(DocumentSymbolResponse::Nested(
+            symbols::document_symbols(&statements),
+        )))
+    }
+
+    async fn symbol(
+        &self,
+        params: WorkspaceSymbolParams,
+    ) -> Result<Option<OneOf<Vec<SymbolInformation>, Vec<WorkspaceSymbol>>>> {
+        let mut result = vec![];
+
+        for uri in self.workspace_files() {
+            let Some(statements) = self
+                .load_document(&uri)
+                .and_then(|file| self.parse_document(file))
+            else {
+                continue;
+            };
+
+            let outline = symbols::document_symbols(&statements);
+            result.extend(symbols::workspace_symbols(&uri, &outline, &params.query));
+        }
+
+        Ok(Some(OneOf::Right(result)))
+    }
+
+    async fn semantic_tokens_full(
+        &self,
+        params: SemanticTokensParams,
+    ) -> Result<Option<SemanticTokensResult>> {
+        let Some(data) = self
+            .load_document(&params.text_document.uri)
+            .and_then(|file| semantic_tokens::semantic_tokens(file, None))
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(SemanticTokensResult::Tokens(SemanticTokens {
+            result_id: None,
+            data,
+        })))
+    }
+
+    async fn semantic_tokens_range(
+        &self,
+        params: SemanticTokensRangeParams,
+    ) -> Result<Option<SemanticTokensRangeResult>> {
+        let Some(data) = self
+            .load_document(&params.text_document.uri)
+            .and_then(|file| semantic_tokens::semantic_tokens(file, Some(params.range)))
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(SemanticTokensRangeResult::Tokens(SemanticTokens {
+            result_id: None,
+            data,
+        })))
+    }
+
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        let TextDocumentPositionParams {
+            text_document,
+            position,
+        } = params.text_document_position_params;
+
+        Ok(self
+            .load_document(&text_document.uri)
+            .and_then(|file| signature_help::signature_help(file, position)))
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
+        Ok(self
+            .load_document(&params.text_document.uri)
+            .and_then(|file| {
+                let range = params.range;
+                self.checkers
+                    .with_checker(&params.text_document.uri, move |checker| {
+                        inlay_hints::inlay_hints(checker, file, range)
+                    })
+            }))
+    }
+
+    async fn formatting(&self, params: DocumentFormattingParams) -> Result<Option<Vec<TextEdit>>> {
+        Ok(self
+            .get_document(&params.text_document.uri)
+            .and_then(|content| formatting::formatting(&content, &params.options)))
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let CodeActionParams {
+            text_document: TextDocumentIdentifier { uri },
+            range,
+            context,
+            ..
+        } = params;
+
+        Ok(self
+            .load_document(&uri)
+            .map(|file| code_actions::code_actions(&uri, file, range, &context.diagnostics)))
+    }
+
+    async fn diagnostic(
+        &self,
+        params: DocumentDiagnosticParams,
+    ) -> Result<DocumentDiagnosticReportResult> {
+        let DocumentDiagnosticParams {
+            text_document: TextDocumentIdentifier { uri },
+            previous_result_id,
+            ..
+        } = params;
+
+        let Some(report) = self.diagnostic_report(&uri, previous_result_id.as_deref()) else {
+            return Err(Error::invalid_params(format!(
+                "could not read document {}",
+                uri.as_str()
+            )));
+        };
+
+        Ok(DocumentDiagnosticReportResult::Report(report))
+    }
+
+    async fn workspace_diagnostic(
+        &self,
+        params: WorkspaceDiagnosticParams,
+    ) -> Result<WorkspaceDiagnosticReportResult> {
+        let previous_result_ids = params
+            .previous_result_ids
+            .into_iter()
+            .map(|PreviousResultId { uri, value }| (uri.as_str().to_string(), value))
+            .collect::<HashMap<_, _>>();
+
+        let mut items = vec![];
+
+        for uri in self.workspace_files() {
+            let previous_result_id = previous_result_ids.get(uri.as_str()).map(String::as_str);
+
+            let item = match self.diagnostic_report(&uri, previous_result_id) {
+                Some(DocumentDiagnosticReport::Full(report)) => {
+                    WorkspaceDocumentDiagnosticReport::Full(WorkspaceFullDocumentDiagnosticReport {
+                        uri,
+                        version: None,
+                        full_document_diagnostic_report: report.full_document_diagnostic_report,
+                    })
+                }
+                Some(DocumentDiagnosticReport::Unchanged(report)) => {
+                    WorkspaceDocumentDiagnosticReport::Unchanged(
+                        WorkspaceUnchangedDocumentDiagnosticReport {
+                            uri,
+                            version: None,
+                            unchanged_document_diagnostic_report: report
+                                .unchanged_document_diagnostic_report,
+                        },
+                    )
+                }
+                None => continue,
+            };
+
+            items.push(item);
+        }
+
+        Ok(WorkspaceDiagnosticReportResult::Report(
+            WorkspaceDiagnosticReport { items },
+        ))
+    }
+}
+
+fn init() {
+    let filter = filter::Targets::new().with_target("ylsp", tracing::metadata::LevelFilter::TRACE);
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(std::io::stderr)
+                .compact()
+                .with_ansi(false),
+        )
+        .with(filter)
+        .init();
+}
+
+#[tokio::main]
+async fn main() {
+    init();
+
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+
+    let (service, socket) = LspService::new(Backend::new);
+    Server::new(stdin, stdout, socket).serve(service).await;
+}
@@ -0,0 +1,695 @@
+use std::collections::HashMap;
+
+use tower_lsp_server::lsp_types::{
+    Range, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokensLegend,
+};
+use why_lib::lexer::{GetPosition, Lexer, Span, Token};
+use why_lib::parser::{self, ast::*};
+use why_lib::source_map::FileId;
+use why_lib::typechecker::TypeChecker;
+
+use crate::span_to_range;
+use crate::visit::TypeHint;
+
+/// Token types in the order of the legend announced to the client.
+const TOKEN_TYPES: &[SemanticTokenType] = &[
+    SemanticTokenType::KEYWORD,
+    SemanticTokenType::COMMENT,
+    SemanticTokenType::STRING,
+    SemanticTokenType::NUMBER,
+    SemanticTokenType::OPERATOR,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::METHOD,
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::STRUCT,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::TYPE,
+];
+
+const KEYWORD: u32 = 0;
+const COMMENT: u32 = 1;
+const STRING: u32 = 2;
+const NUMBER: u32 = 3;
+const OPERATOR: u32 = 4;
+const FUNCTION: u32 = 5;
+const METHOD: u32 = 6;
+const VARIABLE: u32 = 7;
+const PARAMETER: u32 = 8;
+const STRUCT: u32 = 9;
+const PROPERTY: u32 = 10;
+const TYPE: u32 = 11;
+
+/// Token modifiers in the order of the legend announced to the client. Each modifier is a bit
+/// in the modifier set of a token.
+const TOKEN_MODIFIERS: &[SemanticTokenModifier] = &[
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::READONLY,
+    SemanticTokenModifier::new("mutable"),
+];
+
+const DECLARATION: u32 = 1 << 0;
+const READONLY: u32 = 1 << 1;
+const MUTABLE: u32 = 1 << 2;
+
+const BUILTIN_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "u8", "u16", "u32", "u64", "usize", "f32", "f64", "bool", "char",
+    "str", "void",
+];
+
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: TOKEN_TYPES.to_vec(),
+        token_modifiers: TOKEN_MODIFIERS.to_vec(),
+    }
+}
+
+/// Classification of a single token, i.e., its type and its set of modifiers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Class(u32, u32);
+
+/// Compute the semantic tokens of a document, optionally restricted to the given range.
+///
+/// Every token is classified by the lexer first. Identifiers are then refined by walking the
+/// typed AST (or the parsed AST, if the document does not typecheck).
+pub fn semantic_tokens(file: FileId, range: Option<Range>) -> Option<Vec<SemanticToken>> {
+    let tokens = Lexer::for_file(file).lex().ok()?;
+
+    let identifiers = parser::parse(&mut tokens.clone().into())
+        .map(
+            |statements| match TypeChecker::new(statements.clone()).check_all() {
+                Ok(checked) => Classifier::classify(&checked),
+                Err(_) => Classifier::classify(&statements),
+            },
+        )
+        .unwrap_or_default();
+
+    let mut classified = tokens
+        .iter()
+        .filter_map(|token| {
+            let position = token.position();
+            let class = match token {
+                Token::Id { .. } => identifiers
+                    .get(&position.start)
+                    .copied()
+                    .unwrap_or(Class(VARIABLE, 0)),
+                token => classify_token(token)?,
+            };
+            Some((position, class))
+        })
+        .filter(|(position, _)| position.start_line_col().0 == position.end_line_col().0)
+        .filter(|(position, _)| {
+            range.is_none_or(|range| {
+                let token_range = span_to_range(position);
+                token_range.end > range.start && token_range.start < range.end
+            })
+        })
+        .collect::<Vec<_>>();
+
+    classified.sort_by_key(|(position, _)| position.start);
+
+    Some(encode(&classified))
+}
+
+fn classify_token(token: &Token) -> Option<Class> {
+    let token_type = match token {
+        Token::Let { .. }
+        | Token::Const { .. }
+        | Token::Mut { .. }
+        | Token::FnKeyword { .. }
+        | Token::IfKeyword { .. }
+        | Token::ElseKeyword { .. }
+        | Token::WhileKeyword { .. }
+        | Token::ReturnKeyword { .. }
+        | Token::DeclareKeyword { .. }
+        | Token::StructKeyword { .. }
+        | Token::ClassKeyword { .. }
+        | Token::InstanceKeyword { .. }
+        | Token::AsKeyword { .. } => KEYWORD,
+        Token::Comment { .. } => COMMENT,
+        Token::String { .. } | Token::Character { .. } => STRING,
+        Token::Integer { .. } | Token::FloatingPoint { .. } => NUMBER,
+        Token::Assign { .. }
+        | Token::Plus { .. }
+        | Token::Minus { .. }
+        | Token::Times { .. }
+        | Token::Slash { .. }
+        | Token::SmallRightArrow { .. }
+        | Token::BigRightArrow { .. }
+        | Token::Backslash { .. }
+        | Token::Equal { .. }
+        | Token::NotEqual { .. }
+        | Token::GreaterThan { .. }
+        | Token::LessThan { .. }
+        | Token::GreaterOrEqual { .. }
+        | Token::LessOrEqual { .. }
+        | Token::Ampersand { .. }
+        | Token::ExclamationMark { .. }
+        | Token::QuestionMark { .. }
+        | Token::QuestionDot { .. }
+        | Token::DoubleQuestionMark { .. } => OPERATOR,
+        _ => return None,
+    };
+
+    Some(Class(token_type, 0))
+}
+
+/// Encode the (sorted) tokens relative to each other, as required by the LSP.
+fn encode(tokens: &[(Span, Class)]) -> Vec<SemanticToken> {
+    let mut previous = (0, 0);
+
+    tokens
+        .iter()
+        .map(|(position, Class(token_type, token_modifiers_bitset))| {
+            let (line, col) = position.start_utf16();
+            let delta_line = line - previous.0;
+            let delta_start = if delta_line == 0 {
+                col - previous.1
+            } else {
+                col
+            };
+            previous = (line, col);
+
+            SemanticToken {
+                delta_line: delta_line as u32,
+                delta_start: delta_start as u32,
+                length: (position.end_utf16().1 - col) as u32,
+                token_type: *token_type,
+                token_modifiers_bitset: *token_modifiers_bitset,
+            }
+        })
+        .collect()
+}
+
+/// Walks the AST and classifies every identifier by what it refers to.
+#[derive(Default)]
+struct Classifier {
+    scopes: Vec<HashMap<String, Class>>,
+    structs: Vec<String>,
+    classes: HashMap<usize, Class>,
+}
+
+impl Classifier {
+    fn classify<T: TypeHint>(statements: &[TopLevelStatement<T>]) -> HashMap<usize, Class> {
+        let mut classifier = Classifier {
+            scopes: vec![HashMap::new()],
+            ..Default::default()
+        };
+
+        // toplevel items are visible everywhere, independent of their order
+        for statement in statements {
+            match statement {
+                TopLevelStatement::Function(Function { id, .. }) => {
+                    classifier.define_name(id, Class(FUNCTION, 0))
+                }
+                TopLevelStatement::Constant(Constant { id, .. }) => {
+                    classifier.define_name(id, Class(VARIABLE, READONLY))
+                }
+                TopLevelStatement::Declaration(declaration) => {
+                    classifier.define_name(&declaration.name, Self::declaration_class(declaration))
+                }
+                TopLevelStatement::StructDeclaration(StructDeclaration { id, .. }) => {
+                    classifier.structs.push(id.name.clone())
+                }
+                TopLevelStatement::Comment(..) | TopLevelStatement::Instance(_) => {}
+            }
+        }
+
+        for statement in statements {
+            classifier.top_level_statement(statement);
+        }
+
+        classifier.classes
+    }
+
+    fn declaration_class<T>(declaration: &Declaration<T>) -> Class {
+        match declaration.type_name {
+            TypeName::Fn { .. } => Class(FUNCTION, 0),
+            _ => Class(VARIABLE, READONLY),
+        }
+    }
+
+    fn define_name<T>(&mut self, id: &Id<T>, class: Class) {
+        self.scopes
+            .last_mut()
+            .expect("there is always a scope")
+            .insert(id.name.clone(), class);
+    }
+
+    fn declare<T>(&mut self, id: &Id<T>, Class(token_type, modifiers): Class) {
+        self.define_name(id, Class(token_type, modifiers));
+        self.mark(&id.position, Class(token_type, modifiers | DECLARATION));
+    }
+
+    fn mark(&mut self, position: &Span, class: Class) {
+        self.classes.insert(position.start, class);
+    }
+
+    fn resolve<T: TypeHint>(&mut self, id: &Id<T>) {
+        let class = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(&id.name).copied())
+            .unwrap_or(Class(VARIABLE, 0));
+
+        // variables holding a function (e.g., a lambda) are callable like functions
+        let class = match class {
+            Class(VARIABLE | PARAMETER, modifiers) if id.info.is_function() => {
+                Class(FUNCTION, modifiers)
+            }
+            class => class,
+        };
+
+        self.mark(&id.position, class);
+    }
+
+    fn scoped(&mut self, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(HashMap::new());
+        f(self);
+        self.scopes.pop();
+    }
+
+    fn type_name(&mut self, type_name: &TypeName) {
+        match type_name {
+            TypeName::Literal(name, position) => {
+                if self.structs.contains(name) {
+                    self.mark(position, Class(STRUCT, 0));
+                } else if BUILTIN_TYPES.contains(&name.as_str()) {
+                    self.mark(position, Class(TYPE, 0));
+                }
+            }
+            TypeName::Fn {
+                params,
+                return_type,
+                ..
+            } => {
+                params.iter().for_each(|param| self.type_name(param));
+                self.type_name(return_type);
+            }
+            TypeName::Tuple(types, _) => types.iter().for_each(|inner| self.type_name(inner)),
+            TypeName::Array(inner, _)
+            | TypeName::Reference(inner, _)
+            | TypeName::Optional(inner, _) => self.type_name(inner),
+            TypeName::Result(value, error, _) => {
+                self.type_name(value);
+                self.type_name(error);
+            }
+            TypeName::FixedArray(inner, length, _) => {
+                self.type_name(inner);
+                if let ArrayLength::Constant(name, position) = length {
+                    let class = self
+                        .scopes
+                        .iter()
+                        .rev()
+                        .find_map(|scope| scope.get(name).copied())
+                        .unwrap_or(Class(VARIABLE, READONLY));
+                    self.mark(position, class);
+                }
+            }
+        }
+    }
+
+    fn top_level_statement<T: TypeHint>(&mut self, statement: &TopLevelStatement<T>) {
+        match statement {
+            TopLevelStatement::Comment(..) => {}
+            TopLevelStatement::Function(function) => self.function(function, FUNCTION),
+            TopLevelStatement::Constant(constant) => self.constant(constant),
+            TopLevelStatement::Declaration(declaration) => self.declaration(declaration),
+            TopLevelStatement::StructDeclaration(declaration) => {
+                self.struct_declaration(declaration)
+            }
+            TopLevelStatement::Instance(Instance {
+                name,
+                functions,
+                declarations,
+                ..
+            }) => {
+                self.type_name(name);
+                functions
+                    .iter()
+                    .for_each(|function| self.function(function, METHOD));
+                for MethodDeclaration {
+                    id,
+                    parameter_types,
+                    return_type,
+                    ..
+                } in declarations
+                {
+                    self.mark(&id.position, Class(METHOD, DECLARATION));
+                    parameter_types
+                        .iter()
+                        .for_each(|type_name| self.type_name(type_name));
+                    self.type_name(return_type);
+                }
+            }
+        }
+    }
+
+    fn function<T: TypeHint>(&mut self, function: &Function<T>, token_type: u32) {
+        let Function {
+            id,
+            parameters,
+            return_type,
+            statements,
+            ..
+        } = function;
+
+        if token_type == METHOD {
+            self.mark(&id.position, Class(METHOD, DECLARATION));
+        } else {
+            self.declare(id, Class(token_type, 0));
+        }
+
+        self.scoped(|this| {
+            for FunctionParameter {
+                name, type_name, ..
+            } in parameters
+            {
+                this.declare(name, Class(PARAMETER, 0));
+                this.type_name(type_name);
+            }
+            this.type_name(return_type);
+            this.statements(statements);
+        });
+    }
+
+    fn constant<T: TypeHint>(&mut self, constant: &Constant<T>) {
+        let Constant {
+            id,
+            type_name,
+            value,
+            ..
+        } = constant;
+
+        self.expression(value);
+        self.declare(id, Class(VARIABLE, READONLY));
+        self.type_name(type_name);
+    }
+
+    fn declaration<T>(&mut self, declaration: &Declaration<T>) {
+        self.declare(&declaration.name, Self::declaration_class(declaration));
+        self.type_name(&declaration.type_name);
+    }
+
+    fn struct_declaration<T>(&mut self, declaration: &StructDeclaration<T>) {
+        let StructDeclaration { id, fields, .. } = declaration;
+
+        if !self.structs.contains(&id.name) {
+            self.structs.push(id.name.clone());
+        }
+        self.mark(&id.position, Class(STRUCT, DECLARATION));
+
+        for StructFieldDeclaration {
+            name, type_name, ..
+        } in fields
+        {
+            self.mark(&name.position, Class(PROPERTY, DECLARATION));
+            self.type_name(type_name);
+        }
+    }
+
+    fn statements<T: TypeHint>(&mut self, statements: &[Statement<T>]) {
+        statements
+            .iter()
+            .for_each(|statement| self.statement(statement));
+    }
+
+    fn statement<T: TypeHint>(&mut self, statement: &Statement<T>) {
+        match statement {
+            Statement::Function(function) => self.function(function, FUNCTION),
+            Statement::If(if_expression) => self.if_expression(if_expression),
+            Statement::WhileLoop(WhileLoop {
+                condition, block, ..
+            }) => {
+                self.expression(condition);
+                self.scoped(|this| this.statements(&block.statements));
+            }
+            Statement::Initialization(Initialisation {
+                id,
+                mutable,
+                type_name,
+                value,
+                ..
+            }) => {
+                if let Some(value) = value {
+                    self.expression(value);
+                }
+
+                let token_type = if id.info.is_function() {
+                    FUNCTION
+                } else {
+                    VARIABLE
+                };
+                let modifiers = if *mutable { MUTABLE } else { 0 };
+                self.declare(id, Class(token_type, modifiers));
+
+                if let Some(type_name) = type_name {
+                    self.type_name(type_name);
+                }
+            }
+            Statement::Constant(constant) => self.constant(constant),
+            Statement::Assignment(Assignment { lvalue, rvalue, .. }) => {
+                match lvalue {
+                    LValue::Id(id) => self.resolve(id),
+                    LValue::Postfix(postfix) => self.postfix(postfix),
+                }
+                self.expression(rvalue);
+            }
+            Statement::Expression(expression)
+            | Statement::YieldingExpression(expression)
+            | Statement::Return(expression) => self.expression(expression),
+            Statement::Comment(..) => {}
+            Statement::Declaration(declaration) => self.declaration(declaration),
+            Statement::StructDeclaration(declaration) => self.struct_declaration(declaration),
+        }
+    }
+
+    fn if_expression<T: TypeHint>(&mut self, if_expression: &If<T>) {
+        let If {
+            condition,
+            statements,
+            else_statements,
+            ..
+        } = if_expression;
+
+        self.expression(condition);
+        self.scoped(|this| this.statements(statements));
+        self.scoped(|this| this.statements(else_statements));
+    }
+
+    fn expression<T: TypeHint>(&mut self, expression: &Expression<T>) {
+        match expression {
+            Expression::Id(id) => self.resolve(id),
+            Expression::Num(_) | Expression::Character(_) | Expression::AstString(_) => {}
+            Expression::Function(function) => self.function(function, FUNCTION),
+            Expression::Lambda(Lambda {
+                parameters,
+                expression,
+                ..
+            }) => self.scoped(|this| {
+                for LambdaParameter { name, .. } in parameters {
+                    this.declare(name, Class(PARAMETER, 0));
+                }
+                this.expression(expression);
+            }),
+            Expression::If(if_expression) => self.if_expression(if_expression),
+            Expression::Block(block) => self.scoped(|this| this.statements(&block.statements)),
+            Expression::Parens(inner) => self.expression(inner),
+            Expression::Postfix(postfix) => self.postfix(postfix),
+            Expression::Prefix(Prefix::Negation { expr, .. } | Prefix::Minus { expr, .. }) => {
+                self.expression(expr)
+            }
+            Expression::Binary(binary) => {
+                self.expression(&binary.left);
+                self.expression(&binary.right);
+            }
+            Expression::Array(Array::Literal { values, .. }) => {
+                values.iter().for_each(|value| self.expression(value))
+            }
+            Expression::Array(Array::Default {
+                initial_value,
+                length,
+                ..
+            }) => {
+                self.expression(initial_value);
+                self.expression(length);
+            }
+            Expression::StructInitialisation(StructInitialisation { id, fields, .. }) => {
+                self.mark(&id.position, Class(STRUCT, 0));
+                for StructFieldInitialisation { name, value, .. } in fields {
+                    self.mark(&name.position, Class(PROPERTY, 0));
+                    self.expression(value);
+                }
+            }
+            Expression::Interpolation(interpolation) => interpolation
+                .expressions()
+                .for_each(|expression| self.expression(expression)),
+        }
+    }
+
+    fn postfix<T: TypeHint>(&mut self, postfix: &Postfix<T>) {
+        match postfix {
+            Postfix::Call { expr, args, .. } => {
+                match expr.as_ref() {
+                    // calling a property always calls a method
+                    Expression::Postfix(Postfix::PropertyAccess { expr, property, .. }) => {
+                        self.expression(expr);
+                        self.mark(&property.position, Class(METHOD, 0));
+                    }
+                    expr => self.expression(expr),
+                }
+                args.iter().for_each(|arg| self.expression(arg));
+            }
+            Postfix::Index { expr, index, .. } => {
+                self.expression(expr);
+                self.expression(index);
+            }
+            Postfix::PropertyAccess { expr, property, .. }
+            | Postfix::OptionalPropertyAccess { expr, property, .. } => {
+                self.expression(expr);
+                let token_type = if property.info.is_function() {
+                    METHOD
+                } else {
+                    PROPERTY
+                };
+                self.mark(&property.position, Class(token_type, 0));
+            }
+            Postfix::Propagate { expr, .. } => self.expression(expr),
+            Postfix::Cast { expr, target, .. } => {
+                self.expression(expr);
+                self.type_name(target);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use why_lib::source_map::SourceMap;
+
+    use super::*;
+
+    /// Decode the relative tokens into absolute (line, column, length, type, modifiers).
+    fn decode(tokens: &[SemanticToken]) -> Vec<(u32, u32, u32, u32, u32)> {
+        let mut line = 0;
+        let mut col = 0;
+
+        tokens
+            .iter()
+            .map(|token| {
+                line += token.delta_line;
+                col = if token.delta_line == 0 {
+                    col + token.delta_start
+                } else {
+                    token.delta_start
+                };
+                (
+                    line,
+                    col,
+                    token.length,
+                    token.token_type,
+                    token.token_modifiers_bitset,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_keywords_and_literals() {
+        let tokens = semantic_tokens(SourceMap::add("const X: i64 = 42; // answer"), None).unwrap();
+
+        assert_eq!(
+            decode(&tokens),
+            vec![
+                (0, 0, 5, KEYWORD, 0),
+                (0, 6, 1, VARIABLE, READONLY | DECLARATION),
+                (0, 9, 3, TYPE, 0),
+                (0, 13, 1, OPERATOR, 0),
+                (0, 15, 2, NUMBER, 0),
+                (0, 19, 9, COMMENT, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_utf16_columns() {
+        let input = "const Ö: str = \"😀\"; // größe";
+        let tokens = semantic_tokens(SourceMap::add(input), None).unwrap();
+
+        assert_eq!(
+            decode(&tokens),
+            vec![
+                (0, 0, 5, KEYWORD, 0),
+                (0, 6, 1, VARIABLE, READONLY | DECLARATION),
+                (0, 9, 3, TYPE, 0),
+                (0, 13, 1, OPERATOR, 0),
+                (0, 15, 4, STRING, 0),
+                (0, 21, 8, COMMENT, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_functions_parameters_and_mutable_bindings() {
+        let input = "fn add(x: i64): i64 {\n    let mut y = x;\n    y = add(y);\n    y\n}";
+        let tokens = decode(&semantic_tokens(SourceMap::add(input), None).unwrap());
+        let class_at = |line, col| {
+            tokens
+                .iter()
+                .find(|token| token.0 == line && token.1 == col)
+                .map(|token| (token.3, token.4))
+        };
+
+        assert_eq!(class_at(0, 3), Some((FUNCTION, DECLARATION)));
+        assert_eq!(class_at(0, 7), Some((PARAMETER, DECLARATION)));
+        assert_eq!(class_at(1, 12), Some((VARIABLE, MUTABLE | DECLARATION)));
+        assert_eq!(class_at(1, 16), Some((PARAMETER, 0)));
+        assert_eq!(class_at(2, 4), Some((VARIABLE, MUTABLE)));
+        assert_eq!(class_at(2, 8), Some((FUNCTION, 0)));
+        assert_eq!(class_at(3, 4), Some((VARIABLE, MUTABLE)));
+    }
+
+    #[test]
+    fn test_structs_fields_and_methods() {
+        let input = "struct Foo {\n    bar: i64;\n}\n\ninstance Foo {\n    fn get(): i64 {\n        42\n    }\n}\n\nfn main(): void {\n    let foo = Foo { bar: 42 };\n    foo.bar;\n    foo.get();\n}";
+        let tokens = decode(&semantic_tokens(SourceMap::add(input), None).unwrap());
+        let class_at = |line, col| {
+            tokens
+                .iter()
+                .find(|token| token.0 == line && token.1 == col)
+                .map(|token| (token.3, token.4))
+        };
+
+        assert_eq!(class_at(0, 7), Some((STRUCT, DECLARATION)));
+        assert_eq!(class_at(1, 4), Some((PROPERTY, DECLARATION)));
+        assert_eq!(class_at(4, 9), Some((STRUCT, 0)));
+        assert_eq!(class_at(5, 7), Some((METHOD, DECLARATION)));
+        assert_eq!(class_at(11, 14), Some((STRUCT, 0)));
+        assert_eq!(class_at(11, 20), Some((PROPERTY, 0)));
+        assert_eq!(class_at(12, 8), Some((PROPERTY, 0)));
+        assert_eq!(class_at(13, 8), Some((METHOD, 0)));
+    }
+
+    #[test]
+    fn test_lambda_bound_to_variable_is_function() {
+        let input =
+            "fn main(): void {\n    let f: (i64) -> i64 = \\(x) => x;\n    let y: i64 = f(42);\n}";
+        let tokens = decode(&semantic_tokens(SourceMap::add(input), None).unwrap());
+
+        assert!(tokens.contains(&(2, 17, 1, FUNCTION, 0)));
+    }
+
+    #[test]
+    fn test_range() {
+        let input = "fn foo(): i64 {\n    42\n}\n\nfn bar(): i64 {\n    42\n}";
+        let range = Range {
+            start: tower_lsp_server::lsp_types::Position::new(4, 0),
+            end: tower_lsp_server::lsp_types::Position::new(5, 0),
+        };
+        let tokens = decode(&semantic_tokens(SourceMap::add(input), Some(range)).unwrap());
+
+        assert!(tokens.iter().all(|token| token.0 == 4));
+        assert_eq!(tokens[1], (4, 3, 3, FUNCTION, DECLARATION));
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+
+use tower_lsp_server::lsp_types::{
+    ParameterInformation, ParameterLabel, Position, SignatureHelp, SignatureInformation,
+};
+use why_lib::lexer::{GetPosition, Lexer, Span, Token};
+use why_lib::parser::{self, ast::*};
+use why_lib::source_map::{FileId, SourceMap};
+use why_lib::typechecker::{Type, TypeChecker};
+
+use crate::visit::{self, TypeHint, Visitor};
+
+/// Parameters and return type of something callable, as written in the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Signature {
+    parameters: Vec<String>,
+    return_type: String,
+}
+
+impl Signature {
+    fn from_function<T>(function: &Function<T>) -> Self {
+        Signature {
+            parameters: function
+                .parameters
+                .iter()
+                .map(
+                    |FunctionParameter {
+                         name, type_name, ..
+                     }| format!("{}: {type_name}", name.name),
+                )
+                .collect(),
+            return_type: function.return_type.to_string(),
+        }
+    }
+
+    fn from_type_name(type_name: &TypeName) -> Option<Self> {
+        let TypeName::Fn {
+            params,
+            return_type,
+            ..
+        } = type_name
+        else {
+            return None;
+        };
+
+        Some(Signature {
+            parameters: params.iter().map(TypeName::to_string).collect(),
+            return_type: return_type.to_string(),
+        })
+    }
+
+    fn from_type(type_id: &Type) -> Option<Self> {
+        let Type::Function {
+            params,
+            return_value,
+        } = type_id
+        else {
+            return None;
+        };
+
+        Some(Signature {
+            parameters: params.iter().map(Type::to_string).collect(),
+            return_type: return_value.to_string(),
+        })
+    }
+}
+
+/// Collects the signatures of all named functions, declarations and methods of a document.
+#[derive(Default)]
+struct Signatures(HashMap<String, Signature>);
+
+impl Signatures {
+    fn collect<T>(statements: &[TopLevelStatement<T>]) -> Self {
+        let mut signatures = Signatures::default();
+
+        for statement in statements {
+            match statement {
+                TopLevelStatement::Declaration(declaration) => signatures.declaration(declaration),
+                TopLevelStatement::Instance(Instance { declarations, .. }) => {
+                    for MethodDeclaration {
+                        id,
+                        parameter_types,
+                        return_type,
+                        ..
+                    } in declarations
+                    {
+                        signatures.0.insert(
+                            id.name.clone(),
+                            Signature {
+                                parameters: parameter_types
+                                    .iter()
+                                    .map(TypeName::to_string)
+                                    .collect(),
+                                return_type: return_type.to_string(),
+                            },
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        visit::walk(statements, &mut signatures);
+
+        signatures
+    }
+
+    fn declaration<T>(&mut self, declaration: &Declaration<T>) {
+        if let Some(signature) = Signature::from_type_name(&declaration.type_name) {
+            self.0.insert(declaration.name.name.clone(), signature);
+        }
+    }
+}
+
+impl<T> Visitor<T> for Signatures {
+    fn function(&mut self, function: &Function<T>) {
+        self.0
+            .insert(function.id.name.clone(), Signature::from_function(function));
+    }
+
+    fn statement(&mut self, statement: &Statement<T>) {
+        if let Statement::Declaration(declaration) = statement {
+            self.declaration(declaration);
+        }
+    }
+}
+
+/// Finds the innermost call whose argument list contains the cursor.
+struct CallFinder<T> {
+    /// Byte offset of the cursor.
+    cursor: usize,
+    call: Option<(Expression<T>, Span)>,
+}
+
+impl<T: Clone> Visitor<T> for CallFinder<T> {
+    fn expression(&mut self, expression: &Expression<T>) {
+        let Expression::Postfix(Postfix::Call { expr, position, .. }) = expression else {
+            return;
+        };
+
+        if position.start < self.cursor && self.cursor < position.end {
+            // calls are visited before their arguments, so nested calls win
+            self.call = Some((expr.as_ref().clone(), position.clone()));
+        }
+    }
+}
+
+impl<T: Clone> CallFinder<T> {
+    fn find(statements: &[TopLevelStatement<T>], cursor: usize) -> Option<(Expression<T>, Span)> {
+        let mut finder = CallFinder { cursor, call: None };
+        visit::walk(statements, &mut finder);
+        finder.call
+    }
+}
+
+/// Show the signature of the callee of the call enclosing the given position.
+pub fn signature_help(file: FileId, position: Position) -> Option<SignatureHelp> {
+    let cursor =
+        SourceMap::get(file)?.offset_utf16(position.line as usize, position.character as usize);
+
+    let tokens = Lexer::for_file(file).lex().ok()?;
+    let statements = parser::parse(&mut tokens.clone().into()).ok()?;
+    let signatures = Signatures::collect(&statements);
+
+    let (callee, arguments) = CallFinder::find(&statements, cursor)?;
+
+    let name = match &callee {
+        Expression::Id(id) => Some(id.name.clone()),
+        Expression::Postfix(Postfix::PropertyAccess { property, .. }) => {
+            Some(property.name.clone())
+        }
+        _ => None,
+    };
+
+    let signature = match name.as_ref().and_then(|name| signatures.0.get(name)) {
+        Some(signature) => signature.clone(),
+        // fall back to what the typechecker knows about the callee (e.g., for lambdas)
+        None => {
+            let checked = TypeChecker::new(statements).check_all().ok()?;
+            let (callee, _) = CallFinder::find(&checked, cursor)?;
+            Signature::from_type(&callee.get_info().type_id()?)?
+        }
+    };
+
+    let mut label = format!("{}(", name.as_deref().unwrap_or_default());
+    let mut parameters = vec![];
+
+    for (i, parameter) in signature.parameters.iter().enumerate() {
+        if i > 0 {
+            label.push_str(", ");
+        }
+        let start = label.len() as u32;
+        label.push_str(parameter);
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([start, label.len() as u32]),
+            documentation: None,
+        });
+    }
+
+    label.push_str(&format!("): {}", signature.return_type));
+
+    Some(SignatureHelp {
+        signatures: vec![SignatureInformation {
+            label,
+            documentation: None,
+            parameters: Some(parameters),
+            active_parameter: None,
+        }],
+        active_signature: Some(0),
+        active_parameter: Some(active_parameter(&tokens, &arguments, cursor)),
+    })
+}
+
+/// Count the commas between the opening parenthesis of a call and the cursor, ignoring commas
+/// of nested expressions.
+fn active_parameter(tokens: &[Token], arguments: &Span, cursor: usize) -> u32 {
+    let mut depth = 0;
+    let mut active = 0;
+
+    for token in tokens {
+        let start = token.position().start;
+        if start <= arguments.start {
+            continue;
+        }
+        if start >= cursor {
+            break;
+        }
+
+        match token {
+            Token::LParen { .. } | Token::LBracket { .. } | Token::LBrace { .. } => depth += 1,
+            Token::RParen { .. } | Token::RBracket { .. } | Token::RBrace { .. } => depth -= 1,
+            Token::Comma { .. } if depth == 0 => active += 1,
+            _ => {}
+        }
+    }
+
+    active
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label_of(help: &SignatureHelp) -> &str {
+        &help.signatures[0].label
+    }
+
+    #[test]
+    fn test_function_call() {
+        let input =
+            "fn add(x: i64, y: i64): i64 {\n    x + y\n}\n\nfn main(): void {\n    add(1, 2);\n}";
+
+        let help = signature_help(SourceMap::add(input), Position::new(5, 8)).unwrap();
+        assert_eq!(label_of(&help), "add(x: i64, y: i64): i64");
+        assert_eq!(help.active_parameter, Some(0));
+
+        let help = signature_help(SourceMap::add(input), Position::new(5, 11)).unwrap();
+        assert_eq!(help.active_parameter, Some(1));
+
+        let parameters = help.signatures[0].parameters.as_ref().unwrap();
+        assert_eq!(parameters[1].label, ParameterLabel::LabelOffsets([12, 18]));
+    }
+
+    #[test]
+    fn test_nested_call() {
+        let input = "fn add(x: i64, y: i64): i64 {\n    x + y\n}\n\nfn inc(x: i64): i64 {\n    x + 1\n}\n\nfn main(): void {\n    add(inc(1), 2);\n}";
+
+        let help = signature_help(SourceMap::add(input), Position::new(9, 12)).unwrap();
+        assert_eq!(label_of(&help), "inc(x: i64): i64");
+
+        let help = signature_help(SourceMap::add(input), Position::new(9, 16)).unwrap();
+        assert_eq!(label_of(&help), "add(x: i64, y: i64): i64");
+        assert_eq!(help.active_parameter, Some(1));
+    }
+
+    #[test]
+    fn test_lambda_call() {
+        let input = "fn main(): void {\n    let f: (i64) -> i64 = \\(x) => x;\n    f(42);\n}";
+
+        let help = signature_help(SourceMap::add(input), Position::new(2, 6)).unwrap();
+        assert_eq!(label_of(&help), "f(i64): i64");
+    }
+
+    #[test]
+    fn test_outside_of_call() {
+        let input = "fn main(): void {\n    main();\n}";
+
+        assert!(signature_help(SourceMap::add(input), Position::new(1, 4)).is_none());
+    }
+}
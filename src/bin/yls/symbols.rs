@@ -0,0 +1,355 @@
+use tower_lsp_server::lsp_types::{
+    DocumentSymbol, Location, OneOf, Range, SymbolKind, Uri, WorkspaceSymbol,
+};
+use why_lib::parser::ast::{
+    Constant, Declaration, Function, FunctionParameter, Instance, MethodDeclaration,
+    StructDeclaration, StructFieldDeclaration, TopLevelStatement,
+};
+
+use crate::span_to_range;
+
+/// Build the hierarchical outline of a document from its toplevel statements.
+pub fn document_symbols(statements: &[TopLevelStatement<()>]) -> Vec<DocumentSymbol> {
+    statements
+        .iter()
+        .filter_map(|statement| match statement {
+            TopLevelStatement::Comment(..) => None,
+            TopLevelStatement::Function(function) => {
+                Some(function_symbol(function, SymbolKind::FUNCTION))
+            }
+            TopLevelStatement::Constant(constant) => Some(constant_symbol(constant)),
+            TopLevelStatement::Declaration(declaration) => Some(declaration_symbol(declaration)),
+            TopLevelStatement::StructDeclaration(declaration) => Some(struct_symbol(declaration)),
+            TopLevelStatement::Instance(instance) => Some(instance_symbol(instance)),
+        })
+        .collect()
+}
+
+/// Flatten the outline of a document into workspace symbols matching the given query. An empty
+/// query matches every symbol.
+pub fn workspace_symbols(
+    uri: &Uri,
+    symbols: &[DocumentSymbol],
+    query: &str,
+) -> Vec<WorkspaceSymbol> {
+    let query = query.to_lowercase();
+    let mut result = vec![];
+
+    collect_workspace_symbols(uri, symbols, None, &query, &mut result);
+
+    result
+}
+
+fn collect_workspace_symbols(
+    uri: &Uri,
+    symbols: &[DocumentSymbol],
+    container_name: Option<&str>,
+    query: &str,
+    result: &mut Vec<WorkspaceSymbol>,
+) {
+    for symbol in symbols {
+        if symbol.name.to_lowercase().contains(query) {
+            result.push(WorkspaceSymbol {
+                name: symbol.name.clone(),
+                kind: symbol.kind,
+                tags: None,
+                container_name: container_name.map(str::to_string),
+                location: OneOf::Left(Location {
+                    uri: uri.clone(),
+                    range: symbol.selection_range,
+                }),
+                data: None,
+            });
+        }
+
+        if let Some(children) = &symbol.children {
+            collect_workspace_symbols(uri, children, Some(&symbol.name), query, result);
+        }
+    }
+}
+
+#[allow(deprecated)]
+fn symbol(
+    name: impl ToString,
+    detail: Option<String>,
+    kind: SymbolKind,
+    range: Range,
+    selection_range: Range,
+    children: Option<Vec<DocumentSymbol>>,
+) -> DocumentSymbol {
+    DocumentSymbol {
+        name: name.to_string(),
+        detail,
+        kind,
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range,
+        children,
+    }
+}
+
+fn function_symbol(function: &Function<()>, kind: SymbolKind) -> DocumentSymbol {
+    let Function {
+        id,
+        parameters,
+        return_type,
+        position,
+        ..
+    } = function;
+
+    let parameters = parameters
+        .iter()
+        .map(
+            |FunctionParameter {
+                 name, type_name, ..
+             }| format!("{}: {type_name}", name.name),
+        )
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    symbol(
+        &id.name,
+        Some(format!("({parameters}): {return_type}")),
+        kind,
+        span_to_range(position),
+        span_to_range(&id.position),
+        None,
+    )
+}
+
+fn constant_symbol(constant: &Constant<()>) -> DocumentSymbol {
+    let Constant {
+        id,
+        type_name,
+        position,
+        ..
+    } = constant;
+
+    symbol(
+        &id.name,
+        Some(type_name.to_string()),
+        SymbolKind::CONSTANT,
+        span_to_range(position),
+        span_to_range(&id.position),
+        None,
+    )
+}
+
+fn declaration_symbol(declaration: &Declaration<()>) -> DocumentSymbol {
+    let Declaration {
+        name,
+        type_name,
+        position,
+        ..
+    } = declaration;
+
+    symbol(
+        &name.name,
+        Some(format!("declare {type_name}")),
+        SymbolKind::FUNCTION,
+        span_to_range(position),
+        span_to_range(&name.position),
+        None,
+    )
+}
+
+fn struct_symbol(declaration: &StructDeclaration<()>) -> DocumentSymbol {
+    let StructDeclaration {
+        id,
+        fields,
+        position,
+        ..
+    } = declaration;
+
+    let fields = fields
+        .iter()
+        .map(
+            |StructFieldDeclaration {
+                 name,
+                 type_name,
+                 position,
+                 ..
+             }| {
+                symbol(
+                    &name.name,
+                    Some(type_name.to_string()),
+                    SymbolKind::FIELD,
+                    span_to_range(position),
+                    span_to_range(&name.position),
+                    None,
+                )
+            },
+        )
+        .collect();
+
+    symbol(
+        &id.name,
+        None,
+        SymbolKind::STRUCT,
+        span_to_range(position),
+        span_to_range(&id.position),
+        Some(fields),
+    )
+}
+
+fn instance_symbol(instance: &Instance<()>) -> DocumentSymbol {
+    let Instance {
+        name,
+        functions,
+        declarations,
+        position,
+        ..
+    } = instance;
+
+    let mut children = functions
+        .iter()
+        .map(|function| function_symbol(function, SymbolKind::METHOD))
+        .chain(declarations.iter().map(method_declaration_symbol))
+        .collect::<Vec<_>>();
+
+    // keep the children in the order they appear in the source
+    children.sort_by_key(|child| (child.range.start.line, child.range.start.character));
+
+    symbol(
+        name,
+        Some("instance".into()),
+        SymbolKind::CLASS,
+        span_to_range(position),
+        span_to_range(&name.position()),
+        Some(children),
+    )
+}
+
+fn method_declaration_symbol(declaration: &MethodDeclaration<()>) -> DocumentSymbol {
+    let MethodDeclaration {
+        id,
+        parameter_types,
+        return_type,
+        position,
+        ..
+    } = declaration;
+
+    let parameters = parameter_types
+        .iter()
+        .map(|type_name| type_name.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    symbol(
+        &id.name,
+        Some(format!("declare ({parameters}): {return_type}")),
+        SymbolKind::METHOD,
+        span_to_range(position),
+        span_to_range(&id.position),
+        None,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use why_lib::{lexer::Lexer, parser::parse};
+
+    use super::*;
+
+    fn outline(input: &str) -> Vec<DocumentSymbol> {
+        let tokens = Lexer::new(input).lex().expect("should lex");
+        let statements = parse(&mut tokens.into()).expect("should parse");
+        document_symbols(&statements)
+    }
+
+    #[test]
+    fn test_function_symbol() {
+        let symbols = outline("fn add(x: i64, y: i64): i64 {\n    x + y\n}");
+
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name, "add");
+        assert_eq!(symbols[0].kind, SymbolKind::FUNCTION);
+        assert_eq!(symbols[0].detail.as_deref(), Some("(x: i64, y: i64): i64"));
+        assert_eq!(symbols[0].range.start.line, 0);
+        assert_eq!(symbols[0].range.end.line, 2);
+    }
+
+    #[test]
+    fn test_struct_with_fields() {
+        let symbols = outline("struct Foo {\n    x: i64;\n    y: f64;\n}");
+
+        let children = symbols[0].children.as_ref().expect("struct has fields");
+        assert_eq!(
+            children.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+            vec!["x", "y"]
+        );
+        assert!(children.iter().all(|c| c.kind == SymbolKind::FIELD));
+    }
+
+    #[test]
+    fn test_instance_children_in_source_order() {
+        let symbols = outline(
+            "instance Foo {\n    fn get(): i64 {\n        42\n    }\n    declare len(): i64;\n}",
+        );
+
+        assert_eq!(symbols[0].name, "Foo");
+        let children = symbols[0].children.as_ref().expect("instance has methods");
+        assert_eq!(
+            children.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+            vec!["get", "len"]
+        );
+        assert!(children.iter().all(|c| c.kind == SymbolKind::METHOD));
+    }
+
+    #[test]
+    fn test_ranges_contain_selection_ranges() {
+        fn check(symbols: &[DocumentSymbol]) {
+            for symbol in symbols {
+                let DocumentSymbol {
+                    range,
+                    selection_range,
+                    ..
+                } = symbol;
+                assert!(
+                    (range.start.line, range.start.character)
+                        <= (selection_range.start.line, selection_range.start.character)
+                        && (selection_range.end.line, selection_range.end.character)
+                            <= (range.end.line, range.end.character),
+                    "range of '{}' does not contain its selection range",
+                    symbol.name
+                );
+                check(symbol.children.as_deref().unwrap_or_default());
+            }
+        }
+
+        let symbols = outline(
+            "fn add(x: i64, y: i64): i64 {\n    x + y\n}\n\nconst BAZ: i64 = 42;\n\ndeclare print: (str) -> void;\n\nstruct Foo {\n    bar: i64;\n}\n\ninstance Foo {\n    fn get(): i64 {\n        42\n    }\n    declare len(): i64;\n}",
+        );
+
+        assert_eq!(
+            symbols.iter().map(|s| s.kind).collect::<Vec<_>>(),
+            vec![
+                SymbolKind::FUNCTION,
+                SymbolKind::CONSTANT,
+                SymbolKind::FUNCTION,
+                SymbolKind::STRUCT,
+                SymbolKind::CLASS
+            ]
+        );
+        check(&symbols);
+
+        // constants and declarations run through their semicolon
+        assert_eq!(symbols[1].range.end.character, 20);
+        assert_eq!(symbols[2].range.end.character, 29);
+    }
+
+    #[test]
+    fn test_workspace_symbols_query() {
+        let symbols = outline("struct Foo {\n    bar: i64;\n}\n\nconst BAZ: i64 = 42;");
+        let uri = "file:///test.why".parse::<Uri>().unwrap();
+
+        let found = workspace_symbols(&uri, &symbols, "ba");
+
+        assert_eq!(
+            found.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(),
+            vec!["bar", "BAZ"]
+        );
+        assert_eq!(found[0].container_name.as_deref(), Some("Foo"));
+    }
+}
@@ -0,0 +1,155 @@
+use why_lib::parser::ast::*;
+use why_lib::typechecker::{Type, TypeInformation};
+
+/// Type knowledge about a node of the AST. Nodes which did not pass the typechecker know
+/// nothing about their type.
+pub trait TypeHint {
+    fn type_id(&self) -> Option<Type>;
+
+    fn is_function(&self) -> bool {
+        matches!(self.type_id(), Some(Type::Function { .. }))
+    }
+}
+
+impl TypeHint for () {
+    fn type_id(&self) -> Option<Type> {
+        None
+    }
+}
+
+impl TypeHint for TypeInformation {
+    fn type_id(&self) -> Option<Type> {
+        self.resolved_type()
+    }
+}
+
+/// Callbacks for nodes of the AST. Every node is visited before its children.
+pub trait Visitor<T> {
+    fn function(&mut self, _function: &Function<T>) {}
+
+    fn statement(&mut self, _statement: &Statement<T>) {}
+
+    fn expression(&mut self, _expression: &Expression<T>) {}
+}
+
+/// Visit every function, statement and expression within the given toplevel statements.
+pub fn walk<T>(statements: &[TopLevelStatement<T>], visitor: &mut impl Visitor<T>) {
+    for statement in statements {
+        match statement {
+            TopLevelStatement::Function(function) => walk_function(function, visitor),
+            TopLevelStatement::Constant(Constant { value, .. }) => walk_expression(value, visitor),
+            TopLevelStatement::Instance(Instance { functions, .. }) => functions
+                .iter()
+                .for_each(|function| walk_function(function, visitor)),
+            TopLevelStatement::Comment(..)
+            | TopLevelStatement::Declaration(_)
+            | TopLevelStatement::StructDeclaration(_) => {}
+        }
+    }
+}
+
+fn walk_function<T>(function: &Function<T>, visitor: &mut impl Visitor<T>) {
+    visitor.function(function);
+    walk_statements(&function.statements, visitor);
+}
+
+fn walk_statements<T>(statements: &[Statement<T>], visitor: &mut impl Visitor<T>) {
+    statements
+        .iter()
+        .for_each(|statement| walk_statement(statement, visitor));
+}
+
+fn walk_statement<T>(statement: &Statement<T>, visitor: &mut impl Visitor<T>) {
+    visitor.statement(statement);
+
+    match statement {
+        Statement::Function(function) => walk_function(function, visitor),
+        Statement::If(if_expression) => walk_if(if_expression, visitor),
+        Statement::WhileLoop(WhileLoop {
+            condition, block, ..
+        }) => {
+            walk_expression(condition, visitor);
+            walk_statements(&block.statements, visitor);
+        }
+        Statement::Initialization(Initialisation { value, .. }) => {
+            if let Some(value) = value {
+                walk_expression(value, visitor);
+            }
+        }
+        Statement::Constant(Constant { value, .. }) => walk_expression(value, visitor),
+        Statement::Assignment(Assignment { lvalue, rvalue, .. }) => {
+            if let LValue::Postfix(postfix) = lvalue {
+                walk_postfix(postfix, visitor);
+            }
+            walk_expression(rvalue, visitor);
+        }
+        Statement::Expression(expression)
+        | Statement::YieldingExpression(expression)
+        | Statement::Return(expression) => walk_expression(expression, visitor),
+        Statement::Comment(..) | Statement::Declaration(_) | Statement::StructDeclaration(_) => {}
+    }
+}
+
+fn walk_if<T>(if_expression: &If<T>, visitor: &mut impl Visitor<T>) {
+    walk_expression(&if_expression.condition, visitor);
+    walk_statements(&if_expression.statements, visitor);
+    walk_statements(&if_expression.else_statements, visitor);
+}
+
+fn walk_expression<T>(expression: &Expression<T>, visitor: &mut impl Visitor<T>) {
+    visitor.expression(expression);
+
+    match expression {
+        Expression::Id(_)
+        | Expression::Num(_)
+        | Expression::Character(_)
+        | Expression::AstString(_) => {}
+        Expression::Function(function) => walk_function(function, visitor),
+        Expression::Lambda(Lambda { expression, .. }) => walk_expression(expression, visitor),
+        Expression::If(if_expression) => walk_if(if_expression, visitor),
+        Expression::Block(block) => walk_statements(&block.statements, visitor),
+        Expression::Parens(inner) => walk_expression(inner, visitor),
+        Expression::Postfix(postfix) => walk_postfix(postfix, visitor),
+        Expression::Prefix(Prefix::Negation { expr, .. } | Prefix::Minus { expr, .. }) => {
+            walk_expression(expr, visitor)
+        }
+        Expression::Binary(binary) => {
+            walk_expression(&binary.left, visitor);
+            walk_expression(&binary.right, visitor);
+        }
+        Expression::Array(Array::Literal { values, .. }) => values
+            .iter()
+            .for_each(|value| walk_expression(value, visitor)),
+        Expression::Array(Array::Default {
+            initial_value,
+            length,
+            ..
+        }) => {
+            walk_expression(initial_value, visitor);
+            walk_expression(length, visitor);
+        }
+        Expression::StructInitialisation(StructInitialisation { fields, .. }) => fields
+            .iter()
+            .for_each(|field| walk_expression(&field.value, visitor)),
+        Expression::Interpolation(interpolation) => interpolation
+            .expressions()
+            .for_each(|expression| walk_expression(expression, visitor)),
+    }
+}
+
+fn walk_postfix<T>(postfix: &Postfix<T>, visitor: &mut impl Visitor<T>) {
+    match postfix {
+        Postfix::Call { expr, args, .. } => {
+            walk_expression(expr, visitor);
+            args.iter().for_each(|arg| walk_expression(arg, visitor));
+        }
+        Postfix::Index { expr, index, .. } => {
+            walk_expression(expr, visitor);
+            walk_expression(index, visitor);
+        }
+        Postfix::PropertyAccess { expr, .. }
+        | Postfix::OptionalPropertyAccess { expr, .. }
+        | Postfix::Propagate { expr, .. }
+        | Postfix::Cast { expr, .. } => walk_expression(expr, visitor),
+    }
+}
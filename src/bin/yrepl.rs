@@ -0,0 +1,301 @@
+use std::io::{self, BufRead, Write};
+
+use anyhow::Result;
+use typed_arena::Arena;
+use why_lib::{
+    interpreter::{Interpreter, Value},
+    lexer::{Lexer, Span, Token},
+    parser::{
+        ast::{Constant, Function, Initialisation, Statement, TopLevelStatement},
+        parse, parse_statements,
+    },
+    source_map::SourceMap,
+    typechecker::{Type, TypeChecker, ValidatedTypeInformation},
+};
+
+/// Storage for the validated statements of a session. Later inputs might call functions and
+/// lambdas defined by previous ones, so the statements have to live as long as the session.
+#[derive(Default)]
+struct Inputs {
+    statements: Arena<Statement<ValidatedTypeInformation>>,
+    top_level_statements: Arena<TopLevelStatement<ValidatedTypeInformation>>,
+}
+
+/// Interactive session, which checks every input against all previous inputs and evaluates it.
+struct Repl<'a> {
+    inputs: &'a Inputs,
+    checker: TypeChecker,
+    interpreter: Interpreter<'a>,
+    /// Name of the file within the source map, which holds the inputs of this session.
+    name: String,
+    /// All inputs so far. New inputs are appended to them, so the positions within previous
+    /// inputs (e.g., of the functions defined by them) stay valid.
+    source: String,
+}
+
+impl<'a> Repl<'a> {
+    fn new(inputs: &'a Inputs, name: impl ToString) -> Self {
+        Repl {
+            inputs,
+            checker: TypeChecker::default(),
+            interpreter: Interpreter::empty(),
+            name: name.to_string(),
+            source: String::new(),
+        }
+    }
+
+    /// Get the span of an input, which is placed after all previous inputs of this session.
+    fn span(&self, input: &str) -> Span {
+        let file = SourceMap::add_named(&self.name, format!("{}{input}", self.source));
+        Span::new(file, self.source.len(), self.source.len() + input.len())
+    }
+
+    /// Check and evaluate a complete input, which is either a sequence of toplevel statements
+    /// or a sequence of statements (including bare expressions). Returns a line for every
+    /// binding and every expression with a value.
+    fn eval(&mut self, input: &str) -> Result<Vec<String>, String> {
+        let tokens = Lexer::for_span(&self.span(input))
+            .lex()
+            .map_err(|e| e.to_string())?;
+        self.source.push_str(input);
+
+        let is_top_level = matches!(
+            tokens.first(),
+            Some(
+                Token::FnKeyword { .. }
+                    | Token::Const { .. }
+                    | Token::DeclareKeyword { .. }
+                    | Token::StructKeyword { .. }
+                    | Token::InstanceKeyword { .. }
+            )
+        );
+
+        if is_top_level {
+            let statements = parse(&mut tokens.into()).map_err(|e| e.to_string())?;
+            return self.eval_top_level(statements);
+        }
+
+        let statements = parse_statements(&mut tokens.into()).map_err(|e| e.to_string())?;
+        let mut output = vec![];
+
+        for statement in statements {
+            let checked = self
+                .checker
+                .check_statement(statement)
+                .map_err(|e| e.to_string())?;
+            let validated =
+                TypeChecker::validate_statement(checked).map_err(|e| e.err().to_string())?;
+
+            let validated = &*self.inputs.statements.alloc(validated);
+            let value = self
+                .interpreter
+                .evaluate(validated)
+                .map_err(|e| e.to_string())?;
+
+            let described = match validated {
+                Statement::Initialization(Initialisation { id, .. })
+                | Statement::Constant(Constant { id, .. }) => describe(
+                    &id.name,
+                    &id.info.type_id,
+                    self.interpreter.global(&id.name),
+                ),
+                Statement::Expression(expression) | Statement::YieldingExpression(expression) => {
+                    describe("", &expression.get_info().type_id, Some(value))
+                }
+                Statement::Function(Function { id, .. }) => {
+                    describe(&id.name, &id.info.type_id, None)
+                }
+                _ => None,
+            };
+            output.extend(described);
+        }
+
+        Ok(output)
+    }
+
+    fn eval_top_level(
+        &mut self,
+        statements: Vec<TopLevelStatement<()>>,
+    ) -> Result<Vec<String>, String> {
+        let mut output = vec![];
+
+        for statement in statements {
+            let checked = self
+                .checker
+                .check_top_level_statement(statement)
+                .map_err(|e| e.to_string())?;
+            let validated = TypeChecker::validate(vec![checked])
+                .map_err(|e| e.err().to_string())?
+                .remove(0);
+
+            let validated = &*self.inputs.top_level_statements.alloc(validated);
+            self.interpreter.add(validated).map_err(|e| e.to_string())?;
+
+            let described = match validated {
+                TopLevelStatement::Function(Function { id, .. }) => {
+                    describe(&id.name, &id.info.type_id, None)
+                }
+                TopLevelStatement::Constant(Constant { id, .. }) => describe(
+                    &id.name,
+                    &id.info.type_id,
+                    self.interpreter.global(&id.name),
+                ),
+                _ => None,
+            };
+            output.extend(described);
+        }
+
+        Ok(output)
+    }
+
+    /// Check whether an input is incomplete, i.e., contains more opening than closing braces,
+    /// brackets or parentheses.
+    fn is_incomplete(&self, input: &str) -> bool {
+        let Ok(tokens) = Lexer::for_span(&self.span(input)).lex() else {
+            return false;
+        };
+
+        let depth = tokens.iter().fold(0isize, |depth, token| match token {
+            Token::LBrace { .. } | Token::LBracket { .. } | Token::LParen { .. } => depth + 1,
+            Token::RBrace { .. } | Token::RBracket { .. } | Token::RParen { .. } => depth - 1,
+            _ => depth,
+        });
+
+        depth > 0
+    }
+}
+
+/// Describe a named binding (`name: type = value`) or the value of an expression
+/// (`: type = value`). Functions are described by their type only, and expressions without a
+/// value (i.e., of type `void`) are not described at all.
+fn describe(name: &str, type_id: &Type, value: Option<Value>) -> Option<String> {
+    match value {
+        Some(Value::Void) => None,
+        Some(Value::Function(_)) | None => Some(format!("{name}: {type_id}")),
+        Some(value) => Some(format!("{name}: {type_id} = {value}")),
+    }
+}
+
+fn main() -> Result<()> {
+    let inputs = Inputs::default();
+    let mut repl = Repl::new(&inputs, "<repl>");
+    let mut input = String::new();
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        print!("{}", if input.is_empty() { "> " } else { "... " });
+        io::stdout().flush()?;
+
+        let Some(line) = lines.next() else {
+            println!();
+            return Ok(());
+        };
+
+        input.push_str(&line?);
+        input.push('\n');
+
+        if repl.is_incomplete(&input) {
+            continue;
+        }
+
+        if !input.trim().is_empty() {
+            match repl.eval(&input) {
+                Ok(output) => output.iter().for_each(|line| println!("{line}")),
+                Err(e) => eprintln!("{e}"),
+            }
+        }
+
+        input.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_persistent_context() {
+        let inputs = Inputs::default();
+        let mut repl = Repl::new(&inputs, "<test_persistent_context>");
+
+        assert_eq!(
+            repl.eval("fn add(x: i64, y: i64): i64 {\n    x + y\n}"),
+            Ok(vec!["add: (i64, i64) -> i64".into()])
+        );
+        assert_eq!(
+            repl.eval("let mut x = add(1, 2);"),
+            Ok(vec!["x: i64 = 3".into()])
+        );
+        assert_eq!(repl.eval("x + 1\n"), Ok(vec![": i64 = 4".into()]));
+        assert_eq!(
+            repl.eval("const PI: f64 = 3.14;"),
+            Ok(vec!["PI: f64 = 3.14".into()])
+        );
+        assert_eq!(
+            repl.eval("let add_x = \\(y) => add(x, y);"),
+            Ok(vec!["add_x: (i64) -> i64".into()])
+        );
+        assert_eq!(repl.eval("add_x(2)\n"), Ok(vec![": i64 = 5".into()]));
+        assert_eq!(repl.eval("x = 10;"), Ok(vec![]));
+        assert_eq!(repl.eval("add_x(2)\n"), Ok(vec![": i64 = 12".into()]));
+    }
+
+    #[test]
+    fn test_errors_do_not_persist() {
+        let inputs = Inputs::default();
+        let mut repl = Repl::new(&inputs, "<test_errors_do_not_persist>");
+
+        assert!(repl.eval("let x = 'a' + y;").is_err());
+        assert!(repl.eval("x").is_err());
+        assert_eq!(repl.eval("let x = 'a';"), Ok(vec!["x: char = 'a'".into()]));
+
+        // the failing initialisation must not leave its variable behind
+        let error = repl.eval("let y: u8 = 300;").unwrap_err();
+        assert!(error.contains("E0031"), "{error}");
+        let error = repl.eval("y\n").unwrap_err();
+        assert!(error.contains("E0004"), "{error}");
+    }
+
+    #[test]
+    fn test_runtime_errors() {
+        let inputs = Inputs::default();
+        let mut repl = Repl::new(&inputs, "<test_runtime_errors>");
+
+        assert_eq!(
+            repl.eval("let xs = [1, 2];"),
+            Ok(vec!["xs: [i64; 2] = [1, 2]".into()])
+        );
+        let error = repl.eval("let i: usize = 2;\nxs[i]\n").unwrap_err();
+        assert!(error.contains("E0021"), "{error}");
+    }
+
+    #[test]
+    fn test_single_source_file() {
+        let inputs = Inputs::default();
+        let mut repl = Repl::new(&inputs, "<test_single_source_file>");
+        let file = repl.span("").file;
+
+        repl.eval("fn one(): i64 {\n    1\n}").unwrap();
+        repl.eval("let two = one() + 1;").unwrap();
+
+        // inputs are appended to the file of the session instead of adding new files
+        assert_eq!(repl.span("").file, file);
+        assert_eq!(
+            SourceMap::get(file).unwrap().source(),
+            "fn one(): i64 {\n    1\n}let two = one() + 1;"
+        );
+    }
+
+    #[test]
+    fn test_incomplete_input() {
+        let inputs = Inputs::default();
+        let repl = Repl::new(&inputs, "<test_incomplete_input>");
+
+        assert!(repl.is_incomplete("fn main(): void {\n"));
+        assert!(repl.is_incomplete("foo(1,\n"));
+        assert!(!repl.is_incomplete("fn main(): void {\n}\n"));
+        assert!(!repl.is_incomplete("let x = 42;"));
+    }
+}
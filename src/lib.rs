@@ -1,21 +1,40 @@
-use std::{fs, process};
+use std::{
+    env,
+    fmt::Debug,
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    process,
+};
 
-use clap::{Parser, command};
-use why_lib::{lexer::Lexer, parser::parse, typechecker::TypeChecker};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use why_lib::{
+    diagnostic::{self, Diagnostic, Severity},
+    lexer::Lexer,
+    parser::{ast::TopLevelStatement, parse},
+    source_map::{FileId, SourceMap},
+    test_runner,
+    typechecker::{TypeCheckError, TypeCheckWarning, TypeChecker, ValidatedTypeInformation},
+};
 
 #[derive(Parser, Debug, serde::Serialize, serde::Deserialize)]
 #[command(author, version, about)]
 #[command(propagate_version = true)]
+#[command(subcommand_negates_reqs = true)]
 pub struct VCArgs {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// The path to the source file.
-    #[arg(index = 1)]
-    pub file: std::path::PathBuf,
+    #[arg(index = 1, required = true)]
+    pub file: Option<PathBuf>,
 
-    /// Print the lexed source tree.
+    /// Print the lexed source tree (same as `--emit=tokens`).
     #[arg(short = 'l', long)]
     pub print_lexed: bool,
 
-    /// Print the parsed AST.
+    /// Print the parsed AST (same as `--emit=ast`).
     #[arg(short = 'p', long)]
     pub print_parsed: bool,
 
@@ -23,66 +42,559 @@ pub struct VCArgs {
     #[arg(short = 'c', long)]
     pub print_checked: bool,
 
-    /// Print the validated AST.
+    /// Print the validated AST (same as `--emit=typed-ast`).
     #[arg(short = 'v', long)]
     pub print_validated: bool,
 
+    /// Intermediate representations to print, separated by commas.
+    #[arg(long, value_delimiter = ',')]
+    pub emit: Vec<Emit>,
+
+    /// Format of the emitted intermediate representations.
+    #[arg(long, value_enum, default_value_t = OutputFormat::Debug, global = true)]
+    pub format: OutputFormat,
+
+    /// Format of reported errors.
+    #[arg(long, value_enum, default_value_t = ErrorFormat::Human, global = true)]
+    pub error_format: ErrorFormat,
+
+    /// When to use colors in reported errors.
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto, global = true)]
+    pub color: ColorChoice,
+
+    /// Report lints as errors, e.g., `-D warnings` to fail on any warning.
+    #[arg(short = 'D', long = "deny", value_enum, global = true)]
+    pub deny: Vec<Lint>,
+
     #[arg(short, long, default_value = "a.out")]
-    pub output: Option<std::path::PathBuf>,
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug, serde::Serialize, serde::Deserialize)]
+pub enum Command {
+    /// Run all functions annotated with `#[test]`.
+    Test {
+        /// Source files or directories to search for tests (defaults to the current directory).
+        paths: Vec<PathBuf>,
+    },
+}
+
+/// Intermediate representations of a compilation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, serde::Serialize, serde::Deserialize)]
+pub enum Emit {
+    Tokens,
+    Ast,
+    TypedAst,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, serde::Serialize, serde::Deserialize)]
+pub enum OutputFormat {
+    Debug,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, serde::Serialize, serde::Deserialize)]
+pub enum ErrorFormat {
+    Human,
+    Json,
+}
+
+/// Groups of lints which can be turned into errors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, serde::Serialize, serde::Deserialize)]
+pub enum Lint {
+    /// All warnings.
+    Warnings,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, serde::Serialize, serde::Deserialize)]
+pub enum ColorChoice {
+    /// Use colors if errors are written to a terminal and `NO_COLOR` is not set.
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /// Configure the coloring of all diagnostics printed from now on.
+    pub fn apply(self) {
+        let colorize = match self {
+            ColorChoice::Auto => {
+                io::stderr().is_terminal() && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        };
+
+        diagnostic::set_colored(colorize);
+    }
 }
 
 impl VCArgs {
     pub fn init() -> Self {
         VCArgs::parse()
     }
+
+    fn emits(&self, emit: Emit) -> bool {
+        let printed = match emit {
+            Emit::Tokens => self.print_lexed,
+            Emit::Ast => self.print_parsed,
+            Emit::TypedAst => self.print_validated,
+        };
+
+        printed || self.emit.contains(&emit)
+    }
+
+    fn print(&self, value: &(impl Serialize + Debug)) -> anyhow::Result<()> {
+        match self.format {
+            OutputFormat::Debug => println!("{value:#?}"),
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        }
+
+        Ok(())
+    }
+
+    /// Report all given errors of a file.
+    fn report(&self, file: &Path, diagnostics: Vec<Diagnostic>) {
+        for diagnostic in diagnostics {
+            match self.error_format {
+                ErrorFormat::Human => eprintln!("{}\n", diagnostic.render(Some(file))),
+                ErrorFormat::Json => eprintln!("{}", to_json(&diagnostic, file)),
+            }
+        }
+    }
+
+    /// Turn the given warnings into errors, if warnings are denied.
+    fn deny_warnings(&self, warnings: Vec<Diagnostic>) -> Result<Vec<Diagnostic>, Vec<Diagnostic>> {
+        if warnings.is_empty() || !self.deny.contains(&Lint::Warnings) {
+            return Ok(warnings);
+        }
+
+        Err(warnings
+            .into_iter()
+            .map(|warning| {
+                Diagnostic {
+                    severity: Severity::Error,
+                    ..warning
+                }
+                .with_note("warnings are denied by '-D warnings'")
+            })
+            .collect())
+    }
+
+    /// Report all given errors of a file and abort the compilation.
+    fn fail(&self, file: &Path, diagnostics: Vec<Diagnostic>) -> ! {
+        self.report(file, diagnostics);
+
+        process::exit(-1);
+    }
+}
+
+/// Position within a source file. Lines and columns start at 1, columns count characters.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct JsonPosition {
+    line: usize,
+    column: usize,
+}
+
+impl From<(usize, usize)> for JsonPosition {
+    fn from((line, column): (usize, usize)) -> Self {
+        JsonPosition {
+            line: line + 1,
+            column: column + 1,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct JsonLabel<'a> {
+    start: JsonPosition,
+    end: JsonPosition,
+    message: Option<&'a str>,
+    primary: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct JsonSuggestion<'a> {
+    message: &'a str,
+    start: JsonPosition,
+    end: JsonPosition,
+    replacement: &'a str,
+}
+
+/// Machine readable representation of a diagnostic. The location of the diagnostic is the one of
+/// its primary label. The end of all ranges is exclusive.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+struct JsonDiagnostic<'a> {
+    code: &'static str,
+    severity: &'static str,
+    message: &'a str,
+    file: &'a Path,
+    start: Option<JsonPosition>,
+    end: Option<JsonPosition>,
+    labels: Vec<JsonLabel<'a>>,
+    notes: &'a [String],
+    suggestions: Vec<JsonSuggestion<'a>>,
+}
+
+fn to_json(diagnostic: &Diagnostic, file: &Path) -> String {
+    let primary = diagnostic.primary_span();
+
+    let json = JsonDiagnostic {
+        code: diagnostic.code,
+        severity: match diagnostic.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        },
+        message: &diagnostic.message,
+        file,
+        start: primary.map(|span| span.start_chars().into()),
+        end: primary.map(|span| span.end_chars().into()),
+        labels: diagnostic
+            .labels
+            .iter()
+            .map(|label| JsonLabel {
+                start: label.span.start_chars().into(),
+                end: label.span.end_chars().into(),
+                message: label.message.as_deref(),
+                primary: label.primary,
+            })
+            .collect(),
+        notes: &diagnostic.notes,
+        suggestions: diagnostic
+            .suggestions
+            .iter()
+            .map(|fix| JsonSuggestion {
+                message: &fix.message,
+                start: fix.span.start_chars().into(),
+                end: fix.span.end_chars().into(),
+                replacement: &fix.replacement,
+            })
+            .collect(),
+    };
+
+    serde_json::to_string(&json).expect("diagnostics should serialize")
 }
 
 pub fn compile_file(args: VCArgs) -> anyhow::Result<()> {
-    let input = fs::read_to_string(args.file)?;
+    args.color.apply();
+
+    let Some(path) = &args.file else {
+        unreachable!("a file is required without a subcommand")
+    };
+
+    let input = fs::read_to_string(path)?;
+    let file = SourceMap::add_named(path.display().to_string(), input);
 
-    let lexer = Lexer::new(&input);
-    let tokens = lexer.lex()?;
+    let lexer = Lexer::for_file(file);
+    let tokens = match lexer.lex() {
+        Ok(tokens) => tokens,
+        Err(e) => args.fail(path, vec![e.diagnostic()]),
+    };
 
-    if args.print_lexed {
-        println!("{tokens:#?}");
+    if args.emits(Emit::Tokens) {
+        args.print(&tokens)?;
     }
 
     let statements = match parse(&mut tokens.into()) {
         Ok(stms) => stms,
-        Err(e) => {
-            eprintln!("{e}");
-            process::exit(-1);
-        }
+        Err(e) => args.fail(path, vec![e.diagnostic()]),
     };
 
-    if args.print_parsed {
-        println!("{statements:#?}");
+    if args.emits(Emit::Ast) {
+        args.print(&statements)?;
     }
 
     let typechecker = TypeChecker::new(statements);
-    let checked = match typechecker.check() {
+    let checked = match typechecker.check_all() {
         Ok(checked) => checked,
-        Err(e) => {
-            eprintln!("{e}");
-            process::exit(-1);
-        }
+        Err(errors) => args.fail(
+            path,
+            errors.iter().map(TypeCheckError::diagnostic).collect(),
+        ),
     };
 
+    let warnings = TypeChecker::warnings(&checked)
+        .iter()
+        .map(TypeCheckWarning::diagnostic)
+        .collect();
+    match args.deny_warnings(warnings) {
+        Ok(warnings) => args.report(path, warnings),
+        Err(errors) => args.fail(path, errors),
+    }
+
     if args.print_checked {
         println!("{checked:#?}");
     }
 
     let validated = match TypeChecker::validate(checked) {
         Ok(validated) => validated,
-        Err(e) => {
-            eprintln!("{e}");
-            process::exit(-1);
+        Err(e) => args.fail(path, vec![e.diagnostic()]),
+    };
+
+    if args.emits(Emit::TypedAst) {
+        args.print(&validated)?;
+    }
+
+    Ok(())
+}
+
+/// Run the tests of all given source files and directories. Files which do not compile and
+/// failing tests are reported, in which case the process exits with an error.
+pub fn run_tests(args: &VCArgs, paths: &[PathBuf]) -> anyhow::Result<()> {
+    args.color.apply();
+
+    let mut files = vec![];
+    if paths.is_empty() {
+        collect_sources(Path::new("."), &mut files)?;
+    }
+    for path in paths {
+        collect_sources(path, &mut files)?;
+    }
+
+    let mut passed = 0;
+    let mut failures = vec![];
+    let mut broken_files = 0;
+
+    for path in files {
+        let input = fs::read_to_string(&path)?;
+        let file = SourceMap::add_named(path.display().to_string(), input);
+
+        let compiled = compile_tests(file).and_then(|(program, warnings)| {
+            args.deny_warnings(warnings)
+                .map(|warnings| (program, warnings))
+        });
+
+        let program = match compiled {
+            Ok((program, warnings)) => {
+                args.report(&path, warnings);
+                program
+            }
+            Err(diagnostics) => {
+                args.report(&path, diagnostics);
+                broken_files += 1;
+                continue;
+            }
+        };
+
+        let tests = test_runner::discover(&program);
+        if tests.is_empty() {
+            continue;
+        }
+
+        println!("\n{}", running(tests.len(), &path));
+
+        for test in tests {
+            match test_runner::run(&program, &test) {
+                Ok(()) => {
+                    println!("test {} ... ok", test.name);
+                    passed += 1;
+                }
+                Err(e) => {
+                    println!("test {} ... FAILED", test.name);
+                    failures.push((path.clone(), e));
+                }
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        println!("\nfailures:\n");
+        for (path, e) in &failures {
+            args.report(path, vec![e.diagnostic()]);
         }
+    }
+
+    println!("\n{}", summary(passed, failures.len(), broken_files));
+
+    if !failures.is_empty() || broken_files > 0 {
+        process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// Header of the tests of a single file.
+fn running(tests: usize, path: &Path) -> String {
+    let tests = if tests == 1 {
+        "1 test".to_string()
+    } else {
+        format!("{tests} tests")
     };
 
-    if args.print_validated {
-        println!("{validated:#?}");
+    format!("running {tests} in {}", path.display())
+}
+
+/// Summary of a test run. Files which do not compile fail the run, even though none of their
+/// tests were run.
+fn summary(passed: usize, failed: usize, broken_files: usize) -> String {
+    let ok = failed == 0 && broken_files == 0;
+    let mut summary = format!(
+        "test result: {}. {passed} passed; {failed} failed",
+        if ok { "ok" } else { "FAILED" }
+    );
+
+    match broken_files {
+        0 => {}
+        1 => summary.push_str("; 1 file failed to compile"),
+        n => summary.push_str(&format!("; {n} files failed to compile")),
+    }
+
+    summary
+}
+
+/// Collect all source files within a path. Directories are searched recursively for `.why` files.
+fn collect_sources(path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !path.is_dir() {
+        files.push(path.to_path_buf());
+        return Ok(());
+    }
+
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for entry in entries {
+        if entry.is_dir() {
+            collect_sources(&entry, files)?;
+        } else if entry
+            .extension()
+            .is_some_and(|extension| extension == "why")
+        {
+            files.push(entry);
+        }
     }
 
     Ok(())
 }
+
+/// A program compiled for running its tests together with its warnings.
+type CompiledTests = (
+    Vec<TopLevelStatement<ValidatedTypeInformation>>,
+    Vec<Diagnostic>,
+);
+
+/// Compile a file for running its tests. In contrast to a regular compilation, test files do not
+/// need a main function.
+fn compile_tests(file: FileId) -> Result<CompiledTests, Vec<Diagnostic>> {
+    let tokens = Lexer::for_file(file)
+        .lex()
+        .map_err(|e| vec![e.diagnostic()])?;
+    let statements = parse(&mut tokens.into()).map_err(|e| vec![e.diagnostic()])?;
+
+    let checked = TypeChecker::new(statements)
+        .check_all_without_main()
+        .map_err(|errors| {
+            errors
+                .iter()
+                .map(TypeCheckError::diagnostic)
+                .collect::<Vec<_>>()
+        })?;
+
+    let warnings = TypeChecker::warnings(&checked)
+        .iter()
+        .map(TypeCheckWarning::diagnostic)
+        .collect();
+
+    TypeChecker::validate(checked)
+        .map(|program| (program, warnings))
+        .map_err(|e| vec![e.diagnostic()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emit_flags() {
+        let args = VCArgs::parse_from(["yc", "main.why", "--emit=tokens,typed-ast", "-p"]);
+
+        assert!(args.emits(Emit::Tokens));
+        assert!(args.emits(Emit::Ast));
+        assert!(args.emits(Emit::TypedAst));
+        assert_eq!(args.format, OutputFormat::Debug);
+        assert_eq!(args.error_format, ErrorFormat::Human);
+    }
+
+    #[test]
+    fn test_test_subcommand() {
+        let args = VCArgs::parse_from(["yc", "test", "tests", "--color", "never"]);
+
+        assert!(matches!(
+            &args.command,
+            Some(Command::Test { paths }) if paths == &[PathBuf::from("tests")]
+        ));
+        assert_eq!(args.color, ColorChoice::Never);
+        assert!(args.file.is_none());
+
+        let args = VCArgs::parse_from(["yc", "main.why"]);
+        assert!(args.command.is_none());
+        assert_eq!(args.file, Some(PathBuf::from("main.why")));
+
+        // global options might also be passed before the subcommand
+        let args = VCArgs::parse_from(["yc", "--color", "never", "--format", "json", "test"]);
+        assert!(matches!(&args.command, Some(Command::Test { paths }) if paths.is_empty()));
+        assert_eq!(args.color, ColorChoice::Never);
+        assert_eq!(args.format, OutputFormat::Json);
+
+        let args = VCArgs::parse_from(["yc", "--color", "never", "main.why"]);
+        assert!(args.command.is_none());
+        assert_eq!(args.file, Some(PathBuf::from("main.why")));
+    }
+
+    #[test]
+    fn test_summary() {
+        assert_eq!(summary(2, 0, 0), "test result: ok. 2 passed; 0 failed");
+        assert_eq!(summary(2, 1, 0), "test result: FAILED. 2 passed; 1 failed");
+        assert_eq!(
+            summary(0, 0, 1),
+            "test result: FAILED. 0 passed; 0 failed; 1 file failed to compile"
+        );
+        assert_eq!(
+            summary(1, 0, 3),
+            "test result: FAILED. 1 passed; 0 failed; 3 files failed to compile"
+        );
+    }
+
+    #[test]
+    fn test_running() {
+        let path = Path::new("examples/math.why");
+
+        assert_eq!(running(1, path), "running 1 test in examples/math.why");
+        assert_eq!(running(2, path), "running 2 tests in examples/math.why");
+        assert_eq!(running(0, path), "running 0 tests in examples/math.why");
+    }
+
+    #[test]
+    fn test_deny_warnings() {
+        let warning = Diagnostic::warning("W0002", "Unused variable 'x'");
+
+        let args = VCArgs::parse_from(["yc", "main.why"]);
+        assert_eq!(
+            args.deny_warnings(vec![warning.clone()]),
+            Ok(vec![warning.clone()])
+        );
+
+        let args = VCArgs::parse_from(["yc", "-D", "warnings", "main.why"]);
+        let errors = args.deny_warnings(vec![warning]).unwrap_err();
+        assert_eq!(errors[0].severity, Severity::Error);
+        assert_eq!(errors[0].code, "W0002");
+        assert_eq!(args.deny_warnings(vec![]), Ok(vec![]));
+
+        let args = VCArgs::parse_from(["yc", "test", "-D", "warnings"]);
+        assert_eq!(args.deny, vec![Lint::Warnings]);
+    }
+
+    #[test]
+    fn test_json_diagnostic() {
+        let input = "fn main(): void {\n    foo\n}";
+        let tokens = Lexer::new(input).lex().unwrap();
+        let statements = parse(&mut tokens.into()).unwrap();
+        let errors = TypeChecker::new(statements).check_all().unwrap_err();
+
+        assert_eq!(
+            to_json(&errors[0].diagnostic(), Path::new("main.why")),
+            r#"{"code":"E0004","severity":"error","message":"Tried to access undefined variable foo","file":"main.why","start":{"line":2,"column":5},"end":{"line":2,"column":8},"labels":[{"start":{"line":2,"column":5},"end":{"line":2,"column":8},"message":"not found in this scope","primary":true}],"notes":[],"suggestions":[]}"#
+        );
+    }
+}